@@ -201,6 +201,7 @@ mod tests {
     #[test_case(AccountsFileProvider::AppendVec, #[allow(deprecated)] StorageAccess::Mmap)]
     #[test_case(AccountsFileProvider::AppendVec, StorageAccess::File)]
     #[test_case(AccountsFileProvider::HotStorage, StorageAccess::File)]
+    #[test_case(AccountsFileProvider::ColdStorage, StorageAccess::File)]
     fn test_account_storage_reader_no_obsolete_accounts(
         provider: AccountsFileProvider,
         storage_access: StorageAccess,
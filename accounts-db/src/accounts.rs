@@ -29,6 +29,7 @@ use {
     std::{
         cmp::Reverse,
         collections::{BinaryHeap, HashMap, HashSet},
+        ops::Bound,
         sync::{
             atomic::{AtomicUsize, Ordering},
             Arc, Mutex,
@@ -364,6 +365,52 @@ impl Accounts {
             .map(|_| collector)
     }
 
+    /// Loads the accounts owned by `program_id` that pass `filter`, in pubkey order, starting
+    /// after `after` if set
+    ///
+    /// The scan stops as soon as `limit` accounts are found, so the cost of loading a page does
+    /// not depend on the accounts that follow it.
+    pub fn load_by_program_with_filter_after<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        program_id: &Pubkey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<KeyedAccountSharedData>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let range = (
+            after.map_or(Bound::Unbounded, Bound::Excluded),
+            Bound::Unbounded,
+        );
+        let config = ScanConfig::new(ScanOrder::Sorted).recreate_with_abort();
+        let mut collector = Vec::new();
+        self.accounts_db
+            .range_scan_accounts(
+                ancestors,
+                bank_id,
+                range,
+                |some_account_tuple| {
+                    // The scan only checks for an abort between batches of pubkeys, so skip the
+                    // rest of the batch once the page is full
+                    if collector.len() >= limit {
+                        return;
+                    }
+                    Self::load_while_filtering(&mut collector, some_account_tuple, |account| {
+                        account.owner() == program_id && filter(account)
+                    });
+                    if collector.len() >= limit {
+                        config.abort();
+                    }
+                },
+                &config,
+            )
+            .map(|_| collector)
+    }
+
     fn calc_scan_result_size(account: &AccountSharedData) -> usize {
         account.data().len()
             + std::mem::size_of::<AccountSharedData>()
@@ -440,6 +487,51 @@ impl Accounts {
         Self::maybe_abort_scan(result, &config)
     }
 
+    /// Loads the accounts of `index_key` that pass `filter`, in pubkey order, starting after
+    /// `after` if set
+    ///
+    /// Only the pubkeys of the index key are collected up front, and accounts are loaded until
+    /// `limit` of them are found.
+    pub fn load_by_index_key_with_filter_after<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: &IndexKey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<KeyedAccountSharedData>> {
+        if limit == 0 {
+            return Ok(vec![]);
+        }
+        let range = (
+            after.map_or(Bound::Unbounded, Bound::Excluded),
+            Bound::Unbounded,
+        );
+        let config = ScanConfig::new(ScanOrder::Sorted).recreate_with_abort();
+        let mut collector = Vec::new();
+        self.accounts_db
+            .range_index_scan_accounts(
+                ancestors,
+                bank_id,
+                *index_key,
+                range,
+                |some_account_tuple| {
+                    // An unindexed fallback scan only checks for an abort between batches of
+                    // pubkeys, so skip the rest of the batch once the page is full
+                    if collector.len() >= limit {
+                        return;
+                    }
+                    Self::load_while_filtering(&mut collector, some_account_tuple, &filter);
+                    if collector.len() >= limit {
+                        config.abort();
+                    }
+                },
+                &config,
+            )
+            .map(|_| collector)
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.accounts_db.account_indexes.include_key(key)
     }
@@ -550,14 +642,19 @@ impl Accounts {
     ///
     /// This version updates the accounts index sequentially,
     /// using the same thread that calls the fn itself.
+    ///
+    /// `ancestors` of the slot `accounts` are stored in are used to notify
+    /// geyser of the state of the accounts before they are updated.
     pub fn store_accounts_seq<'a>(
         &self,
         accounts: impl StorableAccounts<'a>,
         transactions: Option<&'a [&'a SanitizedTransaction]>,
+        ancestors: Option<&Ancestors>,
     ) {
         self.accounts_db.store_accounts_unfrozen(
             accounts,
             transactions,
+            ancestors,
             UpdateIndexThreadSelection::Inline,
         );
     }
@@ -566,14 +663,19 @@ impl Accounts {
     ///
     /// This version updates the accounts index in parallel,
     /// using the foreground AccountsDb thread pool.
+    ///
+    /// `ancestors` of the slot `accounts` are stored in are used to notify
+    /// geyser of the state of the accounts before they are updated.
     pub fn store_accounts_par<'a>(
         &self,
         accounts: impl StorableAccounts<'a>,
         transactions: Option<&'a [&'a SanitizedTransaction]>,
+        ancestors: Option<&Ancestors>,
     ) {
         self.accounts_db.store_accounts_unfrozen(
             accounts,
             transactions,
+            ancestors,
             UpdateIndexThreadSelection::PoolWithThreshold,
         );
     }
@@ -791,6 +893,63 @@ mod tests {
         assert_eq!(loaded, vec![]);
     }
 
+    #[test]
+    fn test_load_by_program_with_filter_after() {
+        let accounts_db = AccountsDb::new_single_for_tests();
+        let accounts = Accounts::new(Arc::new(accounts_db));
+
+        let program_id = Pubkey::new_unique();
+        let mut pubkeys = (0..5)
+            .map(|lamports| {
+                let pubkey = solana_pubkey::new_rand();
+                let account = AccountSharedData::new(lamports + 1, 0, &program_id);
+                accounts.store_for_tests(0, &pubkey, &account);
+                pubkey
+            })
+            .collect::<Vec<_>>();
+        let other_account = AccountSharedData::new(1, 0, &Pubkey::new_unique());
+        accounts.store_for_tests(0, &solana_pubkey::new_rand(), &other_account);
+        accounts.add_root_and_flush_write_cache(0);
+        pubkeys.sort_unstable();
+
+        let load_page = |after: Option<&Pubkey>, limit| {
+            accounts
+                .load_by_program_with_filter_after(
+                    &Ancestors::default(),
+                    0,
+                    &program_id,
+                    |_| true,
+                    after,
+                    limit,
+                )
+                .unwrap()
+                .into_iter()
+                .map(|(pubkey, _)| pubkey)
+                .collect::<Vec<_>>()
+        };
+
+        // Pages are in pubkey order and stop at the limit
+        assert_eq!(load_page(None, 2), pubkeys[..2]);
+        assert_eq!(load_page(Some(&pubkeys[1]), 2), pubkeys[2..4]);
+        assert_eq!(load_page(Some(&pubkeys[3]), 2), pubkeys[4..]);
+        assert_eq!(load_page(Some(&pubkeys[4]), 2), Vec::<Pubkey>::new());
+        assert_eq!(load_page(None, 10), pubkeys);
+        assert_eq!(load_page(None, 0), Vec::<Pubkey>::new());
+
+        // The filter is applied before the limit
+        let loaded = accounts
+            .load_by_program_with_filter_after(
+                &Ancestors::default(),
+                0,
+                &program_id,
+                |account| account.lamports() > 1,
+                None,
+                10,
+            )
+            .unwrap();
+        assert_eq!(loaded.len(), 4);
+    }
+
     #[test_case(false; "old")]
     #[test_case(true; "simd83")]
     fn test_lock_accounts_with_duplicates(relax_intrabatch_account_locks: bool) {
@@ -48,7 +48,9 @@ use {
             AccountsIndexRootsStats, AccountsIndexScanResult, IndexKey, IsCached, ReclaimsSlotList,
             RefCount, ScanConfig, ScanFilter, ScanResult, SlotList, Startup, UpsertReclaim,
         },
-        accounts_update_notifier_interface::{AccountForGeyser, AccountsUpdateNotifier},
+        accounts_update_notifier_interface::{
+            AccountForGeyser, AccountPreState, AccountsUpdateNotifier,
+        },
         active_stats::{ActiveStatItem, ActiveStats},
         ancestors::Ancestors,
         append_vec::{self, AppendVec, STORE_META_OVERHEAD},
@@ -3435,6 +3437,42 @@ impl AccountsDb {
         Ok(())
     }
 
+    /// Scans the accounts whose pubkeys are within `range`, in the order set by `config`
+    pub fn range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<()>
+    where
+        F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // This can error out if the slots being scanned over are aborted
+        self.accounts_index.range_scan_accounts(
+            ancestors,
+            bank_id,
+            range,
+            |pubkey, (account_info, slot)| {
+                let mut account_accessor =
+                    self.get_account_accessor(slot, pubkey, &account_info.storage_location());
+
+                let account_slot = match account_accessor {
+                    LoadedAccountAccessor::Cached(None) => None,
+                    _ => account_accessor.get_loaded_account(|loaded_account| {
+                        (pubkey, loaded_account.take_account(), slot)
+                    }),
+                };
+                scan_func(account_slot)
+            },
+            config,
+        )?;
+
+        Ok(())
+    }
+
     pub fn index_scan_accounts<F>(
         &self,
         ancestors: &Ancestors,
@@ -3450,6 +3488,8 @@ impl AccountsDb {
             IndexKey::ProgramId(key) => key,
             IndexKey::SplTokenMint(key) => key,
             IndexKey::SplTokenOwner(key) => key,
+            IndexKey::SplTokenDelegate(key) => key,
+            IndexKey::StakeAuthority(key) => key,
         };
         if !self.account_indexes.include_key(key) {
             // the requested key was not indexed in the secondary index, so do a normal scan
@@ -3476,6 +3516,53 @@ impl AccountsDb {
         Ok(used_index)
     }
 
+    /// Scans the accounts of `index_key` whose pubkeys are within `range`, in pubkey order
+    pub fn range_index_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        bank_id: BankId,
+        index_key: IndexKey,
+        range: R,
+        mut scan_func: F,
+        config: &ScanConfig,
+    ) -> ScanResult<bool>
+    where
+        F: FnMut(Option<(&Pubkey, AccountSharedData, Slot)>),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        let key = match &index_key {
+            IndexKey::ProgramId(key) => key,
+            IndexKey::SplTokenMint(key) => key,
+            IndexKey::SplTokenOwner(key) => key,
+            IndexKey::SplTokenDelegate(key) => key,
+            IndexKey::StakeAuthority(key) => key,
+        };
+        if !self.account_indexes.include_key(key) {
+            // the requested key was not indexed in the secondary index, so do a normal scan
+            let used_index = false;
+            self.range_scan_accounts(ancestors, bank_id, range, scan_func, config)?;
+            return Ok(used_index);
+        }
+
+        self.accounts_index.range_index_scan_accounts(
+            ancestors,
+            bank_id,
+            index_key,
+            range,
+            |pubkey, (account_info, slot)| {
+                let account_slot = self
+                    .get_account_accessor(slot, pubkey, &account_info.storage_location())
+                    .get_loaded_account(|loaded_account| {
+                        (pubkey, loaded_account.take_account(), slot)
+                    });
+                scan_func(account_slot)
+            },
+            config,
+        )?;
+        let used_index = true;
+        Ok(used_index)
+    }
+
     /// Scan a specific slot through all the account storage
     pub(crate) fn scan_account_storage<R, B>(
         &self,
@@ -5617,10 +5704,14 @@ impl AccountsDb {
 
     /// Stores accounts in the write cache and updates the index.
     /// This should only be used for accounts that are unrooted (unfrozen)
+    ///
+    /// `ancestors` of the slot the accounts are stored in are used to load the state of accounts
+    /// before they are updated, if the geyser notifier requests it.
     pub(crate) fn store_accounts_unfrozen<'a>(
         &self,
         accounts: impl StorableAccounts<'a>,
         transactions: Option<&'a [&'a SanitizedTransaction]>,
+        ancestors: Option<&Ancestors>,
         update_index_thread_selection: UpdateIndexThreadSelection,
     ) {
         // If all transactions in a batch are errored,
@@ -5640,8 +5731,12 @@ impl AccountsDb {
 
         // Store the accounts in the write cache
         let mut store_accounts_time = Measure::start("store_accounts");
-        let (store_account, num_ephemeral_accounts_skipped) =
-            self.write_accounts_to_cache(accounts.target_slot(), &accounts, transactions);
+        let (store_account, num_ephemeral_accounts_skipped) = self.write_accounts_to_cache(
+            accounts.target_slot(),
+            &accounts,
+            transactions,
+            ancestors,
+        );
         store_accounts_time.stop();
         self.stats
             .store_accounts_to_cache_us
@@ -5793,6 +5888,7 @@ impl AccountsDb {
         slot: Slot,
         accounts_and_meta_to_store: &impl StorableAccounts<'b>,
         txs: Option<&[&SanitizedTransaction]>,
+        ancestors: Option<&Ancestors>,
     ) -> (Vec<bool>, usize) {
         let mut accounts_skipped = 0;
         let mut current_write_version = if self.accounts_update_notifier.is_some() {
@@ -5801,6 +5897,14 @@ impl AccountsDb {
         } else {
             0
         };
+        // Ancestors to load the state of accounts before they are updated with. The extra load is
+        // only done if a consumer of the notifications opted in to pre-state, not whenever
+        // notifications are enabled.
+        let pre_state_ancestors = ancestors.filter(|_| {
+            self.accounts_update_notifier
+                .as_ref()
+                .is_some_and(|notifier| notifier.account_pre_state_notifications_enabled())
+        });
 
         let store_account = (0..accounts_and_meta_to_store.len())
             .map(|index| {
@@ -5818,13 +5922,24 @@ impl AccountsDb {
                     }
 
                     // if geyser is enabled, send the account update notification
-                    // with the original version of the account
+                    // with the original version of the account, and the version it replaces
+                    let pre_state = pre_state_ancestors.map(|ancestors| {
+                        let (pre_account, _slot) = self
+                            .load(
+                                ancestors,
+                                pubkey,
+                                LoadHint::FixedMaxRootDoNotPopulateReadCache,
+                            )
+                            .unwrap_or_default();
+                        AccountPreState::new(&pre_account)
+                    });
                     self.notify_account_at_accounts_update(
                         slot,
                         &account_shared_data,
                         &txn,
                         pubkey,
                         current_write_version,
+                        pre_state.as_ref(),
                     );
                     current_write_version = current_write_version.saturating_add(1);
 
@@ -7186,6 +7301,7 @@ impl AccountsDb {
         self.store_accounts_unfrozen(
             (slot, pre_populate_zero_lamport.as_slice()),
             None,
+            None,
             UpdateIndexThreadSelection::PoolWithThreshold,
         );
 
@@ -7193,6 +7309,7 @@ impl AccountsDb {
         self.store_accounts_unfrozen(
             accounts,
             None,
+            None,
             UpdateIndexThreadSelection::PoolWithThreshold,
         );
     }
@@ -1,6 +1,9 @@
 use {
-    crate::accounts_db::AccountsDb, solana_account::AccountSharedData, solana_clock::Slot,
-    solana_pubkey::Pubkey, solana_transaction::sanitized::SanitizedTransaction,
+    crate::{accounts_db::AccountsDb, accounts_update_notifier_interface::AccountPreState},
+    solana_account::AccountSharedData,
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_transaction::sanitized::SanitizedTransaction,
 };
 
 impl AccountsDb {
@@ -11,8 +14,21 @@ impl AccountsDb {
         txn: &Option<&SanitizedTransaction>,
         pubkey: &Pubkey,
         write_version: u64,
+        pre_state: Option<&AccountPreState>,
     ) {
-        if let Some(accounts_update_notifier) = &self.accounts_update_notifier {
+        let Some(accounts_update_notifier) = &self.accounts_update_notifier else {
+            return;
+        };
+        if let Some(pre_state) = pre_state {
+            accounts_update_notifier.notify_account_update_with_pre_state(
+                slot,
+                account,
+                txn,
+                pubkey,
+                write_version,
+                pre_state,
+            );
+        } else {
             accounts_update_notifier.notify_account_update(
                 slot,
                 account,
@@ -29,10 +45,14 @@ pub mod tests {
     use {
         super::*,
         crate::{
-            accounts_db::{AccountsDbConfig, MarkObsoleteAccounts, ACCOUNTS_DB_CONFIG_FOR_TESTING},
+            accounts_db::{
+                AccountsDbConfig, MarkObsoleteAccounts, UpdateIndexThreadSelection,
+                ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            },
             accounts_update_notifier_interface::{
                 AccountForGeyser, AccountsUpdateNotifier, AccountsUpdateNotifierInterface,
             },
+            ancestors::Ancestors,
             utils::create_account_shared_data,
         },
         dashmap::DashMap,
@@ -53,14 +73,41 @@ pub mod tests {
     #[derive(Debug, Default)]
     struct GeyserTestPlugin {
         pub accounts_notified: DashMap<Pubkey, Vec<(Slot, u64, AccountSharedData)>>,
+        pub pre_states_notified: DashMap<Pubkey, Vec<Option<AccountPreState>>>,
+        pub pre_state_notifications_enabled: bool,
         pub is_startup_done: AtomicBool,
     }
 
+    impl GeyserTestPlugin {
+        fn record_account_update(
+            &self,
+            slot: Slot,
+            account: &AccountSharedData,
+            pubkey: &Pubkey,
+            write_version: u64,
+            pre_state: Option<&AccountPreState>,
+        ) {
+            self.accounts_notified.entry(*pubkey).or_default().push((
+                slot,
+                write_version,
+                account.clone(),
+            ));
+            self.pre_states_notified
+                .entry(*pubkey)
+                .or_default()
+                .push(pre_state.cloned());
+        }
+    }
+
     impl AccountsUpdateNotifierInterface for GeyserTestPlugin {
         fn snapshot_notifications_enabled(&self) -> bool {
             true
         }
 
+        fn account_pre_state_notifications_enabled(&self) -> bool {
+            self.pre_state_notifications_enabled
+        }
+
         /// Notified when an account is updated at runtime, due to transaction activities
         fn notify_account_update(
             &self,
@@ -70,11 +117,19 @@ pub mod tests {
             pubkey: &Pubkey,
             write_version: u64,
         ) {
-            self.accounts_notified.entry(*pubkey).or_default().push((
-                slot,
-                write_version,
-                account.clone(),
-            ));
+            self.record_account_update(slot, account, pubkey, write_version, None);
+        }
+
+        fn notify_account_update_with_pre_state(
+            &self,
+            slot: Slot,
+            account: &AccountSharedData,
+            _txn: &Option<&SanitizedTransaction>,
+            pubkey: &Pubkey,
+            write_version: u64,
+            pre_state: &AccountPreState,
+        ) {
+            self.record_account_update(slot, account, pubkey, write_version, Some(pre_state));
         }
 
         /// Notified when the AccountsDb is initialized at start when restored
@@ -243,6 +298,63 @@ pub mod tests {
         assert_eq!(notifier.accounts_notified.get(&key3).unwrap()[0].0, slot1);
     }
 
+    #[test_case(true; "pre-state enabled")]
+    #[test_case(false; "pre-state disabled")]
+    fn test_notify_account_pre_state(pre_state_notifications_enabled: bool) {
+        let mut accounts_db = AccountsDb::new_single_for_tests();
+        let notifier = Arc::new(GeyserTestPlugin {
+            pre_state_notifications_enabled,
+            ..GeyserTestPlugin::default()
+        });
+        accounts_db.set_geyser_plugin_notifier(Some(notifier.clone()));
+
+        let key = solana_pubkey::new_rand();
+        let owner = solana_pubkey::new_rand();
+        let store = |slot: Slot, ancestors: Option<Ancestors>, account: &AccountSharedData| {
+            accounts_db.store_accounts_unfrozen(
+                (slot, [(&key, account)].as_slice()),
+                None,
+                ancestors.as_ref(),
+                UpdateIndexThreadSelection::Inline,
+            );
+        };
+
+        // Slot 1 creates the account, and updates it twice
+        let account1 = AccountSharedData::new(1, 1, &owner);
+        let account2 = AccountSharedData::new(2, 2, &owner);
+        store(1, Some(Ancestors::from(vec![0, 1])), &account1);
+        store(1, Some(Ancestors::from(vec![0, 1])), &account2);
+        // Slot 2 is a child of slot 1
+        let account3 = AccountSharedData::new(3, 0, &Pubkey::default());
+        store(2, Some(Ancestors::from(vec![0, 1, 2])), &account3);
+        // Slot 3 is on a fork from slot 1, so it does not see the update in slot 2
+        store(3, Some(Ancestors::from(vec![0, 1, 3])), &account1);
+        // Stores without ancestors have no pre-state
+        store(4, None, &account1);
+
+        let pre_states = notifier.pre_states_notified.get(&key).unwrap().clone();
+        if !pre_state_notifications_enabled {
+            assert_eq!(pre_states, vec![None; 5]);
+            return;
+        }
+        assert_eq!(
+            pre_states,
+            vec![
+                Some(AccountPreState::new(&AccountSharedData::default())),
+                Some(AccountPreState::new(&account1)),
+                Some(AccountPreState::new(&account2)),
+                Some(AccountPreState::new(&account2)),
+                None,
+            ]
+        );
+        assert_eq!(pre_states[0].as_ref().unwrap().lamports, 0);
+        assert_eq!(pre_states[1].as_ref().unwrap().owner, owner);
+        assert_eq!(
+            pre_states[1].as_ref().unwrap().data_hash,
+            solana_sha256_hasher::hash(account1.data())
+        );
+    }
+
     /// This test ensures that notifications for closed accounts includes the original
     /// account's information.  The most important is the account's original owner.
     #[test]
@@ -3674,7 +3674,7 @@ define_accounts_db_test!(
                     0
                 );
             }
-            AccountsFileProvider::HotStorage => {
+            AccountsFileProvider::HotStorage | AccountsFileProvider::ColdStorage => {
                 // For tired-storage, alive bytes are only an approximation.
                 // Therefore, it won't be zero.
                 assert!(
@@ -6596,6 +6596,7 @@ fn test_new_zero_lamport_accounts_skipped() {
     accounts_db.store_accounts_unfrozen(
         (slot, [(&pubkey1, &zero_account)].as_slice()),
         None,
+        None,
         UpdateIndexThreadSelection::PoolWithThreshold,
     );
     assert!(!accounts_db.accounts_index.contains(&pubkey1));
@@ -6615,6 +6616,7 @@ fn test_new_zero_lamport_accounts_skipped() {
             .as_slice(),
         ),
         None,
+        None,
         UpdateIndexThreadSelection::PoolWithThreshold,
     );
     assert!(!accounts_db.accounts_index.contains(&pubkey1));
@@ -6641,6 +6643,7 @@ fn test_new_zero_lamport_accounts_skipped() {
     accounts_db.store_accounts_unfrozen(
         (slot, [(&pubkey2, &zero_account)].as_slice()),
         None,
+        None,
         UpdateIndexThreadSelection::PoolWithThreshold,
     );
     assert!(accounts_db.accounts_index.contains(&pubkey2));
@@ -6669,6 +6672,7 @@ fn test_new_zero_lamport_accounts_skipped() {
     accounts_db.store_accounts_unfrozen(
         (slot, [(&pubkey1, &account)].as_slice()),
         None,
+        None,
         UpdateIndexThreadSelection::PoolWithThreshold,
     );
     assert!(accounts_db.accounts_index.contains(&pubkey1));
@@ -6678,6 +6682,7 @@ fn test_new_zero_lamport_accounts_skipped() {
     accounts_db.store_accounts_unfrozen(
         (slot, [(&pubkey3, &zero_account)].as_slice()),
         None,
+        None,
         UpdateIndexThreadSelection::PoolWithThreshold,
     );
     accounts_db.add_root_and_flush_write_cache(slot);
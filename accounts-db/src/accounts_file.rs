@@ -6,7 +6,7 @@ use {
         append_vec::{AppendVec, AppendVecError},
         storable_accounts::StorableAccounts,
         tiered_storage::{
-            error::TieredStorageError, hot::HOT_FORMAT, index::IndexOffset, TieredStorage,
+            cold::COLD_FORMAT, error::TieredStorageError, index::IndexOffset, TieredStorage,
         },
     },
     agave_fs::{buffered_reader::RequiredLenBufFileRead, FileInfo},
@@ -328,7 +328,7 @@ impl AccountsFile {
             // assumes all offsets are multiple of 8 while TieredStorage uses
             // IndexOffset that is equivalent to AccountInfo::reduced_offset.
             Self::TieredStorage(ts) => ts
-                .write_accounts(accounts, skip, &HOT_FORMAT)
+                .write_accounts(accounts, skip, ts.format())
                 .map(|mut stored_accounts_info| {
                     stored_accounts_info.offsets.iter_mut().for_each(|offset| {
                         *offset = AccountInfo::reduced_offset_to_offset(*offset as u32);
@@ -358,6 +358,7 @@ pub enum AccountsFileProvider {
     #[default]
     AppendVec,
     HotStorage,
+    ColdStorage,
 }
 
 impl AccountsFileProvider {
@@ -375,6 +376,9 @@ impl AccountsFileProvider {
                 storage_access,
             )),
             Self::HotStorage => AccountsFile::TieredStorage(TieredStorage::new_writable(path)),
+            Self::ColdStorage => AccountsFile::TieredStorage(
+                TieredStorage::new_writable_with_format(path, &COLD_FORMAT),
+            ),
         }
     }
 }
@@ -27,7 +27,7 @@ use {
     solana_account::ReadableAccount,
     solana_clock::{BankId, Slot},
     solana_measure::measure::Measure,
-    solana_pubkey::Pubkey,
+    solana_pubkey::{Pubkey, PUBKEY_BYTES},
     stats::Stats,
     std::{
         collections::{btree_map::BTreeMap, HashSet},
@@ -46,6 +46,8 @@ pub use {
     iter::ITER_BATCH_SIZE,
     secondary::{
         AccountIndex, AccountSecondaryIndexes, AccountSecondaryIndexesIncludeExclude, IndexKey,
+        SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET, SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
+        STAKE_ACCOUNT_STAKER_OFFSET, STAKE_ACCOUNT_WITHDRAWER_OFFSET,
     },
 };
 
@@ -225,7 +227,9 @@ pub enum ScanError {
 
 enum ScanTypes<R: RangeBounds<Pubkey>> {
     Unindexed(Option<R>),
-    Indexed(IndexKey),
+    /// If a range is set, only the pubkeys of the index key within it are scanned, in pubkey
+    /// order
+    Indexed(IndexKey, Option<R>),
 }
 
 /// specification of how much memory the in-mem portion of account index can hold
@@ -303,6 +307,8 @@ pub struct AccountsIndex<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> {
     program_id_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_mint_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     spl_token_owner_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    spl_token_delegate_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
+    stake_authority_index: SecondaryIndex<RwLockSecondaryIndexEntry>,
     pub roots_tracker: RwLock<RootsTracker>,
     ongoing_scan_roots: RwLock<BTreeMap<Slot, u64>>,
     // Each scan has some latest slot `S` that is the tip of the fork the scan
@@ -355,6 +361,12 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             spl_token_owner_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
                 "spl_token_owner_index_stats",
             ),
+            spl_token_delegate_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
+                "spl_token_delegate_index_stats",
+            ),
+            stake_authority_index: SecondaryIndex::<RwLockSecondaryIndexEntry>::new(
+                "stake_authority_index_stats",
+            ),
             roots_tracker: RwLock::<RootsTracker>::default(),
             ongoing_scan_roots: RwLock::<BTreeMap<Slot, u64>>::default(),
             removed_bank_ids: Mutex::<HashSet<BankId>>::default(),
@@ -627,32 +639,57 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 // Pass "" not to log metrics, so RPC doesn't get spammy
                 self.do_scan_accounts(metric_name, ancestors, func, range, Some(max_root), config);
             }
-            ScanTypes::Indexed(IndexKey::ProgramId(program_id)) => {
+            ScanTypes::Indexed(IndexKey::ProgramId(program_id), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.program_id_index,
                     &program_id,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenMint(mint_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_mint_index,
                     &mint_key,
+                    range,
                     Some(max_root),
                     config,
                 );
             }
-            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key)) => {
+            ScanTypes::Indexed(IndexKey::SplTokenOwner(owner_key), range) => {
                 self.do_scan_secondary_index(
                     ancestors,
                     func,
                     &self.spl_token_owner_index,
                     &owner_key,
+                    range,
+                    Some(max_root),
+                    config,
+                );
+            }
+            ScanTypes::Indexed(IndexKey::SplTokenDelegate(delegate_key), range) => {
+                self.do_scan_secondary_index(
+                    ancestors,
+                    func,
+                    &self.spl_token_delegate_index,
+                    &delegate_key,
+                    range,
+                    Some(max_root),
+                    config,
+                );
+            }
+            ScanTypes::Indexed(IndexKey::StakeAuthority(authority_key), range) => {
+                self.do_scan_secondary_index(
+                    ancestors,
+                    func,
+                    &self.stake_authority_index,
+                    &authority_key,
+                    range,
                     Some(max_root),
                     config,
                 );
@@ -764,6 +801,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
 
     fn do_scan_secondary_index<
         F,
+        R,
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
         &self,
@@ -771,12 +809,19 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         mut func: F,
         index: &SecondaryIndex<SecondaryIndexEntryType>,
         index_key: &Pubkey,
+        range: Option<R>,
         max_root: Option<Slot>,
         config: &ScanConfig,
     ) where
         F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey>,
     {
-        for pubkey in index.get(index_key) {
+        let mut pubkeys = index.get(index_key);
+        if let Some(range) = range {
+            pubkeys.retain(|pubkey| range.contains(pubkey));
+            pubkeys.sort_unstable();
+        }
+        for pubkey in pubkeys {
             if config.is_aborted() {
                 break;
             }
@@ -905,6 +950,31 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         )
     }
 
+    /// call func with every pubkey and index visible from a given set of ancestors, for the
+    /// pubkeys within `range`
+    pub(crate) fn range_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            func,
+            ScanTypes::Unindexed(Some(range)),
+            config,
+        )
+    }
+
     /// call func with every pubkey and index visible from a given set of ancestors
     pub(crate) fn index_scan_accounts<F>(
         &self,
@@ -923,7 +993,33 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             ancestors,
             scan_bank_id,
             func,
-            ScanTypes::<Range<Pubkey>>::Indexed(index_key),
+            ScanTypes::<Range<Pubkey>>::Indexed(index_key, None),
+            config,
+        )
+    }
+
+    /// call func with every pubkey and index visible from a given set of ancestors, for the
+    /// pubkeys of `index_key` within `range`, in pubkey order
+    pub(crate) fn range_index_scan_accounts<F, R>(
+        &self,
+        ancestors: &Ancestors,
+        scan_bank_id: BankId,
+        index_key: IndexKey,
+        range: R,
+        func: F,
+        config: &ScanConfig,
+    ) -> Result<(), ScanError>
+    where
+        F: FnMut(&Pubkey, (&T, Slot)),
+        R: RangeBounds<Pubkey> + std::fmt::Debug,
+    {
+        // Pass "" not to log metrics, so RPC doesn't get spammy
+        self.do_checked_scan_accounts(
+            "",
+            ancestors,
+            scan_bank_id,
+            func,
+            ScanTypes::Indexed(index_key, Some(range)),
             config,
         )
     }
@@ -1202,9 +1298,80 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                     }
                 }
             }
+
+            if account_indexes.contains(&AccountIndex::SplTokenDelegate) {
+                if let Some(delegate_key) =
+                    Self::unpack_spl_token_account_delegate::<G>(account_data)
+                {
+                    if account_indexes.include_key(&delegate_key) {
+                        self.spl_token_delegate_index.insert(&delegate_key, pubkey);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the delegate of an SPL token account, if it has one
+    fn unpack_spl_token_account_delegate<G: spl_generic_token::token::GenericTokenAccount>(
+        account_data: &[u8],
+    ) -> Option<Pubkey> {
+        const DELEGATE_TAG_SOME: [u8; 4] = 1u32.to_le_bytes();
+        if !G::valid_account_data(account_data) {
+            return None;
+        }
+        let tag = account_data
+            .get(SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET..SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET)?;
+        if tag != DELEGATE_TAG_SOME {
+            return None;
+        }
+        account_data
+            .get(SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET..)?
+            .get(..PUBKEY_BYTES)
+            .and_then(|bytes| Pubkey::try_from(bytes).ok())
+    }
+
+    fn update_stake_secondary_indexes(
+        &self,
+        pubkey: &Pubkey,
+        account_owner: &Pubkey,
+        account_data: &[u8],
+        account_indexes: &AccountSecondaryIndexes,
+    ) {
+        if *account_owner == solana_sdk_ids::stake::id()
+            && account_indexes.contains(&AccountIndex::StakeAuthority)
+        {
+            for authority_key in Self::unpack_stake_account_authorities(account_data)
+                .iter()
+                .flatten()
+            {
+                if account_indexes.include_key(authority_key) {
+                    self.stake_authority_index.insert(authority_key, pubkey);
+                }
+            }
         }
     }
 
+    /// Returns the staker and withdrawer authorities of an initialized or delegated stake account
+    fn unpack_stake_account_authorities(account_data: &[u8]) -> Option<[Pubkey; 2]> {
+        // `StakeStateV2` discriminants of the variants that carry `Meta`
+        const STAKE_STATE_INITIALIZED: [u8; 4] = 1u32.to_le_bytes();
+        const STAKE_STATE_STAKE: [u8; 4] = 2u32.to_le_bytes();
+        let state = account_data.get(..4)?;
+        if state != STAKE_STATE_INITIALIZED && state != STAKE_STATE_STAKE {
+            return None;
+        }
+        let unpack_authority = |offset: usize| {
+            account_data
+                .get(offset..)?
+                .get(..PUBKEY_BYTES)
+                .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        };
+        Some([
+            unpack_authority(STAKE_ACCOUNT_STAKER_OFFSET)?,
+            unpack_authority(STAKE_ACCOUNT_WITHDRAWER_OFFSET)?,
+        ])
+    }
+
     pub fn get_index_key_size(&self, index: &AccountIndex, index_key: &Pubkey) -> Option<usize> {
         match index {
             AccountIndex::ProgramId => self.program_id_index.index.get(index_key).map(|x| x.len()),
@@ -1218,6 +1385,16 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
                 .index
                 .get(index_key)
                 .map(|x| x.len()),
+            AccountIndex::SplTokenDelegate => self
+                .spl_token_delegate_index
+                .index
+                .get(index_key)
+                .map(|x| x.len()),
+            AccountIndex::StakeAuthority => self
+                .stake_authority_index
+                .index
+                .get(index_key)
+                .map(|x| x.len()),
         }
     }
 
@@ -1235,6 +1412,14 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             info!("secondary index: {:?}", AccountIndex::SplTokenOwner);
             self.spl_token_owner_index.log_contents();
         }
+        if !self.spl_token_delegate_index.index.is_empty() {
+            info!("secondary index: {:?}", AccountIndex::SplTokenDelegate);
+            self.spl_token_delegate_index.log_contents();
+        }
+        if !self.stake_authority_index.index.is_empty() {
+            info!("secondary index: {:?}", AccountIndex::StakeAuthority);
+            self.stake_authority_index.log_contents();
+        }
     }
 
     pub(crate) fn update_secondary_indexes(
@@ -1283,6 +1468,7 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
             account_data,
             account_indexes,
         );
+        self.update_stake_secondary_indexes(pubkey, account_owner, account_data, account_indexes);
     }
 
     pub(crate) fn get_bin(&self, pubkey: &Pubkey) -> &InMemAccountsIndex<T, U> {
@@ -1480,6 +1666,14 @@ impl<T: IndexValue, U: DiskIndexValue + From<T> + Into<T>> AccountsIndex<T, U> {
         if account_indexes.contains(&AccountIndex::SplTokenMint) {
             self.spl_token_mint_index.remove_by_inner_key(inner_key);
         }
+
+        if account_indexes.contains(&AccountIndex::SplTokenDelegate) {
+            self.spl_token_delegate_index.remove_by_inner_key(inner_key);
+        }
+
+        if account_indexes.contains(&AccountIndex::StakeAuthority) {
+            self.stake_authority_index.remove_by_inner_key(inner_key);
+        }
     }
 
     /// Returns true if the slot list was completely purged (is empty at the end).
@@ -1745,7 +1939,6 @@ pub mod tests {
         super::{bucket_map_holder::BucketMapHolder, *},
         crate::accounts_index::account_map_entry::AccountMapEntryMeta,
         solana_account::AccountSharedData,
-        solana_pubkey::PUBKEY_BYTES,
         spl_generic_token::{spl_token_ids, token::SPL_TOKEN_ACCOUNT_OWNER_OFFSET},
         std::ops::{
             Bound::{Excluded, Included, Unbounded},
@@ -1776,6 +1969,24 @@ pub mod tests {
         }
     }
 
+    pub fn spl_token_delegate_index_enabled() -> AccountSecondaryIndexes {
+        let mut account_indexes = HashSet::new();
+        account_indexes.insert(AccountIndex::SplTokenDelegate);
+        AccountSecondaryIndexes {
+            indexes: account_indexes,
+            keys: None,
+        }
+    }
+
+    pub fn stake_authority_index_enabled() -> AccountSecondaryIndexes {
+        let mut account_indexes = HashSet::new();
+        account_indexes.insert(AccountIndex::StakeAuthority);
+        AccountSecondaryIndexes {
+            indexes: account_indexes,
+            keys: None,
+        }
+    }
+
     fn create_spl_token_mint_secondary_index_state() -> (usize, usize, AccountSecondaryIndexes) {
         {
             // Check that we're actually testing the correct variant
@@ -1800,6 +2011,21 @@ pub mod tests {
         )
     }
 
+    fn create_spl_token_delegate_secondary_index_state() -> (usize, usize, AccountSecondaryIndexes)
+    {
+        {
+            // Check that we're actually testing the correct variant
+            let index = AccountsIndex::<bool, bool>::default_for_tests();
+            let _type_check = SecondaryIndexTypes::RwLock(&index.spl_token_delegate_index);
+        }
+
+        (
+            SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET,
+            SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET + PUBKEY_BYTES,
+            spl_token_delegate_index_enabled(),
+        )
+    }
+
     #[test]
     fn test_get_empty() {
         let key = solana_pubkey::new_rand();
@@ -3096,6 +3322,21 @@ pub mod tests {
         data
     }
 
+    fn make_delegated_token_account_data() -> Vec<u8> {
+        let mut data = make_empty_token_account_data();
+        data[SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET] = 1;
+        data
+    }
+
+    /// Only token accounts with a delegate are added to the delegate index
+    fn make_token_account_data_for(secondary_indexes: &AccountSecondaryIndexes) -> Vec<u8> {
+        if secondary_indexes.contains(&AccountIndex::SplTokenDelegate) {
+            make_delegated_token_account_data()
+        } else {
+            make_empty_token_account_data()
+        }
+    }
+
     fn run_test_purge_exact_secondary_index<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
@@ -3110,7 +3351,7 @@ pub mod tests {
         let index_key = Pubkey::new_unique();
         let account_key = Pubkey::new_unique();
 
-        let mut account_data = make_empty_token_account_data();
+        let mut account_data = make_token_account_data_for(secondary_indexes);
         account_data[key_start..key_end].clone_from_slice(&(index_key.to_bytes()));
 
         // Insert slots into secondary index
@@ -3369,6 +3610,20 @@ pub mod tests {
         );
     }
 
+    #[test]
+    fn test_purge_exact_spl_token_delegate_secondary_index() {
+        let (key_start, key_end, secondary_indexes) =
+            create_spl_token_delegate_secondary_index_state();
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        run_test_purge_exact_secondary_index(
+            &index,
+            &index.spl_token_delegate_index,
+            key_start,
+            key_end,
+            &secondary_indexes,
+        );
+    }
+
     #[test]
     fn test_purge_older_root_entries() {
         // No roots, should be no reclaims
@@ -3501,7 +3756,7 @@ pub mod tests {
         let mut secondary_indexes = secondary_indexes.clone();
         let account_key = Pubkey::new_unique();
         let index_key = Pubkey::new_unique();
-        let mut account_data = make_empty_token_account_data();
+        let mut account_data = make_token_account_data_for(&secondary_indexes);
         account_data[key_start..key_end].clone_from_slice(&(index_key.to_bytes()));
 
         // Wrong program id
@@ -3652,6 +3907,110 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_spl_token_delegate_secondary_index() {
+        let (key_start, key_end, secondary_indexes) =
+            create_spl_token_delegate_secondary_index_state();
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        for token_id in &spl_token_ids() {
+            run_test_spl_token_secondary_indexes(
+                token_id,
+                &index,
+                &index.spl_token_delegate_index,
+                key_start,
+                key_end,
+                &secondary_indexes,
+            );
+        }
+
+        // No delegate
+        let account_key = Pubkey::new_unique();
+        let mut account_data = make_empty_token_account_data();
+        account_data[key_start..key_end].clone_from_slice(&Pubkey::new_unique().to_bytes());
+        index.update_secondary_indexes(
+            &account_key,
+            &AccountSharedData::create_from_existing_shared_data(
+                0,
+                Arc::new(account_data),
+                spl_generic_token::token::id(),
+                false,
+                0,
+            ),
+            &secondary_indexes,
+        );
+        assert!(index.spl_token_delegate_index.index.is_empty());
+        assert!(index.spl_token_delegate_index.reverse_index.is_empty());
+    }
+
+    #[test]
+    fn test_stake_authority_secondary_index() {
+        const STAKE_ACCOUNT_SIZE: usize = 200;
+        let secondary_indexes = stake_authority_index_enabled();
+        let index = AccountsIndex::<bool, bool>::default_for_tests();
+        let account_key = Pubkey::new_unique();
+        let staker = Pubkey::new_unique();
+        let withdrawer = Pubkey::new_unique();
+
+        let make_stake_account = |state: u32, owner: Pubkey| {
+            let mut account_data = vec![0; STAKE_ACCOUNT_SIZE];
+            account_data[..4].copy_from_slice(&state.to_le_bytes());
+            account_data[STAKE_ACCOUNT_STAKER_OFFSET..][..PUBKEY_BYTES]
+                .copy_from_slice(&staker.to_bytes());
+            account_data[STAKE_ACCOUNT_WITHDRAWER_OFFSET..][..PUBKEY_BYTES]
+                .copy_from_slice(&withdrawer.to_bytes());
+            AccountSharedData::create_from_existing_shared_data(
+                1,
+                Arc::new(account_data),
+                owner,
+                false,
+                0,
+            )
+        };
+
+        // Wrong program id, and uninitialized/rewards pool stake accounts
+        for (state, owner) in [
+            (1, Pubkey::default()),
+            (0, solana_sdk_ids::stake::id()),
+            (3, solana_sdk_ids::stake::id()),
+        ] {
+            index.upsert(
+                0,
+                0,
+                &account_key,
+                &make_stake_account(state, owner),
+                &secondary_indexes,
+                true,
+                &mut ReclaimsSlotList::new(),
+                UPSERT_RECLAIM_TEST_DEFAULT,
+            );
+            assert!(index.stake_authority_index.index.is_empty());
+            assert!(index.stake_authority_index.reverse_index.is_empty());
+        }
+
+        // Initialized and delegated stake accounts are indexed by both authorities
+        for state in [1, 2] {
+            index.update_secondary_indexes(
+                &account_key,
+                &make_stake_account(state, solana_sdk_ids::stake::id()),
+                &secondary_indexes,
+            );
+            check_secondary_index_mapping_correct(
+                &index.stake_authority_index,
+                &[staker, withdrawer],
+                &account_key,
+            );
+        }
+        assert_eq!(
+            index.get_index_key_size(&AccountIndex::StakeAuthority, &withdrawer),
+            Some(1)
+        );
+
+        index.slot_list_mut(&account_key, |mut slot_list| slot_list.clear());
+        let _ = index.handle_dead_keys(&[account_key], &secondary_indexes);
+        assert!(index.stake_authority_index.index.is_empty());
+        assert!(index.stake_authority_index.reverse_index.is_empty());
+    }
+
     fn run_test_secondary_indexes_same_slot_and_forks<
         SecondaryIndexEntryType: SecondaryIndexEntry + Default + Sync + Send,
     >(
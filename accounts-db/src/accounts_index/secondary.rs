@@ -13,6 +13,15 @@ use {
     },
 };
 
+/// Offset of the `COption` tag of the delegate in an SPL token account
+pub const SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET: usize = 72;
+/// Offset of the delegate address in an SPL token account
+pub const SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET: usize = 76;
+/// Offset of the staker authority in an initialized or delegated stake account
+pub const STAKE_ACCOUNT_STAKER_OFFSET: usize = 12;
+/// Offset of the withdrawer authority in an initialized or delegated stake account
+pub const STAKE_ACCOUNT_WITHDRAWER_OFFSET: usize = 44;
+
 #[derive(Debug, Default, Clone, PartialEq)]
 pub struct AccountSecondaryIndexes {
     pub keys: Option<AccountSecondaryIndexesIncludeExclude>,
@@ -45,6 +54,9 @@ pub enum AccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    SplTokenDelegate,
+    /// Indexes stake accounts by both their staker and withdrawer authorities
+    StakeAuthority,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,6 +64,8 @@ pub enum IndexKey {
     ProgramId(Pubkey),
     SplTokenMint(Pubkey),
     SplTokenOwner(Pubkey),
+    SplTokenDelegate(Pubkey),
+    StakeAuthority(Pubkey),
 }
 
 // The only cases where an inner key should map to a different outer key is
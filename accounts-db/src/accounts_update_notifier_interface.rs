@@ -1,7 +1,9 @@
 use {
     solana_account::{AccountSharedData, ReadableAccount},
     solana_clock::{Epoch, Slot},
+    solana_hash::Hash,
     solana_pubkey::Pubkey,
+    solana_sha256_hasher::hash,
     solana_transaction::sanitized::SanitizedTransaction,
     std::sync::Arc,
 };
@@ -10,6 +12,13 @@ pub trait AccountsUpdateNotifierInterface: std::fmt::Debug {
     /// Enable account notifications from snapshot
     fn snapshot_notifications_enabled(&self) -> bool;
 
+    /// Enable loading the state of accounts before they are updated, to be notified with
+    /// `notify_account_update_with_pre_state()`. This adds an accounts lookup to every account
+    /// update, so it should only be enabled if a consumer of the notifications asked for it.
+    fn account_pre_state_notifications_enabled(&self) -> bool {
+        false
+    }
+
     /// Notified when an account is updated at runtime, due to transaction activities
     fn notify_account_update(
         &self,
@@ -20,6 +29,20 @@ pub trait AccountsUpdateNotifierInterface: std::fmt::Debug {
         write_version: u64,
     );
 
+    /// Notified instead of `notify_account_update()` if `account_pre_state_notifications_enabled()`
+    /// is true, along with the state of the account before the update
+    fn notify_account_update_with_pre_state(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+        _pre_state: &AccountPreState,
+    ) {
+        self.notify_account_update(slot, account, txn, pubkey, write_version);
+    }
+
     /// Notified when the AccountsDb is initialized at start when restored
     /// from a snapshot.
     fn notify_account_restore_from_snapshot(
@@ -63,3 +86,23 @@ impl ReadableAccount for AccountForGeyser<'_> {
         self.rent_epoch
     }
 }
+
+/// State of an account before it is updated, with only the fields necessary for Geyser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountPreState {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    /// SHA-256 hash of the account data
+    pub data_hash: Hash,
+}
+
+impl AccountPreState {
+    /// Accounts that did not exist have the state of the default account
+    pub fn new(account: &impl ReadableAccount) -> Self {
+        Self {
+            lamports: account.lamports(),
+            owner: *account.owner(),
+            data_hash: hash(account.data()),
+        }
+    }
+}
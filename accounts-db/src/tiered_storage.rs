@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 pub mod byte_block;
+pub mod cold;
 pub mod error;
 pub mod file;
 pub mod footer;
@@ -14,6 +15,7 @@ mod test_utils;
 
 use {
     crate::{accounts_file::StoredAccountsInfo, storable_accounts::StorableAccounts},
+    cold::{ColdStorageWriter, COLD_FORMAT},
     error::TieredStorageError,
     footer::{AccountBlockFormat, AccountMetaFormat},
     hot::{HotStorageWriter, HOT_FORMAT},
@@ -54,6 +56,8 @@ pub struct TieredStorage {
     already_written: AtomicBool,
     /// The path to the file that stores accounts.
     path: PathBuf,
+    /// The format used when writing accounts into this TieredStorage.
+    format: &'static TieredStorageFormat,
 }
 
 impl Drop for TieredStorage {
@@ -73,15 +77,28 @@ impl Drop for TieredStorage {
 
 impl TieredStorage {
     /// Creates a new writable instance of TieredStorage based on the
-    /// specified path and TieredStorageFormat.
+    /// specified path and the hot TieredStorageFormat.
     ///
     /// Note that the actual file will not be created until write_accounts
     /// is called.
     pub fn new_writable(path: impl Into<PathBuf>) -> Self {
+        Self::new_writable_with_format(path, &HOT_FORMAT)
+    }
+
+    /// Creates a new writable instance of TieredStorage based on the
+    /// specified path and TieredStorageFormat.
+    ///
+    /// Note that the actual file will not be created until write_accounts
+    /// is called.
+    pub fn new_writable_with_format(
+        path: impl Into<PathBuf>,
+        format: &'static TieredStorageFormat,
+    ) -> Self {
         Self {
             reader: OnceLock::<TieredStorageReader>::new(),
             already_written: false.into(),
             path: path.into(),
+            format,
         }
     }
 
@@ -89,10 +106,16 @@ impl TieredStorage {
     /// specified path.
     pub fn new_readonly(path: impl Into<PathBuf>) -> TieredStorageResult<Self> {
         let path = path.into();
+        let reader = TieredStorageReader::new_from_path(&path)?;
+        let format = match reader.footer().account_meta_format {
+            AccountMetaFormat::Hot => &HOT_FORMAT,
+            AccountMetaFormat::Cold => &COLD_FORMAT,
+        };
         Ok(Self {
-            reader: TieredStorageReader::new_from_path(&path).map(OnceLock::from)?,
+            reader: OnceLock::from(reader),
             already_written: true.into(),
             path,
+            format,
         })
     }
 
@@ -101,6 +124,11 @@ impl TieredStorage {
         self.path.as_path()
     }
 
+    /// Returns the format used when writing accounts into this TieredStorage.
+    pub fn format(&self) -> &'static TieredStorageFormat {
+        self.format
+    }
+
     /// Writes the specified accounts into this TieredStorage.
     ///
     /// Note that this function can only be called once per a TieredStorage
@@ -117,26 +145,29 @@ impl TieredStorage {
             panic!("cannot write same tiered storage file more than once");
         }
 
-        if format == &HOT_FORMAT {
-            let stored_accounts_info = {
-                let mut writer = HotStorageWriter::new(&self.path)?;
-                let stored_accounts_info = writer.write_accounts(accounts, skip)?;
-                writer.flush()?;
-                stored_accounts_info
-            };
-
-            // panic here if self.reader.get() is not None as self.reader can only be
-            // None since a false-value `was_written` indicates the accounts file has
-            // not been written previously, implying is_read_only() was also false.
-            debug_assert!(!self.is_read_only());
-            self.reader
-                .set(TieredStorageReader::new_from_path(&self.path)?)
-                .unwrap();
-
-            Ok(stored_accounts_info)
+        let stored_accounts_info = if format == &HOT_FORMAT {
+            let mut writer = HotStorageWriter::new(&self.path)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
+        } else if format == &COLD_FORMAT {
+            let mut writer = ColdStorageWriter::new(&self.path)?;
+            let stored_accounts_info = writer.write_accounts(accounts, skip)?;
+            writer.flush()?;
+            stored_accounts_info
         } else {
-            Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()))
-        }
+            return Err(TieredStorageError::UnknownFormat(self.path.to_path_buf()));
+        };
+
+        // panic here if self.reader.get() is not None as self.reader can only be
+        // None since a false-value `was_written` indicates the accounts file has
+        // not been written previously, implying is_read_only() was also false.
+        debug_assert!(!self.is_read_only());
+        self.reader
+            .set(TieredStorageReader::new_from_path(&self.path)?)
+            .unwrap();
+
+        Ok(stored_accounts_info)
     }
 
     /// Returns the underlying reader of the TieredStorage.  None will be
@@ -187,6 +218,7 @@ mod tests {
             mem::ManuallyDrop,
         },
         tempfile::tempdir,
+        test_case::test_case,
         test_utils::{create_test_account, verify_test_account_with_footer},
     };
 
@@ -340,7 +372,6 @@ mod tests {
     }
 
     /// The helper function for all write_accounts tests.
-    /// Currently only supports hot accounts.
     fn do_test_write_accounts(
         path_suffix: &str,
         account_data_sizes: &[u64],
@@ -358,6 +389,14 @@ mod tests {
         let tiered_storage_path = temp_dir.path().join(path_suffix);
         let tiered_storage = TieredStorage::new_writable(tiered_storage_path);
         _ = tiered_storage.write_accounts(&storable_accounts, 0, &format);
+        assert_eq!(
+            tiered_storage
+                .reader()
+                .unwrap()
+                .footer()
+                .account_meta_format,
+            format.account_meta_format
+        );
 
         let reader = tiered_storage.reader().unwrap();
         let num_accounts = storable_accounts.len();
@@ -404,32 +443,72 @@ mod tests {
         assert_eq!(verified_accounts.len(), expected_accounts_map.len());
     }
 
-    #[test]
-    fn test_write_accounts_small_accounts() {
+    #[test_case(HOT_FORMAT)]
+    #[test_case(COLD_FORMAT)]
+    fn test_write_accounts_small_accounts(format: TieredStorageFormat) {
         do_test_write_accounts(
             "test_write_accounts_small_accounts",
             &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10],
-            HOT_FORMAT.clone(),
+            format,
         );
     }
 
-    #[test]
-    fn test_write_accounts_one_max_len() {
+    #[test_case(HOT_FORMAT)]
+    #[test_case(COLD_FORMAT)]
+    fn test_write_accounts_one_max_len(format: TieredStorageFormat) {
         do_test_write_accounts(
             "test_write_accounts_one_max_len",
             &[MAX_PERMITTED_DATA_LENGTH],
-            HOT_FORMAT.clone(),
+            format,
         );
     }
 
-    #[test]
-    fn test_write_accounts_mixed_size() {
+    #[test_case(HOT_FORMAT)]
+    #[test_case(COLD_FORMAT)]
+    fn test_write_accounts_mixed_size(format: TieredStorageFormat) {
         do_test_write_accounts(
             "test_write_accounts_mixed_size",
             &[
                 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1,
             ],
-            HOT_FORMAT.clone(),
+            format,
         );
     }
+
+    #[test_case(&HOT_FORMAT)]
+    #[test_case(&COLD_FORMAT)]
+    fn test_reopen_readonly(format: &'static TieredStorageFormat) {
+        let accounts: Vec<_> = [0, 1, 10, 100, 1000]
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+        let storable_accounts = (Slot::MAX, &accounts[..]);
+
+        let temp_dir = tempdir().unwrap();
+        let tiered_storage_path = temp_dir.path().join("test_reopen_readonly");
+        let tiered_storage = ManuallyDrop::new(TieredStorage::new_writable_with_format(
+            &tiered_storage_path,
+            format,
+        ));
+        tiered_storage
+            .write_accounts(&storable_accounts, 0, tiered_storage.format())
+            .unwrap();
+
+        let readonly_storage = TieredStorage::new_readonly(&tiered_storage_path).unwrap();
+        assert_eq!(readonly_storage.format(), format);
+        assert_eq!(readonly_storage.len(), tiered_storage.len());
+
+        let reader = readonly_storage.reader().unwrap();
+        for i in 0..accounts.len() {
+            let index_offset = index::IndexOffset(i as u32);
+            assert_eq!(
+                reader.get_account_shared_data(index_offset).unwrap(),
+                tiered_storage
+                    .reader()
+                    .unwrap()
+                    .get_account_shared_data(index_offset)
+                    .unwrap(),
+            );
+        }
+    }
 }
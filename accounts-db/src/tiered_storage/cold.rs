@@ -0,0 +1,1129 @@
+//! The account meta and related structs for cold accounts.
+//!
+//! Different from hot accounts, the account data of a cold account is
+//! compressed using the AccountBlockFormat of its accounts file.  This
+//! trades read performance for a smaller disk footprint, which makes the
+//! cold format suitable for rarely touched accounts such as those packed
+//! into ancient storages.
+
+use {
+    crate::{
+        account_info::{AccountInfo, Offset},
+        account_storage::stored_account_info::{StoredAccountInfo, StoredAccountInfoWithoutData},
+        accounts_file::StoredAccountsInfo,
+        tiered_storage::{
+            byte_block::{self, ByteBlockReader, ByteBlockWriter},
+            file::{TieredReadableFile, TieredWritableFile},
+            footer::{AccountBlockFormat, AccountMetaFormat, TieredStorageFooter},
+            hot::RENT_EXEMPT_RENT_EPOCH,
+            index::{AccountIndexWriterEntry, AccountOffset, IndexBlockFormat, IndexOffset},
+            meta::{
+                AccountAddressRange, AccountMetaFlags, AccountMetaOptionalFields, TieredAccountMeta,
+            },
+            mmap_utils::{get_pod, get_slice},
+            owners::{OwnerOffset, OwnersBlockFormat, OwnersTable},
+            StorableAccounts, TieredStorageError, TieredStorageFormat, TieredStorageResult,
+        },
+    },
+    bytemuck_derive::{Pod, Zeroable},
+    memmap2::{Mmap, MmapOptions},
+    modular_bitfield::prelude::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_clock::Epoch,
+    solana_pubkey::Pubkey,
+    std::{borrow::Cow, io::Write, option::Option, path::Path, sync::Arc},
+};
+
+pub const COLD_FORMAT: TieredStorageFormat = TieredStorageFormat {
+    meta_entry_size: std::mem::size_of::<ColdAccountMeta>(),
+    account_meta_format: AccountMetaFormat::Cold,
+    owners_block_format: OwnersBlockFormat::AddressesOnly,
+    index_block_format: IndexBlockFormat::AddressesThenOffsets,
+    account_block_format: AccountBlockFormat::Lz4,
+};
+
+/// A helper function that creates a new default footer for cold
+/// accounts storage.
+fn new_cold_footer() -> TieredStorageFooter {
+    TieredStorageFooter {
+        account_meta_format: COLD_FORMAT.account_meta_format,
+        account_meta_entry_size: COLD_FORMAT.meta_entry_size as u32,
+        account_block_format: COLD_FORMAT.account_block_format,
+        index_block_format: COLD_FORMAT.index_block_format,
+        owners_block_format: COLD_FORMAT.owners_block_format,
+        ..TieredStorageFooter::default()
+    }
+}
+
+/// The maximum allowed value for the owner index of a cold account.
+const MAX_COLD_OWNER_OFFSET: OwnerOffset = OwnerOffset((1 << 29) - 1);
+
+/// The byte alignment for cold accounts.  While the account data of a cold
+/// account is not directly accessed under mmap, its meta and optional fields
+/// are.  Aligning each cold account entry keeps those reads aligned.
+pub(crate) const COLD_ACCOUNT_ALIGNMENT: usize = 8;
+
+/// The alignment for the blocks inside a cold accounts file.
+pub(crate) const COLD_BLOCK_ALIGNMENT: usize = 8;
+
+/// The maximum supported offset for cold accounts storage.
+const MAX_COLD_ACCOUNT_OFFSET: usize = u32::MAX as usize * COLD_ACCOUNT_ALIGNMENT;
+
+// returns the required number of padding
+fn padding_bytes(data_len: usize) -> u8 {
+    ((COLD_ACCOUNT_ALIGNMENT - (data_len % COLD_ACCOUNT_ALIGNMENT)) % COLD_ACCOUNT_ALIGNMENT) as u8
+}
+
+/// The buffer that is used for padding.
+const PADDING_BUFFER: [u8; 8] = [0u8; COLD_ACCOUNT_ALIGNMENT];
+
+#[bitfield(bits = 32)]
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+struct ColdMetaPackedFields {
+    /// A cold account entry consists of the following elements:
+    ///
+    /// * ColdAccountMeta
+    /// * optional fields
+    /// * [u8] account data, encoded with the file's AccountBlockFormat
+    /// * 0-7 bytes padding
+    ///
+    /// Account data that does not become smaller after being encoded is
+    /// persisted as-is.  The following field records whether the account
+    /// data of this entry is encoded.
+    encoded: bool,
+    /// reserved bits.
+    reserved: B2,
+    /// The index to the owner of a cold account inside an AccountsFile.
+    owner_offset: B29,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdMetaPackedFields>() == 4);
+
+/// The offset to access a cold account.
+#[repr(C)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Pod, Zeroable)]
+pub struct ColdAccountOffset(u32);
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountOffset>() == 4);
+
+impl AccountOffset for ColdAccountOffset {}
+
+impl ColdAccountOffset {
+    /// Creates a new AccountOffset instance
+    pub fn new(offset: usize) -> TieredStorageResult<Self> {
+        if offset > MAX_COLD_ACCOUNT_OFFSET {
+            return Err(TieredStorageError::OffsetOutOfBounds(
+                offset,
+                MAX_COLD_ACCOUNT_OFFSET,
+            ));
+        }
+
+        // Cold accounts are aligned based on COLD_ACCOUNT_ALIGNMENT.
+        if !offset.is_multiple_of(COLD_ACCOUNT_ALIGNMENT) {
+            return Err(TieredStorageError::OffsetAlignmentError(
+                offset,
+                COLD_ACCOUNT_ALIGNMENT,
+            ));
+        }
+
+        Ok(ColdAccountOffset((offset / COLD_ACCOUNT_ALIGNMENT) as u32))
+    }
+
+    /// Returns the offset to the account.
+    fn offset(&self) -> usize {
+        self.0 as usize * COLD_ACCOUNT_ALIGNMENT
+    }
+}
+
+/// The storage and in-memory representation of the metadata entry for a
+/// cold account.
+///
+/// The account block of a cold account starts with its optional fields
+/// followed by its account data.  As only the account data part might be
+/// encoded, the optional fields can be read without decoding the account
+/// data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Pod, Zeroable)]
+#[repr(C)]
+pub struct ColdAccountMeta {
+    /// The balance of this account.
+    lamports: u64,
+    /// Stores important fields in a packed struct.
+    packed_fields: ColdMetaPackedFields,
+    /// Stores boolean flags and existence of each optional field.
+    flags: AccountMetaFlags,
+    /// The size of the account data before encoding.
+    account_data_size: u32,
+    /// The size of the account data as persisted in the accounts file.
+    stored_data_size: u32,
+}
+
+// Ensure there are no implicit padding bytes
+const _: () = assert!(std::mem::size_of::<ColdAccountMeta>() == 8 + 4 + 4 + 4 + 4);
+
+impl ColdAccountMeta {
+    /// A builder function that initializes the size of the account data
+    /// as persisted in the accounts file and whether it is encoded.
+    fn with_stored_data(mut self, stored_data_size: u32, encoded: bool) -> Self {
+        self.stored_data_size = stored_data_size;
+        self.packed_fields.set_encoded(encoded);
+        self
+    }
+
+    /// Returns the size of the account data as persisted in the accounts
+    /// file.
+    fn stored_data_size(&self) -> usize {
+        self.stored_data_size as usize
+    }
+
+    /// Returns true if the persisted account data is encoded.
+    fn is_encoded(&self) -> bool {
+        self.packed_fields.encoded()
+    }
+
+    /// Returns the size of the optional fields of this account.
+    fn optional_fields_size(&self) -> usize {
+        AccountMetaOptionalFields::size_from_flags(&self.flags)
+    }
+}
+
+impl TieredAccountMeta for ColdAccountMeta {
+    /// Construct a ColdAccountMeta instance.
+    fn new() -> Self {
+        ColdAccountMeta {
+            lamports: 0,
+            packed_fields: ColdMetaPackedFields::default(),
+            flags: AccountMetaFlags::new(),
+            account_data_size: 0,
+            stored_data_size: 0,
+        }
+    }
+
+    /// A builder function that initializes lamports.
+    fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    /// A builder function that initializes the number of padding bytes
+    /// for the account data associated with the current meta.
+    fn with_account_data_padding(self, _padding: u8) -> Self {
+        // Cold meta does not store its padding as it can be derived from
+        // the size of the stored account data.
+        self
+    }
+
+    /// A builder function that initializes the owner's index.
+    fn with_owner_offset(mut self, owner_offset: OwnerOffset) -> Self {
+        if owner_offset > MAX_COLD_OWNER_OFFSET {
+            panic!("owner_offset exceeds MAX_COLD_OWNER_OFFSET");
+        }
+        self.packed_fields.set_owner_offset(owner_offset.0);
+        self
+    }
+
+    /// A builder function that initializes the account data size.
+    fn with_account_data_size(mut self, account_data_size: u64) -> Self {
+        self.account_data_size = account_data_size
+            .try_into()
+            .expect("account_data_size exceeds u32::MAX");
+        self
+    }
+
+    /// A builder function that initializes the AccountMetaFlags of the current
+    /// meta.
+    fn with_flags(mut self, flags: &AccountMetaFlags) -> Self {
+        self.flags = *flags;
+        self
+    }
+
+    /// Returns the balance of the lamports associated with the account.
+    fn lamports(&self) -> u64 {
+        self.lamports
+    }
+
+    /// Always returns 0 as the padding of a cold account is not part of
+    /// its account block.
+    fn account_data_padding(&self) -> u8 {
+        0
+    }
+
+    /// Returns the index to the accounts' owner in the current AccountsFile.
+    fn owner_offset(&self) -> OwnerOffset {
+        OwnerOffset(self.packed_fields.owner_offset())
+    }
+
+    /// Returns the AccountMetaFlags of the current meta.
+    fn flags(&self) -> &AccountMetaFlags {
+        &self.flags
+    }
+
+    /// Always returns false as each cold account has its own encoded
+    /// account data.
+    fn supports_shared_account_block() -> bool {
+        false
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  None will be returned if this account
+    /// does not persist this optional field.
+    fn rent_epoch(&self, account_block: &[u8]) -> Option<Epoch> {
+        self.flags()
+            .has_rent_epoch()
+            .then(|| {
+                let offset = self.optional_fields_offset(account_block)
+                    + AccountMetaOptionalFields::rent_epoch_offset(self.flags());
+                byte_block::read_pod::<Epoch>(account_block, offset).copied()
+            })
+            .flatten()
+    }
+
+    /// Returns the epoch that this account will next owe rent by parsing
+    /// the specified account block.  RENT_EXEMPT_RENT_EPOCH will be returned
+    /// if the account is rent-exempt.
+    ///
+    /// For a zero-lamport account, Epoch::default() will be returned to
+    /// default states of an AccountSharedData.
+    fn final_rent_epoch(&self, account_block: &[u8]) -> Epoch {
+        self.rent_epoch(account_block)
+            .unwrap_or(if self.lamports() != 0 {
+                RENT_EXEMPT_RENT_EPOCH
+            } else {
+                Epoch::default()
+            })
+    }
+
+    /// Always returns 0 as the optional fields are placed at the beginning
+    /// of a cold account block.
+    fn optional_fields_offset(&self, _account_block: &[u8]) -> usize {
+        0
+    }
+
+    /// Returns the length of the data associated to this account.
+    fn account_data_size(&self, _account_block: &[u8]) -> usize {
+        self.account_data_size as usize
+    }
+
+    /// Returns the data associated to this account based on the specified
+    /// decoded account block.
+    fn account_data<'a>(&self, account_block: &'a [u8]) -> &'a [u8] {
+        let offset = self.optional_fields_size();
+        &account_block[offset..offset + self.account_data_size(account_block)]
+    }
+}
+
+/// The reader to a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageReader {
+    mmap: Mmap,
+    footer: TieredStorageFooter,
+}
+
+impl ColdStorageReader {
+    pub fn new(file: TieredReadableFile) -> TieredStorageResult<Self> {
+        let mmap = unsafe { MmapOptions::new().map(&file.0)? };
+        // Here we are copying the footer, as accessing any data in a
+        // TieredStorage instance requires accessing its Footer.
+        let footer = *TieredStorageFooter::new_from_mmap(&mmap)?;
+
+        Ok(Self { mmap, footer })
+    }
+
+    /// Returns the size of the underlying storage.
+    pub fn len(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Returns whether the underlying storage is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> u64 {
+        self.len() as u64
+    }
+
+    /// Returns the footer of the underlying tiered-storage accounts file.
+    pub fn footer(&self) -> &TieredStorageFooter {
+        &self.footer
+    }
+
+    /// Returns the number of files inside the underlying tiered-storage
+    /// accounts file.
+    pub fn num_accounts(&self) -> usize {
+        self.footer.account_entry_count as usize
+    }
+
+    /// Returns the account meta located at the specified offset.
+    fn get_account_meta_from_offset(
+        &self,
+        account_offset: ColdAccountOffset,
+    ) -> TieredStorageResult<&ColdAccountMeta> {
+        let offset = account_offset.offset();
+
+        assert!(
+            offset.saturating_add(std::mem::size_of::<ColdAccountMeta>())
+                <= self.footer.index_block_offset as usize,
+            "reading ColdAccountOffset ({}) would exceed accounts blocks offset boundary ({}).",
+            offset,
+            self.footer.index_block_offset,
+        );
+        let (meta, _) = get_pod::<ColdAccountMeta>(&self.mmap, offset)?;
+        Ok(meta)
+    }
+
+    /// Returns the offset to the account given the specified index.
+    pub(super) fn get_account_offset(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<ColdAccountOffset> {
+        self.footer
+            .index_block_format
+            .get_account_offset::<ColdAccountOffset>(&self.mmap, &self.footer, index_offset)
+    }
+
+    /// Returns the address of the account associated with the specified index.
+    fn get_account_address(&self, index: IndexOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .index_block_format
+            .get_account_address(&self.mmap, &self.footer, index)
+    }
+
+    /// Returns the address of the account owner given the specified
+    /// owner_offset.
+    fn get_owner_address(&self, owner_offset: OwnerOffset) -> TieredStorageResult<&Pubkey> {
+        self.footer
+            .owners_block_format
+            .get_owner_address(&self.mmap, &self.footer, owner_offset)
+    }
+
+    /// Returns the optional fields of the account at the specified offset.
+    ///
+    /// As the optional fields are never encoded, the returned slice is
+    /// also a valid prefix of the account block of the account.
+    fn get_optional_fields(
+        &self,
+        account_offset: ColdAccountOffset,
+        meta: &ColdAccountMeta,
+    ) -> TieredStorageResult<&[u8]> {
+        let (optional_fields, _) = get_slice(
+            &self.mmap,
+            account_offset.offset() + std::mem::size_of::<ColdAccountMeta>(),
+            meta.optional_fields_size(),
+        )?;
+
+        Ok(optional_fields)
+    }
+
+    /// Returns the decoded account data of the account at the specified
+    /// offset.
+    ///
+    /// The returned data is borrowed from the underlying storage when the
+    /// account data is persisted without encoding.
+    fn get_account_data(
+        &self,
+        account_offset: ColdAccountOffset,
+        meta: &ColdAccountMeta,
+    ) -> TieredStorageResult<Cow<'_, [u8]>> {
+        let data_offset = account_offset.offset()
+            + std::mem::size_of::<ColdAccountMeta>()
+            + meta.optional_fields_size();
+        let stored_data_end = data_offset.saturating_add(meta.stored_data_size());
+        assert!(
+            stored_data_end <= self.footer.index_block_offset as usize,
+            "reading ColdAccountOffset ({}) data would exceed accounts blocks offset boundary \
+             ({}).",
+            account_offset.offset(),
+            self.footer.index_block_offset,
+        );
+        let (stored_data, _) = get_slice(&self.mmap, data_offset, meta.stored_data_size())?;
+
+        if !meta.is_encoded() {
+            return Ok(Cow::Borrowed(stored_data));
+        }
+
+        let data = ByteBlockReader::decode(self.footer.account_block_format, stored_data)?;
+        if data.len() != meta.account_data_size(&data) {
+            return Err(TieredStorageError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "decoded account data size {} does not match the expected size {}",
+                    data.len(),
+                    meta.account_data_size(&data),
+                ),
+            )));
+        }
+        Ok(Cow::Owned(data))
+    }
+
+    /// Calls `callback` with the stored account at `offset`.
+    ///
+    /// Returns `None` if there is no account at `offset`, otherwise returns the result of
+    /// `callback` in `Some`.
+    ///
+    /// This fn does *not* load the account's data, just the data length.  If the data is needed,
+    /// use `get_stored_account_callback()` instead.  However, prefer this fn when possible, as
+    /// it does not need to decode the account data.
+    pub fn get_stored_account_without_data_callback<Ret>(
+        &self,
+        index_offset: IndexOffset,
+        mut callback: impl for<'local> FnMut(StoredAccountInfoWithoutData<'local>) -> Ret,
+    ) -> TieredStorageResult<Option<Ret>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let meta = self.get_account_meta_from_offset(account_offset)?;
+        let optional_fields = self.get_optional_fields(account_offset, meta)?;
+
+        let stored_account = StoredAccountInfoWithoutData {
+            pubkey: self.get_account_address(index_offset)?,
+            lamports: meta.lamports(),
+            owner: self.get_owner_address(meta.owner_offset())?,
+            data_len: meta.account_data_size(optional_fields),
+            executable: meta.flags().executable(),
+            rent_epoch: meta.final_rent_epoch(optional_fields),
+        };
+
+        Ok(Some(callback(stored_account)))
+    }
+
+    /// Calls `callback` with the stored account at `offset`.
+    ///
+    /// Returns `None` if there is no account at `offset`, otherwise returns the result of
+    /// `callback` in `Some`.
+    ///
+    /// This fn *does* load and decode the account's data.  If the data is not needed,
+    /// use `get_stored_account_without_data_callback()` instead.
+    pub fn get_stored_account_callback<Ret>(
+        &self,
+        index_offset: IndexOffset,
+        mut callback: impl for<'local> FnMut(StoredAccountInfo<'local>) -> Ret,
+    ) -> TieredStorageResult<Option<Ret>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+        let meta = self.get_account_meta_from_offset(account_offset)?;
+        let optional_fields = self.get_optional_fields(account_offset, meta)?;
+        let data = self.get_account_data(account_offset, meta)?;
+
+        let stored_account = StoredAccountInfo {
+            pubkey: self.get_account_address(index_offset)?,
+            lamports: meta.lamports(),
+            owner: self.get_owner_address(meta.owner_offset())?,
+            data: &data,
+            executable: meta.flags().executable(),
+            rent_epoch: meta.final_rent_epoch(optional_fields),
+        };
+
+        Ok(Some(callback(stored_account)))
+    }
+
+    /// Returns the account located at the specified index offset.
+    pub fn get_account_shared_data(
+        &self,
+        index_offset: IndexOffset,
+    ) -> TieredStorageResult<Option<AccountSharedData>> {
+        if index_offset.0 >= self.footer.account_entry_count {
+            return Ok(None);
+        }
+
+        let account_offset = self.get_account_offset(index_offset)?;
+
+        let meta = self.get_account_meta_from_offset(account_offset)?;
+        let optional_fields = self.get_optional_fields(account_offset, meta)?;
+
+        let lamports = meta.lamports();
+        let data = Arc::new(self.get_account_data(account_offset, meta)?.into_owned());
+        let owner = *self.get_owner_address(meta.owner_offset())?;
+        let executable = meta.flags().executable();
+        let rent_epoch = meta.final_rent_epoch(optional_fields);
+        Ok(Some(AccountSharedData::create_from_existing_shared_data(
+            lamports, data, owner, executable, rent_epoch,
+        )))
+    }
+
+    /// iterate over all pubkeys
+    pub fn scan_pubkeys(&self, mut callback: impl FnMut(&Pubkey)) -> TieredStorageResult<()> {
+        for i in 0..self.footer.account_entry_count {
+            let address = self.get_account_address(IndexOffset(i))?;
+            callback(address);
+        }
+        Ok(())
+    }
+
+    /// Calculate the amount of storage required for an account with the passed
+    /// in data_len
+    pub(crate) fn calculate_stored_size(data_len: usize) -> usize {
+        stored_size(data_len)
+    }
+
+    /// for each offset in `sorted_offsets`, return the length of data stored in the account
+    pub(crate) fn get_account_data_lens(
+        &self,
+        sorted_offsets: &[usize],
+    ) -> TieredStorageResult<Vec<usize>> {
+        let mut result = Vec::with_capacity(sorted_offsets.len());
+        for &offset in sorted_offsets {
+            let index_offset = IndexOffset(AccountInfo::get_reduced_offset(offset));
+            let account_offset = self.get_account_offset(index_offset)?;
+            let meta = self.get_account_meta_from_offset(account_offset)?;
+            let optional_fields = self.get_optional_fields(account_offset, meta)?;
+            result.push(meta.account_data_size(optional_fields));
+        }
+        Ok(result)
+    }
+
+    /// Iterate over all accounts and call `callback` with each account.
+    ///
+    /// `callback` parameters:
+    /// * Offset: the offset within the file of this account
+    /// * StoredAccountInfoWithoutData: the account itself, without account data
+    ///
+    /// Note that account data is not read/passed to the callback.
+    pub fn scan_accounts_without_data(
+        &self,
+        mut callback: impl for<'local> FnMut(Offset, StoredAccountInfoWithoutData<'local>),
+    ) -> TieredStorageResult<()> {
+        for i in 0..self.footer.account_entry_count {
+            self.get_stored_account_without_data_callback(IndexOffset(i), |account| {
+                callback(AccountInfo::reduced_offset_to_offset(i), account)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Iterate over all accounts and call `callback` with each account.
+    ///
+    /// `callback` parameters:
+    /// * Offset: the offset within the file of this account
+    /// * StoredAccountInfo: the account itself, with account data
+    ///
+    /// Prefer scan_accounts_without_data() when account data is not needed,
+    /// as it does not need to decode the account data.
+    pub fn scan_accounts(
+        &self,
+        mut callback: impl for<'local> FnMut(Offset, StoredAccountInfo<'local>),
+    ) -> TieredStorageResult<()> {
+        for i in 0..self.footer.account_entry_count {
+            self.get_stored_account_callback(IndexOffset(i), |account| {
+                callback(AccountInfo::reduced_offset_to_offset(i), account)
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Returns a slice suitable for use when archiving cold storages
+    pub fn data_for_archive(&self) -> &[u8] {
+        self.mmap.as_ref()
+    }
+}
+
+/// return an approximation of the cost to store an account.
+/// Some fields like owner are shared across multiple accounts.
+///
+/// Note that the account data size before encoding is used here, as the
+/// actual stored size is unknown until the account data is encoded.
+fn stored_size(data_len: usize) -> usize {
+    data_len + std::mem::size_of::<Pubkey>()
+}
+
+fn write_optional_fields(
+    file: &mut TieredWritableFile,
+    opt_fields: &AccountMetaOptionalFields,
+) -> TieredStorageResult<usize> {
+    let mut size = 0;
+    if let Some(rent_epoch) = opt_fields.rent_epoch {
+        size += file.write_pod(&rent_epoch)?;
+    }
+
+    debug_assert_eq!(size, opt_fields.size());
+
+    Ok(size)
+}
+
+/// Encodes the specified account data using the specified format.
+///
+/// Returns None if the encoded account data would not be smaller than
+/// the account data itself, in which case the account data should be
+/// persisted as-is.
+fn encode_account_data(
+    format: AccountBlockFormat,
+    account_data: &[u8],
+) -> TieredStorageResult<Option<Vec<u8>>> {
+    if format == AccountBlockFormat::AlignedRaw || account_data.is_empty() {
+        return Ok(None);
+    }
+
+    let mut writer = ByteBlockWriter::new(format);
+    writer.write(account_data)?;
+    let encoded_data = writer.finish()?;
+
+    Ok((encoded_data.len() < account_data.len()).then_some(encoded_data))
+}
+
+/// The writer that creates a cold accounts file.
+#[derive(Debug)]
+pub struct ColdStorageWriter {
+    storage: TieredWritableFile,
+}
+
+impl ColdStorageWriter {
+    /// Create a new ColdStorageWriter with the specified path.
+    pub fn new(file_path: impl AsRef<Path>) -> TieredStorageResult<Self> {
+        Ok(Self {
+            storage: TieredWritableFile::new(file_path)?,
+        })
+    }
+
+    /// Persists an account with the specified information and returns
+    /// the stored size of the account.
+    fn write_account(
+        &mut self,
+        account_block_format: AccountBlockFormat,
+        lamports: u64,
+        owner_offset: OwnerOffset,
+        account_data: &[u8],
+        executable: bool,
+        rent_epoch: Option<Epoch>,
+    ) -> TieredStorageResult<usize> {
+        let optional_fields = AccountMetaOptionalFields { rent_epoch };
+
+        let mut flags = AccountMetaFlags::new_from(&optional_fields);
+        flags.set_executable(executable);
+
+        let encoded_data = encode_account_data(account_block_format, account_data)?;
+        let stored_data = encoded_data.as_deref().unwrap_or(account_data);
+        let padding_len = padding_bytes(stored_data.len());
+        let meta = ColdAccountMeta::new()
+            .with_lamports(lamports)
+            .with_owner_offset(owner_offset)
+            .with_account_data_size(account_data.len() as u64)
+            .with_stored_data(stored_data.len() as u32, encoded_data.is_some())
+            .with_flags(&flags);
+
+        let mut stored_size = 0;
+
+        stored_size += self.storage.write_pod(&meta)?;
+        stored_size += write_optional_fields(&mut self.storage, &optional_fields)?;
+        stored_size += self.storage.write_bytes(stored_data)?;
+        stored_size += self
+            .storage
+            .write_bytes(&PADDING_BUFFER[0..(padding_len as usize)])?;
+
+        Ok(stored_size)
+    }
+
+    /// Persists `accounts` into the underlying cold accounts file associated
+    /// with this ColdStorageWriter.  The first `skip` number of accounts are
+    /// *not* persisted.
+    pub fn write_accounts<'a>(
+        &mut self,
+        accounts: &impl StorableAccounts<'a>,
+        skip: usize,
+    ) -> TieredStorageResult<StoredAccountsInfo> {
+        let mut footer = new_cold_footer();
+        let mut index = vec![];
+        let mut owners_table = OwnersTable::default();
+        let mut cursor = 0;
+        let mut address_range = AccountAddressRange::default();
+
+        let len = accounts.len();
+        let total_input_accounts = len.saturating_sub(skip);
+        let mut offsets = Vec::with_capacity(total_input_accounts);
+
+        // writing accounts blocks
+        for i in skip..len {
+            accounts.account_default_if_zero_lamport::<TieredStorageResult<()>>(i, |account| {
+                let index_entry = AccountIndexWriterEntry {
+                    address: *account.pubkey(),
+                    offset: ColdAccountOffset::new(cursor)?,
+                };
+                address_range.update(account.pubkey());
+
+                // Obtain necessary fields from the account, or default fields
+                // for a zero-lamport account in the None case.
+                let (lamports, owner, data, executable, rent_epoch) = {
+                    (
+                        account.lamports(),
+                        account.owner(),
+                        account.data(),
+                        account.executable(),
+                        // only persist rent_epoch for those rent-paying accounts
+                        (account.rent_epoch() != RENT_EXEMPT_RENT_EPOCH)
+                            .then_some(account.rent_epoch()),
+                    )
+                };
+                let owner_offset = owners_table.insert(owner);
+                cursor += self.write_account(
+                    footer.account_block_format,
+                    lamports,
+                    owner_offset,
+                    data,
+                    executable,
+                    rent_epoch,
+                )?;
+
+                offsets.push(index.len());
+                index.push(index_entry);
+                Ok(())
+            })?;
+        }
+        footer.account_entry_count = total_input_accounts as u32;
+
+        // writing index block
+        // expect the offset of each block aligned.
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.index_block_offset = cursor as u64;
+        cursor += footer
+            .index_block_format
+            .write_index_block(&mut self.storage, &index)?;
+        if cursor % COLD_BLOCK_ALIGNMENT != 0 {
+            // In case it is not yet aligned, it is due to the fact that
+            // the index block has an odd number of entries.  In such case,
+            // we expect the amount off is equal to 4.
+            assert_eq!(cursor % COLD_BLOCK_ALIGNMENT, 4);
+            cursor += self.storage.write_pod(&0u32)?;
+        }
+
+        // writing owners block
+        assert!(cursor % COLD_BLOCK_ALIGNMENT == 0);
+        footer.owners_block_offset = cursor as u64;
+        footer.owner_count = owners_table.len() as u32;
+        cursor += footer
+            .owners_block_format
+            .write_owners_block(&mut self.storage, &owners_table)?;
+
+        // writing footer
+        footer.min_account_address = address_range.min;
+        footer.max_account_address = address_range.max;
+        cursor += footer.write_footer_block(&mut self.storage)?;
+
+        Ok(StoredAccountsInfo {
+            offsets,
+            size: cursor,
+        })
+    }
+
+    /// Flushes any buffered data to the file
+    pub fn flush(&mut self) -> TieredStorageResult<()> {
+        self.storage
+            .0
+            .flush()
+            .map_err(TieredStorageError::FlushColdWriter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::tiered_storage::{
+            file::TieredStorageMagicNumber,
+            hot::{HotStorageReader, HotStorageWriter},
+            test_utils::{create_test_account, verify_test_account},
+        },
+        assert_matches::assert_matches,
+        memoffset::offset_of,
+        solana_clock::Slot,
+        tempfile::TempDir,
+    };
+
+    #[test]
+    fn test_cold_account_meta_layout() {
+        assert_eq!(offset_of!(ColdAccountMeta, lamports), 0x00);
+        assert_eq!(offset_of!(ColdAccountMeta, packed_fields), 0x08);
+        assert_eq!(offset_of!(ColdAccountMeta, flags), 0x0C);
+        assert_eq!(offset_of!(ColdAccountMeta, account_data_size), 0x10);
+        assert_eq!(offset_of!(ColdAccountMeta, stored_data_size), 0x14);
+        assert_eq!(std::mem::size_of::<ColdAccountMeta>(), 24);
+    }
+
+    #[test]
+    fn test_packed_fields_max_values() {
+        let mut packed_fields = ColdMetaPackedFields::default();
+        packed_fields.set_encoded(true);
+        packed_fields.set_owner_offset(MAX_COLD_OWNER_OFFSET.0);
+        assert!(packed_fields.encoded());
+        assert_eq!(packed_fields.owner_offset(), MAX_COLD_OWNER_OFFSET.0);
+    }
+
+    #[test]
+    fn test_max_cold_account_offset() {
+        assert_matches!(ColdAccountOffset::new(0), Ok(_));
+        assert_matches!(ColdAccountOffset::new(MAX_COLD_ACCOUNT_OFFSET), Ok(_));
+        assert_matches!(
+            ColdAccountOffset::new(MAX_COLD_ACCOUNT_OFFSET + COLD_ACCOUNT_ALIGNMENT),
+            Err(TieredStorageError::OffsetOutOfBounds(_, _))
+        );
+        assert_matches!(
+            ColdAccountOffset::new(COLD_ACCOUNT_ALIGNMENT - 1),
+            Err(TieredStorageError::OffsetAlignmentError(_, _))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "owner_offset exceeds MAX_COLD_OWNER_OFFSET")]
+    fn test_cold_meta_owner_offset_exceeds_limit() {
+        ColdAccountMeta::new().with_owner_offset(OwnerOffset(MAX_COLD_OWNER_OFFSET.0 + 1));
+    }
+
+    #[test]
+    fn test_cold_account_meta() {
+        const TEST_LAMPORTS: u64 = 2314232137;
+        const TEST_OWNER_OFFSET: OwnerOffset = OwnerOffset(0x1fef_1234);
+        const TEST_DATA_SIZE: u64 = 4096;
+        const TEST_STORED_DATA_SIZE: u32 = 123;
+        const TEST_RENT_EPOCH: Epoch = 7;
+
+        let optional_fields = AccountMetaOptionalFields {
+            rent_epoch: Some(TEST_RENT_EPOCH),
+        };
+
+        let flags = AccountMetaFlags::new_from(&optional_fields);
+        let meta = ColdAccountMeta::new()
+            .with_lamports(TEST_LAMPORTS)
+            .with_owner_offset(TEST_OWNER_OFFSET)
+            .with_account_data_size(TEST_DATA_SIZE)
+            .with_stored_data(TEST_STORED_DATA_SIZE, true)
+            .with_flags(&flags);
+
+        assert_eq!(meta.lamports(), TEST_LAMPORTS);
+        assert_eq!(meta.account_data_padding(), 0);
+        assert_eq!(meta.owner_offset(), TEST_OWNER_OFFSET);
+        assert_eq!(meta.account_data_size(&[]), TEST_DATA_SIZE as usize);
+        assert_eq!(meta.stored_data_size(), TEST_STORED_DATA_SIZE as usize);
+        assert!(meta.is_encoded());
+        assert_eq!(*meta.flags(), flags);
+        assert_eq!(
+            meta.rent_epoch(bytemuck::bytes_of(&TEST_RENT_EPOCH)),
+            Some(TEST_RENT_EPOCH)
+        );
+    }
+
+    #[test]
+    fn test_encode_account_data() {
+        // compressible data is encoded
+        let data = vec![7u8; 4096];
+        let encoded = encode_account_data(AccountBlockFormat::Lz4, &data)
+            .unwrap()
+            .unwrap();
+        assert!(encoded.len() < data.len());
+        assert_eq!(
+            ByteBlockReader::decode(AccountBlockFormat::Lz4, &encoded).unwrap(),
+            data
+        );
+
+        // empty and raw data are never encoded
+        assert_matches!(encode_account_data(AccountBlockFormat::Lz4, &[]), Ok(None));
+        assert_matches!(
+            encode_account_data(AccountBlockFormat::AlignedRaw, &data),
+            Ok(None)
+        );
+
+        // data that does not benefit from encoding is stored as-is
+        assert_matches!(encode_account_data(AccountBlockFormat::Lz4, &[1]), Ok(None));
+    }
+
+    #[test]
+    fn test_cold_storage_writer_twice_on_same_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir
+            .path()
+            .join("test_cold_storage_writer_twice_on_same_path");
+
+        // Expect the first returns Ok
+        assert_matches!(ColdStorageWriter::new(&path), Ok(_));
+        // Expect the second call on the same path returns Err, as the
+        // ColdStorageWriter only writes once.
+        assert_matches!(ColdStorageWriter::new(&path), Err(_));
+    }
+
+    #[test]
+    fn test_write_account_and_index_blocks() {
+        let account_data_sizes = &[
+            1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 1000, 2000, 3000, 4000, 9, 8, 7, 6, 5, 4, 3, 2, 1, 0,
+        ];
+
+        let accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+
+        // Slot information is not used here
+        let storable_accounts = (Slot::MAX, &accounts[..]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("test_write_account_and_index_blocks");
+        let stored_accounts_info = {
+            let mut writer = ColdStorageWriter::new(&path).unwrap();
+            let stored_accounts_info = writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+            stored_accounts_info
+        };
+
+        let file = TieredReadableFile::new(&path).unwrap();
+        let cold_storage = ColdStorageReader::new(file).unwrap();
+        assert_eq!(
+            cold_storage.footer().account_meta_format,
+            AccountMetaFormat::Cold
+        );
+        assert_eq!(
+            cold_storage.footer().account_block_format,
+            AccountBlockFormat::Lz4
+        );
+
+        let num_accounts = account_data_sizes.len();
+        for i in 0..num_accounts {
+            cold_storage
+                .get_stored_account_callback(IndexOffset(i as u32), |stored_account| {
+                    storable_accounts.account_default_if_zero_lamport(i, |account| {
+                        verify_test_account(
+                            &stored_account,
+                            &account.take_account(),
+                            account.pubkey(),
+                        );
+                    });
+                })
+                .unwrap()
+                .unwrap();
+        }
+        // Make sure it returns None on NUM_ACCOUNTS to allow termination on
+        // while loop in actual accounts-db read case.
+        assert_matches!(
+            cold_storage.get_stored_account_callback(IndexOffset(num_accounts as u32), |_| {
+                panic!("unexpected");
+            }),
+            Ok(None)
+        );
+
+        for offset in stored_accounts_info.offsets {
+            cold_storage
+                .get_stored_account_callback(IndexOffset(offset as u32), |stored_account| {
+                    storable_accounts.account_default_if_zero_lamport(offset, |account| {
+                        verify_test_account(
+                            &stored_account,
+                            &account.take_account(),
+                            account.pubkey(),
+                        );
+                    });
+                })
+                .unwrap()
+                .unwrap();
+        }
+
+        // verify everything
+        let mut i = 0;
+        cold_storage
+            .scan_accounts(|_offset, stored_account| {
+                storable_accounts.account_default_if_zero_lamport(i, |account| {
+                    verify_test_account(&stored_account, &account.take_account(), account.pubkey());
+                });
+                i += 1;
+            })
+            .unwrap();
+        assert_eq!(i, num_accounts);
+
+        let footer = cold_storage.footer();
+
+        let expected_size = footer.owners_block_offset as usize
+            + std::mem::size_of::<Pubkey>() * footer.owner_count as usize
+            + std::mem::size_of::<TieredStorageFooter>()
+            + std::mem::size_of::<TieredStorageMagicNumber>();
+
+        assert!(!cold_storage.is_empty());
+        assert_eq!(expected_size, cold_storage.len());
+    }
+
+    #[test]
+    fn test_cold_storage_matches_hot_storage() {
+        let account_data_sizes = &[0, 1, 7, 8, 9, 100, 1000, 4000, 10_000, 3, 2, 1];
+
+        let accounts: Vec<_> = account_data_sizes
+            .iter()
+            .map(|size| create_test_account(*size))
+            .collect();
+        let storable_accounts = (Slot::MAX, &accounts[..]);
+
+        let temp_dir = TempDir::new().unwrap();
+        let hot_path = temp_dir.path().join("hot");
+        let cold_path = temp_dir.path().join("cold");
+        {
+            let mut writer = HotStorageWriter::new(&hot_path).unwrap();
+            writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+        }
+        {
+            let mut writer = ColdStorageWriter::new(&cold_path).unwrap();
+            writer.write_accounts(&storable_accounts, 0).unwrap();
+            writer.flush().unwrap();
+        }
+
+        let hot_storage =
+            HotStorageReader::new(TieredReadableFile::new(&hot_path).unwrap()).unwrap();
+        let cold_storage =
+            ColdStorageReader::new(TieredReadableFile::new(&cold_path).unwrap()).unwrap();
+
+        assert_eq!(hot_storage.num_accounts(), cold_storage.num_accounts());
+        // The test accounts have highly compressible data, so cold storage
+        // is expected to be smaller.
+        assert!(cold_storage.len() < hot_storage.len());
+
+        for i in 0..account_data_sizes.len() {
+            let index_offset = IndexOffset(i as u32);
+            assert_eq!(
+                hot_storage.get_account_shared_data(index_offset).unwrap(),
+                cold_storage.get_account_shared_data(index_offset).unwrap(),
+            );
+
+            let hot_account = hot_storage
+                .get_stored_account_without_data_callback(index_offset, |account| {
+                    (
+                        *account.pubkey,
+                        account.lamports,
+                        *account.owner,
+                        account.data_len,
+                        account.executable,
+                        account.rent_epoch,
+                    )
+                })
+                .unwrap();
+            let cold_account = cold_storage
+                .get_stored_account_without_data_callback(index_offset, |account| {
+                    (
+                        *account.pubkey,
+                        account.lamports,
+                        *account.owner,
+                        account.data_len,
+                        account.executable,
+                        account.rent_epoch,
+                    )
+                })
+                .unwrap();
+            assert_eq!(hot_account, cold_account);
+        }
+
+        let offsets: Vec<_> = (0..account_data_sizes.len())
+            .map(|i| AccountInfo::reduced_offset_to_offset(i as u32))
+            .collect();
+        assert_eq!(
+            hot_storage.get_account_data_lens(&offsets).unwrap(),
+            cold_storage.get_account_data_lens(&offsets).unwrap(),
+        );
+
+        let mut hot_pubkeys = vec![];
+        hot_storage
+            .scan_pubkeys(|pubkey| hot_pubkeys.push(*pubkey))
+            .unwrap();
+        let mut cold_pubkeys = vec![];
+        cold_storage
+            .scan_pubkeys(|pubkey| cold_pubkeys.push(*pubkey))
+            .unwrap();
+        assert_eq!(hot_pubkeys, cold_pubkeys);
+    }
+}
@@ -3,6 +3,7 @@ use {
         account_info::Offset,
         account_storage::stored_account_info::{StoredAccountInfo, StoredAccountInfoWithoutData},
         tiered_storage::{
+            cold::ColdStorageReader,
             file::TieredReadableFile,
             footer::{AccountMetaFormat, TieredStorageFooter},
             hot::HotStorageReader,
@@ -19,6 +20,7 @@ use {
 #[derive(Debug)]
 pub enum TieredStorageReader {
     Hot(HotStorageReader),
+    Cold(ColdStorageReader),
 }
 
 impl TieredStorageReader {
@@ -28,6 +30,7 @@ impl TieredStorageReader {
         let footer = TieredStorageFooter::new_from_footer_block(&file)?;
         match footer.account_meta_format {
             AccountMetaFormat::Hot => Ok(Self::Hot(HotStorageReader::new(file)?)),
+            AccountMetaFormat::Cold => Ok(Self::Cold(ColdStorageReader::new(file)?)),
         }
     }
 
@@ -35,6 +38,7 @@ impl TieredStorageReader {
     pub fn len(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.len(),
+            Self::Cold(cold) => cold.len(),
         }
     }
 
@@ -42,12 +46,14 @@ impl TieredStorageReader {
     pub fn is_empty(&self) -> bool {
         match self {
             Self::Hot(hot) => hot.is_empty(),
+            Self::Cold(cold) => cold.is_empty(),
         }
     }
 
     pub fn capacity(&self) -> u64 {
         match self {
             Self::Hot(hot) => hot.capacity(),
+            Self::Cold(cold) => cold.capacity(),
         }
     }
 
@@ -55,6 +61,7 @@ impl TieredStorageReader {
     pub fn footer(&self) -> &TieredStorageFooter {
         match self {
             Self::Hot(hot) => hot.footer(),
+            Self::Cold(cold) => cold.footer(),
         }
     }
 
@@ -62,6 +69,7 @@ impl TieredStorageReader {
     pub fn num_accounts(&self) -> usize {
         match self {
             Self::Hot(hot) => hot.num_accounts(),
+            Self::Cold(cold) => cold.num_accounts(),
         }
     }
 
@@ -72,6 +80,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Option<AccountSharedData>> {
         match self {
             Self::Hot(hot) => hot.get_account_shared_data(index_offset),
+            Self::Cold(cold) => cold.get_account_shared_data(index_offset),
         }
     }
 
@@ -89,6 +98,9 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Option<Ret>> {
         match self {
             Self::Hot(hot) => hot.get_stored_account_without_data_callback(index_offset, callback),
+            Self::Cold(cold) => {
+                cold.get_stored_account_without_data_callback(index_offset, callback)
+            }
         }
     }
 
@@ -106,6 +118,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Option<Ret>> {
         match self {
             Self::Hot(hot) => hot.get_stored_account_callback(index_offset, callback),
+            Self::Cold(cold) => cold.get_stored_account_callback(index_offset, callback),
         }
     }
 
@@ -113,6 +126,7 @@ impl TieredStorageReader {
     pub fn scan_pubkeys(&self, callback: impl FnMut(&Pubkey)) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_pubkeys(callback),
+            Self::Cold(cold) => cold.scan_pubkeys(callback),
         }
     }
 
@@ -129,6 +143,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_accounts_without_data(callback),
+            Self::Cold(cold) => cold.scan_accounts_without_data(callback),
         }
     }
 
@@ -146,6 +161,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<()> {
         match self {
             Self::Hot(hot) => hot.scan_accounts(callback),
+            Self::Cold(cold) => cold.scan_accounts(callback),
         }
     }
 
@@ -154,6 +170,7 @@ impl TieredStorageReader {
     pub(crate) fn calculate_stored_size(&self, data_len: usize) -> usize {
         match self {
             Self::Hot(_) => HotStorageReader::calculate_stored_size(data_len),
+            Self::Cold(_) => ColdStorageReader::calculate_stored_size(data_len),
         }
     }
 
@@ -164,6 +181,7 @@ impl TieredStorageReader {
     ) -> TieredStorageResult<Vec<usize>> {
         match self {
             Self::Hot(hot) => hot.get_account_data_lens(sorted_offsets),
+            Self::Cold(cold) => cold.get_account_data_lens(sorted_offsets),
         }
     }
 
@@ -171,6 +189,7 @@ impl TieredStorageReader {
     pub fn data_for_archive(&self) -> &[u8] {
         match self {
             Self::Hot(hot) => hot.data_for_archive(),
+            Self::Cold(cold) => cold.data_for_archive(),
         }
     }
 }
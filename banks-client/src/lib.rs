@@ -15,8 +15,8 @@ use {
     futures::future::join_all,
     solana_account::{from_account, Account},
     solana_banks_interface::{
-        BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation,
+        BanksRequest, BanksResponse, BanksTransactionResultWithComputeUnitProfile,
+        BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation,
     },
     solana_clock::Slot,
     solana_commitment_config::CommitmentLevel,
@@ -159,6 +159,22 @@ impl BanksClient {
             .map_err(Into::into)
     }
 
+    pub async fn simulate_transaction_with_compute_unit_profile_and_context(
+        &self,
+        ctx: Context,
+        transaction: impl Into<VersionedTransaction>,
+        commitment: CommitmentLevel,
+    ) -> Result<BanksTransactionResultWithComputeUnitProfile, BanksClientError> {
+        self.inner
+            .simulate_transaction_with_compute_unit_profile_and_context(
+                ctx,
+                transaction.into(),
+                commitment,
+            )
+            .await
+            .map_err(Into::into)
+    }
+
     pub async fn get_account_with_commitment_and_context(
         &self,
         ctx: Context,
@@ -335,6 +351,21 @@ impl BanksClient {
             .await
     }
 
+    /// Simulate a transaction at the default commitment level, and return the
+    /// compute unit profile of its SBF programs if the server has compute unit
+    /// profiling enabled
+    pub async fn simulate_transaction_with_compute_unit_profile(
+        &self,
+        transaction: impl Into<VersionedTransaction>,
+    ) -> Result<BanksTransactionResultWithComputeUnitProfile, BanksClientError> {
+        self.simulate_transaction_with_compute_unit_profile_and_context(
+            context::current(),
+            transaction,
+            CommitmentLevel::default(),
+        )
+        .await
+    }
+
     /// Return the most recent rooted slot. All transactions at or below this slot
     /// are said to be finalized. The cluster will not fork to a higher slot.
     pub async fn get_root_slot(&self) -> Result<Slot, BanksClientError> {
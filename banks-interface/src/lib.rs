@@ -58,6 +58,15 @@ pub struct BanksTransactionResultWithSimulation {
     pub simulation_details: Option<TransactionSimulationDetails>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanksTransactionResultWithComputeUnitProfile {
+    pub result: Option<transaction::Result<()>>,
+    pub simulation_details: Option<TransactionSimulationDetails>,
+    /// Compute units sampled per call stack of the SBF programs executed, in
+    /// the folded stack format, when compute unit profiling is enabled
+    pub compute_unit_profile: Option<String>,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BanksTransactionResultWithMetadata {
     pub result: transaction::Result<()>,
@@ -98,6 +107,10 @@ pub trait Banks {
         message: Message,
         commitment: CommitmentLevel,
     ) -> Option<u64>;
+    async fn simulate_transaction_with_compute_unit_profile_and_context(
+        transaction: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithComputeUnitProfile;
 }
 
 #[cfg(test)]
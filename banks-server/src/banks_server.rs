@@ -4,9 +4,10 @@ use {
     futures::{future, prelude::stream::StreamExt},
     solana_account::Account,
     solana_banks_interface::{
-        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithMetadata,
-        BanksTransactionResultWithSimulation, TransactionConfirmationStatus, TransactionMetadata,
-        TransactionSimulationDetails, TransactionStatus,
+        Banks, BanksRequest, BanksResponse, BanksTransactionResultWithComputeUnitProfile,
+        BanksTransactionResultWithMetadata, BanksTransactionResultWithSimulation,
+        TransactionConfirmationStatus, TransactionMetadata, TransactionSimulationDetails,
+        TransactionStatus,
     },
     solana_clock::Slot,
     solana_commitment_config::CommitmentLevel,
@@ -172,6 +173,21 @@ fn simulate_transaction(
     bank: &Bank,
     transaction: VersionedTransaction,
 ) -> BanksTransactionResultWithSimulation {
+    let BanksTransactionResultWithComputeUnitProfile {
+        result,
+        simulation_details,
+        compute_unit_profile: _,
+    } = simulate_transaction_with_compute_unit_profile(bank, transaction);
+    BanksTransactionResultWithSimulation {
+        result,
+        simulation_details,
+    }
+}
+
+fn simulate_transaction_with_compute_unit_profile(
+    bank: &Bank,
+    transaction: VersionedTransaction,
+) -> BanksTransactionResultWithComputeUnitProfile {
     let sanitized_transaction = match RuntimeTransaction::try_create(
         transaction,
         MessageHash::Compute,
@@ -182,9 +198,10 @@ fn simulate_transaction(
             .is_active(&agave_feature_set::static_instruction_limit::id()),
     ) {
         Err(err) => {
-            return BanksTransactionResultWithSimulation {
+            return BanksTransactionResultWithComputeUnitProfile {
                 result: Some(Err(err)),
                 simulation_details: None,
+                compute_unit_profile: None,
             };
         }
         Ok(tx) => tx,
@@ -193,6 +210,7 @@ fn simulate_transaction(
         result,
         logs,
         post_simulation_accounts: _,
+        rollback_accounts: _,
         units_consumed,
         loaded_accounts_data_size,
         return_data,
@@ -202,6 +220,7 @@ fn simulate_transaction(
         post_balances: _,
         pre_token_balances: _,
         post_token_balances: _,
+        compute_unit_profile,
     } = bank.simulate_transaction_unchecked(&sanitized_transaction, true);
 
     let simulation_details = TransactionSimulationDetails {
@@ -211,9 +230,10 @@ fn simulate_transaction(
         return_data,
         inner_instructions,
     };
-    BanksTransactionResultWithSimulation {
+    BanksTransactionResultWithComputeUnitProfile {
         result: Some(result),
         simulation_details: Some(simulation_details),
+        compute_unit_profile: compute_unit_profile.map(|profile| profile.to_string()),
     }
 }
 
@@ -418,6 +438,15 @@ impl Banks for BanksServer {
                 .ok()?;
         bank.get_fee_for_message(&sanitized_message)
     }
+
+    async fn simulate_transaction_with_compute_unit_profile_and_context(
+        self,
+        _: Context,
+        transaction: VersionedTransaction,
+        commitment: CommitmentLevel,
+    ) -> BanksTransactionResultWithComputeUnitProfile {
+        simulate_transaction_with_compute_unit_profile(&self.bank(commitment), transaction)
+    }
 }
 
 pub async fn start_local_server(
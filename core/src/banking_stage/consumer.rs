@@ -345,6 +345,8 @@ impl Consumer {
                     ),
                     drop_on_failure: flags.drop_on_failure,
                     all_or_nothing: flags.all_or_nothing,
+                    compute_unit_profiling_environments: None,
+                    instruction_processed_callback: None,
                 }
             ));
         execute_and_commit_timings.load_execute_us = load_execute_us;
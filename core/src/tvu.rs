@@ -31,8 +31,11 @@ use {
     },
     solana_keypair::Keypair,
     solana_ledger::{
-        blockstore::Blockstore, blockstore_cleanup_service::BlockstoreCleanupService,
-        blockstore_processor::TransactionStatusSender, entry_notifier_service::EntryNotifierSender,
+        blockstore::Blockstore,
+        blockstore_archive_service::{BlockstoreArchiveConfig, BlockstoreArchiveService},
+        blockstore_cleanup_service::BlockstoreCleanupService,
+        blockstore_processor::TransactionStatusSender,
+        entry_notifier_service::EntryNotifierSender,
         leader_schedule_cache::LeaderScheduleCache,
     },
     solana_poh::{poh_controller::PohController, poh_recorder::PohRecorder},
@@ -74,6 +77,7 @@ pub struct Tvu {
     cluster_slots_service: ClusterSlotsService,
     replay_stage: Option<ReplayStage>,
     blockstore_cleanup_service: Option<BlockstoreCleanupService>,
+    blockstore_archive_service: Option<BlockstoreArchiveService>,
     cost_update_service: CostUpdateService,
     voting_service: VotingService,
     warm_quic_cache_service: Option<WarmQuicCacheService>,
@@ -91,6 +95,9 @@ pub struct TvuSockets {
 
 pub struct TvuConfig {
     pub max_ledger_shreds: Option<u64>,
+    // Archive rooted slots this many slots older than the latest root; only
+    // used if the blockstore has an archive
+    pub ledger_archive_max_slot_age: Option<u64>,
     pub shred_version: u16,
     // Validators from which repairs are requested
     pub repair_validators: Option<HashSet<Pubkey>>,
@@ -107,6 +114,7 @@ impl Default for TvuConfig {
     fn default() -> Self {
         Self {
             max_ledger_shreds: None,
+            ledger_archive_max_slot_age: None,
             shred_version: 0,
             repair_validators: None,
             repair_whitelist: Arc::new(RwLock::new(HashSet::default())),
@@ -377,9 +385,34 @@ impl Tvu {
             )?)
         };
 
-        let blockstore_cleanup_service = tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
-            BlockstoreCleanupService::new(blockstore.clone(), max_ledger_shreds, exit.clone())
-        });
+        // If the blockstore has an archive, old rooted slots are moved there
+        // instead of being cleaned up
+        let (blockstore_cleanup_service, blockstore_archive_service) = match blockstore.archive() {
+            Some(archive) => {
+                let archive_config = BlockstoreArchiveConfig {
+                    max_ledger_shreds: tvu_config.max_ledger_shreds,
+                    max_slot_age: tvu_config.ledger_archive_max_slot_age,
+                };
+                let blockstore_archive_service = BlockstoreArchiveService::new(
+                    blockstore.clone(),
+                    archive,
+                    archive_config,
+                    exit.clone(),
+                );
+                (None, Some(blockstore_archive_service))
+            }
+            None => {
+                let blockstore_cleanup_service =
+                    tvu_config.max_ledger_shreds.map(|max_ledger_shreds| {
+                        BlockstoreCleanupService::new(
+                            blockstore.clone(),
+                            max_ledger_shreds,
+                            exit.clone(),
+                        )
+                    });
+                (blockstore_cleanup_service, None)
+            }
+        };
 
         let duplicate_shred_listener = DuplicateShredListener::new(
             exit,
@@ -401,6 +434,7 @@ impl Tvu {
             cluster_slots_service,
             replay_stage,
             blockstore_cleanup_service,
+            blockstore_archive_service,
             cost_update_service,
             voting_service,
             warm_quic_cache_service,
@@ -418,6 +452,9 @@ impl Tvu {
         if self.blockstore_cleanup_service.is_some() {
             self.blockstore_cleanup_service.unwrap().join()?;
         }
+        if self.blockstore_archive_service.is_some() {
+            self.blockstore_archive_service.unwrap().join()?;
+        }
         if self.replay_stage.is_some() {
             self.replay_stage.unwrap().join()?;
         }
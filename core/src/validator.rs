@@ -78,6 +78,7 @@ use {
             Blockstore, BlockstoreError, PurgeType, MAX_COMPLETED_SLOTS_IN_CHANNEL,
             MAX_REPLAY_WAKE_UP_SIGNALS,
         },
+        blockstore_archive_service::archive_blockstore_options,
         blockstore_metric_report_service::BlockstoreMetricReportService,
         blockstore_options::{BlockstoreOptions, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL},
         blockstore_processor::{self, TransactionStatusSender},
@@ -100,6 +101,8 @@ use {
     solana_pubkey::Pubkey,
     solana_rayon_threadlimit::get_thread_count,
     solana_rpc::{
+        account_history::{AccountHistory, AccountHistoryNotifier},
+        account_history_service::AccountHistoryService,
         max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::{
             BankNotificationSenderConfig, OptimisticallyConfirmedBank,
@@ -355,6 +358,9 @@ pub struct ValidatorConfig {
     pub pubsub_config: PubSubConfig,
     pub snapshot_config: SnapshotConfig,
     pub max_ledger_shreds: Option<u64>,
+    /// Move old rooted slots to this blockstore instead of dropping them
+    pub ledger_archive_path: Option<PathBuf>,
+    pub ledger_archive_max_slot_age: Option<u64>,
     pub blockstore_options: BlockstoreOptions,
     pub broadcast_stage_type: BroadcastStageType,
     pub turbine_disabled: Arc<AtomicBool>,
@@ -427,6 +433,8 @@ impl ValidatorConfig {
             expected_shred_version: None,
             voting_disabled: false,
             max_ledger_shreds: None,
+            ledger_archive_path: None,
+            ledger_archive_max_slot_age: None,
             blockstore_options: BlockstoreOptions::default_for_tests(),
             account_paths: Vec::new(),
             account_snapshot_paths: Vec::new(),
@@ -651,6 +659,7 @@ pub struct Validator {
     json_rpc_service: Option<JsonRpcService>,
     pubsub_service: Option<PubSubService>,
     rpc_completed_slots_service: Option<JoinHandle<()>>,
+    account_history_service: Option<JoinHandle<()>>,
     optimistically_confirmed_bank_tracker: Option<OptimisticallyConfirmedBankTracker>,
     transaction_status_service: Option<TransactionStatusService>,
     entry_notifier_service: Option<EntryNotifierService>,
@@ -927,6 +936,7 @@ impl Validator {
             blockstore_root_scan,
             pruned_banks_receiver,
             entry_notifier_service,
+            account_history,
         ) = load_blockstore(
             config,
             ledger_path,
@@ -956,6 +966,12 @@ impl Validator {
         let shred_version = compute_shred_version(&genesis_config.hash(), Some(&hard_forks));
         info!("shred version: {shred_version}, hard forks: {hard_forks:?}");
 
+        if let Some(account_history) = &account_history {
+            account_history.set_start_root(root_slot).map_err(|err| {
+                ValidatorError::Other(format!("Failed to start the account history: {err:?}"))
+            })?;
+        }
+
         if let Some(expected_shred_version) = config.expected_shred_version {
             if expected_shred_version != shred_version {
                 return Err(ValidatorError::ShredVersionMismatch {
@@ -1245,6 +1261,7 @@ impl Validator {
             rpc_completed_slots_service,
             optimistically_confirmed_bank_tracker,
             bank_notification_sender,
+            account_history_service,
         ) = if let Some((rpc_addr, rpc_pubsub_addr)) = config.rpc_addrs {
             assert_eq!(
                 node.info.rpc().map(|addr| socket_addr_space.check(&addr)),
@@ -1253,6 +1270,11 @@ impl Validator {
                     .map(|addr| socket_addr_space.check(&addr))
             );
             let (bank_notification_sender, bank_notification_receiver) = unbounded();
+            let account_history_service = account_history.as_ref().map(|account_history| {
+                let (root_sender, root_receiver) = unbounded();
+                bank_notification_senders.push(root_sender);
+                AccountHistoryService::spawn(root_receiver, account_history.clone(), exit.clone())
+            });
             let confirmed_bank_subscribers = if !bank_notification_senders.is_empty() {
                 Some(Arc::new(RwLock::new(bank_notification_senders)))
             } else {
@@ -1292,6 +1314,7 @@ impl Validator {
                 leader_schedule_cache: leader_schedule_cache.clone(),
                 max_complete_transaction_status_slot: max_complete_transaction_status_slot.clone(),
                 prioritization_fee_cache: prioritization_fee_cache.clone(),
+                account_history,
                 rpc_tpu_client_args,
             };
             let json_rpc_service =
@@ -1374,7 +1397,8 @@ impl Validator {
                 ));
             let bank_notification_sender_config = Some(BankNotificationSenderConfig {
                 sender: bank_notification_sender,
-                should_send_parents: geyser_plugin_service.is_some(),
+                should_send_parents: geyser_plugin_service.is_some()
+                    || account_history_service.is_some(),
                 dependency_tracker,
             });
             (
@@ -1386,9 +1410,10 @@ impl Validator {
                 rpc_completed_slots_service,
                 optimistically_confirmed_bank_tracker,
                 bank_notification_sender_config,
+                account_history_service,
             )
         } else {
-            (None, None, None, None, None, None, None, None)
+            (None, None, None, None, None, None, None, None, None)
         };
 
         let ip_echo_server = match node.sockets.ip_echo {
@@ -1630,6 +1655,7 @@ impl Validator {
             duplicate_confirmed_slots_receiver,
             TvuConfig {
                 max_ledger_shreds: config.max_ledger_shreds,
+                ledger_archive_max_slot_age: config.ledger_archive_max_slot_age,
                 shred_version: node.info.shred_version(),
                 repair_validators: config.repair_validators.clone(),
                 repair_whitelist: config.repair_whitelist.clone(),
@@ -1791,6 +1817,7 @@ impl Validator {
             json_rpc_service,
             pubsub_service,
             rpc_completed_slots_service,
+            account_history_service,
             optimistically_confirmed_bank_tracker,
             transaction_status_service,
             entry_notifier_service,
@@ -1928,6 +1955,12 @@ impl Validator {
                 .expect("rpc_completed_slots_service");
         }
 
+        if let Some(account_history_service) = self.account_history_service {
+            account_history_service
+                .join()
+                .expect("account_history_service");
+        }
+
         if let Some(optimistically_confirmed_bank_tracker) =
             self.optimistically_confirmed_bank_tracker
         {
@@ -2193,6 +2226,7 @@ fn load_blockstore(
         BlockstoreRootScan,
         DroppedSlotsReceiver,
         Option<EntryNotifierService>,
+        Option<Arc<AccountHistory>>,
     ),
     String,
 > {
@@ -2201,6 +2235,14 @@ fn load_blockstore(
 
     let blockstore = Blockstore::open_with_options(ledger_path, config.blockstore_options.clone())
         .map_err(|err| format!("Failed to open Blockstore: {err:?}"))?;
+    if let Some(ledger_archive_path) = &config.ledger_archive_path {
+        let archive = Blockstore::open_with_options(
+            ledger_archive_path,
+            archive_blockstore_options(&config.blockstore_options),
+        )
+        .map_err(|err| format!("Failed to open archive Blockstore: {err:?}"))?;
+        blockstore.set_archive(Arc::new(archive));
+    }
 
     let (ledger_signal_sender, ledger_signal_receiver) = bounded(MAX_REPLAY_WAKE_UP_SIGNALS);
     blockstore.add_new_shred_signal(ledger_signal_sender);
@@ -2211,6 +2253,27 @@ fn load_blockstore(
 
     let blockstore = Arc::new(blockstore);
     let blockstore_root_scan = BlockstoreRootScan::new(config, blockstore.clone(), exit.clone());
+
+    // The account history is only served over RPC, and records the accounts
+    // updates alongside the geyser plugins
+    let account_history = config
+        .rpc_config
+        .account_history
+        .clone()
+        .filter(|_| config.rpc_addrs.is_some())
+        .map(|account_history_config| {
+            Arc::new(AccountHistory::new(
+                account_history_config,
+                blockstore.clone(),
+            ))
+        });
+    let accounts_update_notifier = match &account_history {
+        Some(account_history) => Some(Arc::new(AccountHistoryNotifier::new(
+            account_history.clone(),
+            accounts_update_notifier,
+        )) as AccountsUpdateNotifier),
+        None => accounts_update_notifier,
+    };
     let halt_at_slot = blockstore.highest_slot().unwrap_or(None);
 
     let process_options = blockstore_processor::ProcessOptions {
@@ -2286,6 +2349,7 @@ fn load_blockstore(
         blockstore_root_scan,
         pruned_banks_receiver,
         entry_notifier_service,
+        account_history,
     ))
 }
 
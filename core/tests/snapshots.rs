@@ -3,8 +3,9 @@
 use {
     crate::snapshot_utils::create_tmp_accounts_dir_for_tests,
     agave_snapshots::{
-        paths as snapshot_paths, snapshot_archive_info::FullSnapshotArchiveInfo,
-        snapshot_config::SnapshotConfig, SnapshotInterval, SnapshotKind,
+        manifest::ManifestVerification, paths as snapshot_paths,
+        snapshot_archive_info::FullSnapshotArchiveInfo, snapshot_config::SnapshotConfig,
+        SnapshotInterval, SnapshotKind,
     },
     crossbeam_channel::unbounded,
     itertools::Itertools,
@@ -144,6 +145,7 @@ fn restore_from_snapshot(
         false,
         false,
         ACCOUNTS_DB_CONFIG_FOR_TESTING,
+        ManifestVerification::Skip,
         None,
         Arc::default(),
     )
@@ -545,6 +547,7 @@ fn restore_from_snapshots_and_check_banks_are_equal(
         false,
         false,
         ACCOUNTS_DB_CONFIG_FOR_TESTING,
+        ManifestVerification::Skip,
         None,
         Arc::default(),
     )?;
@@ -737,6 +740,7 @@ fn test_snapshots_with_background_services() {
         false,
         false,
         ACCOUNTS_DB_CONFIG_FOR_TESTING,
+        ManifestVerification::Skip,
         None,
         exit.clone(),
     )
@@ -109,6 +109,22 @@ pub struct ReplicaAccountInfoV3<'a> {
     pub txn: Option<&'a SanitizedTransaction>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[repr(C)]
+/// State of an account before it was updated
+pub struct ReplicaAccountPreState<'a> {
+    /// The lamports for the account, zero if the account did not exist
+    pub lamports: u64,
+
+    /// The Pubkey of the owner program account, the default Pubkey if the
+    /// account did not exist
+    pub owner: &'a [u8],
+
+    /// The SHA-256 hash of the data held in this account, the hash of empty
+    /// data if the account did not exist
+    pub data_hash: &'a Hash,
+}
+
 /// A wrapper to future-proof ReplicaAccountInfo handling.
 /// If there were a change to the structure of ReplicaAccountInfo,
 /// there would be new enum entry for the newer version, forcing
@@ -434,6 +450,24 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
         Ok(())
     }
 
+    /// Called instead of `update_account` when an account is updated at a
+    /// slot, if `account_pre_state_notifications_enabled` returns true.
+    /// `pre_state` is the state of the account before the update, as seen by
+    /// the bank performing it. It is None when the account is restored from
+    /// a snapshot, or when the update is not made by a bank.
+    /// The default implementation ignores `pre_state` and calls
+    /// `update_account`.
+    #[allow(unused_variables)]
+    fn update_account_with_pre_state(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        pre_state: Option<&ReplicaAccountPreState>,
+        slot: Slot,
+        is_startup: bool,
+    ) -> Result<()> {
+        self.update_account(account, slot, is_startup)
+    }
+
     /// Called when all accounts are notified of during startup.
     fn notify_end_of_startup(&self) -> Result<()> {
         Ok(())
@@ -500,4 +534,15 @@ pub trait GeyserPlugin: Any + Send + Sync + std::fmt::Debug {
     fn entry_notifications_enabled(&self) -> bool {
         false
     }
+
+    /// Check if the plugin is interested in the state of accounts before
+    /// they are updated. Account updates are then passed to
+    /// `update_account_with_pre_state` instead of `update_account`.
+    /// Only applies if `account_data_notifications_enabled` returns true.
+    /// Default is false -- if the plugin is interested in account
+    /// pre-state, return true. Loading the pre-state adds an accounts
+    /// lookup to every account update.
+    fn account_pre_state_notifications_enabled(&self) -> bool {
+        false
+    }
 }
@@ -2,12 +2,12 @@
 use {
     crate::geyser_plugin_manager::GeyserPluginManager,
     agave_geyser_plugin_interface::geyser_plugin_interface::{
-        ReplicaAccountInfoV3, ReplicaAccountInfoVersions,
+        ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaAccountPreState,
     },
     log::*,
     solana_account::{AccountSharedData, ReadableAccount},
     solana_accounts_db::accounts_update_notifier_interface::{
-        AccountForGeyser, AccountsUpdateNotifierInterface,
+        AccountForGeyser, AccountPreState, AccountsUpdateNotifierInterface,
     },
     solana_clock::Slot,
     solana_pubkey::Pubkey,
@@ -25,6 +25,13 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
         self.snapshot_notifications_enabled
     }
 
+    fn account_pre_state_notifications_enabled(&self) -> bool {
+        self.plugin_manager
+            .read()
+            .unwrap()
+            .account_pre_state_notifications_enabled()
+    }
+
     fn notify_account_update(
         &self,
         slot: Slot,
@@ -35,7 +42,26 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
     ) {
         let account_info =
             self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version);
-        self.notify_plugins_of_account_update(account_info, slot, false);
+        self.notify_plugins_of_account_update(account_info, None, slot, false);
+    }
+
+    fn notify_account_update_with_pre_state(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+        pre_state: &AccountPreState,
+    ) {
+        let account_info =
+            self.accountinfo_from_shared_account_data(account, txn, pubkey, write_version);
+        let pre_state = ReplicaAccountPreState {
+            lamports: pre_state.lamports,
+            owner: pre_state.owner.as_ref(),
+            data_hash: &pre_state.data_hash,
+        };
+        self.notify_plugins_of_account_update(account_info, Some(&pre_state), slot, false);
     }
 
     fn notify_account_restore_from_snapshot(
@@ -46,7 +72,7 @@ impl AccountsUpdateNotifierInterface for AccountsUpdateNotifierImpl {
     ) {
         let mut account = self.accountinfo_from_account_for_geyser(account);
         account.write_version = write_version;
-        self.notify_plugins_of_account_update(account, slot, true);
+        self.notify_plugins_of_account_update(account, None, slot, true);
     }
 
     fn notify_end_of_restore_from_snapshot(&self) {
@@ -124,6 +150,7 @@ impl AccountsUpdateNotifierImpl {
     fn notify_plugins_of_account_update(
         &self,
         account: ReplicaAccountInfoV3,
+        pre_state: Option<&ReplicaAccountPreState>,
         slot: Slot,
         is_startup: bool,
     ) {
@@ -133,11 +160,16 @@ impl AccountsUpdateNotifierImpl {
             return;
         }
         for plugin in plugin_manager.plugins.iter() {
-            match plugin.update_account(
-                ReplicaAccountInfoVersions::V0_0_3(&account),
-                slot,
-                is_startup,
-            ) {
+            if !plugin.should_notify_account(account.pubkey, account.owner) {
+                continue;
+            }
+            let account_info = ReplicaAccountInfoVersions::V0_0_3(&account);
+            let result = if plugin.account_pre_state_notifications_enabled() {
+                plugin.update_account_with_pre_state(account_info, pre_state, slot, is_startup)
+            } else {
+                plugin.update_account(account_info, slot, is_startup)
+            };
+            match result {
                 Err(err) => {
                     error!(
                         "Failed to update account {} at slot {}, error: {} to plugin {}",
@@ -0,0 +1,367 @@
+/// Module responsible for filtering the notifications delivered to plugins
+use {
+    crate::geyser_plugin_manager::GeyserPluginManagerError,
+    serde::Deserialize,
+    solana_pubkey::Pubkey,
+    solana_time_utils::AtomicInterval,
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::TransactionStatusMeta,
+    std::{
+        collections::HashSet,
+        str::FromStr,
+        sync::atomic::{AtomicU64, Ordering},
+    },
+};
+
+const STATS_REPORT_INTERVAL_MS: u64 = 10_000;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilterConfig {
+    #[serde(default)]
+    accounts: AccountFilterConfig,
+    #[serde(default)]
+    transactions: TransactionFilterConfig,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AccountFilterConfig {
+    #[serde(default)]
+    owners: Vec<String>,
+    #[serde(default)]
+    pubkeys: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TransactionFilterConfig {
+    #[serde(default = "default_true")]
+    include_votes: bool,
+    #[serde(default = "default_true")]
+    include_failed: bool,
+    #[serde(default)]
+    mentions: Vec<String>,
+}
+
+impl Default for TransactionFilterConfig {
+    fn default() -> Self {
+        Self {
+            include_votes: true,
+            include_failed: true,
+            mentions: Vec::default(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Filters on the account updates and transactions delivered to a plugin.
+/// The default filter delivers every notification.
+///
+/// Filters are declared in the optional `filters` section of a plugin's config file, and are
+/// evaluated by the plugin manager before calling into the plugin:
+///
+/// ```json
+/// "filters": {
+///     "accounts": {
+///         "owners": ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"],
+///         "pubkeys": ["SysvarC1ock11111111111111111111111111111111"]
+///     },
+///     "transactions": {
+///         "include_votes": false,
+///         "include_failed": false,
+///         "mentions": ["TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"]
+///     }
+/// }
+/// ```
+///
+/// An account update is delivered if the account is listed in `pubkeys`, or is owned by a
+/// program listed in `owners`. A transaction is delivered if it is not an excluded vote or
+/// failed transaction, and mentions an account listed in `mentions`, including accounts loaded
+/// from address lookup tables. Omitted lists do not filter anything.
+#[derive(Debug)]
+pub struct GeyserPluginFilter {
+    /// Accounts owned by these programs are delivered
+    owners: HashSet<Pubkey>,
+    /// These accounts are delivered
+    pubkeys: HashSet<Pubkey>,
+    include_votes: bool,
+    include_failed: bool,
+    /// Transactions mentioning any of these accounts are delivered
+    mentions: HashSet<Pubkey>,
+}
+
+impl Default for GeyserPluginFilter {
+    fn default() -> Self {
+        let transactions = TransactionFilterConfig::default();
+        Self {
+            owners: HashSet::default(),
+            pubkeys: HashSet::default(),
+            include_votes: transactions.include_votes,
+            include_failed: transactions.include_failed,
+            mentions: HashSet::default(),
+        }
+    }
+}
+
+impl GeyserPluginFilter {
+    /// Parses the `filters` section of a plugin config file, which may be absent
+    pub fn from_config(config: &serde_json::Value) -> Result<Self, GeyserPluginManagerError> {
+        let config: FilterConfig = if config.is_null() {
+            FilterConfig::default()
+        } else {
+            serde_json::from_value(config.clone())
+                .map_err(|err| GeyserPluginManagerError::InvalidFilterConfig(err.to_string()))?
+        };
+        Ok(Self {
+            owners: parse_pubkeys(&config.accounts.owners)?,
+            pubkeys: parse_pubkeys(&config.accounts.pubkeys)?,
+            include_votes: config.transactions.include_votes,
+            include_failed: config.transactions.include_failed,
+            mentions: parse_pubkeys(&config.transactions.mentions)?,
+        })
+    }
+
+    /// Whether an update to the account `pubkey` owned by `owner` passes the filter.
+    /// Without `owners` or `pubkeys`, every account passes.
+    pub fn allows_account(&self, pubkey: &[u8], owner: &[u8]) -> bool {
+        if self.owners.is_empty() && self.pubkeys.is_empty() {
+            return true;
+        }
+        let contains = |set: &HashSet<Pubkey>, key: &[u8]| {
+            Pubkey::try_from(key).is_ok_and(|key| set.contains(&key))
+        };
+        contains(&self.pubkeys, pubkey) || contains(&self.owners, owner)
+    }
+
+    /// Whether a transaction passes the filter. Without `mentions`, every transaction that is
+    /// not excluded as a vote or as failed passes.
+    pub fn allows_transaction(
+        &self,
+        is_vote: bool,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &VersionedTransaction,
+    ) -> bool {
+        if is_vote && !self.include_votes {
+            return false;
+        }
+        if transaction_status_meta.status.is_err() && !self.include_failed {
+            return false;
+        }
+        if self.mentions.is_empty() {
+            return true;
+        }
+        let loaded_addresses = &transaction_status_meta.loaded_addresses;
+        transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(&loaded_addresses.writable)
+            .chain(&loaded_addresses.readonly)
+            .any(|key| self.mentions.contains(key))
+    }
+}
+
+fn parse_pubkeys(pubkeys: &[String]) -> Result<HashSet<Pubkey>, GeyserPluginManagerError> {
+    pubkeys
+        .iter()
+        .map(|pubkey| {
+            Pubkey::from_str(pubkey).map_err(|err| {
+                GeyserPluginManagerError::InvalidFilterConfig(format!("{pubkey}: {err}"))
+            })
+        })
+        .collect()
+}
+
+/// Counts of the notifications delivered to a plugin, and filtered out by its filter
+#[derive(Debug, Default)]
+pub struct GeyserPluginFilterStats {
+    last_report: AtomicInterval,
+    accounts_delivered: AtomicU64,
+    accounts_filtered: AtomicU64,
+    transactions_delivered: AtomicU64,
+    transactions_filtered: AtomicU64,
+}
+
+impl GeyserPluginFilterStats {
+    pub fn record_account(&self, delivered: bool) {
+        let count = if delivered {
+            &self.accounts_delivered
+        } else {
+            &self.accounts_filtered
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_transaction(&self, delivered: bool) {
+        let count = if delivered {
+            &self.transactions_delivered
+        } else {
+            &self.transactions_filtered
+        };
+        count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Reports and resets the counts, at most once per reporting interval
+    pub fn maybe_report(&self, plugin_name: &str) {
+        if !self.last_report.should_update(STATS_REPORT_INTERVAL_MS) {
+            return;
+        }
+        solana_metrics::datapoint_info!(
+            "geyser_plugin_filter",
+            "plugin" => plugin_name,
+            (
+                "accounts_delivered",
+                self.accounts_delivered.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "accounts_filtered",
+                self.accounts_filtered.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "transactions_delivered",
+                self.transactions_delivered.swap(0, Ordering::Relaxed),
+                i64
+            ),
+            (
+                "transactions_filtered",
+                self.transactions_filtered.swap(0, Ordering::Relaxed),
+                i64
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        serde_json::json,
+        solana_message::{v0::LoadedAddresses, Message, VersionedMessage},
+        solana_transaction_error::TransactionError,
+    };
+
+    fn transaction_mentioning(keys: &[Pubkey]) -> VersionedTransaction {
+        let mut message = Message::default();
+        message.account_keys = keys.to_vec();
+        VersionedTransaction {
+            signatures: vec![],
+            message: VersionedMessage::Legacy(message),
+        }
+    }
+
+    fn status_meta(status: Result<(), TransactionError>) -> TransactionStatusMeta {
+        TransactionStatusMeta {
+            status,
+            ..TransactionStatusMeta::default()
+        }
+    }
+
+    #[test]
+    fn test_default_filter() {
+        for filter in [
+            GeyserPluginFilter::from_config(&serde_json::Value::Null).unwrap(),
+            GeyserPluginFilter::default(),
+        ] {
+            let key = Pubkey::new_unique();
+            assert!(filter.allows_account(key.as_ref(), key.as_ref()));
+            // A failed vote transaction is delivered
+            assert!(filter.allows_transaction(
+                true,
+                &status_meta(Err(TransactionError::AccountInUse)),
+                &transaction_mentioning(&[]),
+            ));
+        }
+    }
+
+    #[test]
+    fn test_account_filter() {
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let filter = GeyserPluginFilter::from_config(&json!({
+            "accounts": {
+                "owners": [owner.to_string()],
+                "pubkeys": [pubkey.to_string()],
+            },
+        }))
+        .unwrap();
+        assert!(filter.allows_account(other.as_ref(), owner.as_ref()));
+        assert!(filter.allows_account(pubkey.as_ref(), other.as_ref()));
+        assert!(!filter.allows_account(other.as_ref(), other.as_ref()));
+        // Transactions are not filtered
+        assert!(filter.allows_transaction(
+            true,
+            &status_meta(Ok(())),
+            &transaction_mentioning(&[])
+        ));
+    }
+
+    #[test]
+    fn test_transaction_filter() {
+        let mentioned = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let filter = GeyserPluginFilter::from_config(&json!({
+            "transactions": {
+                "include_votes": false,
+                "include_failed": false,
+                "mentions": [mentioned.to_string()],
+            },
+        }))
+        .unwrap();
+        let success = status_meta(Ok(()));
+        let failure = status_meta(Err(TransactionError::AccountInUse));
+        let mentioning = transaction_mentioning(&[other, mentioned]);
+
+        assert!(filter.allows_transaction(false, &success, &mentioning));
+        assert!(!filter.allows_transaction(true, &success, &mentioning));
+        assert!(!filter.allows_transaction(false, &failure, &mentioning));
+        assert!(!filter.allows_transaction(false, &success, &transaction_mentioning(&[other])));
+
+        // Accounts loaded from lookup tables are mentioned
+        let loaded = TransactionStatusMeta {
+            loaded_addresses: LoadedAddresses {
+                writable: vec![],
+                readonly: vec![mentioned],
+            },
+            ..success
+        };
+        assert!(filter.allows_transaction(false, &loaded, &transaction_mentioning(&[other])));
+
+        // Accounts are not filtered
+        assert!(filter.allows_account(other.as_ref(), other.as_ref()));
+    }
+
+    #[test]
+    fn test_invalid_filter_config() {
+        for config in [
+            json!({"accounts": {"owners": ["not a pubkey"]}}),
+            json!({"accounts": {"owner": []}}),
+            json!({"transactions": {"include_votes": "no"}}),
+            json!(["accounts"]),
+        ] {
+            assert!(matches!(
+                GeyserPluginFilter::from_config(&config),
+                Err(GeyserPluginManagerError::InvalidFilterConfig(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_filter_stats() {
+        let stats = GeyserPluginFilterStats::default();
+        stats.record_account(true);
+        stats.record_account(false);
+        stats.record_account(false);
+        stats.record_transaction(true);
+        assert_eq!(stats.accounts_delivered.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.accounts_filtered.load(Ordering::Relaxed), 2);
+        assert_eq!(stats.transactions_delivered.load(Ordering::Relaxed), 1);
+        assert_eq!(stats.transactions_filtered.load(Ordering::Relaxed), 0);
+    }
+}
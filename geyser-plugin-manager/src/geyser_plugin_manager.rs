@@ -1,8 +1,11 @@
 use {
+    crate::geyser_plugin_filter::{GeyserPluginFilter, GeyserPluginFilterStats},
     agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
     jsonrpc_core::{ErrorCode, Result as JsonRpcResult},
     libloading::Library,
     log::*,
+    solana_transaction::versioned::VersionedTransaction,
+    solana_transaction_status::TransactionStatusMeta,
     std::{
         ops::{Deref, DerefMut},
         path::Path,
@@ -14,6 +17,8 @@ use {
 pub struct LoadedGeyserPlugin {
     name: String,
     plugin: Box<dyn GeyserPlugin>,
+    filter: GeyserPluginFilter,
+    filter_stats: GeyserPluginFilterStats,
     // NOTE: While we do not access the library, the plugin we have loaded most
     // certainly does. To ensure we don't SIGSEGV we must declare the library
     // after the plugin so the plugin is dropped first.
@@ -27,10 +32,17 @@ pub struct LoadedGeyserPlugin {
 }
 
 impl LoadedGeyserPlugin {
-    pub fn new(library: Library, plugin: Box<dyn GeyserPlugin>, name: Option<String>) -> Self {
+    pub fn new(
+        library: Library,
+        plugin: Box<dyn GeyserPlugin>,
+        name: Option<String>,
+        filter: GeyserPluginFilter,
+    ) -> Self {
         Self {
             name: name.unwrap_or_else(|| plugin.name().to_owned()),
             plugin,
+            filter,
+            filter_stats: GeyserPluginFilterStats::default(),
             library,
         }
     }
@@ -38,6 +50,29 @@ impl LoadedGeyserPlugin {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// Whether an update to the account `pubkey` owned by `owner` passes the plugin's filter
+    pub fn should_notify_account(&self, pubkey: &[u8], owner: &[u8]) -> bool {
+        let delivered = self.filter.allows_account(pubkey, owner);
+        self.filter_stats.record_account(delivered);
+        self.filter_stats.maybe_report(&self.name);
+        delivered
+    }
+
+    /// Whether a transaction passes the plugin's filter
+    pub fn should_notify_transaction(
+        &self,
+        is_vote: bool,
+        transaction_status_meta: &TransactionStatusMeta,
+        transaction: &VersionedTransaction,
+    ) -> bool {
+        let delivered =
+            self.filter
+                .allows_transaction(is_vote, transaction_status_meta, transaction);
+        self.filter_stats.record_transaction(delivered);
+        self.filter_stats.maybe_report(&self.name);
+        delivered
+    }
 }
 
 impl Deref for LoadedGeyserPlugin {
@@ -89,6 +124,19 @@ impl GeyserPluginManager {
         false
     }
 
+    /// Check if there is any plugin interested in account pre-state. Only plugins that are also
+    /// interested in account data count, since the pre-state is passed with account updates.
+    pub fn account_pre_state_notifications_enabled(&self) -> bool {
+        for plugin in &self.plugins {
+            if plugin.account_data_notifications_enabled()
+                && plugin.account_pre_state_notifications_enabled()
+            {
+                return true;
+            }
+        }
+        false
+    }
+
     /// Check if there is any plugin interested in transaction data
     pub fn transaction_notifications_enabled(&self) -> bool {
         for plugin in &self.plugins {
@@ -331,6 +379,9 @@ pub enum GeyserPluginManagerError {
 
     #[error("The GeyserPlugin on_load method failed (error: {0})")]
     PluginStartError(String),
+
+    #[error("The filters in the plugin config file are invalid (error: {0})")]
+    InvalidFilterConfig(String),
 }
 
 /// # Safety
@@ -389,6 +440,7 @@ pub(crate) fn load_plugin_from_config(
     }
 
     let plugin_name = result["name"].as_str().map(|s| s.to_owned());
+    let filter = GeyserPluginFilter::from_config(&result["filters"])?;
 
     let config_file = geyser_plugin_config_file
         .as_os_str()
@@ -405,7 +457,7 @@ pub(crate) fn load_plugin_from_config(
         (Box::from_raw(plugin_raw), lib)
     };
     Ok((
-        LoadedGeyserPlugin::new(lib, plugin, plugin_name),
+        LoadedGeyserPlugin::new(lib, plugin, plugin_name, filter),
         config_file,
     ))
 }
@@ -444,8 +496,11 @@ pub(crate) fn load_plugin_from_config(
 #[cfg(test)]
 mod tests {
     use {
-        crate::geyser_plugin_manager::{
-            GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+        crate::{
+            geyser_plugin_filter::GeyserPluginFilter,
+            geyser_plugin_manager::{
+                GeyserPluginManager, LoadedGeyserPlugin, TESTPLUGIN2_CONFIG, TESTPLUGIN_CONFIG,
+            },
         },
         agave_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin,
         libloading::Library,
@@ -461,7 +516,12 @@ mod tests {
         #[cfg(windows)]
         let library = libloading::os::windows::Library::this().unwrap();
         (
-            LoadedGeyserPlugin::new(Library::from(library), Box::new(plugin), None),
+            LoadedGeyserPlugin::new(
+                Library::from(library),
+                Box::new(plugin),
+                None,
+                GeyserPluginFilter::default(),
+            ),
             config_path,
         )
     }
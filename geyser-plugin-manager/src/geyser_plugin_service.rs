@@ -54,6 +54,8 @@ impl GeyserPluginService {
     ///   (.so file) to be loaded. The shared library must implement the `GeyserPlugin`
     ///   trait. And the shared library shall export a `C` function `_create_plugin` which
     ///   shall create the implementation of `GeyserPlugin` and returns to the caller.
+    ///   The optional `filters` field restricts the account updates and transactions passed
+    ///   to the plugin, see `GeyserPluginFilter`.
     ///   The rest of the JSON fields' definition is up to to the concrete plugin implementation
     ///   It is usually used to configure the connection information for the external data store.
     pub fn new(
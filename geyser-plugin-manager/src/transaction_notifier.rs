@@ -49,7 +49,9 @@ impl TransactionNotifier for TransactionNotifierImpl {
         }
 
         for plugin in plugin_manager.plugins.iter() {
-            if !plugin.transaction_notifications_enabled() {
+            if !plugin.transaction_notifications_enabled()
+                || !plugin.should_notify_transaction(is_vote, transaction_status_meta, transaction)
+            {
                 continue;
             }
             match plugin.notify_transaction(
@@ -210,8 +210,12 @@ pub fn parse_process_options(ledger_path: &Path, arg_matches: &ArgMatches<'_>) -
     let new_hard_forks = hardforks_of(arg_matches, "hard_forks");
     let accounts_db_config = get_accounts_db_config(ledger_path, arg_matches);
     let log_messages_bytes_limit = value_t!(arg_matches, "log_messages_bytes_limit", usize).ok();
+    let record_account_deltas_slots = values_t!(arg_matches, "record_account_deltas", Slot)
+        .map(|slots| slots.into_iter().collect())
+        .unwrap_or_default();
     let runtime_config = RuntimeConfig {
         log_messages_bytes_limit,
+        record_account_deltas_slots,
         ..RuntimeConfig::default()
     };
 
@@ -0,0 +1,329 @@
+use {
+    crate::output::{CliAccountDeltaDiff, CliBankHashDetailsDiff, CliSlotDetailsDiff},
+    clap::{value_t_or_exit, ArgMatches},
+    itertools::{EitherOrBoth, Itertools},
+    solana_cli_output::OutputFormat,
+    solana_runtime::bank::bank_hash_details::{
+        AccountDeltaDetails, AccountStateDetails, BankHashDetails, SlotDetails,
+    },
+    std::{collections::BTreeMap, fs::File, io::BufReader, path::Path, process::exit},
+};
+
+fn load_bank_hash_details_or_exit(path: &Path) -> BankHashDetails {
+    let file = File::open(path).unwrap_or_else(|err| {
+        eprintln!("Unable to read file: {}: {err:#}", path.display());
+        exit(1);
+    });
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|err| {
+        eprintln!(
+            "Error loading bank hash details file: {}: {err:#}",
+            path.display()
+        );
+        exit(1);
+    })
+}
+
+/// Returns the accounts written in a slot keyed by pubkey, and whether the
+/// account deltas were recorded for the slot
+///
+/// If the account deltas were not recorded, only the post state of each
+/// account is known.
+fn account_deltas_of(slot_details: &SlotDetails) -> (BTreeMap<String, AccountDeltaDetails>, bool) {
+    let Some(components) = &slot_details.bank_hash_components else {
+        return (BTreeMap::new(), false);
+    };
+    if !components.account_deltas.is_empty() {
+        let account_deltas = components
+            .account_deltas
+            .iter()
+            .map(|account_delta| (account_delta.pubkey.clone(), account_delta.clone()))
+            .collect();
+        return (account_deltas, true);
+    }
+    let account_deltas = components
+        .accounts
+        .accounts
+        .iter()
+        .map(|(pubkey, account)| {
+            let account_delta = AccountDeltaDetails {
+                pubkey: pubkey.to_string(),
+                pre_state: None,
+                post_state: AccountStateDetails::from(account),
+                writers: Vec::new(),
+                lt_hash_checksum: String::new(),
+            };
+            (account_delta.pubkey.clone(), account_delta)
+        })
+        .collect();
+    (account_deltas, false)
+}
+
+/// Returns the names of the fields that differ between two versions of an account
+fn diff_account_states(
+    prefix: &str,
+    left: Option<&AccountStateDetails>,
+    right: Option<&AccountStateDetails>,
+) -> Vec<String> {
+    match (left, right) {
+        (None, None) => vec![],
+        (Some(_), None) | (None, Some(_)) => vec![prefix.to_string()],
+        (Some(left), Some(right)) => [
+            ("lamports", left.lamports != right.lamports),
+            ("owner", left.owner != right.owner),
+            ("executable", left.executable != right.executable),
+            ("data", left.data != right.data),
+        ]
+        .into_iter()
+        .filter(|(_field, mismatch)| *mismatch)
+        .map(|(field, _mismatch)| format!("{prefix}.{field}"))
+        .collect(),
+    }
+}
+
+/// Compare the details of the same slot from two bank hash details files
+pub fn diff_slot_details(left: &SlotDetails, right: &SlotDetails) -> CliSlotDetailsDiff {
+    let mut mismatched_components = vec![];
+    if left.bank_hash != right.bank_hash {
+        mismatched_components.push("bankHash".to_string());
+    }
+    if let (Some(left), Some(right)) = (&left.bank_hash_components, &right.bank_hash_components) {
+        for (component, mismatch) in [
+            (
+                "parentBankHash",
+                left.parent_bank_hash != right.parent_bank_hash,
+            ),
+            (
+                "signatureCount",
+                left.signature_count != right.signature_count,
+            ),
+            ("lastBlockhash", left.last_blockhash != right.last_blockhash),
+            (
+                "accountsLtHashChecksum",
+                left.accounts_lt_hash_checksum != right.accounts_lt_hash_checksum,
+            ),
+        ] {
+            if mismatch {
+                mismatched_components.push(component.to_string());
+            }
+        }
+    }
+
+    let (left_accounts, left_has_deltas) = account_deltas_of(left);
+    let (right_accounts, right_has_deltas) = account_deltas_of(right);
+    // The pre state, writers and lt hash can only be compared if both files recorded them
+    let compare_deltas = left_has_deltas && right_has_deltas;
+    let accounts = left_accounts
+        .into_iter()
+        .merge_join_by(right_accounts, |(left_pubkey, _), (right_pubkey, _)| {
+            left_pubkey.cmp(right_pubkey)
+        })
+        .filter_map(|item| {
+            let (pubkey, left, right) = match item {
+                EitherOrBoth::Left((pubkey, left)) => (pubkey, Some(left), None),
+                EitherOrBoth::Right((pubkey, right)) => (pubkey, None, Some(right)),
+                EitherOrBoth::Both((pubkey, left), (_, right)) => (pubkey, Some(left), Some(right)),
+            };
+            let mismatched_fields = match (&left, &right) {
+                (Some(_), None) => vec!["missingRight".to_string()],
+                (None, Some(_)) => vec!["missingLeft".to_string()],
+                (Some(left), Some(right)) => {
+                    let mut mismatched_fields = vec![];
+                    if compare_deltas {
+                        mismatched_fields.extend(diff_account_states(
+                            "preState",
+                            left.pre_state.as_ref(),
+                            right.pre_state.as_ref(),
+                        ));
+                    }
+                    mismatched_fields.extend(diff_account_states(
+                        "postState",
+                        Some(&left.post_state),
+                        Some(&right.post_state),
+                    ));
+                    if compare_deltas {
+                        if left.writers != right.writers {
+                            mismatched_fields.push("writers".to_string());
+                        }
+                        if left.lt_hash_checksum != right.lt_hash_checksum {
+                            mismatched_fields.push("ltHash".to_string());
+                        }
+                    }
+                    mismatched_fields
+                }
+                (None, None) => unreachable!(),
+            };
+            (!mismatched_fields.is_empty()).then_some(CliAccountDeltaDiff {
+                pubkey,
+                mismatched_fields,
+                left,
+                right,
+            })
+        })
+        .collect();
+
+    CliSlotDetailsDiff {
+        slot: left.slot,
+        left_bank_hash: Some(left.bank_hash.clone()),
+        right_bank_hash: Some(right.bank_hash.clone()),
+        mismatched_components,
+        accounts,
+    }
+}
+
+/// Compare two bank hash details files, slot by slot
+pub fn diff_bank_hash_details(
+    left: &BankHashDetails,
+    right: &BankHashDetails,
+) -> CliBankHashDetailsDiff {
+    let left_slots: BTreeMap<_, _> = left
+        .bank_hash_details
+        .iter()
+        .map(|details| (details.slot, details))
+        .collect();
+    let right_slots: BTreeMap<_, _> = right
+        .bank_hash_details
+        .iter()
+        .map(|details| (details.slot, details))
+        .collect();
+
+    let slots = left_slots
+        .into_iter()
+        .merge_join_by(right_slots, |(left_slot, _), (right_slot, _)| {
+            left_slot.cmp(right_slot)
+        })
+        .filter_map(|item| match item {
+            EitherOrBoth::Left((slot, left)) => Some(CliSlotDetailsDiff {
+                slot,
+                left_bank_hash: Some(left.bank_hash.clone()),
+                right_bank_hash: None,
+                mismatched_components: vec!["missingRight".to_string()],
+                accounts: vec![],
+            }),
+            EitherOrBoth::Right((slot, right)) => Some(CliSlotDetailsDiff {
+                slot,
+                left_bank_hash: None,
+                right_bank_hash: Some(right.bank_hash.clone()),
+                mismatched_components: vec!["missingLeft".to_string()],
+                accounts: vec![],
+            }),
+            EitherOrBoth::Both((_, left), (_, right)) => {
+                let diff = diff_slot_details(left, right);
+                (!diff.mismatched_components.is_empty() || !diff.accounts.is_empty())
+                    .then_some(diff)
+            }
+        })
+        .collect();
+
+    CliBankHashDetailsDiff { slots }
+}
+
+pub fn bank_hash_details_diff(arg_matches: &ArgMatches<'_>) {
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    let left_path = value_t_or_exit!(arg_matches, "left", String);
+    let right_path = value_t_or_exit!(arg_matches, "right", String);
+
+    let left = load_bank_hash_details_or_exit(Path::new(&left_path));
+    let right = load_bank_hash_details_or_exit(Path::new(&right_path));
+
+    let diff = diff_bank_hash_details(&left, &right);
+    println!("{}", output_format.formatted_string(&diff));
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_runtime::bank::bank_hash_details::{AccountsDetails, BankHashComponents},
+    };
+
+    fn account_state(lamports: u64) -> AccountStateDetails {
+        AccountStateDetails {
+            owner: "11111111111111111111111111111111".to_string(),
+            lamports,
+            executable: false,
+            data: String::new(),
+        }
+    }
+
+    fn account_delta(pubkey: &str, pre_lamports: u64, post_lamports: u64) -> AccountDeltaDetails {
+        AccountDeltaDetails {
+            pubkey: pubkey.to_string(),
+            pre_state: Some(account_state(pre_lamports)),
+            post_state: account_state(post_lamports),
+            writers: vec!["signature".to_string()],
+            lt_hash_checksum: format!("{pre_lamports}-{post_lamports}"),
+        }
+    }
+
+    fn slot_details(
+        slot: u64,
+        bank_hash: &str,
+        account_deltas: Vec<AccountDeltaDetails>,
+    ) -> SlotDetails {
+        SlotDetails {
+            slot,
+            bank_hash: bank_hash.to_string(),
+            bank_hash_components: Some(BankHashComponents {
+                parent_bank_hash: "parent".to_string(),
+                signature_count: 1,
+                last_blockhash: "blockhash".to_string(),
+                accounts_lt_hash_checksum: bank_hash.to_string(),
+                accounts: AccountsDetails::default(),
+                account_deltas,
+            }),
+            transactions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_diff_bank_hash_details() {
+        let left = BankHashDetails::new(vec![
+            slot_details(1, "same", vec![account_delta("a", 1, 2)]),
+            slot_details(
+                2,
+                "left",
+                vec![account_delta("a", 2, 3), account_delta("b", 1, 1)],
+            ),
+            slot_details(3, "only-left", vec![]),
+        ]);
+        let right = BankHashDetails::new(vec![
+            slot_details(1, "same", vec![account_delta("a", 1, 2)]),
+            slot_details(
+                2,
+                "right",
+                vec![account_delta("a", 2, 4), account_delta("c", 0, 1)],
+            ),
+        ]);
+
+        let diff = diff_bank_hash_details(&left, &right);
+        assert_eq!(
+            diff.slots.iter().map(|slot| slot.slot).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+
+        let slot_diff = &diff.slots[0];
+        assert_eq!(
+            slot_diff.mismatched_components,
+            vec!["bankHash", "accountsLtHashChecksum"]
+        );
+        let accounts: Vec<_> = slot_diff
+            .accounts
+            .iter()
+            .map(|account| (account.pubkey.as_str(), account.mismatched_fields.clone()))
+            .collect();
+        assert_eq!(
+            accounts,
+            vec![
+                (
+                    "a",
+                    vec!["postState.lamports".to_string(), "ltHash".to_string()]
+                ),
+                ("b", vec!["missingRight".to_string()]),
+                ("c", vec!["missingLeft".to_string()]),
+            ]
+        );
+
+        assert_eq!(diff.slots[1].mismatched_components, vec!["missingRight"]);
+        assert_eq!(diff.slots[1].right_bank_hash, None);
+    }
+}
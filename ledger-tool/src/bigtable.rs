@@ -8,7 +8,7 @@ use {
             encode_confirmed_block, CliBlockWithEntries, CliEntries,
             EncodedConfirmedBlockWithEntries,
         },
-        parse_process_options, LoadAndProcessLedgerOutput,
+        parse_process_options, LoadAndProcessLedgerOutput, LEDGER_TOOL_DIRECTORY,
     },
     clap::{
         value_t, value_t_or_exit, values_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand,
@@ -30,7 +30,7 @@ use {
     solana_hash::Hash,
     solana_keypair::keypair_from_seed,
     solana_ledger::{
-        bigtable_upload::ConfirmedBlockUploadConfig,
+        bigtable_upload::{verify_uploaded_blocks, ConfirmedBlockUploadConfig, UploadCheckpoint},
         blockstore::Blockstore,
         blockstore_options::AccessType,
         shred::{ProcessShredsStats, ReedSolomonCache, Shred, Shredder},
@@ -43,7 +43,7 @@ use {
     std::{
         cmp::min,
         collections::HashSet,
-        path::Path,
+        path::{Path, PathBuf},
         process::exit,
         result::Result,
         str::FromStr,
@@ -51,11 +51,17 @@ use {
     },
 };
 
+/// Uploads the blocks from `starting_slot` to `ending_slot`. If `checkpoint_path`
+/// is provided, each uploaded range is verified against the blockstore and
+/// recorded in the checkpoint, and with `resume` the ranges already recorded
+/// are skipped.
 async fn upload(
     blockstore: Blockstore,
     starting_slot: Option<Slot>,
     ending_slot: Option<Slot>,
     force_reupload: bool,
+    checkpoint_path: Option<PathBuf>,
+    resume: bool,
     config: solana_storage_bigtable::LedgerStorageConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let bigtable = solana_storage_bigtable::LedgerStorage::new_with_config(config)
@@ -79,9 +85,25 @@ async fn upload(
 
     let ending_slot = ending_slot.unwrap_or_else(|| blockstore.max_root());
 
+    let mut checkpoint = checkpoint_path
+        .as_deref()
+        .map(UploadCheckpoint::load)
+        .transpose()?;
+
     while starting_slot <= ending_slot {
+        let mut current_ending_slot = ending_slot;
+        if let Some(checkpoint) = checkpoint.as_ref().filter(|_| resume) {
+            starting_slot = checkpoint.first_unverified_slot(starting_slot);
+            if starting_slot > ending_slot {
+                break;
+            }
+            // Stop before the next verified range so that it is not checked again
+            if let Some(next_verified_range) = checkpoint.next_verified_range(starting_slot) {
+                current_ending_slot = min(current_ending_slot, next_verified_range.first_slot - 1);
+            }
+        }
         let current_ending_slot = min(
-            ending_slot,
+            current_ending_slot,
             starting_slot.saturating_add(config.max_num_slots_to_check as u64 * 2),
         );
         let last_slot_checked = solana_ledger::bigtable_upload::upload_confirmed_blocks(
@@ -94,6 +116,29 @@ async fn upload(
         )
         .await?;
         info!("last slot checked: {last_slot_checked}");
+
+        if let (Some(checkpoint), Some(checkpoint_path)) = (&mut checkpoint, &checkpoint_path) {
+            let uploaded_range = verify_uploaded_blocks(
+                &blockstore,
+                &bigtable,
+                starting_slot,
+                last_slot_checked,
+                checkpoint.content_hash_seed(starting_slot),
+            )
+            .await?;
+            if let Some(num_blocks) = uploaded_range.num_blocks {
+                info!(
+                    "Verified {num_blocks} blocks from slot {starting_slot} to {last_slot_checked}"
+                );
+            }
+            checkpoint.record(uploaded_range);
+            checkpoint.save(checkpoint_path).map_err(|err| {
+                format!(
+                    "Failed to save upload checkpoint {}: {err}",
+                    checkpoint_path.display()
+                )
+            })?;
+        }
         starting_slot = last_slot_checked.saturating_add(1);
     }
     info!("No more blocks to upload.");
@@ -914,6 +959,20 @@ impl BigTableSubCommand for App<'_, '_> {
                         .default_value(solana_storage_bigtable::DEFAULT_APP_PROFILE_ID)
                         .help("Bigtable application profile id to use in requests"),
                 )
+                .arg(
+                    Arg::with_name("storage_url")
+                        .global(true)
+                        .long("storage-url")
+                        .takes_value(true)
+                        .value_name("URL")
+                        .help(
+                            "Use the ledger stored in an object store instead of BigTable: \
+                             file://<PATH> for a local directory, or s3://<BUCKET>[/<PREFIX>] \
+                             for an S3-compatible bucket configured by the AWS_ACCESS_KEY_ID, \
+                             AWS_SECRET_ACCESS_KEY, AWS_REGION and AWS_ENDPOINT_URL environment \
+                             variables. Not supported by the copy command",
+                        ),
+                )
                 .subcommand(
                     SubCommand::with_name("upload")
                         .about("Upload the ledger to BigTable")
@@ -946,6 +1005,36 @@ impl BigTableSubCommand for App<'_, '_> {
                                      instance. Note: reupload will *not* delete any data from the \
                                      tx-by-addr table; Use with care.",
                                 ),
+                        )
+                        .arg(
+                            Arg::with_name("verify")
+                                .long("verify")
+                                .takes_value(false)
+                                .help(
+                                    "After uploading each range of slots, verify that BigTable \
+                                     holds the same blocks as the ledger by comparing the block \
+                                     count and a content hash, and record the verified range in \
+                                     the checkpoint file",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("resume")
+                                .long("resume")
+                                .takes_value(false)
+                                .help(
+                                    "Skip the ranges of slots recorded as verified in the \
+                                     checkpoint file. Implies --verify",
+                                ),
+                        )
+                        .arg(
+                            Arg::with_name("checkpoint_file")
+                                .long("checkpoint-file")
+                                .value_name("PATH")
+                                .takes_value(true)
+                                .help(
+                                    "File that records the verified ranges of slots [default: \
+                                     <LEDGER>/ledger_tool/bigtable_upload_checkpoint.json]",
+                                ),
                         ),
                 )
                 .subcommand(
@@ -1366,21 +1455,35 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
         "rpc_bigtable_app_profile_id",
         solana_storage_bigtable::DEFAULT_APP_PROFILE_ID,
     );
+    let storage_url = sub_matches
+        .and_then(|sub_matches| sub_matches.value_of("storage_url"))
+        .or_else(|| matches.value_of("storage_url"))
+        .map(str::to_string);
 
     let future = match (subcommand, sub_matches) {
         ("upload", Some(arg_matches)) => {
             let starting_slot = value_t!(arg_matches, "starting_slot", Slot).ok();
             let ending_slot = value_t!(arg_matches, "ending_slot", Slot).ok();
             let force_reupload = arg_matches.is_present("force_reupload");
-            let blockstore = crate::open_blockstore(
-                &canonicalize_ledger_path(ledger_path),
-                arg_matches,
-                AccessType::ReadOnly,
-            );
+            let resume = arg_matches.is_present("resume");
+            let ledger_path = canonicalize_ledger_path(ledger_path);
+            let checkpoint_path = (resume || arg_matches.is_present("verify")).then(|| {
+                arg_matches
+                    .value_of("checkpoint_file")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|| {
+                        ledger_path
+                            .join(LEDGER_TOOL_DIRECTORY)
+                            .join("bigtable_upload_checkpoint.json")
+                    })
+            });
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly);
             let config = solana_storage_bigtable::LedgerStorageConfig {
                 read_only: false,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(upload(
@@ -1388,6 +1491,8 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 starting_slot,
                 ending_slot,
                 force_reupload,
+                checkpoint_path,
+                resume,
                 config,
             ))
         }
@@ -1397,6 +1502,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: !arg_matches.is_present("force"),
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(delete_slots(slots, config))
@@ -1406,6 +1512,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(first_available_block(config))
@@ -1417,6 +1524,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(block(slot, output_format, show_entries, config))
@@ -1427,6 +1535,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
             runtime.block_on(entries(slot, output_format, config))
@@ -1471,6 +1580,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1489,6 +1599,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1501,6 +1612,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1534,6 +1646,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
@@ -1554,6 +1667,7 @@ pub fn bigtable_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) {
                 read_only: true,
                 instance_name,
                 app_profile_id,
+                storage_url,
                 ..solana_storage_bigtable::LedgerStorageConfig::default()
             };
 
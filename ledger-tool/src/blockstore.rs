@@ -3,9 +3,12 @@
 use {
     crate::{
         error::{LedgerToolError, Result},
+        export::BlockExporter,
         ledger_path::canonicalize_ledger_path,
         ledger_utils::get_program_ids,
-        output::{output_ledger, output_slot, CliDuplicateSlotProof, SlotBounds, SlotInfo},
+        output::{
+            follow_ledger, output_ledger, output_slot, CliDuplicateSlotProof, SlotBounds, SlotInfo,
+        },
     },
     chrono::{DateTime, Utc},
     clap::{
@@ -15,7 +18,10 @@ use {
     log::*,
     regex::Regex,
     serde_json::json,
-    solana_clap_utils::{hidden_unless_forced, input_validators::is_slot},
+    solana_clap_utils::{
+        hidden_unless_forced,
+        input_validators::{is_parsable, is_slot},
+    },
     solana_cli_output::OutputFormat,
     solana_clock::{Slot, UnixTimestamp},
     solana_hash::Hash,
@@ -31,10 +37,10 @@ use {
     std::{
         borrow::Cow,
         collections::{BTreeMap, BTreeSet, HashMap},
-        fs::File,
+        fs::{self, File},
         io::{stdout, BufRead, BufReader, Write},
         path::{Path, PathBuf},
-        sync::atomic::AtomicBool,
+        sync::{atomic::AtomicBool, Arc},
         time::{Duration, UNIX_EPOCH},
     },
 };
@@ -133,6 +139,22 @@ fn analyze_storage(blockstore: &Blockstore) -> Result<()> {
     analyze_column(blockstore, OptimisticSlots::NAME)
 }
 
+fn analyze_compression(blockstore: &Blockstore) -> Result<()> {
+    for column_name in Blockstore::column_names() {
+        let stats = blockstore.column_compression_stats(column_name)?;
+        let json_result = json!({
+            "column":column_name,
+            "entries":stats.num_entries,
+            "raw_key_bytes":stats.raw_key_size,
+            "raw_value_bytes":stats.raw_value_size,
+            "compressed_bytes":stats.data_block_size,
+            "compression_ratio":stats.compression_ratio(),
+        });
+        println!("{}", serde_json::to_string_pretty(&json_result)?);
+    }
+    Ok(())
+}
+
 fn raw_key_to_slot(key: &[u8], column_name: &str) -> Option<Slot> {
     use solana_ledger::blockstore::column::columns as cf;
     match column_name {
@@ -303,6 +325,12 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
         .help("Output dead slots as well");
 
     vec![
+        SubCommand::with_name("analyze-compression")
+            .about(
+                "Output the compression ratio of all column families in the ledger rocksdb, in \
+                 JSON format. Only data that has been flushed to SST files is accounted for",
+            )
+            .settings(&hidden),
         SubCommand::with_name("analyze-storage")
             .about(
                 "Output statistics in JSON format about all column families in the ledger rocksdb",
@@ -341,6 +369,37 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
             .about("Print all the duplicate slots in the ledger")
             .settings(&hidden)
             .arg(&starting_slot_arg),
+        SubCommand::with_name("export")
+            .about(
+                "Export the rooted blocks in a slot range to Parquet files of blocks, \
+                 transactions, instructions, token balances and rewards",
+            )
+            .settings(&hidden)
+            .arg(&starting_slot_arg)
+            .arg(&ending_slot_arg)
+            .arg(
+                Arg::with_name("output_directory")
+                    .long("output-directory")
+                    .value_name("DIR")
+                    .takes_value(true)
+                    .required(true)
+                    .help(
+                        "Directory to write the Parquet files to. Existing files of the exported \
+                         tables are overwritten",
+                    ),
+            )
+            .arg(
+                Arg::with_name("batch_size")
+                    .long("batch-size")
+                    .value_name("ROWS")
+                    .takes_value(true)
+                    .validator(is_parsable::<usize>)
+                    .default_value("100000")
+                    .help(
+                        "Number of rows of each table to buffer in memory before they are written \
+                         out as a Parquet row group",
+                    ),
+            ),
         SubCommand::with_name("latest-optimistic-slots")
             .about(
                 "Output up to the most recent <num-slots> optimistic slots with their hashes and \
@@ -437,6 +496,17 @@ pub fn blockstore_subcommands<'a, 'b>(hidden: bool) -> Vec<App<'a, 'b>> {
                     .long("only-rooted")
                     .takes_value(false)
                     .help("Only print root slots"),
+            )
+            .arg(
+                Arg::with_name("follow")
+                    .long("follow")
+                    .takes_value(false)
+                    .help(
+                        "Open the blockstore with secondary access and, after printing the \
+                         existing root slots, keep printing new root slots as they are rooted. \
+                         The blockstore may be in use by a running validator. Printing stops once \
+                         --ending-slot or --num-slots is reached",
+                    ),
             ),
         SubCommand::with_name("print-file-metadata")
             .about(
@@ -596,6 +666,9 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
     let verbose_level = matches.occurrences_of("verbose");
 
     match matches.subcommand() {
+        ("analyze-compression", Some(arg_matches)) => analyze_compression(
+            &crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly),
+        )?,
         ("analyze-storage", Some(arg_matches)) => analyze_storage(&crate::open_blockstore(
             &ledger_path,
             arg_matches,
@@ -706,6 +779,43 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
                 }
             }
         }
+        ("export", Some(arg_matches)) => {
+            let starting_slot = value_t_or_exit!(arg_matches, "starting_slot", Slot);
+            let ending_slot = value_t!(arg_matches, "ending_slot", Slot).unwrap_or(Slot::MAX);
+            let output_directory =
+                PathBuf::from(value_t_or_exit!(arg_matches, "output_directory", String));
+            let batch_size = value_t_or_exit!(arg_matches, "batch_size", usize);
+
+            let blockstore =
+                crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly);
+            fs::create_dir_all(&output_directory)?;
+            let mut exporter = BlockExporter::new(&output_directory, batch_size)?;
+            for slot in blockstore.rooted_slot_iterator(starting_slot)? {
+                if slot > ending_slot {
+                    break;
+                }
+                match blockstore.get_rooted_block(slot, false) {
+                    Ok(block) => exporter.export_block(slot, &block)?,
+                    // Roots may be missing their block, such as the root the
+                    // ledger was started from
+                    Err(BlockstoreError::SlotUnavailable) => {
+                        warn!("Skipping slot {slot}, its block is not available");
+                    }
+                    Err(err) => return Err(err.into()),
+                }
+            }
+            let summary = exporter.finish()?;
+            println!(
+                "Exported {} blocks, {} transactions, {} instructions, {} token balances and {} \
+                 rewards to {}",
+                summary.num_blocks,
+                summary.num_transactions,
+                summary.num_instructions,
+                summary.num_token_balances,
+                summary.num_rewards,
+                output_directory.display(),
+            );
+        }
         ("latest-optimistic-slots", Some(arg_matches)) => {
             let blockstore =
                 crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly);
@@ -834,16 +944,30 @@ fn do_blockstore_process_command(ledger_path: &Path, matches: &ArgMatches<'_>) -
             let only_rooted = arg_matches.is_present("only_rooted");
             let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
 
-            output_ledger(
-                crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly),
-                starting_slot,
-                ending_slot,
-                allow_dead_slots,
-                output_format,
-                num_slots,
-                verbose_level,
-                only_rooted,
-            )?;
+            if arg_matches.is_present("follow") {
+                let blockstore =
+                    crate::open_blockstore(&ledger_path, arg_matches, AccessType::Secondary);
+                follow_ledger(
+                    Arc::new(blockstore),
+                    starting_slot,
+                    ending_slot,
+                    allow_dead_slots,
+                    output_format,
+                    num_slots,
+                    verbose_level,
+                )?;
+            } else {
+                output_ledger(
+                    crate::open_blockstore(&ledger_path, arg_matches, AccessType::ReadOnly),
+                    starting_slot,
+                    ending_slot,
+                    allow_dead_slots,
+                    output_format,
+                    num_slots,
+                    verbose_level,
+                    only_rooted,
+                )?;
+            }
         }
         ("print-file-metadata", Some(arg_matches)) => {
             let blockstore =
@@ -0,0 +1,833 @@
+//! The `blockstore export` subcommand, which writes blocks out as columnar Parquet files
+//!
+//! Every exported table is written to `<table name>.parquet` in the output directory. Rows are
+//! buffered until a batch is full and then written out as a row group, so that an arbitrarily
+//! large slot range can be exported in bounded memory.
+
+use {
+    crate::error::Result,
+    arrow_array::{
+        builder::{
+            ArrayBuilder, BooleanBuilder, Int64Builder, ListBuilder, StringBuilder, UInt32Builder,
+            UInt64Builder, UInt8Builder,
+        },
+        ArrayRef, RecordBatch,
+    },
+    arrow_schema::{DataType, Field, Schema, SchemaRef},
+    parquet::{
+        arrow::ArrowWriter,
+        basic::{Compression, ZstdLevel},
+        file::properties::WriterProperties,
+    },
+    solana_clock::Slot,
+    solana_message::{compiled_instruction::CompiledInstruction, AccountKeys},
+    solana_pubkey::Pubkey,
+    solana_transaction::versioned::TransactionVersion,
+    solana_transaction_status::{
+        parse_instruction, Reward, TransactionTokenBalance, VersionedConfirmedBlock,
+        VersionedTransactionWithStatusMeta,
+    },
+    std::{collections::BTreeMap, fs::File, path::Path, sync::Arc},
+};
+
+/// The columns of an exported table, buffered until they are written out
+trait Table: Default {
+    const NAME: &'static str;
+
+    fn fields() -> Vec<Field>;
+
+    fn num_rows(&self) -> usize;
+
+    /// Take the buffered columns, leaving the table empty
+    fn finish(&mut self) -> Vec<ArrayRef>;
+}
+
+fn list_field(name: &str) -> Field {
+    Field::new_list(name, Field::new_list_field(DataType::Utf8, true), true)
+}
+
+#[derive(Default)]
+struct BlocksTable {
+    slot: UInt64Builder,
+    parent_slot: UInt64Builder,
+    blockhash: StringBuilder,
+    previous_blockhash: StringBuilder,
+    block_time: Int64Builder,
+    block_height: UInt64Builder,
+    num_transactions: UInt64Builder,
+}
+
+impl Table for BlocksTable {
+    const NAME: &'static str = "blocks";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("parent_slot", DataType::UInt64, false),
+            Field::new("blockhash", DataType::Utf8, false),
+            Field::new("previous_blockhash", DataType::Utf8, false),
+            Field::new("block_time", DataType::Int64, true),
+            Field::new("block_height", DataType::UInt64, true),
+            Field::new("num_transactions", DataType::UInt64, false),
+        ]
+    }
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.parent_slot.finish()),
+            Arc::new(self.blockhash.finish()),
+            Arc::new(self.previous_blockhash.finish()),
+            Arc::new(self.block_time.finish()),
+            Arc::new(self.block_height.finish()),
+            Arc::new(self.num_transactions.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TransactionsTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    fee_payer: StringBuilder,
+    version: StringBuilder,
+    success: BooleanBuilder,
+    err: StringBuilder,
+    fee: UInt64Builder,
+    compute_units_consumed: UInt64Builder,
+    cost_units: UInt64Builder,
+    num_instructions: UInt32Builder,
+    log_messages: ListBuilder<StringBuilder>,
+}
+
+impl Table for TransactionsTable {
+    const NAME: &'static str = "transactions";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("fee_payer", DataType::Utf8, false),
+            Field::new("version", DataType::Utf8, false),
+            Field::new("success", DataType::Boolean, false),
+            Field::new("err", DataType::Utf8, true),
+            Field::new("fee", DataType::UInt64, false),
+            Field::new("compute_units_consumed", DataType::UInt64, true),
+            Field::new("cost_units", DataType::UInt64, true),
+            Field::new("num_instructions", DataType::UInt32, false),
+            list_field("log_messages"),
+        ]
+    }
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.transaction_index.finish()),
+            Arc::new(self.signature.finish()),
+            Arc::new(self.fee_payer.finish()),
+            Arc::new(self.version.finish()),
+            Arc::new(self.success.finish()),
+            Arc::new(self.err.finish()),
+            Arc::new(self.fee.finish()),
+            Arc::new(self.compute_units_consumed.finish()),
+            Arc::new(self.cost_units.finish()),
+            Arc::new(self.num_instructions.finish()),
+            Arc::new(self.log_messages.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct InstructionsTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    instruction_index: UInt32Builder,
+    inner_instruction_index: UInt32Builder,
+    stack_height: UInt32Builder,
+    program_id: StringBuilder,
+    accounts: ListBuilder<StringBuilder>,
+    data: StringBuilder,
+    program: StringBuilder,
+    parsed: StringBuilder,
+}
+
+impl Table for InstructionsTable {
+    const NAME: &'static str = "instructions";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("instruction_index", DataType::UInt32, false),
+            Field::new("inner_instruction_index", DataType::UInt32, true),
+            Field::new("stack_height", DataType::UInt32, true),
+            Field::new("program_id", DataType::Utf8, true),
+            list_field("accounts"),
+            Field::new("data", DataType::Utf8, false),
+            Field::new("program", DataType::Utf8, true),
+            Field::new("parsed", DataType::Utf8, true),
+        ]
+    }
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.transaction_index.finish()),
+            Arc::new(self.signature.finish()),
+            Arc::new(self.instruction_index.finish()),
+            Arc::new(self.inner_instruction_index.finish()),
+            Arc::new(self.stack_height.finish()),
+            Arc::new(self.program_id.finish()),
+            Arc::new(self.accounts.finish()),
+            Arc::new(self.data.finish()),
+            Arc::new(self.program.finish()),
+            Arc::new(self.parsed.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct TokenBalancesTable {
+    slot: UInt64Builder,
+    transaction_index: UInt32Builder,
+    signature: StringBuilder,
+    account_index: UInt8Builder,
+    account: StringBuilder,
+    mint: StringBuilder,
+    owner: StringBuilder,
+    program_id: StringBuilder,
+    decimals: UInt8Builder,
+    pre_amount: UInt64Builder,
+    post_amount: UInt64Builder,
+}
+
+impl Table for TokenBalancesTable {
+    const NAME: &'static str = "token_balances";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("transaction_index", DataType::UInt32, false),
+            Field::new("signature", DataType::Utf8, false),
+            Field::new("account_index", DataType::UInt8, false),
+            Field::new("account", DataType::Utf8, true),
+            Field::new("mint", DataType::Utf8, false),
+            Field::new("owner", DataType::Utf8, true),
+            Field::new("program_id", DataType::Utf8, true),
+            Field::new("decimals", DataType::UInt8, false),
+            Field::new("pre_amount", DataType::UInt64, true),
+            Field::new("post_amount", DataType::UInt64, true),
+        ]
+    }
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.transaction_index.finish()),
+            Arc::new(self.signature.finish()),
+            Arc::new(self.account_index.finish()),
+            Arc::new(self.account.finish()),
+            Arc::new(self.mint.finish()),
+            Arc::new(self.owner.finish()),
+            Arc::new(self.program_id.finish()),
+            Arc::new(self.decimals.finish()),
+            Arc::new(self.pre_amount.finish()),
+            Arc::new(self.post_amount.finish()),
+        ]
+    }
+}
+
+#[derive(Default)]
+struct RewardsTable {
+    slot: UInt64Builder,
+    pubkey: StringBuilder,
+    lamports: Int64Builder,
+    post_balance: UInt64Builder,
+    reward_type: StringBuilder,
+    commission: UInt8Builder,
+}
+
+impl Table for RewardsTable {
+    const NAME: &'static str = "rewards";
+
+    fn fields() -> Vec<Field> {
+        vec![
+            Field::new("slot", DataType::UInt64, false),
+            Field::new("pubkey", DataType::Utf8, false),
+            Field::new("lamports", DataType::Int64, false),
+            Field::new("post_balance", DataType::UInt64, false),
+            Field::new("reward_type", DataType::Utf8, true),
+            Field::new("commission", DataType::UInt8, true),
+        ]
+    }
+
+    fn num_rows(&self) -> usize {
+        self.slot.len()
+    }
+
+    fn finish(&mut self) -> Vec<ArrayRef> {
+        vec![
+            Arc::new(self.slot.finish()),
+            Arc::new(self.pubkey.finish()),
+            Arc::new(self.lamports.finish()),
+            Arc::new(self.post_balance.finish()),
+            Arc::new(self.reward_type.finish()),
+            Arc::new(self.commission.finish()),
+        ]
+    }
+}
+
+/// Buffers the rows of a table and writes them out to its Parquet file in batches
+struct TableWriter<T: Table> {
+    table: T,
+    schema: SchemaRef,
+    writer: ArrowWriter<File>,
+    num_rows: usize,
+}
+
+impl<T: Table> TableWriter<T> {
+    fn new(output_dir: &Path, properties: WriterProperties) -> Result<Self> {
+        let schema = Arc::new(Schema::new(T::fields()));
+        let file = File::create(output_dir.join(format!("{}.parquet", T::NAME)))?;
+        let writer = ArrowWriter::try_new(file, schema.clone(), Some(properties))?;
+        Ok(Self {
+            table: T::default(),
+            schema,
+            writer,
+            num_rows: 0,
+        })
+    }
+
+    fn maybe_flush(&mut self, batch_size: usize) -> Result<()> {
+        if self.table.num_rows() >= batch_size {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        let num_rows = self.table.num_rows();
+        if num_rows == 0 {
+            return Ok(());
+        }
+        let batch = RecordBatch::try_new(self.schema.clone(), self.table.finish())?;
+        self.writer.write(&batch)?;
+        // Write the batch out as its own row group, rather than accumulating
+        // row groups in memory until the writer is closed
+        self.writer.flush()?;
+        self.num_rows += num_rows;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<usize> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(self.num_rows)
+    }
+}
+
+/// The number of rows that were exported to each table
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub num_blocks: usize,
+    pub num_transactions: usize,
+    pub num_instructions: usize,
+    pub num_token_balances: usize,
+    pub num_rewards: usize,
+}
+
+pub struct BlockExporter {
+    blocks: TableWriter<BlocksTable>,
+    transactions: TableWriter<TransactionsTable>,
+    instructions: TableWriter<InstructionsTable>,
+    token_balances: TableWriter<TokenBalancesTable>,
+    rewards: TableWriter<RewardsTable>,
+    batch_size: usize,
+}
+
+impl BlockExporter {
+    /// Create the Parquet files of all tables in `output_dir`, overwriting any existing ones.
+    /// Up to `batch_size` rows are buffered per table before they are written out.
+    pub fn new(output_dir: &Path, batch_size: usize) -> Result<Self> {
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .set_max_row_group_size(batch_size)
+            .build();
+        Ok(Self {
+            blocks: TableWriter::new(output_dir, properties.clone())?,
+            transactions: TableWriter::new(output_dir, properties.clone())?,
+            instructions: TableWriter::new(output_dir, properties.clone())?,
+            token_balances: TableWriter::new(output_dir, properties.clone())?,
+            rewards: TableWriter::new(output_dir, properties)?,
+            batch_size,
+        })
+    }
+
+    pub fn export_block(&mut self, slot: Slot, block: &VersionedConfirmedBlock) -> Result<()> {
+        let blocks = &mut self.blocks.table;
+        blocks.slot.append_value(slot);
+        blocks.parent_slot.append_value(block.parent_slot);
+        blocks.blockhash.append_value(&block.blockhash);
+        blocks
+            .previous_blockhash
+            .append_value(&block.previous_blockhash);
+        blocks.block_time.append_option(block.block_time);
+        blocks.block_height.append_option(block.block_height);
+        blocks
+            .num_transactions
+            .append_value(block.transactions.len() as u64);
+
+        for (transaction_index, transaction) in block.transactions.iter().enumerate() {
+            self.export_transaction(slot, transaction_index as u32, transaction);
+        }
+
+        for reward in &block.rewards {
+            self.export_reward(slot, reward);
+        }
+
+        self.blocks.maybe_flush(self.batch_size)?;
+        self.transactions.maybe_flush(self.batch_size)?;
+        self.instructions.maybe_flush(self.batch_size)?;
+        self.token_balances.maybe_flush(self.batch_size)?;
+        self.rewards.maybe_flush(self.batch_size)
+    }
+
+    fn export_transaction(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        transaction_with_meta: &VersionedTransactionWithStatusMeta,
+    ) {
+        let VersionedTransactionWithStatusMeta { transaction, meta } = transaction_with_meta;
+        let signature = transaction
+            .signatures
+            .first()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        let account_keys = AccountKeys::new(
+            transaction.message.static_account_keys(),
+            Some(&meta.loaded_addresses),
+        );
+        let account_key = |index: usize| account_keys.get(index).map(Pubkey::to_string);
+
+        let transactions = &mut self.transactions.table;
+        transactions.slot.append_value(slot);
+        transactions
+            .transaction_index
+            .append_value(transaction_index);
+        transactions.signature.append_value(&signature);
+        transactions
+            .fee_payer
+            .append_value(account_key(0).unwrap_or_default());
+        transactions
+            .version
+            .append_value(match transaction.version() {
+                TransactionVersion::Legacy(_) => "legacy".to_string(),
+                TransactionVersion::Number(number) => number.to_string(),
+            });
+        transactions.success.append_value(meta.status.is_ok());
+        transactions
+            .err
+            .append_option(meta.status.as_ref().err().map(ToString::to_string));
+        transactions.fee.append_value(meta.fee);
+        transactions
+            .compute_units_consumed
+            .append_option(meta.compute_units_consumed);
+        transactions.cost_units.append_option(meta.cost_units);
+        transactions
+            .num_instructions
+            .append_value(transaction.message.instructions().len() as u32);
+        transactions.log_messages.append_option(
+            meta.log_messages
+                .as_ref()
+                .map(|log_messages| log_messages.iter().map(Some)),
+        );
+
+        let inner_instructions = meta.inner_instructions.as_deref().unwrap_or_default();
+        for (instruction_index, instruction) in
+            transaction.message.instructions().iter().enumerate()
+        {
+            self.export_instruction(
+                slot,
+                transaction_index,
+                &signature,
+                &account_keys,
+                instruction_index as u32,
+                None,
+                None,
+                instruction,
+            );
+            for inner_instructions in inner_instructions
+                .iter()
+                .filter(|inner_instructions| inner_instructions.index as usize == instruction_index)
+            {
+                for (inner_instruction_index, inner_instruction) in
+                    inner_instructions.instructions.iter().enumerate()
+                {
+                    self.export_instruction(
+                        slot,
+                        transaction_index,
+                        &signature,
+                        &account_keys,
+                        instruction_index as u32,
+                        Some(inner_instruction_index as u32),
+                        inner_instruction.stack_height,
+                        &inner_instruction.instruction,
+                    );
+                }
+            }
+        }
+
+        // Report the pre and post balances of each token account in a single row
+        let mut token_balances: BTreeMap<
+            u8,
+            (
+                Option<&TransactionTokenBalance>,
+                Option<&TransactionTokenBalance>,
+            ),
+        > = BTreeMap::new();
+        for pre_token_balance in meta.pre_token_balances.iter().flatten() {
+            token_balances
+                .entry(pre_token_balance.account_index)
+                .or_default()
+                .0 = Some(pre_token_balance);
+        }
+        for post_token_balance in meta.post_token_balances.iter().flatten() {
+            token_balances
+                .entry(post_token_balance.account_index)
+                .or_default()
+                .1 = Some(post_token_balance);
+        }
+        let token_balances_table = &mut self.token_balances.table;
+        for (account_index, (pre_token_balance, post_token_balance)) in token_balances {
+            // At least one of the balances is always present
+            let Some(token_balance) = post_token_balance.or(pre_token_balance) else {
+                continue;
+            };
+            let non_empty = |value: &String| (!value.is_empty()).then(|| value.clone());
+            let amount = |token_balance: Option<&TransactionTokenBalance>| {
+                token_balance.and_then(|token_balance| {
+                    token_balance.ui_token_amount.amount.parse::<u64>().ok()
+                })
+            };
+            token_balances_table.slot.append_value(slot);
+            token_balances_table
+                .transaction_index
+                .append_value(transaction_index);
+            token_balances_table.signature.append_value(&signature);
+            token_balances_table
+                .account_index
+                .append_value(account_index);
+            token_balances_table
+                .account
+                .append_option(account_key(account_index as usize));
+            token_balances_table.mint.append_value(&token_balance.mint);
+            token_balances_table
+                .owner
+                .append_option(non_empty(&token_balance.owner));
+            token_balances_table
+                .program_id
+                .append_option(non_empty(&token_balance.program_id));
+            token_balances_table
+                .decimals
+                .append_value(token_balance.ui_token_amount.decimals);
+            token_balances_table
+                .pre_amount
+                .append_option(amount(pre_token_balance));
+            token_balances_table
+                .post_amount
+                .append_option(amount(post_token_balance));
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn export_instruction(
+        &mut self,
+        slot: Slot,
+        transaction_index: u32,
+        signature: &str,
+        account_keys: &AccountKeys,
+        instruction_index: u32,
+        inner_instruction_index: Option<u32>,
+        stack_height: Option<u32>,
+        instruction: &CompiledInstruction,
+    ) {
+        let program_id = account_keys.get(instruction.program_id_index as usize);
+        let parsed_instruction = program_id.and_then(|program_id| {
+            parse_instruction::parse(program_id, instruction, account_keys, stack_height).ok()
+        });
+
+        let instructions = &mut self.instructions.table;
+        instructions.slot.append_value(slot);
+        instructions
+            .transaction_index
+            .append_value(transaction_index);
+        instructions.signature.append_value(signature);
+        instructions
+            .instruction_index
+            .append_value(instruction_index);
+        instructions
+            .inner_instruction_index
+            .append_option(inner_instruction_index);
+        instructions.stack_height.append_option(stack_height);
+        instructions
+            .program_id
+            .append_option(program_id.map(Pubkey::to_string));
+        instructions.accounts.append_value(
+            instruction
+                .accounts
+                .iter()
+                .map(|index| account_keys.get(*index as usize).map(Pubkey::to_string)),
+        );
+        instructions
+            .data
+            .append_value(bs58::encode(&instruction.data).into_string());
+        instructions.program.append_option(
+            parsed_instruction
+                .as_ref()
+                .map(|parsed_instruction| &parsed_instruction.program),
+        );
+        instructions.parsed.append_option(
+            parsed_instruction.map(|parsed_instruction| parsed_instruction.parsed.to_string()),
+        );
+    }
+
+    fn export_reward(&mut self, slot: Slot, reward: &Reward) {
+        let rewards = &mut self.rewards.table;
+        rewards.slot.append_value(slot);
+        rewards.pubkey.append_value(&reward.pubkey);
+        rewards.lamports.append_value(reward.lamports);
+        rewards.post_balance.append_value(reward.post_balance);
+        rewards
+            .reward_type
+            .append_option(reward.reward_type.as_ref().map(ToString::to_string));
+        rewards.commission.append_option(reward.commission);
+    }
+
+    /// Write out the remaining buffered rows and finalize the Parquet files
+    pub fn finish(self) -> Result<ExportSummary> {
+        Ok(ExportSummary {
+            num_blocks: self.blocks.finish()?,
+            num_transactions: self.transactions.finish()?,
+            num_instructions: self.instructions.finish()?,
+            num_token_balances: self.token_balances.finish()?,
+            num_rewards: self.rewards.finish()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        arrow_array::{
+            cast::AsArray,
+            types::{UInt32Type, UInt64Type},
+            Array,
+        },
+        parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder,
+        solana_account_decoder::parse_token::UiTokenAmount,
+        solana_hash::Hash,
+        solana_message::{Message, MessageHeader, VersionedMessage},
+        solana_sdk_ids::system_program,
+        solana_signature::Signature,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::{
+            InnerInstruction, InnerInstructions, RewardType, TransactionStatusMeta,
+        },
+    };
+
+    fn read_table(output_dir: &Path, name: &str) -> RecordBatch {
+        let file = File::open(output_dir.join(format!("{name}.parquet"))).unwrap();
+        let batches: Vec<_> = ParquetRecordBatchReaderBuilder::try_new(file)
+            .unwrap()
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap())
+            .collect();
+        let schema = batches[0].schema();
+        arrow_select::concat::concat_batches(&schema, &batches).unwrap()
+    }
+
+    fn test_block(slot: Slot) -> VersionedConfirmedBlock {
+        let payer = Pubkey::new_from_array([1; 32]);
+        let recipient = Pubkey::new_from_array([2; 32]);
+        let mint = Pubkey::new_from_array([3; 32]);
+        let lamports = 42u64;
+        // A system program transfer instruction
+        let transfer_data = [2u32.to_le_bytes().as_slice(), &lamports.to_le_bytes()].concat();
+        let message = Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, recipient, system_program::id()],
+            recent_blockhash: Hash::default(),
+            instructions: vec![CompiledInstruction::new_from_raw_parts(
+                2,
+                transfer_data,
+                vec![0, 1],
+            )],
+        };
+        let transaction = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::Legacy(message),
+        };
+        let token_balance = |amount: &str| TransactionTokenBalance {
+            account_index: 1,
+            mint: mint.to_string(),
+            ui_token_amount: UiTokenAmount {
+                ui_amount: None,
+                decimals: 6,
+                amount: amount.to_string(),
+                ui_amount_string: String::new(),
+            },
+            owner: payer.to_string(),
+            program_id: String::new(),
+        };
+        let meta = TransactionStatusMeta {
+            fee: 5000,
+            log_messages: Some(vec!["log".to_string()]),
+            inner_instructions: Some(vec![InnerInstructions {
+                index: 0,
+                instructions: vec![InnerInstruction {
+                    instruction: CompiledInstruction::new_from_raw_parts(2, vec![], vec![0]),
+                    stack_height: Some(2),
+                }],
+            }]),
+            pre_token_balances: Some(vec![token_balance("10")]),
+            post_token_balances: Some(vec![token_balance("7")]),
+            compute_units_consumed: Some(150),
+            ..TransactionStatusMeta::default()
+        };
+        VersionedConfirmedBlock {
+            previous_blockhash: Hash::new_from_array([slot.saturating_sub(1) as u8; 32])
+                .to_string(),
+            blockhash: Hash::new_from_array([slot as u8; 32]).to_string(),
+            parent_slot: slot.saturating_sub(1),
+            transactions: vec![VersionedTransactionWithStatusMeta { transaction, meta }],
+            rewards: vec![Reward {
+                pubkey: payer.to_string(),
+                lamports: 100,
+                post_balance: 1_000,
+                reward_type: Some(RewardType::Fee),
+                commission: None,
+                commission_bps: None,
+            }],
+            num_partitions: None,
+            block_time: Some(1_700_000_000),
+            block_height: Some(slot),
+        }
+    }
+
+    #[test]
+    fn test_block_exporter() {
+        let output_dir = tempfile::tempdir().unwrap();
+        // Use a batch size smaller than the number of blocks so that the
+        // tables are written out as multiple row groups
+        let mut exporter = BlockExporter::new(output_dir.path(), 2).unwrap();
+        for slot in 1..=3 {
+            exporter.export_block(slot, &test_block(slot)).unwrap();
+        }
+        let summary = exporter.finish().unwrap();
+        assert_eq!(
+            summary,
+            ExportSummary {
+                num_blocks: 3,
+                num_transactions: 3,
+                num_instructions: 6,
+                num_token_balances: 3,
+                num_rewards: 3,
+            }
+        );
+
+        let blocks = read_table(output_dir.path(), BlocksTable::NAME);
+        assert_eq!(blocks.num_rows(), 3);
+        assert_eq!(
+            blocks
+                .column_by_name("slot")
+                .unwrap()
+                .as_primitive::<UInt64Type>()
+                .values()
+                .to_vec(),
+            vec![1, 2, 3]
+        );
+
+        let transactions = read_table(output_dir.path(), TransactionsTable::NAME);
+        assert_eq!(transactions.num_rows(), 3);
+        assert!(transactions
+            .column_by_name("success")
+            .unwrap()
+            .as_boolean()
+            .iter()
+            .all(|success| success == Some(true)));
+
+        let instructions = read_table(output_dir.path(), InstructionsTable::NAME);
+        let program = instructions
+            .column_by_name("program")
+            .unwrap()
+            .as_string::<i32>();
+        let parsed = instructions
+            .column_by_name("parsed")
+            .unwrap()
+            .as_string::<i32>();
+        let inner_instruction_index = instructions
+            .column_by_name("inner_instruction_index")
+            .unwrap()
+            .as_primitive::<UInt32Type>();
+        // The outer transfer instruction is parsed, whereas the inner
+        // instruction has no valid instruction data
+        assert_eq!(program.value(0), "system");
+        assert!(parsed.value(0).contains("transfer"));
+        assert!(inner_instruction_index.is_null(0));
+        assert!(parsed.is_null(1));
+        assert_eq!(inner_instruction_index.value(1), 0);
+
+        let token_balances = read_table(output_dir.path(), TokenBalancesTable::NAME);
+        let pre_amount = token_balances
+            .column_by_name("pre_amount")
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        let post_amount = token_balances
+            .column_by_name("post_amount")
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        assert_eq!(pre_amount.value(0), 10);
+        assert_eq!(post_amount.value(0), 7);
+        assert!(token_balances
+            .column_by_name("program_id")
+            .unwrap()
+            .is_null(0));
+
+        let rewards = read_table(output_dir.path(), RewardsTable::NAME);
+        assert_eq!(
+            rewards
+                .column_by_name("reward_type")
+                .unwrap()
+                .as_string::<i32>()
+                .value(0),
+            "fee"
+        );
+    }
+}
@@ -496,8 +496,8 @@ pub fn open_blockstore(
                 .starts_with("Invalid argument: Column family not found:");
             // The blockstore settings with Primary access can resolve the
             // above issues automatically, so only emit the help messages
-            // if access type is ReadOnly
-            let is_read_only = access_type == AccessType::ReadOnly;
+            // if access type is ReadOnly or Secondary
+            let is_read_only = matches!(access_type, AccessType::ReadOnly | AccessType::Secondary);
 
             if missing_blockstore && is_read_only {
                 eprintln!(
@@ -2,6 +2,7 @@
 use {
     crate::{
         args::*,
+        bank_hash_details_diff::bank_hash_details_diff,
         bigtable::*,
         blockstore::*,
         ledger_path::*,
@@ -11,12 +12,14 @@ use {
             SlotBankHash,
         },
         program::*,
+        snapshot_diff::snapshot_diff,
+        transaction_trace::{transaction, TransactionSubCommand},
     },
     agave_feature_set::{self as feature_set, FeatureSet},
     agave_reserved_account_keys::ReservedAccountKeys,
     agave_snapshots::{
-        snapshot_archive_info::SnapshotArchiveInfoGetter as _, ArchiveFormat, SnapshotVersion,
-        DEFAULT_ARCHIVE_COMPRESSION, SUPPORTED_ARCHIVE_COMPRESSION,
+        manifest, paths as snapshot_paths, snapshot_archive_info::SnapshotArchiveInfoGetter as _,
+        ArchiveFormat, SnapshotVersion, DEFAULT_ARCHIVE_COMPRESSION, SUPPORTED_ARCHIVE_COMPRESSION,
     },
     clap::{
         crate_description, crate_name, value_t, value_t_or_exit, values_t_or_exit, App,
@@ -102,13 +105,17 @@ use {
 };
 
 mod args;
+mod bank_hash_details_diff;
 mod bigtable;
 mod blockstore;
 mod error;
+mod export;
 mod ledger_path;
 mod ledger_utils;
 mod output;
 mod program;
+mod snapshot_diff;
+mod transaction_trace;
 
 fn render_dot(dot: String, output_file: &str, output_format: &str) -> io::Result<()> {
     let mut child = Command::new("dot")
@@ -436,6 +443,49 @@ fn graph_forks(bank_forks: &BankForks, config: &GraphConfig) -> String {
     dot.join("\n")
 }
 
+fn verify_snapshot_archive(arg_matches: &ArgMatches<'_>) {
+    let archive_path = PathBuf::from(value_t_or_exit!(arg_matches, "snapshot_archive", String));
+    let archive_format = snapshot_paths::path_to_file_name_str(&archive_path)
+        .and_then(|file_name| {
+            snapshot_paths::parse_full_snapshot_archive_filename(file_name)
+                .map(|(_slot, _hash, archive_format)| archive_format)
+                .or_else(|_| {
+                    snapshot_paths::parse_incremental_snapshot_archive_filename(file_name)
+                        .map(|(_base_slot, _slot, _hash, archive_format)| archive_format)
+                })
+        })
+        .unwrap_or_else(|err| {
+            eprintln!(
+                "Unable to determine archive format of '{}': {err}",
+                archive_path.display()
+            );
+            exit(1);
+        });
+
+    let (result, measure) = measure_time!(
+        manifest::verify_snapshot_archive(&archive_path, archive_format),
+        "verify snapshot archive"
+    );
+    match result {
+        Ok(manifest) => {
+            info!("{measure}");
+            println!(
+                "Verified {} entries ({} bytes) of '{}'",
+                manifest.entries().len(),
+                manifest.total_size(),
+                archive_path.display(),
+            );
+        }
+        Err(err) => {
+            eprintln!(
+                "Failed to verify snapshot archive '{}': {err}",
+                archive_path.display()
+            );
+            exit(1);
+        }
+    }
+}
+
 fn compute_slot_cost(
     blockstore: &Blockstore,
     slot: Slot,
@@ -1195,6 +1245,22 @@ fn main() {
                              The file will be written within <LEDGER_DIR>/bank_hash_details/",
                         ),
                 )
+                .arg(
+                    Arg::with_name("record_account_deltas")
+                        .long("record-account-deltas")
+                        .value_name("SLOT")
+                        .validator(is_slot)
+                        .takes_value(true)
+                        .multiple(true)
+                        .help(
+                            "Record every account written in SLOT, along with its state before \
+                             the slot, the transactions that wrote it and its contribution to the \
+                             accounts lt hash. The details are written to a file within \
+                             <LEDGER_DIR>/ledger_tool/bank_hash_details/ once SLOT is frozen, and \
+                             can be compared with `bank-hash-details-diff`. May be specified \
+                             multiple times",
+                        ),
+                )
                 .arg(
                     Arg::with_name("record_slots")
                         .long("record-slots")
@@ -1496,6 +1562,11 @@ fn main() {
                         .value_name("ARCHIVE_TYPE")
                         .takes_value(true)
                         .help("Snapshot archive format to use.")
+                        .long_help(
+                            "Snapshot archive format to use. `none` creates uncompressed tar \
+                             archives, which are faster to create and unpack at the expense of \
+                             disk space.",
+                        )
                         .conflicts_with("no_snapshot"),
                 )
                 .arg(
@@ -1527,6 +1598,41 @@ fn main() {
                         ),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("verify-snapshot-archive")
+                .about("Verify a snapshot archive against its manifest without unpacking it")
+                .arg(
+                    Arg::with_name("snapshot_archive")
+                        .index(1)
+                        .value_name("ARCHIVE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Path to the full or incremental snapshot archive"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("bank-hash-details-diff")
+                .about(
+                    "Compare two bank hash details files, such as those written by two nodes that \
+                     computed different bank hashes for the same slot",
+                )
+                .arg(
+                    Arg::with_name("left")
+                        .index(1)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The first bank hash details file"),
+                )
+                .arg(
+                    Arg::with_name("right")
+                        .index(2)
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("The second bank hash details file"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("simulate-block-production")
                 .about("Simulate producing blocks with banking trace event files in the ledger")
@@ -1675,6 +1781,71 @@ fn main() {
                         .help("Output file in the csv format"),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("snapshot-diff")
+                .about(
+                    "Compare the accounts of two snapshots, or of a snapshot and the bank \
+                     produced by processing the ledger",
+                )
+                .arg(&load_genesis_config_arg)
+                .args(&accounts_db_config_args)
+                .args(&snapshot_config_args)
+                .arg(&halt_at_slot_arg)
+                .arg(&hard_forks_arg)
+                .arg(&log_messages_bytes_limit_arg)
+                .arg(
+                    Arg::with_name("base_snapshot_archive")
+                        .long("base-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Full snapshot archive to use as the base of the comparison"),
+                )
+                .arg(
+                    Arg::with_name("base_incremental_snapshot_archive")
+                        .long("base-incremental-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help("Incremental snapshot archive to apply on top of the base"),
+                )
+                .arg(
+                    Arg::with_name("other_snapshot_archive")
+                        .long("other-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .help(
+                            "Full snapshot archive to compare against the base. If not specified, \
+                             the base is compared against the bank produced by processing the \
+                             ledger",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("other_incremental_snapshot_archive")
+                        .long("other-incremental-snapshot-archive")
+                        .value_name("PATH")
+                        .takes_value(true)
+                        .requires("other_snapshot_archive")
+                        .help("Incremental snapshot archive to apply on top of the other"),
+                )
+                .arg(
+                    Arg::with_name("program_accounts")
+                        .long("program-accounts")
+                        .takes_value(true)
+                        .value_name("PUBKEY")
+                        .validator(is_pubkey)
+                        .multiple(true)
+                        .help(
+                            "Limit output to accounts owned by the provided program pubkey(s), \
+                             may be specified multiple times",
+                        ),
+                )
+                .arg(
+                    Arg::with_name("include_sysvars")
+                        .long("include-sysvars")
+                        .takes_value(false)
+                        .help("Include sysvars too"),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("compute-slot-cost")
                 .about(
@@ -1696,6 +1867,7 @@ fn main() {
                 .arg(&allow_dead_slots_arg),
         )
         .program_subcommand()
+        .transaction_subcommand()
         .get_matches();
 
     let logfile = value_t!(matches, "logfile", PathBuf).ok();
@@ -1722,13 +1894,18 @@ fn main() {
         ("bigtable", Some(arg_matches)) => bigtable_process_command(&ledger_path, arg_matches),
         ("blockstore", Some(arg_matches)) => blockstore_process_command(&ledger_path, arg_matches),
         ("program", Some(arg_matches)) => program(&ledger_path, arg_matches),
+        ("transaction", Some(arg_matches)) => transaction(&ledger_path, arg_matches),
+        ("verify-snapshot-archive", Some(arg_matches)) => verify_snapshot_archive(arg_matches),
+        ("bank-hash-details-diff", Some(arg_matches)) => bank_hash_details_diff(arg_matches),
         // This match case provides legacy support for commands that were previously top level
         // subcommands of the binary, but have been moved under the blockstore subcommand.
-        ("analyze-storage", Some(_))
+        ("analyze-compression", Some(_))
+        | ("analyze-storage", Some(_))
         | ("bounds", Some(_))
         | ("copy", Some(_))
         | ("dead-slots", Some(_))
         | ("duplicate-slots", Some(_))
+        | ("export", Some(_))
         | ("latest-optimistic-slots", Some(_))
         | ("list-roots", Some(_))
         | ("parse_full_frozen", Some(_))
@@ -1744,6 +1921,7 @@ fn main() {
             let ledger_path = canonicalize_ledger_path(&ledger_path);
 
             match matches.subcommand() {
+                ("snapshot-diff", Some(arg_matches)) => snapshot_diff(&ledger_path, arg_matches),
                 ("genesis", Some(arg_matches)) => {
                     let output_format =
                         OutputFormat::from_matches(arg_matches, "output_format", false);
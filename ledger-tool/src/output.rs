@@ -4,6 +4,7 @@ use {
         ledger_utils::get_program_ids,
     },
     chrono::{Local, TimeZone},
+    crossbeam_channel::unbounded,
     itertools::Either,
     pretty_hex::PrettyHex,
     serde::{
@@ -23,17 +24,21 @@ use {
     solana_hash::Hash,
     solana_ledger::{
         blockstore::{Blockstore, BlockstoreError},
+        blockstore_follower_service::{BlockstoreFollowerService, DEFAULT_CATCH_UP_INTERVAL},
         blockstore_meta::{DuplicateSlotProof, ErasureMeta},
         shred::{Shred, ShredType},
     },
     solana_pubkey::Pubkey,
-    solana_runtime::bank::Bank,
+    solana_runtime::bank::{
+        bank_hash_details::{AccountDeltaDetails, AccountStateDetails},
+        Bank,
+    },
     solana_transaction::versioned::VersionedTransaction,
     solana_transaction_status::{
         BlockEncodingOptions, ConfirmedBlock, Encodable, EncodedConfirmedBlock,
         EncodedTransactionWithStatusMeta, EntrySummary, Rewards, TransactionDetails,
-        UiTransactionEncoding, VersionedConfirmedBlock, VersionedConfirmedBlockWithEntries,
-        VersionedTransactionWithStatusMeta,
+        UiTransactionEncoding, UiTransactionReturnData, VersionedConfirmedBlock,
+        VersionedConfirmedBlockWithEntries, VersionedTransactionWithStatusMeta,
     },
     std::{
         cell::RefCell,
@@ -41,7 +46,10 @@ use {
         fmt::{self, Display, Formatter},
         io::{stdout, Write},
         rc::Rc,
-        sync::Arc,
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
     },
 };
 
@@ -742,6 +750,75 @@ pub fn output_ledger(
     Ok(())
 }
 
+/// Output the rooted slots of a blockstore opened with Secondary access, and
+/// keep outputting new rooted slots as the primary roots them
+///
+/// Following stops once a slot past `ending_slot` is rooted or `num_slots`
+/// slots have been output.
+pub fn follow_ledger(
+    blockstore: Arc<Blockstore>,
+    starting_slot: Slot,
+    ending_slot: Slot,
+    allow_dead_slots: bool,
+    output_format: OutputFormat,
+    num_slots: Option<Slot>,
+    verbose_level: u64,
+) -> Result<()> {
+    let num_slots = num_slots.unwrap_or(Slot::MAX);
+    let mut num_printed = 0;
+    let mut all_program_ids = HashMap::new();
+    // Returns whether following should continue
+    let mut output_rooted_slot = |slot: Slot| {
+        if slot > ending_slot {
+            return false;
+        }
+        if let Err(err) = output_slot(
+            &blockstore,
+            slot,
+            allow_dead_slots,
+            &output_format,
+            verbose_level,
+            &mut all_program_ids,
+        ) {
+            eprintln!("{err}");
+        }
+        num_printed += 1;
+        num_printed < num_slots
+    };
+
+    // Output the slots that were already rooted when the blockstore was opened
+    let initial_root = blockstore.max_root();
+    for slot in blockstore
+        .rooted_slot_iterator(starting_slot)?
+        .take_while(|slot| *slot <= initial_root)
+    {
+        if !output_rooted_slot(slot) {
+            return Ok(());
+        }
+    }
+
+    let exit = Arc::new(AtomicBool::new(false));
+    let latest_root = Arc::new(AtomicU64::new(
+        initial_root.max(starting_slot.saturating_sub(1)),
+    ));
+    let (rooted_slots_sender, rooted_slots_receiver) = unbounded();
+    let follower_service = BlockstoreFollowerService::new(
+        blockstore.clone(),
+        latest_root,
+        Some(rooted_slots_sender),
+        DEFAULT_CATCH_UP_INTERVAL,
+        exit.clone(),
+    );
+    for slot in rooted_slots_receiver.iter() {
+        if !output_rooted_slot(slot) {
+            break;
+        }
+    }
+    exit.store(true, Ordering::Relaxed);
+    follower_service.join().unwrap();
+    Ok(())
+}
+
 pub fn output_sorted_program_ids(program_ids: HashMap<Pubkey, u64>) {
     let mut program_ids_array: Vec<_> = program_ids.into_iter().collect();
     // Sort descending by count of program id
@@ -965,3 +1042,355 @@ impl fmt::Display for CliAccounts {
 }
 impl QuietDisplay for CliAccounts {}
 impl VerboseDisplay for CliAccounts {}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CliAccountChange {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl fmt::Display for CliAccountChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Added => write!(f, "added"),
+            Self::Removed => write!(f, "removed"),
+            Self::Changed => write!(f, "changed"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountDiff {
+    pub pubkey: String,
+    pub change: CliAccountChange,
+    pub owner: String,
+    pub base_lamports: u64,
+    pub other_lamports: u64,
+    pub lamports_delta: i64,
+    pub base_data_len: usize,
+    pub other_data_len: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub changed_fields: Vec<String>,
+}
+
+impl fmt::Display for CliAccountDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {:<7} owner: {}, lamports: {} -> {} ({:+}), data len: {} -> {}",
+            self.pubkey,
+            self.change,
+            self.owner,
+            self.base_lamports,
+            self.other_lamports,
+            self.lamports_delta,
+            self.base_data_len,
+            self.other_data_len,
+        )?;
+        if !self.changed_fields.is_empty() {
+            write!(f, ", changed: {}", self.changed_fields.join(","))?;
+        }
+        writeln!(f)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSnapshotDiff {
+    pub base_slot: Slot,
+    pub base_bank_hash: String,
+    pub base_capitalization: u64,
+    pub other_slot: Slot,
+    pub other_bank_hash: String,
+    pub other_capitalization: u64,
+    pub capitalization_delta: i64,
+    pub num_added: usize,
+    pub num_removed: usize,
+    pub num_changed: usize,
+    pub lamports_delta: i64,
+    pub accounts: Vec<CliAccountDiff>,
+}
+
+impl QuietDisplay for CliSnapshotDiff {}
+impl VerboseDisplay for CliSnapshotDiff {}
+
+impl fmt::Display for CliSnapshotDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "Base:  slot {}, bank hash {}, capitalization {}",
+            self.base_slot, self.base_bank_hash, self.base_capitalization,
+        )?;
+        writeln!(
+            f,
+            "Other: slot {}, bank hash {}, capitalization {}",
+            self.other_slot, self.other_bank_hash, self.other_capitalization,
+        )?;
+        for account in &self.accounts {
+            write!(f, "{account}")?;
+        }
+        writeln!(
+            f,
+            "Accounts added: {}, removed: {}, changed: {}",
+            self.num_added, self.num_removed, self.num_changed,
+        )?;
+        writeln!(
+            f,
+            "Lamports delta of listed accounts: {:+}",
+            self.lamports_delta
+        )?;
+        writeln!(f, "Capitalization delta: {:+}", self.capitalization_delta)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountDeltaDiff {
+    pub pubkey: String,
+    /// The fields of the account that differ between the two files
+    pub mismatched_fields: Vec<String>,
+    pub left: Option<AccountDeltaDetails>,
+    pub right: Option<AccountDeltaDetails>,
+}
+
+fn writeln_account_delta(
+    f: &mut dyn fmt::Write,
+    side: &str,
+    account_delta: Option<&AccountDeltaDetails>,
+) -> fmt::Result {
+    let Some(account_delta) = account_delta else {
+        return writeln!(f, "    {side}: not written");
+    };
+    let format_state = |state: Option<&AccountStateDetails>| {
+        state.map_or("none".to_string(), |state| {
+            format!(
+                "{{lamports: {}, owner: {}, executable: {}}}",
+                state.lamports, state.owner, state.executable,
+            )
+        })
+    };
+    writeln!(
+        f,
+        "    {side}: pre: {}, post: {}, lt hash: {}, writers: [{}]",
+        format_state(account_delta.pre_state.as_ref()),
+        format_state(Some(&account_delta.post_state)),
+        account_delta.lt_hash_checksum,
+        account_delta.writers.join(", "),
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliSlotDetailsDiff {
+    pub slot: Slot,
+    pub left_bank_hash: Option<String>,
+    pub right_bank_hash: Option<String>,
+    /// The bank hash components that differ between the two files
+    pub mismatched_components: Vec<String>,
+    pub accounts: Vec<CliAccountDeltaDiff>,
+}
+
+impl fmt::Display for CliSlotDetailsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format_hash = |hash: &Option<String>| hash.as_deref().unwrap_or("missing").to_string();
+        writeln!(
+            f,
+            "Slot {}: left bank hash: {}, right bank hash: {}",
+            self.slot,
+            format_hash(&self.left_bank_hash),
+            format_hash(&self.right_bank_hash),
+        )?;
+        if !self.mismatched_components.is_empty() {
+            writeln!(
+                f,
+                "  Mismatched components: {}",
+                self.mismatched_components.join(", ")
+            )?;
+        }
+        for account in &self.accounts {
+            writeln!(
+                f,
+                "  Account {}: {}",
+                account.pubkey,
+                account.mismatched_fields.join(", ")
+            )?;
+            writeln_account_delta(f, "left ", account.left.as_ref())?;
+            writeln_account_delta(f, "right", account.right.as_ref())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliBankHashDetailsDiff {
+    pub slots: Vec<CliSlotDetailsDiff>,
+}
+
+impl QuietDisplay for CliBankHashDetailsDiff {}
+impl VerboseDisplay for CliBankHashDetailsDiff {}
+
+impl fmt::Display for CliBankHashDetailsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.slots.is_empty() {
+            return writeln!(f, "No differences found");
+        }
+        for slot in &self.slots {
+            write!(f, "{slot}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliInstructionTrace {
+    /// Index of the instruction in the transaction's instruction trace
+    pub index_in_trace: usize,
+    pub stack_height: usize,
+    pub program_id: String,
+    /// Compute units consumed by the instruction, including the instructions it invoked.
+    /// `None` if the program was never entered, such as for precompiles.
+    pub compute_units_consumed: Option<u64>,
+    pub succeeded: Option<bool>,
+    pub logs: Vec<String>,
+    pub inner_instructions: Vec<CliInstructionTrace>,
+}
+
+impl CliInstructionTrace {
+    fn write_tree(&self, f: &mut fmt::Formatter, indent: usize) -> fmt::Result {
+        let padding = " ".repeat(indent);
+        let compute_units = self
+            .compute_units_consumed
+            .map(|units| units.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        let result = match self.succeeded {
+            Some(true) => "success",
+            Some(false) => "failed",
+            None => "not invoked",
+        };
+        writeln!(
+            f,
+            "{padding}#{} {} (compute units: {compute_units}, {result})",
+            self.index_in_trace, self.program_id,
+        )?;
+        for log in &self.logs {
+            writeln!(f, "{padding}  | {log}")?;
+        }
+        for inner_instruction in &self.inner_instructions {
+            inner_instruction.write_tree(f, indent + 4)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliDataRangeDiff {
+    pub offset: usize,
+    /// Hex encoded bytes of the range before the transaction
+    pub pre: String,
+    /// Hex encoded bytes of the range after the transaction
+    pub post: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CliAccountTrace {
+    pub pubkey: String,
+    pub pre_lamports: u64,
+    pub post_lamports: u64,
+    pub pre_owner: String,
+    pub post_owner: String,
+    pub pre_data_len: usize,
+    pub post_data_len: usize,
+    pub data_diffs: Vec<CliDataRangeDiff>,
+}
+
+impl fmt::Display for CliAccountTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "  {}", self.pubkey)?;
+        if self.pre_lamports != self.post_lamports {
+            writeln!(
+                f,
+                "    lamports: {} -> {} ({:+})",
+                self.pre_lamports,
+                self.post_lamports,
+                i128::from(self.post_lamports) - i128::from(self.pre_lamports),
+            )?;
+        }
+        if self.pre_owner != self.post_owner {
+            writeln!(f, "    owner: {} -> {}", self.pre_owner, self.post_owner)?;
+        }
+        if self.pre_data_len != self.post_data_len {
+            writeln!(
+                f,
+                "    data length: {} -> {}",
+                self.pre_data_len, self.post_data_len
+            )?;
+        }
+        for data_diff in &self.data_diffs {
+            writeln!(f, "    data at offset {}:", data_diff.offset)?;
+            writeln!(f, "      - {}", data_diff.pre)?;
+            writeln!(f, "      + {}", data_diff.post)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CliTransactionTrace {
+    pub signature: String,
+    pub slot: Slot,
+    /// The error of the transaction, if it failed
+    pub err: Option<String>,
+    pub compute_units_consumed: u64,
+    pub instructions: Vec<CliInstructionTrace>,
+    /// Log messages that could not be attributed to an instruction
+    pub unattributed_logs: Vec<String>,
+    pub return_data: Option<UiTransactionReturnData>,
+    pub accounts: Vec<CliAccountTrace>,
+}
+
+impl QuietDisplay for CliTransactionTrace {}
+impl VerboseDisplay for CliTransactionTrace {}
+
+impl fmt::Display for CliTransactionTrace {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Transaction {} in slot {}", self.signature, self.slot)?;
+        match &self.err {
+            Some(err) => writeln!(f, "Status: Error: {err}")?,
+            None => writeln!(f, "Status: Ok")?,
+        }
+        writeln!(f, "Compute units consumed: {}", self.compute_units_consumed)?;
+        writeln!(f, "Instructions:")?;
+        for instruction in &self.instructions {
+            instruction.write_tree(f, 2)?;
+        }
+        if !self.unattributed_logs.is_empty() {
+            writeln!(f, "Other logs:")?;
+            for log in &self.unattributed_logs {
+                writeln!(f, "  {log}")?;
+            }
+        }
+        if let Some(return_data) = &self.return_data {
+            writeln!(
+                f,
+                "Return data from {}: {} ({:?})",
+                return_data.program_id, return_data.data.0, return_data.data.1,
+            )?;
+        }
+        writeln!(f, "Account changes:")?;
+        if self.accounts.is_empty() {
+            writeln!(f, "  None")?;
+        }
+        for account in &self.accounts {
+            write!(f, "{account}")?;
+        }
+        Ok(())
+    }
+}
@@ -19,6 +19,7 @@ use {
         loaded_programs::{
             LoadProgramMetrics, ProgramCacheEntryType, DELAY_VISIBILITY_SLOT_OFFSET,
         },
+        profiler::ComputeUnitProfile,
         serialization::serialize_parameters,
         with_mock_invoke_context,
     },
@@ -200,6 +201,17 @@ and the following fields are required
                         .takes_value(true)
                         .value_name("FILE"),
                 )
+                .arg(
+                    Arg::with_name("profile")
+                        .help(
+                            "Output compute unit profile of the program in the folded stack \
+                             format, for flame graph tools. Programs it invokes are not \
+                             profiled",
+                        )
+                        .long("profile")
+                        .takes_value(true)
+                        .value_name("FILE"),
+                )
                 .arg(&program_arg)
         )
         )
@@ -537,6 +549,15 @@ pub fn program(ledger_path: &Path, matches: &ArgMatches<'_>) {
     }
     let (instruction_count, result) = vm.execute_program(&verified_executable, interpreted);
     let duration = Instant::now() - start_time;
+    let register_trace = std::mem::take(&mut vm.register_trace);
+    // The program run from the file is not in the program cache, which
+    // `InvokeContext::compute_unit_profile()` resolves the traces against
+    let profile = matches.is_present("profile").then(|| {
+        let mut profile = ComputeUnitProfile::default();
+        profile.add_register_trace(&program_id, &verified_executable, &register_trace);
+        profile
+    });
+    vm.context_object_pointer.insert_register_trace(register_trace);
     if let Some(trace_option) = matches.value_of("trace") {
         vm.context_object_pointer.iterate_vm_traces(
             &|instruction_context: InstructionContext, executable, register_trace| {
@@ -575,6 +596,10 @@ pub fn program(ledger_path: &Path, matches: &ArgMatches<'_>) {
             },
         );
     }
+    if let (Some(profile_path), Some(profile)) = (matches.value_of("profile"), profile) {
+        let mut fd = File::create(profile_path).unwrap();
+        profile.write_folded(&mut fd).unwrap();
+    }
     drop(vm);
 
     let output = Output {
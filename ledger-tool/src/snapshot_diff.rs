@@ -0,0 +1,430 @@
+use {
+    crate::{
+        args::parse_process_options,
+        ledger_path::LEDGER_TOOL_DIRECTORY,
+        ledger_utils::{
+            get_access_type, load_and_process_ledger_or_exit, open_blockstore,
+            open_genesis_config_by, LoadAndProcessLedgerOutput,
+        },
+        output::{CliAccountChange, CliAccountDiff, CliSnapshotDiff},
+    },
+    agave_snapshots::{
+        manifest::ManifestVerification,
+        paths::BANK_SNAPSHOTS_DIR,
+        snapshot_archive_info::{FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo},
+    },
+    clap::{value_t_or_exit, ArgMatches},
+    crossbeam_channel::{bounded, Receiver},
+    itertools::{EitherOrBoth, Itertools},
+    log::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_accounts_db::{
+        is_loadable::IsLoadable as _,
+        utils::{create_all_accounts_run_and_snapshot_dirs, move_and_async_delete_path_contents},
+    },
+    solana_clap_utils::input_parsers::pubkeys_of,
+    solana_cli_output::OutputFormat,
+    solana_genesis_config::GenesisConfig,
+    solana_ledger::blockstore_processor::ProcessOptions,
+    solana_measure::measure_time,
+    solana_pubkey::Pubkey,
+    solana_runtime::{bank::Bank, snapshot_bank_utils},
+    solana_sdk_ids::sysvar,
+    std::{
+        collections::HashSet,
+        fs,
+        path::{Path, PathBuf},
+        process::exit,
+        sync::{atomic::AtomicBool, Arc},
+        thread,
+    },
+};
+
+/// Number of accounts that may be buffered between a bank scan and the diff
+const SCAN_CHANNEL_CAPACITY: usize = 10_000;
+
+/// Selects which accounts are included in a snapshot diff
+#[derive(Debug, Default)]
+pub struct AccountDiffFilter {
+    /// Only include accounts owned by one of these programs, if specified
+    pub owners: Option<HashSet<Pubkey>>,
+    pub include_sysvars: bool,
+}
+
+impl AccountDiffFilter {
+    fn includes(&self, account: &AccountSharedData) -> bool {
+        (self.include_sysvars || !sysvar::check_id(account.owner()))
+            && self
+                .owners
+                .as_ref()
+                .is_none_or(|owners| owners.contains(account.owner()))
+    }
+}
+
+/// Compare two streams of accounts, each sorted by pubkey, and return the
+/// accounts that were added, removed or changed going from `base` to `other`.
+///
+/// An account is reported if either of its versions passes `filter`.
+pub fn diff_accounts(
+    base: impl IntoIterator<Item = (Pubkey, AccountSharedData)>,
+    other: impl IntoIterator<Item = (Pubkey, AccountSharedData)>,
+    filter: &AccountDiffFilter,
+) -> Vec<CliAccountDiff> {
+    base.into_iter()
+        .merge_join_by(other, |(base_pubkey, _), (other_pubkey, _)| {
+            base_pubkey.cmp(other_pubkey)
+        })
+        .filter_map(|item| {
+            let (pubkey, base, other) = match item {
+                EitherOrBoth::Left((pubkey, base)) => (pubkey, Some(base), None),
+                EitherOrBoth::Right((pubkey, other)) => (pubkey, None, Some(other)),
+                EitherOrBoth::Both((pubkey, base), (_, other)) => (pubkey, Some(base), Some(other)),
+            };
+            diff_account(&pubkey, base.as_ref(), other.as_ref(), filter)
+        })
+        .collect()
+}
+
+fn diff_account(
+    pubkey: &Pubkey,
+    base: Option<&AccountSharedData>,
+    other: Option<&AccountSharedData>,
+    filter: &AccountDiffFilter,
+) -> Option<CliAccountDiff> {
+    if !base
+        .into_iter()
+        .chain(other)
+        .any(|account| filter.includes(account))
+    {
+        return None;
+    }
+
+    let (change, changed_fields) = match (base, other) {
+        (Some(base), Some(other)) => {
+            let changed_fields: Vec<_> = [
+                ("lamports", base.lamports() != other.lamports()),
+                ("owner", base.owner() != other.owner()),
+                ("data", base.data() != other.data()),
+                ("executable", base.executable() != other.executable()),
+                ("rentEpoch", base.rent_epoch() != other.rent_epoch()),
+            ]
+            .into_iter()
+            .filter_map(|(field, changed)| changed.then(|| field.to_string()))
+            .collect();
+            if changed_fields.is_empty() {
+                return None;
+            }
+            (CliAccountChange::Changed, changed_fields)
+        }
+        (None, Some(_)) => (CliAccountChange::Added, vec![]),
+        (Some(_), None) => (CliAccountChange::Removed, vec![]),
+        (None, None) => return None,
+    };
+
+    let owner = other.or(base).map(|account| *account.owner()).unwrap();
+    let base_lamports = base.map(ReadableAccount::lamports).unwrap_or_default();
+    let other_lamports = other.map(ReadableAccount::lamports).unwrap_or_default();
+    Some(CliAccountDiff {
+        pubkey: pubkey.to_string(),
+        change,
+        owner: owner.to_string(),
+        base_lamports,
+        other_lamports,
+        lamports_delta: other_lamports as i64 - base_lamports as i64,
+        base_data_len: base.map(|account| account.data().len()).unwrap_or_default(),
+        other_data_len: other
+            .map(|account| account.data().len())
+            .unwrap_or_default(),
+        changed_fields,
+    })
+}
+
+/// Stream all loadable accounts of `bank`, sorted by pubkey, from a scoped thread
+fn scan_accounts_sorted<'scope>(
+    scope: &'scope thread::Scope<'scope, '_>,
+    bank: &'scope Bank,
+) -> Receiver<(Pubkey, AccountSharedData)> {
+    let (sender, receiver) = bounded(SCAN_CHANNEL_CAPACITY);
+    thread::Builder::new()
+        .name(format!("solSnapDiff{:02}", bank.slot() % 100))
+        .spawn_scoped(scope, move || {
+            bank.scan_all_accounts(
+                |item| {
+                    if let Some((pubkey, account, _slot)) = item {
+                        if account.is_loadable() {
+                            // The receiver is only dropped once the diff is complete
+                            let _ = sender.send((*pubkey, account));
+                        }
+                    }
+                },
+                true,
+            )
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to scan accounts of bank {}: {err}", bank.slot());
+                exit(1);
+            });
+        })
+        .unwrap();
+    receiver
+}
+
+/// Diff the accounts of two banks
+///
+/// Both banks are scanned concurrently in pubkey order so that neither set of
+/// accounts has to be held in memory in its entirety.
+pub fn diff_banks(base: &Bank, other: &Bank, filter: &AccountDiffFilter) -> CliSnapshotDiff {
+    let (accounts, measure) = measure_time!(
+        thread::scope(|scope| {
+            let base_accounts = scan_accounts_sorted(scope, base);
+            let other_accounts = scan_accounts_sorted(scope, other);
+            diff_accounts(base_accounts, other_accounts, filter)
+        }),
+        "diff accounts"
+    );
+    info!("{measure}");
+
+    let (mut num_added, mut num_removed, mut num_changed) = (0, 0, 0);
+    for account in &accounts {
+        match account.change {
+            CliAccountChange::Added => num_added += 1,
+            CliAccountChange::Removed => num_removed += 1,
+            CliAccountChange::Changed => num_changed += 1,
+        }
+    }
+    let lamports_delta = accounts.iter().map(|account| account.lamports_delta).sum();
+
+    CliSnapshotDiff {
+        base_slot: base.slot(),
+        base_bank_hash: base.hash().to_string(),
+        base_capitalization: base.capitalization(),
+        other_slot: other.slot(),
+        other_bank_hash: other.hash().to_string(),
+        other_capitalization: other.capitalization(),
+        capitalization_delta: other.capitalization() as i64 - base.capitalization() as i64,
+        num_added,
+        num_removed,
+        num_changed,
+        lamports_delta,
+        accounts,
+    }
+}
+
+/// Load a bank directly from a full and optional incremental snapshot archive
+///
+/// Each bank gets its own accounts and accounts index directories under
+/// `scratch_dir` so that multiple banks can be loaded side by side.
+fn load_bank_from_snapshot_archives(
+    full_snapshot_archive: PathBuf,
+    incremental_snapshot_archive: Option<PathBuf>,
+    scratch_dir: &Path,
+    genesis_config: &GenesisConfig,
+    process_options: &ProcessOptions,
+) -> Bank {
+    let full_snapshot_archive_info = FullSnapshotArchiveInfo::new_from_path(full_snapshot_archive)
+        .unwrap_or_else(|err| {
+            eprintln!("Invalid full snapshot archive: {err}");
+            exit(1);
+        });
+    let incremental_snapshot_archive_info = incremental_snapshot_archive.map(|path| {
+        IncrementalSnapshotArchiveInfo::new_from_path(path).unwrap_or_else(|err| {
+            eprintln!("Invalid incremental snapshot archive: {err}");
+            exit(1);
+        })
+    });
+
+    if scratch_dir.exists() {
+        info!("Cleaning contents of {}", scratch_dir.display());
+        move_and_async_delete_path_contents(scratch_dir);
+    }
+    let bank_snapshots_dir = scratch_dir.join(BANK_SNAPSHOTS_DIR);
+    fs::create_dir_all(&bank_snapshots_dir).unwrap_or_else(|err| {
+        eprintln!(
+            "Unable to create bank snapshots directory {}: {err}",
+            bank_snapshots_dir.display()
+        );
+        exit(1);
+    });
+    let (account_run_paths, _account_snapshot_paths) = create_all_accounts_run_and_snapshot_dirs(
+        &[scratch_dir.join("accounts")],
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Unable to create accounts directories: {err}");
+        exit(1);
+    });
+
+    let mut accounts_db_config = process_options.accounts_db_config.clone();
+    if let Some(index) = accounts_db_config.index.as_mut() {
+        index.drives = Some(vec![scratch_dir.join("accounts_index")]);
+    }
+
+    let (bank, measure) = measure_time!(
+        snapshot_bank_utils::bank_from_snapshot_archives(
+            &account_run_paths,
+            &bank_snapshots_dir,
+            &full_snapshot_archive_info,
+            incremental_snapshot_archive_info.as_ref(),
+            genesis_config,
+            &process_options.runtime_config,
+            process_options.debug_keys.clone(),
+            process_options.limit_load_slot_count_from_snapshot,
+            process_options.accounts_db_skip_shrink,
+            false,
+            process_options.verify_index,
+            accounts_db_config,
+            ManifestVerification::Skip,
+            None,
+            Arc::new(AtomicBool::new(false)),
+        ),
+        "load bank from snapshot archives"
+    );
+    let bank = bank.unwrap_or_else(|err| {
+        eprintln!(
+            "Failed to load bank from snapshot archive {}: {err}",
+            full_snapshot_archive_info.path().display()
+        );
+        exit(1);
+    });
+    info!("{measure}");
+    bank
+}
+
+pub fn snapshot_diff(ledger_path: &Path, arg_matches: &ArgMatches<'_>) {
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    let filter = AccountDiffFilter {
+        owners: pubkeys_of(arg_matches, "program_accounts")
+            .map(|owners| owners.into_iter().collect()),
+        include_sysvars: arg_matches.is_present("include_sysvars"),
+    };
+
+    let process_options = parse_process_options(ledger_path, arg_matches);
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    let scratch_dir = ledger_path
+        .join(LEDGER_TOOL_DIRECTORY)
+        .join("snapshot_diff");
+
+    let base_bank = load_bank_from_snapshot_archives(
+        PathBuf::from(value_t_or_exit!(
+            arg_matches,
+            "base_snapshot_archive",
+            String
+        )),
+        arg_matches
+            .value_of("base_incremental_snapshot_archive")
+            .map(PathBuf::from),
+        &scratch_dir.join("base"),
+        &genesis_config,
+        &process_options,
+    );
+
+    let other_bank =
+        if let Some(other_snapshot_archive) = arg_matches.value_of("other_snapshot_archive") {
+            Arc::new(load_bank_from_snapshot_archives(
+                PathBuf::from(other_snapshot_archive),
+                arg_matches
+                    .value_of("other_incremental_snapshot_archive")
+                    .map(PathBuf::from),
+                &scratch_dir.join("other"),
+                &genesis_config,
+                &process_options,
+            ))
+        } else {
+            let blockstore =
+                open_blockstore(ledger_path, arg_matches, get_access_type(&process_options));
+            let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+                arg_matches,
+                &genesis_config,
+                Arc::new(blockstore),
+                process_options,
+                None,
+            );
+            let bank = bank_forks.read().unwrap().working_bank();
+            bank
+        };
+
+    let diff = diff_banks(&base_bank, &other_bank, &filter);
+    println!("{}", output_format.formatted_string(&diff));
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, solana_sdk_ids::system_program};
+
+    fn account(lamports: u64, data_len: usize, owner: &Pubkey) -> AccountSharedData {
+        AccountSharedData::new(lamports, data_len, owner)
+    }
+
+    #[test]
+    fn test_diff_accounts() {
+        let program = Pubkey::new_from_array([0xff; 32]);
+        let keys: Vec<_> = (0..5u8).map(|i| Pubkey::new_from_array([i; 32])).collect();
+
+        let base = vec![
+            (keys[0], account(10, 0, &system_program::id())),
+            (keys[1], account(20, 0, &system_program::id())),
+            (keys[2], account(30, 8, &program)),
+            (keys[3], account(40, 0, &system_program::id())),
+        ];
+        let other = vec![
+            (keys[1], account(20, 0, &system_program::id())),
+            (keys[2], account(35, 16, &program)),
+            (keys[3], account(40, 0, &program)),
+            (keys[4], account(50, 0, &program)),
+        ];
+
+        let diffs = diff_accounts(base.clone(), other.clone(), &AccountDiffFilter::default());
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|diff| (diff.pubkey.clone(), diff.change))
+                .collect::<Vec<_>>(),
+            vec![
+                (keys[0].to_string(), CliAccountChange::Removed),
+                (keys[2].to_string(), CliAccountChange::Changed),
+                (keys[3].to_string(), CliAccountChange::Changed),
+                (keys[4].to_string(), CliAccountChange::Added),
+            ]
+        );
+        assert_eq!(diffs[0].lamports_delta, -10);
+        assert_eq!(diffs[1].lamports_delta, 5);
+        assert_eq!(diffs[1].changed_fields, vec!["lamports", "data"]);
+        assert_eq!(diffs[2].changed_fields, vec!["owner"]);
+        assert_eq!(diffs[2].owner, program.to_string());
+        assert_eq!(diffs[3].lamports_delta, 50);
+
+        // Accounts are included if either version is owned by a filtered program
+        let filter = AccountDiffFilter {
+            owners: Some(HashSet::from([program])),
+            include_sysvars: false,
+        };
+        let diffs = diff_accounts(base, other, &filter);
+        assert_eq!(
+            diffs
+                .iter()
+                .map(|diff| diff.pubkey.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                keys[2].to_string(),
+                keys[3].to_string(),
+                keys[4].to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_accounts_sysvars() {
+        let clock = sysvar::clock::id();
+        let base = vec![(clock, account(1, 8, &sysvar::id()))];
+        let other = vec![(clock, account(1, 16, &sysvar::id()))];
+
+        let diffs = diff_accounts(base.clone(), other.clone(), &AccountDiffFilter::default());
+        assert!(diffs.is_empty());
+
+        let filter = AccountDiffFilter {
+            owners: None,
+            include_sysvars: true,
+        };
+        let diffs = diff_accounts(base, other, &filter);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].changed_fields, vec!["data"]);
+    }
+}
@@ -0,0 +1,643 @@
+use {
+    crate::{
+        args::*,
+        canonicalize_ledger_path,
+        ledger_utils::*,
+        output::{CliAccountTrace, CliDataRangeDiff, CliInstructionTrace, CliTransactionTrace},
+    },
+    clap::{value_t, value_t_or_exit, App, AppSettings, Arg, ArgMatches, SubCommand},
+    log::*,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_clap_utils::input_validators::{is_parsable, is_slot},
+    solana_cli_output::OutputFormat,
+    solana_clock::{Slot, MAX_PROCESSING_AGE},
+    solana_ledger::{blockstore::Blockstore, leader_schedule_cache::LeaderScheduleCache},
+    solana_message::inner_instruction::InnerInstruction,
+    solana_pubkey::Pubkey,
+    solana_runtime::bank::Bank,
+    solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
+    solana_signature::Signature,
+    solana_svm::{
+        transaction_error_metrics::TransactionErrorMetrics,
+        transaction_processing_result::ProcessedTransaction,
+        transaction_processor::{ExecutionRecordingConfig, TransactionProcessingConfig},
+    },
+    solana_svm_callback::InstructionProcessedCallback,
+    solana_transaction::{
+        sanitized::SanitizedTransaction, versioned::VersionedTransaction,
+        TransactionVerificationMode,
+    },
+    solana_transaction_status::UiTransactionReturnData,
+    std::{cell::RefCell, collections::BTreeMap, path::Path, process::exit, slice, sync::Arc},
+};
+
+pub trait TransactionSubCommand {
+    fn transaction_subcommand(self) -> Self;
+}
+
+impl TransactionSubCommand for App<'_, '_> {
+    fn transaction_subcommand(self) -> Self {
+        self.subcommand(
+            SubCommand::with_name("transaction")
+                .about("Commands to inspect the execution of transactions in the ledger")
+                .setting(AppSettings::InferSubcommands)
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("trace")
+                        .about(
+                            "Re-execute a transaction from the ledger and print its instruction \
+                             trace, compute units, program logs and account changes",
+                        )
+                        .arg(&load_genesis_arg())
+                        .args(&accounts_db_args())
+                        .args(&snapshot_args())
+                        .arg(
+                            Arg::with_name("signature")
+                                .index(1)
+                                .value_name("SIGNATURE")
+                                .takes_value(true)
+                                .required(true)
+                                .validator(is_parsable::<Signature>)
+                                .help("Signature of the transaction to trace"),
+                        )
+                        .arg(
+                            Arg::with_name("slot")
+                                .long("slot")
+                                .value_name("SLOT")
+                                .takes_value(true)
+                                .validator(is_slot)
+                                .help(
+                                    "Slot that contains the transaction. Required if the \
+                                     transaction status was not recorded in the blockstore",
+                                ),
+                        ),
+                ),
+        )
+    }
+}
+
+/// Records every instruction that is processed
+#[derive(Default)]
+struct InstructionRecorder {
+    /// (compute units consumed, succeeded) keyed by index in the instruction
+    /// trace. The compute units of an instruction include those of the
+    /// instructions it invoked
+    processed_instructions: RefCell<BTreeMap<usize, (u64, bool)>>,
+}
+
+impl InstructionProcessedCallback for InstructionRecorder {
+    fn on_instruction_processed(
+        &self,
+        index_in_trace: usize,
+        _program_id: &Pubkey,
+        compute_units_consumed: u64,
+        succeeded: bool,
+    ) {
+        self.processed_instructions
+            .borrow_mut()
+            .insert(index_in_trace, (compute_units_consumed, succeeded));
+    }
+}
+
+/// Returns the instructions of a transaction in instruction trace order, as
+/// (stack height, program id) pairs
+fn instructions_in_trace_order(
+    transaction: &SanitizedTransaction,
+    inner_instructions: &[Vec<InnerInstruction>],
+) -> Vec<(usize, Pubkey)> {
+    let message = transaction.message();
+    let account_keys = message.account_keys();
+    let program_id_at = |index: u8| {
+        account_keys
+            .get(usize::from(index))
+            .copied()
+            .unwrap_or_default()
+    };
+    // Only the top level instructions that were reached have an entry in the
+    // inner instructions list
+    message
+        .instructions()
+        .iter()
+        .zip(inner_instructions)
+        .flat_map(|(instruction, inner_instructions)| {
+            std::iter::once((1, program_id_at(instruction.program_id_index))).chain(
+                inner_instructions.iter().map(|inner_instruction| {
+                    (
+                        usize::from(inner_instruction.stack_height),
+                        program_id_at(inner_instruction.instruction.program_id_index),
+                    )
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Attributes each log message to the instruction that emitted it, and returns
+/// the log messages that could not be attributed to any instruction
+///
+/// Every instruction whose program was entered starts with an invoke log and, if
+/// it ran to completion, ends with a success or failure log. Instructions whose
+/// program was never entered, such as precompiles, have no logs.
+fn attribute_logs(
+    log_messages: &[String],
+    instructions: &mut [CliInstructionTrace],
+) -> Vec<String> {
+    let mut unattributed_logs = vec![];
+    let mut invoked_instructions = instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| instruction.succeeded.is_some())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>()
+        .into_iter();
+    let mut stack: Vec<usize> = vec![];
+    for log in log_messages {
+        let is_invoke = log.starts_with("Program ") && log.contains(" invoke [");
+        if is_invoke {
+            match invoked_instructions.next() {
+                Some(index) => stack.push(index),
+                None => {
+                    unattributed_logs.push(log.clone());
+                    continue;
+                }
+            }
+        }
+        let Some(&index) = stack.last() else {
+            unattributed_logs.push(log.clone());
+            continue;
+        };
+        let instruction = &mut instructions[index];
+        instruction.logs.push(log.clone());
+        let program_prefix = format!("Program {} ", instruction.program_id);
+        if let Some(result) = log.strip_prefix(&program_prefix) {
+            if result == "success" || result.starts_with("failed: ") {
+                stack.pop();
+            }
+        }
+    }
+    unattributed_logs
+}
+
+/// Nests the instructions, given in instruction trace order, under the
+/// instructions that invoked them
+fn nest_instructions(instructions: Vec<CliInstructionTrace>) -> Vec<CliInstructionTrace> {
+    fn attach(
+        stack: &mut [CliInstructionTrace],
+        roots: &mut Vec<CliInstructionTrace>,
+        instruction: CliInstructionTrace,
+    ) {
+        match stack.last_mut() {
+            Some(parent) => parent.inner_instructions.push(instruction),
+            None => roots.push(instruction),
+        }
+    }
+
+    let mut roots = vec![];
+    let mut stack: Vec<CliInstructionTrace> = vec![];
+    for instruction in instructions {
+        while stack
+            .last()
+            .is_some_and(|top| top.stack_height >= instruction.stack_height)
+        {
+            let completed = stack.pop().unwrap();
+            attach(&mut stack, &mut roots, completed);
+        }
+        stack.push(instruction);
+    }
+    while let Some(completed) = stack.pop() {
+        attach(&mut stack, &mut roots, completed);
+    }
+    roots
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Returns the contiguous byte ranges that differ between two versions of
+/// account data
+fn diff_data(pre: &[u8], post: &[u8]) -> Vec<CliDataRangeDiff> {
+    let len = pre.len().max(post.len());
+    let mut diffs = vec![];
+    let mut offset = 0;
+    while offset < len {
+        if pre.get(offset) == post.get(offset) {
+            offset += 1;
+            continue;
+        }
+        let start = offset;
+        while offset < len && pre.get(offset) != post.get(offset) {
+            offset += 1;
+        }
+        let range = |data: &[u8]| hex(data.get(start..offset.min(data.len())).unwrap_or_default());
+        diffs.push(CliDataRangeDiff {
+            offset: start,
+            pre: range(pre),
+            post: range(post),
+        });
+    }
+    diffs
+}
+
+fn trace_account(
+    pubkey: &Pubkey,
+    pre: &AccountSharedData,
+    post: &AccountSharedData,
+) -> Option<CliAccountTrace> {
+    let data_diffs = diff_data(pre.data(), post.data());
+    let changed =
+        pre.lamports() != post.lamports() || pre.owner() != post.owner() || !data_diffs.is_empty();
+    changed.then(|| CliAccountTrace {
+        pubkey: pubkey.to_string(),
+        pre_lamports: pre.lamports(),
+        post_lamports: post.lamports(),
+        pre_owner: pre.owner().to_string(),
+        post_owner: post.owner().to_string(),
+        pre_data_len: pre.data().len(),
+        post_data_len: post.data().len(),
+        data_diffs,
+    })
+}
+
+/// Executes a transaction against a bank, without committing it, and traces
+/// its execution
+pub fn trace_transaction(
+    bank: &Bank,
+    transaction: VersionedTransaction,
+) -> Result<CliTransactionTrace, String> {
+    let transaction = bank
+        .verify_transaction(transaction, TransactionVerificationMode::FullVerification)
+        .map_err(|err| format!("Invalid transaction: {err}"))?;
+    let signature = *transaction.signature();
+    let pre_accounts: Vec<_> = transaction
+        .message()
+        .account_keys()
+        .iter()
+        .map(|pubkey| (*pubkey, bank.get_account(pubkey).unwrap_or_default()))
+        .collect();
+
+    let check_results = bank.check_transactions::<RuntimeTransaction<SanitizedTransaction>>(
+        slice::from_ref(&transaction),
+        &[Ok(())],
+        MAX_PROCESSING_AGE,
+        &mut TransactionErrorMetrics::default(),
+    );
+    let recorder = InstructionRecorder::default();
+    let processing_config = TransactionProcessingConfig {
+        recording_config: ExecutionRecordingConfig::new_single_setting(true),
+        instruction_processed_callback: Some(&recorder),
+        ..TransactionProcessingConfig::default()
+    };
+    let output = bank
+        .get_transaction_processor()
+        .load_and_execute_sanitized_transactions(
+            bank,
+            slice::from_ref(&transaction),
+            check_results,
+            &bank.transaction_processing_environment(),
+            &processing_config,
+        );
+    let processed_transaction = output
+        .processing_results
+        .into_iter()
+        .next()
+        .unwrap()
+        .map_err(|err| format!("Transaction could not be processed: {err}"))?;
+    let executed_transaction = match processed_transaction {
+        ProcessedTransaction::Executed(executed_transaction) => executed_transaction,
+        ProcessedTransaction::FeesOnly(fees_only_transaction) => {
+            return Err(format!(
+                "Transaction was not executed: {}",
+                fees_only_transaction.load_error
+            ));
+        }
+    };
+    let execution_details = executed_transaction.execution_details;
+
+    let processed_instructions = recorder.processed_instructions.into_inner();
+    let mut instructions: Vec<_> = instructions_in_trace_order(
+        &transaction,
+        execution_details
+            .inner_instructions
+            .as_deref()
+            .unwrap_or_default(),
+    )
+    .into_iter()
+    .enumerate()
+    .map(|(index_in_trace, (stack_height, program_id))| {
+        let processed_instruction = processed_instructions.get(&index_in_trace);
+        CliInstructionTrace {
+            index_in_trace,
+            stack_height,
+            program_id: program_id.to_string(),
+            compute_units_consumed: processed_instruction
+                .map(|(compute_units_consumed, _)| *compute_units_consumed),
+            succeeded: processed_instruction.map(|(_, succeeded)| *succeeded),
+            logs: vec![],
+            inner_instructions: vec![],
+        }
+    })
+    .collect();
+    let unattributed_logs = attribute_logs(
+        execution_details
+            .log_messages
+            .as_deref()
+            .unwrap_or_default(),
+        &mut instructions,
+    );
+
+    let accounts = pre_accounts
+        .iter()
+        .zip(&executed_transaction.loaded_transaction.accounts)
+        .filter_map(|((pubkey, pre), (_, post))| trace_account(pubkey, pre, post))
+        .collect();
+
+    Ok(CliTransactionTrace {
+        signature: signature.to_string(),
+        slot: bank.slot(),
+        err: execution_details.status.err().map(|err| err.to_string()),
+        compute_units_consumed: execution_details.executed_units,
+        instructions: nest_instructions(instructions),
+        unattributed_logs,
+        return_data: execution_details
+            .return_data
+            .map(UiTransactionReturnData::from),
+        accounts,
+    })
+}
+
+/// Returns the slot of the transaction, and the transactions of the slot that
+/// precede it, grouped by entry
+fn find_transaction(
+    blockstore: &Blockstore,
+    signature: &Signature,
+    slot: Option<Slot>,
+) -> Result<(Slot, VersionedTransaction, Vec<Vec<VersionedTransaction>>), String> {
+    let slot = match slot {
+        Some(slot) => slot,
+        None => blockstore
+            .get_rooted_transaction_status(*signature)
+            .map_err(|err| format!("Failed to read transaction status: {err}"))?
+            .map(|(slot, _status_meta)| slot)
+            .ok_or_else(|| {
+                "Transaction status not found in the blockstore, specify the slot with --slot"
+                    .to_string()
+            })?,
+    };
+    let entries = blockstore
+        .get_slot_entries(slot, 0)
+        .map_err(|err| format!("Failed to load entries for slot {slot}: {err}"))?;
+
+    let mut preceding_transactions = vec![];
+    for entry in entries {
+        let mut transactions = entry.transactions;
+        if let Some(position) = transactions
+            .iter()
+            .position(|transaction| transaction.signatures.first() == Some(signature))
+        {
+            let transaction = transactions.swap_remove(position);
+            transactions.truncate(position);
+            if !transactions.is_empty() {
+                preceding_transactions.push(transactions);
+            }
+            return Ok((slot, transaction, preceding_transactions));
+        }
+        if !transactions.is_empty() {
+            preceding_transactions.push(transactions);
+        }
+    }
+    Err(format!("Transaction {signature} not found in slot {slot}"))
+}
+
+fn trace(ledger_path: &Path, arg_matches: &ArgMatches<'_>) {
+    let output_format = OutputFormat::from_matches(arg_matches, "output_format", false);
+    let signature = value_t_or_exit!(arg_matches, "signature", Signature);
+    let slot = value_t!(arg_matches, "slot", Slot).ok();
+
+    let mut process_options = parse_process_options(ledger_path, arg_matches);
+    let genesis_config = open_genesis_config_by(ledger_path, arg_matches);
+    let blockstore = Arc::new(open_blockstore(
+        ledger_path,
+        arg_matches,
+        get_access_type(&process_options),
+    ));
+
+    let (slot, transaction, preceding_transactions) =
+        find_transaction(&blockstore, &signature, slot).unwrap_or_else(|err| {
+            eprintln!("{err}");
+            exit(1);
+        });
+    let parent_slot = blockstore
+        .meta(slot)
+        .ok()
+        .flatten()
+        .and_then(|slot_meta| slot_meta.parent_slot)
+        .unwrap_or_else(|| {
+            eprintln!("Unable to determine the parent of slot {slot}");
+            exit(1);
+        });
+    info!(
+        "Transaction {signature} found in slot {slot}, replaying up to parent slot {parent_slot}"
+    );
+
+    process_options.halt_at_slot = Some(parent_slot);
+    let LoadAndProcessLedgerOutput { bank_forks, .. } = load_and_process_ledger_or_exit(
+        arg_matches,
+        &genesis_config,
+        blockstore,
+        process_options,
+        None,
+    );
+    let parent_bank = bank_forks
+        .read()
+        .unwrap()
+        .get(parent_slot)
+        .unwrap_or_else(|| {
+            eprintln!(
+                "Parent slot {parent_slot} is not available, the snapshot used may be newer than \
+                 the slot"
+            );
+            exit(1);
+        });
+    let leader = LeaderScheduleCache::new_from_bank(&parent_bank)
+        .slot_leader_at(slot, Some(&parent_bank))
+        .unwrap_or_else(|| {
+            eprintln!("Unable to determine the leader of slot {slot}");
+            exit(1);
+        });
+    let bank = Bank::new_from_parent(parent_bank, &leader, slot);
+
+    // Transactions earlier in the slot can modify the accounts that the traced
+    // transaction reads, so they must be processed first
+    for transactions in preceding_transactions {
+        bank.try_process_entry_transactions(transactions)
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to process transactions preceding {signature}: {err}");
+                exit(1);
+            });
+    }
+
+    let transaction_trace = trace_transaction(&bank, transaction).unwrap_or_else(|err| {
+        eprintln!("{err}");
+        exit(1);
+    });
+    println!("{}", output_format.formatted_string(&transaction_trace));
+}
+
+pub fn transaction(ledger_path: &Path, matches: &ArgMatches<'_>) {
+    let ledger_path = canonicalize_ledger_path(ledger_path);
+    match matches.subcommand() {
+        ("trace", Some(arg_matches)) => trace(&ledger_path, arg_matches),
+        _ => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_runtime::genesis_utils::create_genesis_config, solana_signer::Signer,
+        solana_system_interface::instruction as system_instruction,
+        solana_transaction::Transaction,
+    };
+
+    fn instruction(
+        index_in_trace: usize,
+        stack_height: usize,
+        program_id: &str,
+    ) -> CliInstructionTrace {
+        CliInstructionTrace {
+            index_in_trace,
+            stack_height,
+            program_id: program_id.to_string(),
+            compute_units_consumed: Some(index_in_trace as u64),
+            succeeded: Some(true),
+            logs: vec![],
+            inner_instructions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_attribute_logs_and_nest_instructions() {
+        let mut instructions = vec![
+            instruction(0, 1, "A"),
+            instruction(1, 2, "B"),
+            instruction(2, 3, "C"),
+            instruction(3, 2, "C"),
+            CliInstructionTrace {
+                compute_units_consumed: None,
+                succeeded: None,
+                ..instruction(4, 1, "Precompile")
+            },
+            instruction(5, 1, "D"),
+        ];
+        let log_messages: Vec<String> = [
+            "Program A invoke [1]",
+            "Program log: a",
+            "Program B invoke [2]",
+            "Program C invoke [3]",
+            "Program C success",
+            "Program B consumed 10 of 100 compute units",
+            "Program B success",
+            "Program log: a again",
+            "Program C invoke [2]",
+            "Program C failed: custom program error: 0x0",
+            "Program A success",
+            "Program D invoke [1]",
+            "Program D success",
+            "Log truncated",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        let unattributed_logs = attribute_logs(&log_messages, &mut instructions);
+        assert_eq!(unattributed_logs, vec!["Log truncated".to_string()]);
+        let logs: Vec<_> = instructions
+            .iter()
+            .map(|instruction| instruction.logs.len())
+            .collect();
+        assert_eq!(logs, vec![4, 3, 2, 2, 0, 2]);
+        assert_eq!(instructions[0].logs[2], "Program log: a again");
+
+        let nested = nest_instructions(instructions);
+        assert_eq!(
+            nested
+                .iter()
+                .map(|instruction| instruction.index_in_trace)
+                .collect::<Vec<_>>(),
+            vec![0, 4, 5]
+        );
+        assert_eq!(
+            nested[0]
+                .inner_instructions
+                .iter()
+                .map(|instruction| instruction.index_in_trace)
+                .collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(nested[0].inner_instructions[0].inner_instructions.len(), 1);
+        assert!(nested[1].inner_instructions.is_empty());
+    }
+
+    #[test]
+    fn test_diff_data() {
+        assert!(diff_data(&[1, 2, 3], &[1, 2, 3]).is_empty());
+        assert_eq!(
+            diff_data(&[1, 2, 3, 4], &[1, 9, 9, 4, 5]),
+            vec![
+                CliDataRangeDiff {
+                    offset: 1,
+                    pre: "0203".to_string(),
+                    post: "0909".to_string(),
+                },
+                CliDataRangeDiff {
+                    offset: 4,
+                    pre: String::new(),
+                    post: "05".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_trace_transaction() {
+        let genesis_config_info = create_genesis_config(1_000_000_000);
+        let bank = Bank::new_for_tests(&genesis_config_info.genesis_config);
+        let mint_keypair = genesis_config_info.mint_keypair;
+        let recipient = Pubkey::new_from_array([1; 32]);
+        let transaction = Transaction::new_signed_with_payer(
+            &[system_instruction::transfer(
+                &mint_keypair.pubkey(),
+                &recipient,
+                1_000,
+            )],
+            Some(&mint_keypair.pubkey()),
+            &[&mint_keypair],
+            bank.last_blockhash(),
+        );
+
+        let transaction_trace =
+            trace_transaction(&bank, VersionedTransaction::from(transaction)).unwrap();
+        assert_eq!(transaction_trace.err, None);
+        assert_eq!(transaction_trace.instructions.len(), 1);
+        let instruction = &transaction_trace.instructions[0];
+        assert_eq!(
+            instruction.program_id,
+            solana_system_interface::program::id().to_string()
+        );
+        assert_eq!(instruction.succeeded, Some(true));
+        assert!(instruction.compute_units_consumed.unwrap() > 0);
+        assert_eq!(instruction.logs.len(), 2);
+        assert!(transaction_trace.unattributed_logs.is_empty());
+
+        let recipient_trace = transaction_trace
+            .accounts
+            .iter()
+            .find(|account| account.pubkey == recipient.to_string())
+            .unwrap();
+        assert_eq!(recipient_trace.pre_lamports, 0);
+        assert_eq!(recipient_trace.post_lamports, 1_000);
+        // The transaction is traced without being committed
+        assert_eq!(bank.get_balance(&recipient), 0);
+    }
+}
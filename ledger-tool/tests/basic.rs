@@ -87,3 +87,33 @@ fn ledger_tool_copy_test() {
         assert!(!src_slot_output.stdout.is_empty());
     }
 }
+
+#[test]
+fn program_run_profile() {
+    let genesis_config = create_genesis_config(100).genesis_config;
+    let (ledger_path, _blockhash) = create_new_tmp_ledger_auto_delete!(&genesis_config);
+    let ledger_path = ledger_path.path().to_str().unwrap();
+
+    let program_dir = tempfile::tempdir().unwrap();
+    let program_path = program_dir.path().join("program.asm");
+    std::fs::write(
+        &program_path,
+        "entrypoint:\n    call function_foo\n    exit\nfunction_foo:\n    mov64 r0, 0\n    exit\n",
+    )
+    .unwrap();
+    let profile_path = program_dir.path().join("profile.folded");
+    let output = run_ledger_tool(&[
+        "-l",
+        ledger_path,
+        "program",
+        "run",
+        program_path.to_str().unwrap(),
+        "--mode",
+        "interpreter",
+        "--profile",
+        profile_path.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let profile = std::fs::read_to_string(&profile_path).unwrap();
+    assert!(!profile.is_empty());
+}
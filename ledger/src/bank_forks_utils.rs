@@ -306,6 +306,7 @@ fn bank_forks_from_snapshot(
             process_options.accounts_db_force_initial_clean,
             process_options.verify_index,
             process_options.accounts_db_config.clone(),
+            snapshot_config.manifest_verification,
             accounts_update_notifier,
             exit,
         )
@@ -2,12 +2,20 @@ use {
     crate::blockstore::Blockstore,
     crossbeam_channel::{bounded, unbounded},
     log::*,
+    serde::{Deserialize, Serialize},
     solana_clock::Slot,
+    solana_hash::Hash,
     solana_measure::measure::Measure,
+    solana_sha256_hasher::hashv,
+    solana_transaction_status::ConfirmedBlock,
     std::{
         cmp::{max, min},
         collections::HashSet,
+        fs,
+        io::{self, ErrorKind},
+        path::Path,
         result::Result,
+        str::FromStr,
         sync::{
             atomic::{AtomicBool, Ordering},
             Arc,
@@ -41,6 +49,264 @@ struct BlockstoreLoadStats {
     pub elapsed: Duration,
 }
 
+/// A range of slots whose blocks in bigtable were verified against the blockstore
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadedRange {
+    pub first_slot: Slot,
+    pub last_slot: Slot,
+    /// Unknown for what remains of a range that was partly verified again,
+    /// see `UploadCheckpoint::record()`
+    pub num_blocks: Option<usize>,
+    /// Hash chained over the slot, parent slot, blockhashes and transaction
+    /// signatures of every block in the range, in slot order. Unknown for what
+    /// remains of a range that was partly verified again.
+    pub content_hash: Option<String>,
+}
+
+/// Persistent record of the ranges of slots that have been uploaded to
+/// bigtable and verified, so that an interrupted upload can be resumed
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadCheckpoint {
+    /// Sorted by slot and non-overlapping
+    pub verified_ranges: Vec<UploadedRange>,
+}
+
+impl UploadCheckpoint {
+    /// Loads the checkpoint from `path`, or returns an empty checkpoint if the
+    /// file does not exist
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        match fs::read(path) {
+            Ok(contents) => serde_json::from_slice(&contents).map_err(|err| {
+                format!(
+                    "Failed to parse upload checkpoint {}: {err}",
+                    path.display()
+                )
+                .into()
+            }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Atomically replaces the checkpoint at `path`
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, serde_json::to_vec_pretty(self)?)?;
+        fs::rename(temp_path, path)
+    }
+
+    /// Returns the first slot at or after `slot` that is not in a verified range
+    pub fn first_unverified_slot(&self, mut slot: Slot) -> Slot {
+        for range in &self.verified_ranges {
+            if range.first_slot <= slot && slot <= range.last_slot {
+                slot = range.last_slot.saturating_add(1);
+            }
+        }
+        slot
+    }
+
+    /// Returns the first verified range that starts after `slot`
+    pub fn next_verified_range(&self, slot: Slot) -> Option<&UploadedRange> {
+        self.verified_ranges
+            .iter()
+            .find(|range| range.first_slot > slot)
+    }
+
+    /// Returns the hash to chain the content hash of a range starting at
+    /// `first_slot` from, so that the range can be merged with the verified
+    /// range that ends right before it
+    pub fn content_hash_seed(&self, first_slot: Slot) -> Hash {
+        self.verified_ranges
+            .iter()
+            .find(|range| range.last_slot.saturating_add(1) == first_slot)
+            .and_then(|range| range.content_hash.as_deref())
+            .and_then(|content_hash| Hash::from_str(content_hash).ok())
+            .unwrap_or_default()
+    }
+
+    /// Records a verified range. `range` must have been hashed from
+    /// `content_hash_seed(range.first_slot)`.
+    ///
+    /// Verified ranges that overlap `range` keep the slots outside of it, but
+    /// the number of blocks and content hash of what remains are unknown, so
+    /// no range is chained from or merged into it.
+    pub fn record(&mut self, range: UploadedRange) {
+        let mut verified_ranges = Vec::with_capacity(self.verified_ranges.len().saturating_add(2));
+        for verified_range in self.verified_ranges.drain(..) {
+            if verified_range.last_slot < range.first_slot
+                || verified_range.first_slot > range.last_slot
+            {
+                verified_ranges.push(verified_range);
+                continue;
+            }
+            if verified_range.first_slot < range.first_slot {
+                verified_ranges.push(UploadedRange {
+                    first_slot: verified_range.first_slot,
+                    last_slot: range.first_slot - 1,
+                    num_blocks: None,
+                    content_hash: None,
+                });
+            }
+            if verified_range.last_slot > range.last_slot {
+                verified_ranges.push(UploadedRange {
+                    first_slot: range.last_slot + 1,
+                    last_slot: verified_range.last_slot,
+                    num_blocks: None,
+                    content_hash: None,
+                });
+            }
+        }
+        self.verified_ranges = verified_ranges;
+
+        let index = self
+            .verified_ranges
+            .partition_point(|verified_range| verified_range.last_slot < range.first_slot);
+        match index
+            .checked_sub(1)
+            .and_then(|previous| self.verified_ranges.get_mut(previous))
+        {
+            Some(previous)
+                if previous.last_slot.saturating_add(1) == range.first_slot
+                    && previous.content_hash.is_some() =>
+            {
+                previous.last_slot = range.last_slot;
+                previous.num_blocks = previous
+                    .num_blocks
+                    .zip(range.num_blocks)
+                    .map(|(num_blocks, range_num_blocks)| num_blocks + range_num_blocks);
+                previous.content_hash = range.content_hash;
+            }
+            _ => self.verified_ranges.insert(index, range),
+        }
+    }
+}
+
+fn hash_block(seed: &Hash, slot: Slot, block: &ConfirmedBlock) -> Hash {
+    let slot = slot.to_le_bytes();
+    let parent_slot = block.parent_slot.to_le_bytes();
+    let mut data: Vec<&[u8]> = vec![
+        seed.as_ref(),
+        &slot,
+        &parent_slot,
+        block.previous_blockhash.as_bytes(),
+        block.blockhash.as_bytes(),
+    ];
+    data.extend(
+        block
+            .transactions
+            .iter()
+            .map(|transaction| transaction.transaction_signature().as_ref()),
+    );
+    hashv(&data)
+}
+
+/// Returns the slots of the blocks in bigtable from `first_slot` to
+/// `last_slot`, inclusive
+async fn get_bigtable_slots(
+    bigtable: &solana_storage_bigtable::LedgerStorage,
+    first_slot: Slot,
+    last_slot: Slot,
+    limit: usize,
+) -> Vec<Slot> {
+    let mut bigtable_slots = vec![];
+    let mut start_slot = first_slot;
+    while start_slot <= last_slot {
+        let mut next_bigtable_slots = loop {
+            match bigtable.get_confirmed_blocks(start_slot, limit).await {
+                Ok(slots) => break slots,
+                Err(err) => {
+                    error!("get_confirmed_blocks for {start_slot} failed: {err:?}");
+                    // Consider exponential backoff...
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                }
+            }
+        };
+        if next_bigtable_slots.is_empty() {
+            break;
+        }
+        bigtable_slots.append(&mut next_bigtable_slots);
+        start_slot = bigtable_slots.last().unwrap() + 1;
+    }
+    bigtable_slots
+        .into_iter()
+        .filter(|slot| *slot <= last_slot)
+        .collect()
+}
+
+/// Verifies that bigtable holds the same blocks as the blockstore from
+/// `first_slot` to `last_slot`, inclusive, by comparing the number of blocks
+/// and a content hash over them
+///
+/// The content hash is chained from `seed`, see `UploadCheckpoint::content_hash_seed()`.
+pub async fn verify_uploaded_blocks(
+    blockstore: &Blockstore,
+    bigtable: &solana_storage_bigtable::LedgerStorage,
+    first_slot: Slot,
+    last_slot: Slot,
+    seed: Hash,
+) -> Result<UploadedRange, Box<dyn std::error::Error>> {
+    let blockstore_slots: Vec<_> = blockstore
+        .rooted_slot_iterator(first_slot)
+        .map_err(|err| format!("Failed to load entries starting from slot {first_slot}: {err:?}"))?
+        .take_while(|slot| *slot <= last_slot)
+        .collect();
+    let bigtable_slots = get_bigtable_slots(bigtable, first_slot, last_slot, 1000).await;
+    if blockstore_slots.len() != bigtable_slots.len() {
+        let bigtable_slots: HashSet<_> = bigtable_slots.iter().collect();
+        let missing_slots: Vec<_> = blockstore_slots
+            .iter()
+            .filter(|slot| !bigtable_slots.contains(slot))
+            .collect();
+        return Err(format!(
+            "Block count mismatch for slots {first_slot} to {last_slot}: blockstore has {} \
+             blocks, bigtable has {}. Missing from bigtable: {missing_slots:?}",
+            blockstore_slots.len(),
+            bigtable_slots.len(),
+        )
+        .into());
+    }
+
+    let mut blockstore_hash = seed;
+    for slot in &blockstore_slots {
+        let block = blockstore
+            .get_rooted_block(*slot, true)
+            .map_err(|err| format!("Failed to load block {slot} from blockstore: {err:?}"))?;
+        blockstore_hash = hash_block(&blockstore_hash, *slot, &ConfirmedBlock::from(block));
+    }
+
+    let bigtable_blocks = futures::future::join_all(
+        bigtable_slots
+            .iter()
+            .map(|slot| bigtable.get_confirmed_block(*slot)),
+    )
+    .await;
+    let mut bigtable_hash = seed;
+    for (slot, block) in bigtable_slots.iter().zip(bigtable_blocks) {
+        let block =
+            block.map_err(|err| format!("Failed to load block {slot} from bigtable: {err:?}"))?;
+        bigtable_hash = hash_block(&bigtable_hash, *slot, &block);
+    }
+
+    if blockstore_hash != bigtable_hash {
+        return Err(format!(
+            "Content hash mismatch for slots {first_slot} to {last_slot}: blockstore \
+             {blockstore_hash}, bigtable {bigtable_hash}"
+        )
+        .into());
+    }
+    Ok(UploadedRange {
+        first_slot,
+        last_slot,
+        num_blocks: Some(blockstore_slots.len()),
+        content_hash: Some(blockstore_hash.to_string()),
+    })
+}
+
 /// Uploads a range of blocks from a Blockstore to bigtable LedgerStorage
 /// Returns the Slot of the last block checked. If no blocks in the range `[staring_slot,
 /// ending_slot]` are found in Blockstore, this value is equal to `ending_slot`.
@@ -79,38 +345,17 @@ pub async fn upload_confirmed_blocks(
 
     // Gather the blocks that are already present in bigtable, by slot
     let bigtable_slots = if !config.force_reupload {
-        let mut bigtable_slots = vec![];
         info!(
             "Loading list of bigtable blocks between slots {first_blockstore_slot} and \
              {last_blockstore_slot}..."
         );
-
-        let mut start_slot = first_blockstore_slot;
-        while start_slot <= last_blockstore_slot {
-            let mut next_bigtable_slots = loop {
-                let num_bigtable_blocks = min(1000, config.max_num_slots_to_check * 2);
-                match bigtable
-                    .get_confirmed_blocks(start_slot, num_bigtable_blocks)
-                    .await
-                {
-                    Ok(slots) => break slots,
-                    Err(err) => {
-                        error!("get_confirmed_blocks for {start_slot} failed: {err:?}");
-                        // Consider exponential backoff...
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                    }
-                }
-            };
-            if next_bigtable_slots.is_empty() {
-                break;
-            }
-            bigtable_slots.append(&mut next_bigtable_slots);
-            start_slot = bigtable_slots.last().unwrap() + 1;
-        }
-        bigtable_slots
-            .into_iter()
-            .filter(|slot| *slot <= last_blockstore_slot)
-            .collect::<Vec<_>>()
+        get_bigtable_slots(
+            &bigtable,
+            first_blockstore_slot,
+            last_blockstore_slot,
+            min(1000, config.max_num_slots_to_check * 2),
+        )
+        .await
     } else {
         Vec::new()
     };
@@ -279,3 +524,117 @@ pub async fn upload_confirmed_blocks(
         Ok(last_slot)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uploaded_range(first_slot: Slot, last_slot: Slot, content_hash: &str) -> UploadedRange {
+        UploadedRange {
+            first_slot,
+            last_slot,
+            num_blocks: Some((last_slot - first_slot + 1) as usize),
+            content_hash: Some(content_hash.to_string()),
+        }
+    }
+
+    fn remaining_range(first_slot: Slot, last_slot: Slot) -> UploadedRange {
+        UploadedRange {
+            first_slot,
+            last_slot,
+            num_blocks: None,
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_upload_checkpoint_record() {
+        let mut checkpoint = UploadCheckpoint::default();
+        checkpoint.record(uploaded_range(10, 19, "a"));
+        checkpoint.record(uploaded_range(30, 39, "b"));
+        // Adjacent to the end of a range, so it is merged into it
+        checkpoint.record(uploaded_range(20, 24, "c"));
+        // Not adjacent to the end of a range
+        checkpoint.record(uploaded_range(0, 4, "d"));
+        assert_eq!(
+            checkpoint.verified_ranges,
+            vec![
+                uploaded_range(0, 4, "d"),
+                uploaded_range(10, 24, "c"),
+                uploaded_range(30, 39, "b"),
+            ]
+        );
+
+        // Ranges fully covered by a new range are replaced
+        checkpoint.record(uploaded_range(0, 24, "e"));
+        assert_eq!(
+            checkpoint.verified_ranges,
+            vec![uploaded_range(0, 24, "e"), uploaded_range(30, 39, "b")]
+        );
+    }
+
+    #[test]
+    fn test_upload_checkpoint_record_partial_overlap() {
+        let mut checkpoint = UploadCheckpoint::default();
+        checkpoint.record(uploaded_range(0, 100, "a"));
+        checkpoint.record(uploaded_range(200, 300, "b"));
+
+        // The slots of overlapped ranges outside of the new range stay verified
+        checkpoint.record(uploaded_range(50, 150, "c"));
+        checkpoint.record(uploaded_range(180, 220, "d"));
+        checkpoint.record(uploaded_range(240, 260, "e"));
+        assert_eq!(
+            checkpoint.verified_ranges,
+            vec![
+                remaining_range(0, 49),
+                uploaded_range(50, 150, "c"),
+                uploaded_range(180, 220, "d"),
+                remaining_range(221, 239),
+                uploaded_range(240, 260, "e"),
+                remaining_range(261, 300),
+            ]
+        );
+        assert_eq!(checkpoint.first_unverified_slot(0), 151);
+        assert_eq!(checkpoint.first_unverified_slot(180), 301);
+
+        // Nothing is chained from or merged into what remains of a range
+        assert_eq!(checkpoint.content_hash_seed(50), Hash::default());
+        checkpoint.record(uploaded_range(301, 310, "f"));
+        assert_eq!(
+            checkpoint.verified_ranges[5..],
+            [remaining_range(261, 300), uploaded_range(301, 310, "f")]
+        );
+    }
+
+    #[test]
+    fn test_upload_checkpoint_resume() {
+        let seed = Hash::new_from_array([1; 32]);
+        let mut checkpoint = UploadCheckpoint::default();
+        checkpoint.record(uploaded_range(10, 19, &seed.to_string()));
+        checkpoint.record(uploaded_range(30, 39, "b"));
+
+        assert_eq!(checkpoint.first_unverified_slot(5), 5);
+        assert_eq!(checkpoint.first_unverified_slot(10), 20);
+        assert_eq!(checkpoint.first_unverified_slot(25), 25);
+        assert_eq!(checkpoint.first_unverified_slot(35), 40);
+        assert_eq!(
+            checkpoint
+                .next_verified_range(20)
+                .map(|range| range.first_slot),
+            Some(30)
+        );
+        assert_eq!(checkpoint.next_verified_range(30), None);
+
+        assert_eq!(checkpoint.content_hash_seed(20), seed);
+        assert_eq!(checkpoint.content_hash_seed(21), Hash::default());
+
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("checkpoint.json");
+        assert_eq!(
+            UploadCheckpoint::load(&path).unwrap(),
+            UploadCheckpoint::default()
+        );
+        checkpoint.save(&path).unwrap();
+        assert_eq!(UploadCheckpoint::load(&path).unwrap(), checkpoint);
+    }
+}
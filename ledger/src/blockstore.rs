@@ -8,7 +8,10 @@ use {
     crate::{
         ancestor_iterator::AncestorIterator,
         blockstore::column::{columns as cf, Column, ColumnIndexDeprecation, TypedColumn},
-        blockstore_db::{IteratorDirection, IteratorMode, LedgerColumn, Rocks, WriteBatch},
+        blockstore_db::{
+            ColumnCompressionStats, IteratorDirection, IteratorMode, LedgerColumn, Rocks,
+            WriteBatch,
+        },
         blockstore_meta::*,
         blockstore_options::{
             BlockstoreOptions, LedgerColumnOptions, BLOCKSTORE_DIRECTORY_ROCKS_LEVEL,
@@ -245,6 +248,8 @@ pub struct Blockstore {
     ledger_path: PathBuf,
     db: Arc<Rocks>,
     // Column families
+    account_history_cf: LedgerColumn<cf::AccountHistory>,
+    account_history_roots_cf: LedgerColumn<cf::AccountHistoryRoots>,
     address_signatures_cf: LedgerColumn<cf::AddressSignatures>,
     bank_hash_cf: LedgerColumn<cf::BankHash>,
     block_height_cf: LedgerColumn<cf::BlockHeight>,
@@ -273,6 +278,8 @@ pub struct Blockstore {
     completed_slots_senders: Mutex<Vec<CompletedSlotsSender>>,
     pub lowest_cleanup_slot: RwLock<Slot>,
     pub slots_stats: SlotsStats,
+    // Rooted slots that have been cleaned up are read from the archive, if any
+    archive: RwLock<Option<Arc<Blockstore>>>,
 }
 
 pub struct IndexMetaWorkingSetEntry {
@@ -389,6 +396,8 @@ impl Blockstore {
         info!("Opening blockstore at {blockstore_path:?}");
         let db = Arc::new(Rocks::open(blockstore_path, options)?);
 
+        let account_history_cf = db.column();
+        let account_history_roots_cf = db.column();
         let address_signatures_cf = db.column();
         let bank_hash_cf = db.column();
         let block_height_cf = db.column();
@@ -423,6 +432,8 @@ impl Blockstore {
         let blockstore = Blockstore {
             ledger_path: ledger_path.to_path_buf(),
             db,
+            account_history_cf,
+            account_history_roots_cf,
             address_signatures_cf,
             bank_hash_cf,
             block_height_cf,
@@ -450,6 +461,7 @@ impl Blockstore {
             max_root,
             lowest_cleanup_slot: RwLock::<Slot>::default(),
             slots_stats: SlotsStats::default(),
+            archive: RwLock::default(),
         };
         blockstore.cleanup_old_entries()?;
         blockstore.update_highest_primary_index_slot()?;
@@ -671,6 +683,17 @@ impl Blockstore {
         self.db.live_files_metadata()
     }
 
+    /// Returns the names of all columns in the blockstore
+    pub const fn column_names() -> [&'static str; 22] {
+        Rocks::columns()
+    }
+
+    /// Returns the raw and compressed sizes of the data in the specified
+    /// column, which must be one of `Blockstore::column_names()`
+    pub fn column_compression_stats(&self, cf_name: &str) -> Result<ColumnCompressionStats> {
+        self.db.column_compression_stats(cf_name)
+    }
+
     #[cfg(feature = "dev-context-only-utils")]
     #[allow(clippy::type_complexity)]
     pub fn iterator_cf(
@@ -860,6 +883,8 @@ impl Blockstore {
         self.bank_hash_cf.submit_rocksdb_cf_metrics();
         self.optimistic_slots_cf.submit_rocksdb_cf_metrics();
         self.merkle_root_meta_cf.submit_rocksdb_cf_metrics();
+        self.account_history_cf.submit_rocksdb_cf_metrics();
+        self.account_history_roots_cf.submit_rocksdb_cf_metrics();
     }
 
     /// Attempts to insert shreds into blockstore and updates relevant metrics
@@ -2553,7 +2578,12 @@ impl Blockstore {
     }
 
     pub fn get_rooted_block_time(&self, slot: Slot) -> Result<UnixTimestamp> {
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = match self.check_lowest_cleanup_slot(slot) {
+            Err(BlockstoreError::SlotCleanedUp) => {
+                return self.read_from_archive(|archive| archive.get_rooted_block_time(slot));
+            }
+            lock => lock?,
+        };
 
         if self.is_root(slot) {
             return self
@@ -2578,19 +2608,66 @@ impl Blockstore {
         self.block_height_cf.put(slot, &block_height)
     }
 
-    /// The first complete block that is available in the Blockstore ledger
+    /// The first complete block that is available in the Blockstore ledger, or in its archive
     pub fn get_first_available_block(&self) -> Result<Slot> {
+        let archived_first_available_block = match self.archive() {
+            Some(archive) => archive.first_available_block()?,
+            None => None,
+        };
+        Ok(archived_first_available_block
+            .into_iter()
+            .chain(self.first_available_block()?)
+            .min()
+            .unwrap_or_default())
+    }
+
+    /// The first complete block that is available in this Blockstore, not counting its archive
+    fn first_available_block(&self) -> Result<Option<Slot>> {
         let mut root_iterator = self.rooted_slot_iterator(self.lowest_slot_with_genesis())?;
-        let first_root = root_iterator.next().unwrap_or_default();
+        let Some(first_root) = root_iterator.next() else {
+            return Ok(None);
+        };
         // If the first root is slot 0, it is genesis. Genesis is always complete, so it is correct
         // to return it as first-available.
         if first_root == 0 {
-            return Ok(first_root);
+            return Ok(Some(first_root));
         }
         // Otherwise, the block at root-index 0 cannot ever be complete, because it is missing its
         // parent blockhash. A parent blockhash must be calculated from the entries of the previous
         // block. Therefore, the first available complete block is that at root-index 1.
-        Ok(root_iterator.next().unwrap_or_default())
+        Ok(root_iterator.next())
+    }
+
+    /// Returns up to `limit` rooted slots in `[start_slot, end_slot]` whose blocks are complete,
+    /// in ascending order. Slots below the first available block of this Blockstore are read
+    /// from the archive, if any.
+    pub fn get_rooted_slots(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        limit: usize,
+    ) -> Result<Vec<Slot>> {
+        let first_available_block = self.first_available_block()?.unwrap_or_default();
+        let mut slots = vec![];
+        if let Some(archive) = self.archive() {
+            if start_slot < first_available_block {
+                if let Some(archived_first_available_block) = archive.first_available_block()? {
+                    slots.extend(
+                        archive
+                            .rooted_slot_iterator(start_slot.max(archived_first_available_block))?
+                            .take_while(|&slot| slot < first_available_block && slot <= end_slot)
+                            .take(limit),
+                    );
+                }
+            }
+        }
+        let limit = limit.saturating_sub(slots.len());
+        slots.extend(
+            self.rooted_slot_iterator(start_slot.max(first_available_block))?
+                .take_while(|&slot| slot <= end_slot)
+                .take(limit),
+        );
+        Ok(slots)
     }
 
     pub fn get_rooted_block(
@@ -2598,7 +2675,14 @@ impl Blockstore {
         slot: Slot,
         require_previous_blockhash: bool,
     ) -> Result<VersionedConfirmedBlock> {
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = match self.check_lowest_cleanup_slot(slot) {
+            Err(BlockstoreError::SlotCleanedUp) => {
+                return self.read_from_archive(|archive| {
+                    archive.get_rooted_block(slot, require_previous_blockhash)
+                });
+            }
+            lock => lock?,
+        };
 
         if self.is_root(slot) {
             return self.get_complete_block(slot, require_previous_blockhash);
@@ -2625,7 +2709,14 @@ impl Blockstore {
         slot: Slot,
         require_previous_blockhash: bool,
     ) -> Result<VersionedConfirmedBlockWithEntries> {
-        let _lock = self.check_lowest_cleanup_slot(slot)?;
+        let _lock = match self.check_lowest_cleanup_slot(slot) {
+            Err(BlockstoreError::SlotCleanedUp) => {
+                return self.read_from_archive(|archive| {
+                    archive.get_rooted_block_with_entries(slot, require_previous_blockhash)
+                });
+            }
+            lock => lock?,
+        };
 
         if self.is_root(slot) {
             return self.do_get_complete_block_with_entries(
@@ -3057,7 +3148,7 @@ impl Blockstore {
     ) -> Result<(Option<(Slot, TransactionStatusMeta)>, u64)> {
         let mut counter = 0;
         let (lock, _) = self.ensure_lowest_cleanup_slot();
-        let first_available_block = self.get_first_available_block()?;
+        let first_available_block = self.first_available_block()?.unwrap_or_default();
 
         let iterator =
             self.transaction_status_cf
@@ -3180,6 +3271,9 @@ impl Blockstore {
                 block_time,
                 index,
             }))
+        } else if let Some(archive) = self.archive() {
+            // Only rooted slots are archived
+            archive.get_transaction_with_status(signature, &HashSet::default())
         } else {
             Ok(None)
         }
@@ -3313,7 +3407,20 @@ impl Blockstore {
                 let transaction_status =
                     self.get_transaction_status(before, &confirmed_unrooted_slots)?;
                 match transaction_status {
-                    None => return Ok(SignatureInfosForAddress::default()),
+                    // The `before` signature may be in a slot that has been archived, in which
+                    // case every signature to list is in the archive too
+                    None => {
+                        return match self.archive() {
+                            Some(archive) => archive.get_confirmed_signatures_for_address2(
+                                address,
+                                archive.max_root(),
+                                Some(before),
+                                until,
+                                limit,
+                            ),
+                            None => Ok(SignatureInfosForAddress::default()),
+                        };
+                    }
                     Some((slot, _)) => {
                         let mut slot_signatures = self.get_block_signatures_rev(slot)?;
                         if let Some(pos) = slot_signatures.iter().position(|&x| x == before) {
@@ -3330,24 +3437,28 @@ impl Blockstore {
         };
         get_before_slot_timer.stop();
 
-        let first_available_block = self.get_first_available_block()?;
+        let first_available_block = self.first_available_block()?.unwrap_or_default();
         // Generate a HashSet of signatures that should be excluded from the results based on
         // `until` signature
         let mut get_until_slot_timer = Measure::start("get_until_slot_timer");
-        let (lowest_slot, until_excluded_signatures) = match until {
-            None => (first_available_block, HashSet::new()),
+        let (lowest_slot, until_excluded_signatures, until_found) = match until {
+            None => (first_available_block, HashSet::new(), false),
             Some(until) => {
                 let transaction_status =
                     self.get_transaction_status(until, &confirmed_unrooted_slots)?;
                 match transaction_status {
-                    None => (first_available_block, HashSet::new()),
+                    None => (first_available_block, HashSet::new(), false),
                     Some((slot, _)) => {
                         let mut slot_signatures = self.get_block_signatures_rev(slot)?;
                         if let Some(pos) = slot_signatures.iter().position(|&x| x == until) {
                             slot_signatures = slot_signatures.split_off(pos);
                         }
 
-                        (slot, slot_signatures.into_iter().collect::<HashSet<_>>())
+                        (
+                            slot,
+                            slot_signatures.into_iter().collect::<HashSet<_>>(),
+                            true,
+                        )
                     }
                 }
             }
@@ -3427,6 +3538,23 @@ impl Blockstore {
         }
         get_status_info_timer.stop();
 
+        // Continue listing the signatures of archived slots, unless the `until` signature has
+        // already been reached
+        let archive = self.archive().filter(|_| !until_found);
+        let archived_highest_slot = slot.min(lowest_slot).checked_sub(1);
+        if let (Some(archive), Some(archived_highest_slot)) = (archive, archived_highest_slot) {
+            if infos.len() < limit {
+                let archived_infos = archive.get_confirmed_signatures_for_address2(
+                    address,
+                    archived_highest_slot,
+                    None,
+                    until,
+                    limit - infos.len(),
+                )?;
+                infos.extend(archived_infos.infos);
+            }
+        }
+
         datapoint_info!(
             "blockstore-get-conf-sigs-for-addr-2",
             (
@@ -3510,6 +3638,107 @@ impl Blockstore {
         self.perf_samples_cf.put_bytes(index, &bytes)
     }
 
+    /// Writes the account versions recorded by the RPC account history in the
+    /// rooted `slot`, and marks `slot` as covered by a history that is
+    /// continuous since `first_available_slot`
+    pub fn write_account_history(
+        &self,
+        slot: Slot,
+        first_available_slot: Slot,
+        versions: impl IntoIterator<Item = (Pubkey, AccountHistoryVersion)>,
+    ) -> Result<()> {
+        let mut write_batch = self.get_write_batch()?;
+        for (pubkey, version) in versions {
+            self.account_history_cf
+                .put_in_batch(&mut write_batch, (pubkey, slot), &version)?;
+        }
+        self.account_history_roots_cf.put_in_batch(
+            &mut write_batch,
+            slot,
+            &first_available_slot,
+        )?;
+        self.write_batch(write_batch)
+    }
+
+    /// Writes an account version restored from the snapshot at the rooted
+    /// `slot`, which is marked by the following `write_account_history()`
+    pub fn write_account_history_version(
+        &self,
+        pubkey: Pubkey,
+        slot: Slot,
+        version: &AccountHistoryVersion,
+    ) -> Result<()> {
+        self.account_history_cf.put((pubkey, slot), version)
+    }
+
+    /// Returns the first slot of the account history that is continuous up to
+    /// `slot`, if the account history recorded a root at or after `slot`
+    pub fn get_account_history_first_available_slot(&self, slot: Slot) -> Result<Option<Slot>> {
+        let (_lock, lowest_available_slot) = self.ensure_lowest_cleanup_slot();
+        Ok(self
+            .account_history_roots_cf
+            .iter(IteratorMode::From(slot, IteratorDirection::Forward))?
+            .next()
+            .map(|(_root, data)| deserialize::<Slot>(&data))
+            .transpose()?
+            .filter(|first_available_slot| *first_available_slot <= slot)
+            .map(|first_available_slot| first_available_slot.max(lowest_available_slot)))
+    }
+
+    /// Returns whether the account history holds any version of the account
+    pub fn has_account_history(&self, pubkey: Pubkey) -> Result<bool> {
+        Ok(self
+            .account_history_cf
+            .iter(IteratorMode::From((pubkey, 0), IteratorDirection::Forward))?
+            .next()
+            .is_some_and(|((key_pubkey, _slot), _data)| key_pubkey == pubkey))
+    }
+
+    /// Returns the accounts the account history holds any version of
+    pub fn get_account_history_pubkeys(&self) -> Result<Vec<Pubkey>> {
+        let mut pubkeys: Vec<Pubkey> = vec![];
+        loop {
+            // Skip the other versions of the last account found
+            let from = pubkeys
+                .last()
+                .map_or((Pubkey::default(), 0), |pubkey| (*pubkey, Slot::MAX));
+            let next_pubkey = self
+                .account_history_cf
+                .iter(IteratorMode::From(from, IteratorDirection::Forward))?
+                .map(|((pubkey, _slot), _data)| pubkey)
+                .find(|pubkey| pubkeys.last() != Some(pubkey));
+            match next_pubkey {
+                Some(pubkey) => pubkeys.push(pubkey),
+                None => return Ok(pubkeys),
+            }
+        }
+    }
+
+    /// Returns the latest version of the account recorded by the account
+    /// history at or before `slot`, and not before `first_slot`
+    pub fn get_account_history_version(
+        &self,
+        pubkey: Pubkey,
+        first_slot: Slot,
+        slot: Slot,
+    ) -> Result<Option<AccountHistoryVersion>> {
+        // Versions before the lowest cleanup slot may still be present until
+        // the compaction filter removes them, but are no longer the latest
+        let (_lock, lowest_available_slot) = self.ensure_lowest_cleanup_slot();
+        let first_slot = first_slot.max(lowest_available_slot);
+        self.account_history_cf
+            .iter(IteratorMode::From(
+                (pubkey, slot),
+                IteratorDirection::Reverse,
+            ))?
+            .next()
+            .filter(|((key_pubkey, key_slot), _data)| {
+                *key_pubkey == pubkey && *key_slot >= first_slot
+            })
+            .map(|(_key, data)| deserialize::<AccountHistoryVersion>(&data).map_err(Into::into))
+            .transpose()
+    }
+
     /// Returns the entry vector for the slot starting with `shred_start_index`
     pub fn get_slot_entries(&self, slot: Slot, shred_start_index: u64) -> Result<Vec<Entry>> {
         self.get_slot_entries_with_shred_info(slot, shred_start_index, false)
@@ -4144,11 +4373,139 @@ impl Blockstore {
             .get_int_property(RocksProperties::TOTAL_SST_FILES_SIZE)
     }
 
+    /// Attach the blockstore that rooted slots are archived to before they
+    /// are cleaned up from this blockstore. Rooted blocks and transactions
+    /// that have been cleaned up are then read from the archive.
+    pub fn set_archive(&self, archive: Arc<Blockstore>) {
+        *self.archive.write().unwrap() = Some(archive);
+    }
+
+    /// Returns the blockstore that rooted slots are archived to, if any
+    pub fn archive(&self) -> Option<Arc<Blockstore>> {
+        self.archive.read().unwrap().clone()
+    }
+
+    fn read_from_archive<T>(&self, read: impl FnOnce(&Blockstore) -> Result<T>) -> Result<T> {
+        match self.archive() {
+            Some(archive) => read(&archive),
+            None => Err(BlockstoreError::SlotCleanedUp),
+        }
+    }
+
+    /// Copy a rooted slot to `archive` and mark it as a root there. Only the
+    /// columns needed to serve the block and its transactions are copied: the
+    /// slot meta, data shreds, transaction statuses, address signatures,
+    /// memos, rewards, block time, block height and bank hash. Coding shreds
+    /// and the other repair related columns are left behind, as a rooted slot
+    /// is never repaired.
+    pub fn archive_rooted_slot(&self, slot: Slot, archive: &Blockstore) -> Result<()> {
+        if !self.is_root(slot) {
+            return Err(BlockstoreError::SlotNotRooted);
+        }
+        let mut write_batch = archive.get_write_batch()?;
+        if let Some(slot_meta) = self.meta_cf.get_bytes(slot)? {
+            archive
+                .meta_cf
+                .put_bytes_in_batch(&mut write_batch, slot, &slot_meta)?;
+        }
+        for (key, shred) in self.slot_data_iterator(slot, 0)? {
+            archive
+                .data_shred_cf
+                .put_bytes_in_batch(&mut write_batch, key, &shred)?;
+        }
+        if let Some(block_time) = self.blocktime_cf.get_bytes(slot)? {
+            archive
+                .blocktime_cf
+                .put_bytes_in_batch(&mut write_batch, slot, &block_time)?;
+        }
+        if let Some(block_height) = self.block_height_cf.get_bytes(slot)? {
+            archive
+                .block_height_cf
+                .put_bytes_in_batch(&mut write_batch, slot, &block_height)?;
+        }
+        if let Some(rewards) = self.rewards_cf.get_bytes(slot)? {
+            archive
+                .rewards_cf
+                .put_bytes_in_batch(&mut write_batch, slot, &rewards)?;
+        }
+        if let Some(bank_hash) = self.bank_hash_cf.get_bytes(slot)? {
+            archive
+                .bank_hash_cf
+                .put_bytes_in_batch(&mut write_batch, slot, &bank_hash)?;
+        }
+
+        let transactions = self
+            .get_slot_entries(slot, 0)?
+            .into_iter()
+            .flat_map(|entry| entry.transactions);
+        for (transaction_index, transaction) in transactions.enumerate() {
+            let signature = transaction.signatures[0];
+            let Some(status) = self.transaction_status_cf.get_bytes((signature, slot))? else {
+                continue;
+            };
+            archive.transaction_status_cf.put_bytes_in_batch(
+                &mut write_batch,
+                (signature, slot),
+                &status,
+            )?;
+            if let Some(memos) = self.transaction_memos_cf.get_bytes((signature, slot))? {
+                archive.transaction_memos_cf.put_bytes_in_batch(
+                    &mut write_batch,
+                    (signature, slot),
+                    &memos,
+                )?;
+            }
+
+            // Address signatures are keyed by address first, so look up the
+            // entry of every address the transaction loaded
+            let transaction_index = u32::try_from(transaction_index)
+                .map_err(|_| BlockstoreError::TransactionIndexOverflow)?;
+            let loaded_addresses = self
+                .read_transaction_status((signature, slot))?
+                .map(|status| status.loaded_addresses)
+                .unwrap_or_default();
+            for address in transaction
+                .message
+                .static_account_keys()
+                .iter()
+                .chain(&loaded_addresses.writable)
+                .chain(&loaded_addresses.readonly)
+            {
+                let key = (*address, slot, transaction_index, signature);
+                if let Some(address_signature) = self.address_signatures_cf.get_bytes(key)? {
+                    archive.address_signatures_cf.put_bytes_in_batch(
+                        &mut write_batch,
+                        key,
+                        &address_signature,
+                    )?;
+                }
+            }
+        }
+
+        archive
+            .roots_cf
+            .put_in_batch(&mut write_batch, slot, &true)?;
+        archive.write_batch(write_batch)?;
+        archive.max_root.fetch_max(slot, Ordering::Relaxed);
+        Ok(())
+    }
+
     /// Returns whether the blockstore has primary (read and write) access
     pub fn is_primary_access(&self) -> bool {
         self.db.is_primary_access()
     }
 
+    /// Catch up a blockstore opened with `AccessType::Secondary` with the
+    /// writes made by the primary since the blockstore was opened or last
+    /// caught up, and refresh the cached max root accordingly
+    pub fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        if let Some((max_root, _)) = self.roots_cf.iter(IteratorMode::End)?.next() {
+            self.max_root.fetch_max(max_root, Ordering::Relaxed);
+        }
+        self.update_highest_primary_index_slot()
+    }
+
     /// Scan for any ancestors of the supplied `start_root` that are not
     /// marked as roots themselves. Mark any found slots as roots since
     /// the ancestor of a root is also inherently a root. Returns the
@@ -9859,6 +10216,92 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_account_history() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let version = |lamports| AccountHistoryVersion {
+            lamports,
+            data: vec![1, 2, 3],
+            ..AccountHistoryVersion::default()
+        };
+        assert!(!blockstore.has_account_history(pubkey).unwrap());
+        assert!(blockstore.get_account_history_pubkeys().unwrap().is_empty());
+        assert_eq!(
+            blockstore
+                .get_account_history_first_available_slot(2)
+                .unwrap(),
+            None
+        );
+
+        blockstore
+            .write_account_history_version(pubkey, 2, &version(10))
+            .unwrap();
+        blockstore.write_account_history(2, 2, []).unwrap();
+        blockstore
+            .write_account_history(4, 2, [(pubkey, version(20)), (other_pubkey, version(30))])
+            .unwrap();
+        blockstore.write_account_history(5, 2, []).unwrap();
+        assert!(blockstore.has_account_history(pubkey).unwrap());
+        let mut pubkeys = vec![pubkey, other_pubkey];
+        pubkeys.sort_unstable();
+        assert_eq!(blockstore.get_account_history_pubkeys().unwrap(), pubkeys);
+
+        assert_eq!(
+            blockstore
+                .get_account_history_first_available_slot(1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history_first_available_slot(3)
+                .unwrap(),
+            Some(2)
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history_first_available_slot(6)
+                .unwrap(),
+            None
+        );
+
+        assert_eq!(
+            blockstore
+                .get_account_history_version(pubkey, 2, 1)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history_version(pubkey, 2, 3)
+                .unwrap(),
+            Some(version(10))
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history_version(pubkey, 2, 5)
+                .unwrap(),
+            Some(version(20))
+        );
+        // The version written at slot 2 is before the first available slot
+        assert_eq!(
+            blockstore
+                .get_account_history_version(pubkey, 3, 3)
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            blockstore
+                .get_account_history_version(Pubkey::new_unique(), 2, 5)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn test_lowest_slot() {
         let ledger_path = get_tmp_ledger_path_auto_delete!();
@@ -208,6 +208,27 @@ pub mod columns {
     /// * index type: `crate::shred::ErasureSetId` `(Slot, fec_set_index: u32)`
     /// * value type: [`blockstore_meta::MerkleRootMeta`]`
     pub struct MerkleRootMeta;
+
+    #[derive(Debug)]
+    /// The account history column
+    ///
+    /// This column family stores the versions of the accounts selected for the
+    /// RPC account history, by the rooted slot they were written in. Like the
+    /// address signatures, it is cleaned up by the compaction filter.
+    ///
+    /// * index type: `(`[`Pubkey`]`, `[`Slot`]`)`
+    /// * value type: [`blockstore_meta::AccountHistoryVersion`]
+    pub struct AccountHistory;
+
+    #[derive(Debug)]
+    /// The account history roots column
+    ///
+    /// This column family marks the roots recorded by the RPC account history,
+    /// with the first slot of the history that is continuous up to that root.
+    ///
+    /// * index type: `u64` (see [`SlotColumn`])
+    /// * value type: [`Slot`]
+    pub struct AccountHistoryRoots;
 }
 
 macro_rules! convert_column_index_to_key_bytes {
@@ -837,3 +858,47 @@ impl ColumnName for columns::MerkleRootMeta {
 impl TypedColumn for columns::MerkleRootMeta {
     type Type = blockstore_meta::MerkleRootMeta;
 }
+
+impl Column for columns::AccountHistory {
+    type Index = (Pubkey, Slot);
+    type Key = [u8; PUBKEY_BYTES + std::mem::size_of::<Slot>()];
+
+    #[inline]
+    fn key((pubkey, slot): &Self::Index) -> Self::Key {
+        convert_column_index_to_key_bytes!(Key,
+              ..32 => pubkey.as_ref(),
+            32..   => &slot.to_be_bytes(),
+        )
+    }
+
+    fn index(key: &[u8]) -> Self::Index {
+        convert_column_key_bytes_to_index!(key,
+             0..32 => Pubkey::from,
+            32..40 => Slot::from_be_bytes,
+        )
+    }
+
+    fn slot((_pubkey, slot): Self::Index) -> Slot {
+        slot
+    }
+
+    // The AccountHistory column is not keyed by slot so this method is meaningless
+    // See Column::as_index() declaration for more details
+    fn as_index(_index: u64) -> Self::Index {
+        (Pubkey::default(), 0)
+    }
+}
+impl ColumnName for columns::AccountHistory {
+    const NAME: &'static str = "account_history";
+}
+impl TypedColumn for columns::AccountHistory {
+    type Type = blockstore_meta::AccountHistoryVersion;
+}
+
+impl SlotColumn for columns::AccountHistoryRoots {}
+impl ColumnName for columns::AccountHistoryRoots {
+    const NAME: &'static str = "account_history_roots";
+}
+impl TypedColumn for columns::AccountHistoryRoots {
+    type Type = Slot;
+}
@@ -0,0 +1,371 @@
+//! The `blockstore_archive_service` is an alternative to the
+//! `blockstore_cleanup_service` that keeps old rooted slots instead of dropping
+//! them. Once the ledger holds more shreds than a size threshold, or rooted
+//! slots are older than an age threshold, the service copies the old rooted
+//! slots into an archive blockstore, typically on a cheaper disk, and only then
+//! purges them from the ledger. Archived blocks and transactions can still be
+//! read through the ledger's Blockstore; see `Blockstore::set_archive()`.
+
+use {
+    crate::{
+        blockstore::{
+            column::{columns, ColumnName},
+            Blockstore,
+        },
+        blockstore_cleanup_service::{
+            BlockstoreCleanupService, DEFAULT_CLEANUP_SLOT_INTERVAL, LOOP_LIMITER,
+        },
+        blockstore_options::{
+            AccessType, BlockstoreCompressionType, BlockstoreOptions, ZstdCompressionOptions,
+        },
+    },
+    solana_clock::Slot,
+    solana_measure::measure::Measure,
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
+};
+
+/// The thresholds past which rooted slots are moved to the archive
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BlockstoreArchiveConfig {
+    /// Archive the oldest rooted slots once the ledger holds more than this
+    /// many shreds
+    pub max_ledger_shreds: Option<u64>,
+    /// Archive rooted slots that are more than this many slots older than the
+    /// latest root
+    pub max_slot_age: Option<u64>,
+}
+
+/// Returns the options to open an archive blockstore with, based on the
+/// options of the ledger blockstore
+///
+/// Archived data is rarely read, so the archived columns are compressed with
+/// zstd unless a compression has been explicitly set for them.
+pub fn archive_blockstore_options(ledger_options: &BlockstoreOptions) -> BlockstoreOptions {
+    let mut archive_options = BlockstoreOptions {
+        access_type: AccessType::Primary,
+        ..ledger_options.clone()
+    };
+    for column_name in [
+        columns::SlotMeta::NAME,
+        columns::ShredData::NAME,
+        columns::TransactionStatus::NAME,
+        columns::AddressSignatures::NAME,
+        columns::TransactionMemos::NAME,
+        columns::Rewards::NAME,
+    ] {
+        archive_options
+            .column_options
+            .column_compression_types
+            .entry(column_name.to_string())
+            .or_insert(BlockstoreCompressionType::Zstd(
+                ZstdCompressionOptions::default(),
+            ));
+    }
+    archive_options
+}
+
+pub struct BlockstoreArchiveService {
+    t_archive: JoinHandle<()>,
+}
+
+impl BlockstoreArchiveService {
+    pub fn new(
+        blockstore: Arc<Blockstore>,
+        archive: Arc<Blockstore>,
+        config: BlockstoreArchiveConfig,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        let mut last_archive_root = 0;
+        let mut last_check_time = Instant::now();
+        // Slots are purged from the ledger once archived, so reads of them
+        // must go to the archive
+        blockstore.set_archive(archive.clone());
+
+        let t_archive = Builder::new()
+            .name("solBstoreArchive".to_string())
+            .spawn(move || {
+                info!("BlockstoreArchiveService has started with {config:?}");
+                loop {
+                    if exit.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if last_check_time.elapsed() > LOOP_LIMITER {
+                        Self::archive_ledger(
+                            &blockstore,
+                            &archive,
+                            &config,
+                            &mut last_archive_root,
+                            DEFAULT_CLEANUP_SLOT_INTERVAL,
+                        );
+
+                        last_check_time = Instant::now();
+                    }
+                    // Only sleep for 1 second instead of LOOP_LIMITER so that this
+                    // thread can respond to the exit flag in a timely manner
+                    thread::sleep(Duration::from_secs(1));
+                }
+                info!("BlockstoreArchiveService has stopped");
+            })
+            .unwrap();
+
+        Self { t_archive }
+    }
+
+    /// Returns the highest slot that should be archived (and then purged) at
+    /// the given root, or `None` if nothing beyond what was already archived
+    /// has crossed the thresholds in `config`.
+    fn find_slots_to_archive(
+        blockstore: &Blockstore,
+        root: Slot,
+        config: &BlockstoreArchiveConfig,
+    ) -> Option<Slot> {
+        let highest_slot_by_age = config
+            .max_slot_age
+            .and_then(|max_slot_age| root.checked_sub(max_slot_age));
+        let highest_slot_by_size = config.max_ledger_shreds.and_then(|max_ledger_shreds| {
+            let (slots_to_clean, lowest_cleanup_slot, _total_shreds) =
+                BlockstoreCleanupService::find_slots_to_clean(blockstore, root, max_ledger_shreds);
+            slots_to_clean.then_some(lowest_cleanup_slot)
+        });
+        highest_slot_by_age
+            .max(highest_slot_by_size)
+            .filter(|slot| *slot > blockstore.lowest_cleanup_slot())
+    }
+
+    /// Checks for new roots and, if the last check was at least
+    /// `archive_interval` slots ago, copies the rooted slots that crossed the
+    /// thresholds in `config` to `archive` before purging them from
+    /// `blockstore`. Slots that were never rooted are purged without being
+    /// archived.
+    ///
+    /// If a slot fails to be archived, nothing is purged so that no rooted
+    /// slot is lost; archiving is retried on the next check.
+    pub fn archive_ledger(
+        blockstore: &Blockstore,
+        archive: &Blockstore,
+        config: &BlockstoreArchiveConfig,
+        last_archive_root: &mut Slot,
+        archive_interval: u64,
+    ) {
+        let root = blockstore.max_root();
+        if root.saturating_sub(*last_archive_root) <= archive_interval {
+            return;
+        }
+        *last_archive_root = root;
+        info!("Looking for Blockstore data to archive, latest root: {root}");
+
+        let Some(highest_slot_to_archive) = Self::find_slots_to_archive(blockstore, root, config)
+        else {
+            return;
+        };
+
+        let mut archive_time = Measure::start("archive_slots");
+        let mut num_archived_slots = 0;
+        let rooted_slots = match blockstore.rooted_slot_iterator(blockstore.lowest_cleanup_slot()) {
+            Ok(rooted_slots) => rooted_slots,
+            Err(err) => {
+                error!("Failed to iterate over rooted slots to archive: {err:?}");
+                return;
+            }
+        };
+        for slot in rooted_slots.take_while(|slot| *slot <= highest_slot_to_archive) {
+            if let Err(err) = blockstore.archive_rooted_slot(slot, archive) {
+                error!("Failed to archive slot {slot}, skipping Blockstore cleanup: {err:?}");
+                return;
+            }
+            num_archived_slots += 1;
+        }
+        archive_time.stop();
+        info!(
+            "Archived {num_archived_slots} rooted slots up to slot {highest_slot_to_archive}. \
+             {archive_time}"
+        );
+
+        BlockstoreCleanupService::purge_slots_up_to(blockstore, highest_slot_to_archive);
+        datapoint_info!(
+            "blockstore_archive",
+            ("num_archived_slots", num_archived_slots, i64),
+            ("archive_us", archive_time.as_us(), i64),
+        );
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.t_archive.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            blockstore::{entries_to_test_shreds, tests::make_slot_entries_with_transactions},
+            get_tmp_ledger_path_auto_delete,
+        },
+        solana_transaction_status::TransactionStatusMeta,
+    };
+
+    #[test]
+    fn test_archive_ledger() {
+        agave_logger::setup();
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Blockstore::open(ledger_path.path()).unwrap();
+        let archive_path = get_tmp_ledger_path_auto_delete!();
+        let archive = Arc::new(
+            Blockstore::open_with_options(
+                archive_path.path(),
+                archive_blockstore_options(&BlockstoreOptions::default()),
+            )
+            .unwrap(),
+        );
+
+        // Insert and root slots [1, 10], along with the status of each transaction
+        let mut slot_entries = vec![vec![]];
+        for slot in 1..=10 {
+            let entries = make_slot_entries_with_transactions(4);
+            let shreds = entries_to_test_shreds(&entries, slot, slot - 1, true, 0);
+            blockstore.insert_shreds(shreds, None, false).unwrap();
+            let transactions = entries.iter().flat_map(|entry| &entry.transactions);
+            for (transaction_index, transaction) in transactions.enumerate() {
+                blockstore
+                    .write_transaction_status(
+                        slot,
+                        transaction.signatures[0],
+                        transaction
+                            .message
+                            .static_account_keys()
+                            .iter()
+                            .map(|key| (key, true)),
+                        TransactionStatusMeta::default(),
+                        transaction_index,
+                    )
+                    .unwrap();
+            }
+            slot_entries.push(entries);
+        }
+        blockstore
+            .set_roots((1..=10).collect::<Vec<_>>().iter())
+            .unwrap();
+        blockstore.set_archive(archive.clone());
+
+        // Nothing is old enough to be archived
+        let config = BlockstoreArchiveConfig {
+            max_ledger_shreds: None,
+            max_slot_age: Some(10),
+        };
+        let mut last_archive_root = 0;
+        BlockstoreArchiveService::archive_ledger(
+            &blockstore,
+            &archive,
+            &config,
+            &mut last_archive_root,
+            0,
+        );
+        assert_eq!(last_archive_root, 10);
+        assert_eq!(blockstore.lowest_cleanup_slot(), 0);
+        assert_eq!(archive.max_root(), 0);
+
+        // Slots [1, 7] are at least 4 slots older than the latest root
+        let config = BlockstoreArchiveConfig {
+            max_ledger_shreds: None,
+            max_slot_age: Some(4),
+        };
+        blockstore.set_roots([11].iter()).unwrap();
+        BlockstoreArchiveService::archive_ledger(
+            &blockstore,
+            &archive,
+            &config,
+            &mut last_archive_root,
+            0,
+        );
+        assert_eq!(blockstore.lowest_cleanup_slot(), 7);
+        assert_eq!(archive.max_root(), 7);
+        assert_eq!(
+            archive.rooted_slot_iterator(0).unwrap().collect::<Vec<_>>(),
+            (1..=7).collect::<Vec<_>>()
+        );
+        assert_eq!(archive.get_slot_entries(3, 0).unwrap(), slot_entries[3]);
+
+        // Archived slots are read from the archive, the others from the ledger
+        for slot in [1, 7, 8] {
+            let block = blockstore.get_rooted_block(slot, false).unwrap();
+            assert_eq!(block.parent_slot, slot - 1);
+            assert_eq!(block.transactions.len(), 4);
+        }
+        let archived_transaction = &slot_entries[3][0].transactions[0];
+        let signature = archived_transaction.signatures[0];
+        let confirmed_transaction = blockstore.get_rooted_transaction(signature).unwrap();
+        assert_eq!(confirmed_transaction.unwrap().slot, 3);
+
+        let address = archived_transaction.message.static_account_keys()[0];
+        let signatures_for_address = archive
+            .get_confirmed_signatures_for_address2(address, 7, None, None, 10)
+            .unwrap()
+            .infos;
+        assert_eq!(signatures_for_address.len(), 1);
+        assert_eq!(signatures_for_address[0].signature, signature);
+
+        // The first available block and the rooted slots include the archive. Slot 8 is the
+        // first root left in the ledger, so its parent's entries are not available
+        assert_eq!(blockstore.get_first_available_block().unwrap(), 2);
+        assert_eq!(
+            blockstore.get_rooted_slots(0, Slot::MAX, 100).unwrap(),
+            vec![2, 3, 4, 5, 6, 7, 9, 10, 11]
+        );
+        assert_eq!(
+            blockstore.get_rooted_slots(5, 10, 3).unwrap(),
+            vec![5, 6, 7]
+        );
+        assert_eq!(
+            blockstore.get_rooted_slots(7, 10, 100).unwrap(),
+            vec![7, 9, 10]
+        );
+
+        // Signatures for an address continue into the archive
+        let unarchived_transaction = &slot_entries[9][0].transactions[0];
+        let unarchived_signature = unarchived_transaction.signatures[0];
+        blockstore
+            .write_transaction_status(
+                9,
+                unarchived_signature,
+                [(&address, true)].into_iter(),
+                TransactionStatusMeta::default(),
+                0,
+            )
+            .unwrap();
+        let signatures_for_address = |before, limit| {
+            blockstore
+                .get_confirmed_signatures_for_address2(address, 11, before, None, limit)
+                .unwrap()
+                .infos
+                .into_iter()
+                .map(|info| (info.slot, info.signature))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            signatures_for_address(None, 10),
+            vec![(9, unarchived_signature), (3, signature)]
+        );
+        assert_eq!(
+            signatures_for_address(None, 1),
+            vec![(9, unarchived_signature)]
+        );
+        assert_eq!(
+            signatures_for_address(Some(unarchived_signature), 10),
+            vec![(3, signature)]
+        );
+        // A `before` signature in the archive is found there
+        let result = blockstore
+            .get_confirmed_signatures_for_address2(address, 11, Some(signature), None, 10)
+            .unwrap();
+        assert!(result.found_before);
+        assert!(result.infos.is_empty());
+    }
+}
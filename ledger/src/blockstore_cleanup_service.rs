@@ -38,14 +38,14 @@ pub const DEFAULT_MIN_MAX_LEDGER_SHREDS: u64 = 50_000_000;
 
 // Perform blockstore cleanup at this interval to limit the overhead of cleanup
 // Cleanup will be considered after the latest root has advanced by this value
-const DEFAULT_CLEANUP_SLOT_INTERVAL: u64 = 512;
+pub(crate) const DEFAULT_CLEANUP_SLOT_INTERVAL: u64 = 512;
 // The above slot interval can be roughly equated to a time interval. So, scale
 // how often we check for cleanup with the interval. Doing so will avoid wasted
 // checks when we know that the latest root could not have advanced far enough
 //
 // Given that the timing of new slots/roots is not exact, divide by 10 to avoid
 // a long wait incase a check occurs just before the interval has elapsed
-const LOOP_LIMITER: Duration =
+pub(crate) const LOOP_LIMITER: Duration =
     Duration::from_millis(DEFAULT_CLEANUP_SLOT_INTERVAL * DEFAULT_MS_PER_SLOT / 10);
 
 pub struct BlockstoreCleanupService {
@@ -101,7 +101,7 @@ impl BlockstoreCleanupService {
     ///   cleaned up.
     /// - `total_shreds` (u64): the total estimated number of shreds before the
     ///   `root`.
-    fn find_slots_to_clean(
+    pub(crate) fn find_slots_to_clean(
         blockstore: &Blockstore,
         root: Slot,
         max_ledger_shreds: u64,
@@ -212,35 +212,40 @@ impl BlockstoreCleanupService {
             Self::find_slots_to_clean(blockstore, root, max_ledger_shreds);
 
         if slots_to_clean {
-            *blockstore.lowest_cleanup_slot.write().unwrap() = lowest_cleanup_slot;
-
-            let mut purge_time = Measure::start("purge_slots()");
-            // purge any slots older than lowest_cleanup_slot.
-            let _ = blockstore
-                .purge_slots(0, lowest_cleanup_slot, PurgeType::CompactionFilter)
-                .inspect_err(|e| {
-                    error!("Purge failed when cleaning ledger to {lowest_cleanup_slot}: {e:?}")
-                });
-            // Update only after purge operation.
-            // Safety: This value can be used by compaction_filters shared via Arc<AtomicU64>.
-            // Compactions are async and run as a multi-threaded background job. However, this
-            // shouldn't cause consistency issues for iterators and getters because we have
-            // already expired all affected keys (older than or equal to lowest_cleanup_slot)
-            // by the above `purge_slots`. According to the general RocksDB design where SST
-            // files are immutable, even running iterators aren't affected; the database grabs
-            // a snapshot of the live set of sst files at iterator's creation.
-            // Also, we passed the PurgeType::CompactionFilter, meaning no delete_range for
-            // transaction_status and address_signatures CFs. These are fine because they
-            // don't require strong consistent view for their operation.
-            blockstore.set_max_expired_slot(lowest_cleanup_slot);
-            purge_time.stop();
-            info!("Cleaned up Blockstore data older than slot {lowest_cleanup_slot}. {purge_time}");
+            Self::purge_slots_up_to(blockstore, lowest_cleanup_slot);
         }
 
         let disk_utilization_post = blockstore.storage_size();
         Self::report_disk_metrics(disk_utilization_pre, disk_utilization_post, total_shreds);
     }
 
+    /// Purges all slots older than or equal to `lowest_cleanup_slot`
+    pub(crate) fn purge_slots_up_to(blockstore: &Blockstore, lowest_cleanup_slot: Slot) {
+        *blockstore.lowest_cleanup_slot.write().unwrap() = lowest_cleanup_slot;
+
+        let mut purge_time = Measure::start("purge_slots()");
+        // purge any slots older than lowest_cleanup_slot.
+        let _ = blockstore
+            .purge_slots(0, lowest_cleanup_slot, PurgeType::CompactionFilter)
+            .inspect_err(|e| {
+                error!("Purge failed when cleaning ledger to {lowest_cleanup_slot}: {e:?}")
+            });
+        // Update only after purge operation.
+        // Safety: This value can be used by compaction_filters shared via Arc<AtomicU64>.
+        // Compactions are async and run as a multi-threaded background job. However, this
+        // shouldn't cause consistency issues for iterators and getters because we have
+        // already expired all affected keys (older than or equal to lowest_cleanup_slot)
+        // by the above `purge_slots`. According to the general RocksDB design where SST
+        // files are immutable, even running iterators aren't affected; the database grabs
+        // a snapshot of the live set of sst files at iterator's creation.
+        // Also, we passed the PurgeType::CompactionFilter, meaning no delete_range for
+        // transaction_status and address_signatures CFs. These are fine because they
+        // don't require strong consistent view for their operation.
+        blockstore.set_max_expired_slot(lowest_cleanup_slot);
+        purge_time.stop();
+        info!("Cleaned up Blockstore data older than slot {lowest_cleanup_slot}. {purge_time}");
+    }
+
     fn report_disk_metrics(
         pre: blockstore::Result<u64>,
         post: blockstore::Result<u64>,
@@ -14,7 +14,9 @@ use {
             PERF_METRIC_OP_NAME_MULTI_GET, PERF_METRIC_OP_NAME_PUT,
             PERF_METRIC_OP_NAME_WRITE_BATCH,
         },
-        blockstore_options::{AccessType, BlockstoreOptions, LedgerColumnOptions},
+        blockstore_options::{
+            AccessType, BlockstoreCompressionType, BlockstoreOptions, LedgerColumnOptions,
+        },
     },
     bincode::deserialize,
     log::*,
@@ -36,11 +38,13 @@ use {
         marker::PhantomData,
         num::NonZeroUsize,
         path::{Path, PathBuf},
+        process,
         sync::{
             atomic::{AtomicBool, AtomicU64, Ordering},
             Arc,
         },
     },
+    tempfile::TempDir,
 };
 
 const BLOCKSTORE_METRICS_ERROR: i64 = -1;
@@ -55,6 +59,15 @@ const MAX_WRITE_BUFFER_SIZE: u64 = 256 * 1024 * 1024; // 256MB
 //   include/rocksdb/advanced_options.h#L908C30-L908C30
 const PERIODIC_COMPACTION_SECONDS: u64 = 60 * 60 * 24;
 
+// RocksDB defaults for the compression options that are not configurable
+// through `ZstdCompressionOptions`; these are ignored by zstd.
+const ZSTD_WINDOW_BITS: i32 = -14;
+const ZSTD_STRATEGY: i32 = 0;
+// The amount of sampled data to train a zstd dictionary on, relative to the
+// size of the dictionary. RocksDB recommends a training set of ~100x the size
+// of the dictionary.
+const ZSTD_TRAINING_BYTES_PER_DICT_BYTE: i32 = 100;
+
 pub enum IteratorMode<Index> {
     Start,
     End,
@@ -102,6 +115,9 @@ pub(crate) struct Rocks {
     oldest_slot: OldestSlot,
     column_options: Arc<LedgerColumnOptions>,
     write_batch_perf_status: PerfSamplingStatus,
+    // The directory where a Secondary instance keeps its own info logs. It is
+    // removed when dropped, which happens after `db` is closed
+    _secondary_dir: Option<TempDir>,
 }
 
 impl Rocks {
@@ -120,6 +136,7 @@ impl Rocks {
         let column_options = Arc::from(options.column_options);
 
         // Open the database
+        let mut secondary_dir = None;
         let mut db = match options.access_type {
             AccessType::Primary | AccessType::PrimaryForMaintenance => {
                 DB::open_cf_descriptors(&db_options, &path, cf_descriptors)?
@@ -137,6 +154,27 @@ impl Rocks {
                     error_if_log_file_exists,
                 )?
             }
+            AccessType::Secondary => {
+                // Every secondary instance needs a directory of its own for
+                // its info logs, which must not be shared with the primary or
+                // any other secondary instance
+                let dir = tempfile::Builder::new()
+                    .prefix(&format!("solana-secondary-{}-", process::id()))
+                    .tempdir()?;
+                info!(
+                    "Opening Rocks with secondary access at {}. This additional access could \
+                     temporarily degrade other accesses, such as by agave-validator",
+                    dir.path().display()
+                );
+                let db = DB::open_cf_descriptors_as_secondary(
+                    &db_options,
+                    &path,
+                    dir.path(),
+                    cf_descriptors,
+                )?;
+                secondary_dir = Some(dir);
+                db
+            }
         };
 
         // Delete the now unused program_costs column if it is present
@@ -151,6 +189,7 @@ impl Rocks {
             oldest_slot,
             column_options,
             write_batch_perf_status: PerfSamplingStatus::default(),
+            _secondary_dir: secondary_dir,
         };
 
         rocks.configure_compaction();
@@ -193,6 +232,8 @@ impl Rocks {
             new_cf_descriptor::<columns::BlockHeight>(options, oldest_slot),
             new_cf_descriptor::<columns::OptimisticSlots>(options, oldest_slot),
             new_cf_descriptor::<columns::MerkleRootMeta>(options, oldest_slot),
+            new_cf_descriptor::<columns::AccountHistory>(options, oldest_slot),
+            new_cf_descriptor::<columns::AccountHistoryRoots>(options, oldest_slot),
         ];
 
         // When remaining columns are optional we can just return immediately here.
@@ -237,7 +278,7 @@ impl Rocks {
         cf_descriptors
     }
 
-    const fn columns() -> [&'static str; 20] {
+    pub(crate) const fn columns() -> [&'static str; 22] {
         [
             columns::ErasureMeta::NAME,
             columns::DeadSlots::NAME,
@@ -259,6 +300,8 @@ impl Rocks {
             columns::BlockHeight::NAME,
             columns::OptimisticSlots::NAME,
             columns::MerkleRootMeta::NAME,
+            columns::AccountHistory::NAME,
+            columns::AccountHistoryRoots::NAME,
         ]
     }
 
@@ -423,6 +466,13 @@ impl Rocks {
         }
     }
 
+    /// Tails the primary's MANIFEST and WAL so that this (Secondary) instance
+    /// observes the writes made by the primary since the last catch up
+    pub(crate) fn try_catch_up_with_primary(&self) -> Result<()> {
+        self.db.try_catch_up_with_primary()?;
+        Ok(())
+    }
+
     pub(crate) fn is_primary_access(&self) -> bool {
         self.access_type == AccessType::Primary
             || self.access_type == AccessType::PrimaryForMaintenance
@@ -441,6 +491,23 @@ impl Rocks {
         }
     }
 
+    /// Retrieves the raw and on-disk sizes of the SST files of the specified
+    /// column family, as aggregated from their table properties.
+    pub(crate) fn column_compression_stats(&self, cf_name: &str) -> Result<ColumnCompressionStats> {
+        let cf = self.cf_handle(cf_name);
+        match self
+            .db
+            .property_value_cf(cf, RocksProperties::AGGREGATED_TABLE_PROPERTIES)
+        {
+            Ok(table_properties) => Ok(table_properties
+                .map(|table_properties| {
+                    ColumnCompressionStats::from_table_properties(&table_properties)
+                })
+                .unwrap_or_default()),
+            Err(e) => Err(BlockstoreError::RocksDb(e)),
+        }
+    }
+
     pub(crate) fn live_files_metadata(&self) -> Result<Vec<LiveFile>> {
         match self.db.live_files() {
             Ok(live_files) => Ok(live_files),
@@ -461,6 +528,51 @@ impl Rocks {
     }
 }
 
+/// The sizes of the data in the SST files of a column family, before and after
+/// compression. Data that is still in the memtables is not accounted for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnCompressionStats {
+    pub num_entries: u64,
+    pub raw_key_size: u64,
+    pub raw_value_size: u64,
+    pub data_block_size: u64,
+}
+
+impl ColumnCompressionStats {
+    /// Parses the aggregated table properties of a column family, which are
+    /// reported as `name=value` pairs separated by `;`
+    fn from_table_properties(table_properties: &str) -> Self {
+        let mut stats = Self::default();
+        for (name, value) in table_properties
+            .split(';')
+            .filter_map(|property| property.split_once('='))
+        {
+            let Ok(value) = value.trim().parse() else {
+                continue;
+            };
+            match name.trim() {
+                "# entries" => stats.num_entries = value,
+                "raw key size" => stats.raw_key_size = value,
+                "raw value size" => stats.raw_value_size = value,
+                "data block size" => stats.data_block_size = value,
+                _ => {}
+            }
+        }
+        stats
+    }
+
+    /// The size of the keys and values before compression
+    pub fn raw_size(&self) -> u64 {
+        self.raw_key_size.saturating_add(self.raw_value_size)
+    }
+
+    /// The ratio of the raw size to the compressed size of the data, or
+    /// `None` if the column has no data in its SST files
+    pub fn compression_ratio(&self) -> Option<f64> {
+        (self.data_block_size > 0).then(|| self.raw_size() as f64 / self.data_block_size as f64)
+    }
+}
+
 #[derive(Debug)]
 pub struct LedgerColumn<C: Column + ColumnName> {
     backend: Arc<Rocks>,
@@ -1135,12 +1247,28 @@ fn process_cf_options_advanced<C: 'static + Column + ColumnName>(
     // See https://docs.rs/rocksdb/0.21.0/rocksdb/struct.Options.html#method.set_compression_type
     cf_options.set_compression_type(DBCompressionType::None);
 
-    if should_enable_compression::<C>() {
-        cf_options.set_compression_type(
-            column_options
-                .compression_type
-                .to_rocksdb_compression_type(),
+    let compression_type = match column_options.get_column_compression_type(C::NAME) {
+        Some(compression_type) => compression_type,
+        None if should_enable_compression::<C>() => &column_options.compression_type,
+        None => return,
+    };
+    cf_options.set_compression_type(compression_type.to_rocksdb_compression_type());
+
+    if let BlockstoreCompressionType::Zstd(zstd_options) = compression_type {
+        let max_dict_bytes = i32::try_from(zstd_options.max_dict_bytes).unwrap_or(i32::MAX);
+        cf_options.set_compression_options(
+            ZSTD_WINDOW_BITS,
+            zstd_options.level,
+            ZSTD_STRATEGY,
+            max_dict_bytes,
         );
+        if max_dict_bytes > 0 {
+            // Train the dictionary on a sample of the values rather than just
+            // using the first values of the SST file as the dictionary
+            cf_options.set_zstd_max_train_bytes(
+                max_dict_bytes.saturating_mul(ZSTD_TRAINING_BYTES_PER_DICT_BYTE),
+            );
+        }
     }
 }
 
@@ -1229,6 +1357,7 @@ fn should_enable_cf_compaction(cf_name: &str) -> bool {
         columns::TransactionStatus::NAME
             | columns::TransactionMemos::NAME
             | columns::AddressSignatures::NAME
+            | columns::AccountHistory::NAME
     )
 }
 
@@ -1237,15 +1366,18 @@ fn should_enable_compression<C: 'static + Column + ColumnName>() -> bool {
     C::NAME == columns::TransactionStatus::NAME
 }
 
-// If the access type is read-only, we don't need to open all of the columns
+// If the access type is read-only or secondary, we don't need to open all of the columns
 fn must_open_all_column_families(access_type: &AccessType) -> bool {
-    !matches!(access_type, AccessType::ReadOnly)
+    !matches!(access_type, AccessType::ReadOnly | AccessType::Secondary)
 }
 
 #[cfg(test)]
 pub mod tests {
     use {
-        super::*, crate::blockstore_db::columns::ShredData, std::path::PathBuf, tempfile::tempdir,
+        super::*,
+        crate::{blockstore_db::columns::ShredData, blockstore_options::ZstdCompressionOptions},
+        std::{collections::HashMap, path::PathBuf},
+        tempfile::tempdir,
     };
 
     #[test]
@@ -1318,6 +1450,7 @@ pub mod tests {
             &AccessType::PrimaryForMaintenance
         ));
         assert!(should_disable_auto_compactions(&AccessType::ReadOnly));
+        assert!(should_disable_auto_compactions(&AccessType::Secondary));
     }
 
     #[test]
@@ -1325,6 +1458,7 @@ pub mod tests {
         let columns_to_compact = [
             columns::TransactionStatus::NAME,
             columns::AddressSignatures::NAME,
+            columns::AccountHistory::NAME,
         ];
         columns_to_compact.iter().for_each(|cf_name| {
             assert!(should_enable_cf_compaction(cf_name));
@@ -1420,6 +1554,74 @@ pub mod tests {
         assert!(!is_program_costs_column_present(db_path));
     }
 
+    #[test]
+    fn test_column_compression_stats_from_table_properties() {
+        let table_properties = "# data blocks=2; # entries=100; # deletions=0; raw key size=1600; \
+                                raw average key size=16; raw value size=25600; raw average value \
+                                size=256; data block size=6800; index block size (user-key? 1, \
+                                delta-value? 1)=40; filter block size=0;";
+        let stats = ColumnCompressionStats::from_table_properties(table_properties);
+        assert_eq!(
+            stats,
+            ColumnCompressionStats {
+                num_entries: 100,
+                raw_key_size: 1600,
+                raw_value_size: 25600,
+                data_block_size: 6800,
+            }
+        );
+        assert_eq!(stats.raw_size(), 27200);
+        assert_eq!(stats.compression_ratio(), Some(4.0));
+
+        let stats = ColumnCompressionStats::from_table_properties("");
+        assert_eq!(stats, ColumnCompressionStats::default());
+        assert_eq!(stats.compression_ratio(), None);
+    }
+
+    #[test]
+    fn test_column_compression_stats() {
+        let temp_dir = tempdir().unwrap();
+        let db_path = temp_dir.path();
+
+        let zstd = BlockstoreCompressionType::Zstd(ZstdCompressionOptions {
+            level: 19,
+            max_dict_bytes: 16 * 1024,
+        });
+        let options = BlockstoreOptions {
+            access_type: AccessType::Primary,
+            column_options: LedgerColumnOptions {
+                column_compression_types: HashMap::from([(
+                    columns::Rewards::NAME.to_string(),
+                    zstd,
+                )]),
+                ..LedgerColumnOptions::default()
+            },
+            ..BlockstoreOptions::default()
+        };
+        let rocks = Rocks::open(db_path.to_path_buf(), options).unwrap();
+        let cf = rocks.cf_handle(columns::Rewards::NAME);
+        for slot in 0..1_000u64 {
+            let value = format!("rewards for slot {} ", slot % 10).repeat(16);
+            rocks
+                .put_cf(cf, slot.to_be_bytes(), value.as_bytes())
+                .unwrap();
+        }
+        rocks.db.flush_cf(cf).unwrap();
+
+        let stats = rocks
+            .column_compression_stats(columns::Rewards::NAME)
+            .unwrap();
+        assert_eq!(stats.num_entries, 1_000);
+        assert_eq!(stats.raw_key_size, 8 * 1_000);
+        assert!(stats.compression_ratio().unwrap() > 1.0);
+
+        // Columns without any SST files have no data to report on
+        let stats = rocks
+            .column_compression_stats(columns::Blocktime::NAME)
+            .unwrap();
+        assert_eq!(stats.compression_ratio(), None);
+    }
+
     impl<C> LedgerColumn<C>
     where
         C: ColumnIndexDeprecation + ProtobufColumn + ColumnName,
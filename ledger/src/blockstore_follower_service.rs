@@ -0,0 +1,156 @@
+//! The `blockstore_follower_service` keeps a Blockstore that was opened with
+//! `AccessType::Secondary` caught up with the primary instance, such as a
+//! running validator, and reports the slots that the primary roots. This
+//! allows an external reader to tail new blocks live without interfering with
+//! the primary.
+
+use {
+    crate::blockstore::{Blockstore, Result},
+    crossbeam_channel::Sender,
+    solana_clock::{Slot, DEFAULT_MS_PER_SLOT},
+    std::{
+        sync::{
+            atomic::{AtomicBool, AtomicU64, Ordering},
+            Arc,
+        },
+        thread::{self, Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+// Catching up more often than a slot is produced would mostly find nothing new
+pub const DEFAULT_CATCH_UP_INTERVAL: Duration = Duration::from_millis(DEFAULT_MS_PER_SLOT);
+
+pub struct BlockstoreFollowerService {
+    t_follow: JoinHandle<()>,
+}
+
+impl BlockstoreFollowerService {
+    /// Spawn a thread that catches `blockstore` up with the primary every
+    /// `catch_up_interval`.
+    ///
+    /// `latest_root` holds the latest rooted slot that has been observed; only
+    /// slots rooted after its initial value are reported. Each newly rooted
+    /// slot is sent, in order, on `rooted_slots_sender` if one is provided.
+    pub fn new(
+        blockstore: Arc<Blockstore>,
+        latest_root: Arc<AtomicU64>,
+        rooted_slots_sender: Option<Sender<Slot>>,
+        catch_up_interval: Duration,
+        exit: Arc<AtomicBool>,
+    ) -> Self {
+        assert!(
+            !blockstore.is_primary_access(),
+            "BlockstoreFollowerService requires a blockstore with Secondary access"
+        );
+        let t_follow = Builder::new()
+            .name("solBstoreFollow".to_string())
+            .spawn(move || {
+                info!(
+                    "BlockstoreFollowerService has started from root {}",
+                    latest_root.load(Ordering::Relaxed)
+                );
+                while !exit.load(Ordering::Relaxed) {
+                    if let Err(err) =
+                        Self::follow(&blockstore, &latest_root, rooted_slots_sender.as_ref())
+                    {
+                        warn!("BlockstoreFollowerService failed to catch up: {err:?}");
+                    }
+                    thread::sleep(catch_up_interval);
+                }
+                info!("BlockstoreFollowerService has stopped");
+            })
+            .unwrap();
+
+        Self { t_follow }
+    }
+
+    /// Catch `blockstore` up with the primary once, and report any slots that
+    /// were rooted after `latest_root`.
+    pub fn follow(
+        blockstore: &Blockstore,
+        latest_root: &AtomicU64,
+        rooted_slots_sender: Option<&Sender<Slot>>,
+    ) -> Result<()> {
+        blockstore.try_catch_up_with_primary()?;
+
+        let last_root = latest_root.load(Ordering::Relaxed);
+        if blockstore.max_root() <= last_root {
+            return Ok(());
+        }
+        for slot in blockstore.rooted_slot_iterator(last_root.saturating_add(1))? {
+            if let Some(sender) = rooted_slots_sender {
+                // The reader going away is not fatal; keep tracking the root
+                let _ = sender.send(slot);
+            }
+            latest_root.store(slot, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    pub fn join(self) -> thread::Result<()> {
+        self.t_follow.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            blockstore_options::{AccessType, BlockstoreOptions},
+            get_tmp_ledger_path_auto_delete,
+        },
+        crossbeam_channel::unbounded,
+    };
+
+    #[test]
+    fn test_follow_primary_roots() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let primary = Blockstore::open(ledger_path.path()).unwrap();
+        primary.set_roots([0, 1].iter()).unwrap();
+
+        let secondary = Blockstore::open_with_options(
+            ledger_path.path(),
+            BlockstoreOptions {
+                access_type: AccessType::Secondary,
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+        assert!(!secondary.is_primary_access());
+        assert_eq!(secondary.max_root(), 1);
+
+        let latest_root = AtomicU64::new(secondary.max_root());
+        let (sender, receiver) = unbounded();
+
+        // Nothing new has been rooted by the primary
+        BlockstoreFollowerService::follow(&secondary, &latest_root, Some(&sender)).unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        // New roots are only visible to the secondary after catching up
+        primary.set_roots([2, 4, 5].iter()).unwrap();
+        assert_eq!(secondary.max_root(), 1);
+        BlockstoreFollowerService::follow(&secondary, &latest_root, Some(&sender)).unwrap();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![2, 4, 5]);
+        assert_eq!(latest_root.load(Ordering::Relaxed), 5);
+        assert_eq!(secondary.max_root(), 5);
+
+        // Secondary instances don't share any state with each other
+        let other_secondary = Blockstore::open_with_options(
+            ledger_path.path(),
+            BlockstoreOptions {
+                access_type: AccessType::Secondary,
+                ..BlockstoreOptions::default()
+            },
+        )
+        .unwrap();
+        primary.set_roots([6].iter()).unwrap();
+        other_secondary.try_catch_up_with_primary().unwrap();
+        assert_eq!(other_secondary.max_root(), 6);
+        assert_eq!(secondary.max_root(), 5);
+        drop(other_secondary);
+        BlockstoreFollowerService::follow(&secondary, &latest_root, Some(&sender)).unwrap();
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![6]);
+    }
+}
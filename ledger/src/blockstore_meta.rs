@@ -7,6 +7,7 @@ use {
     serde::{Deserialize, Deserializer, Serialize, Serializer},
     solana_clock::{Slot, UnixTimestamp},
     solana_hash::Hash,
+    solana_pubkey::Pubkey,
     std::{
         collections::BTreeSet,
         ops::{Range, RangeBounds},
@@ -812,6 +813,18 @@ pub struct AddressSignatureMeta {
     pub writeable: bool,
 }
 
+/// The state of an account recorded by the RPC account history at the end of a
+/// rooted slot it was written in
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AccountHistoryVersion {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub executable: bool,
+    pub rent_epoch: u64,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
 /// Performance information about validator execution during a time slice.
 ///
 /// Older versions should only arise as a result of deserialization of entries stored by a previous
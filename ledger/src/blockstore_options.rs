@@ -1,7 +1,7 @@
 use {
     crate::blockstore_db::{default_num_compaction_threads, default_num_flush_threads},
     rocksdb::{DBCompressionType as RocksCompressionType, DBRecoveryMode},
-    std::num::NonZeroUsize,
+    std::{collections::HashMap, num::NonZeroUsize},
 };
 
 /// The subdirectory under ledger directory where the Blockstore lives
@@ -50,6 +50,13 @@ pub enum AccessType {
     /// Read only access; multiple processes can obtain ReadOnly access.
     /// ReadOnly instance gets a static view of the database at creation time.
     ReadOnly,
+    /// Secondary (read) access; multiple processes can obtain Secondary access,
+    /// including while another process holds Primary access. Unlike ReadOnly,
+    /// a Secondary instance can observe writes made by the primary after
+    /// creation time by calling `Blockstore::try_catch_up_with_primary()`.
+    /// Each Secondary instance keeps its info logs in a temporary directory of
+    /// its own, outside of the ledger, that is removed when it is dropped.
+    Secondary,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -98,6 +105,11 @@ pub struct LedgerColumnOptions {
     // compression.
     pub compression_type: BlockstoreCompressionType,
 
+    // Per-column compression, keyed by column name. Takes precedence over
+    // `compression_type` and may be set for any column, including the ones
+    // that are not compressed by default.
+    pub column_compression_types: HashMap<String, BlockstoreCompressionType>,
+
     // Control how often RocksDB read/write performance samples are collected.
     // If the value is greater than 0, then RocksDB read/write perf sample
     // will be collected once for every `rocks_perf_sample_interval` ops.
@@ -105,12 +117,22 @@ pub struct LedgerColumnOptions {
 }
 
 impl LedgerColumnOptions {
-    pub fn get_compression_type_string(&self) -> &'static str {
-        match self.compression_type {
+    /// Returns the compression configured for the column, if it has been
+    /// overridden from the default `compression_type`
+    pub fn get_column_compression_type(&self, cf_name: &str) -> Option<&BlockstoreCompressionType> {
+        self.column_compression_types.get(cf_name)
+    }
+
+    pub fn get_compression_type_string(&self, cf_name: &str) -> &'static str {
+        match self
+            .get_column_compression_type(cf_name)
+            .unwrap_or(&self.compression_type)
+        {
             BlockstoreCompressionType::None => "None",
             BlockstoreCompressionType::Snappy => "Snappy",
             BlockstoreCompressionType::Lz4 => "Lz4",
             BlockstoreCompressionType::Zlib => "Zlib",
+            BlockstoreCompressionType::Zstd(_) => "Zstd",
         }
     }
 }
@@ -122,6 +144,7 @@ pub enum BlockstoreCompressionType {
     Snappy,
     Lz4,
     Zlib,
+    Zstd(ZstdCompressionOptions),
 }
 
 impl BlockstoreCompressionType {
@@ -131,6 +154,32 @@ impl BlockstoreCompressionType {
             Self::Snappy => RocksCompressionType::Snappy,
             Self::Lz4 => RocksCompressionType::Lz4,
             Self::Zlib => RocksCompressionType::Zlib,
+            Self::Zstd(_) => RocksCompressionType::Zstd,
+        }
+    }
+}
+
+pub const DEFAULT_ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZstdCompressionOptions {
+    // The zstd compression level; higher levels trade compaction CPU time for
+    // a smaller ledger.
+    pub level: i32,
+
+    // The maximum size of the dictionary that is trained for, and shared by,
+    // the values of each SST file. Small values such as protobuf encoded
+    // transaction statuses compress poorly on their own, so a dictionary can
+    // considerably improve their compression ratio. Zero disables the
+    // dictionary.
+    pub max_dict_bytes: u32,
+}
+
+impl Default for ZstdCompressionOptions {
+    fn default() -> Self {
+        Self {
+            level: DEFAULT_ZSTD_COMPRESSION_LEVEL,
+            max_dict_bytes: 0,
         }
     }
 }
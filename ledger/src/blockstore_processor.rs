@@ -88,9 +88,11 @@ pub struct LockedTransactionsWithIndexes<Tx: SVMMessage> {
     starting_index: usize,
 }
 
-struct ReplayEntry {
-    entry: EntryType<RuntimeTransaction<SanitizedTransaction>>,
-    starting_index: usize,
+/// A verified entry to replay, along with the index of its first transaction
+/// within the slot.
+pub struct ReplayEntry {
+    pub entry: EntryType<RuntimeTransaction<SanitizedTransaction>>,
+    pub starting_index: usize,
 }
 
 fn first_err(results: &[Result<()>]) -> Result<()> {
@@ -640,7 +642,12 @@ pub fn process_entries_for_tests(
     result
 }
 
-fn process_entries(
+/// Process an ordered list of verified entries against `bank`, the same way
+/// replay does. See `process_entries_for_tests` for the steps taken.
+///
+/// Entry hashes are not verified here; callers are expected to have verified
+/// them against the previous entry.
+pub fn process_entries(
     bank: &BankWithScheduler,
     replay_tx_thread_pool: &ThreadPool,
     entries: Vec<ReplayEntry>,
@@ -2378,7 +2385,7 @@ pub mod tests {
                 // just pass the original session if it is a Primary variant
                 test_process_blockstore(genesis_config, blockstore, opts, Arc::default())
             }
-            AccessType::ReadOnly => {
+            AccessType::ReadOnly | AccessType::Secondary => {
                 let read_only_blockstore = Blockstore::open_with_options(
                     blockstore.ledger_path(),
                     BlockstoreOptions {
@@ -2459,9 +2466,9 @@ pub mod tests {
 
         let dead_slots: Vec<Slot> = blockstore.dead_slots_iterator(0).unwrap().collect();
         match blockstore_access_type {
-            // In ReadOnly access even though a dead slot
+            // In ReadOnly or Secondary access even though a dead slot
             // will be identified, it won't actually be marked dead.
-            AccessType::ReadOnly => {
+            AccessType::ReadOnly | AccessType::Secondary => {
                 assert_eq!(dead_slots.len(), 0);
             }
             AccessType::Primary | AccessType::PrimaryForMaintenance => {
@@ -12,8 +12,10 @@ pub mod block_error;
 pub mod blockstore;
 pub mod ancestor_iterator;
 pub mod bit_vec;
+pub mod blockstore_archive_service;
 pub mod blockstore_cleanup_service;
 pub mod blockstore_db;
+pub mod blockstore_follower_service;
 pub mod blockstore_meta;
 pub mod blockstore_metric_report_service;
 pub mod blockstore_metrics;
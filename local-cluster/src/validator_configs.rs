@@ -20,6 +20,8 @@ pub fn safe_clone_config(config: &ValidatorConfig) -> ValidatorConfig {
         pubsub_config: config.pubsub_config.clone(),
         snapshot_config: config.snapshot_config.clone(),
         max_ledger_shreds: config.max_ledger_shreds,
+        ledger_archive_path: config.ledger_archive_path.clone(),
+        ledger_archive_max_slot_age: config.ledger_archive_max_slot_age,
         blockstore_options: config.blockstore_options.clone(),
         broadcast_stage_type: config.broadcast_stage_type.clone(),
         turbine_disabled: config.turbine_disabled.clone(),
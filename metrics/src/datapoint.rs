@@ -87,6 +87,35 @@ impl DataPoint {
     }
 }
 
+/// Numeric value of a `DataPoint` field
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum FieldValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl FieldValue {
+    /// Parses a field value, as formatted for `InfluxDB` line protocol. Booleans parse as 1 and 0,
+    /// and string fields, which have no numeric value, as `None`.
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        if let Some(value) = value.strip_suffix('i') {
+            return value.parse().ok().map(Self::Int);
+        }
+        match value {
+            "true" => Some(Self::Int(1)),
+            "false" => Some(Self::Int(0)),
+            value => value.parse().ok().map(Self::Float),
+        }
+    }
+
+    pub(crate) fn as_f64(self) -> f64 {
+        match self {
+            Self::Int(value) => value as f64,
+            Self::Float(value) => value,
+        }
+    }
+}
+
 impl fmt::Display for DataPoint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "datapoint: {}", self.name)?;
@@ -205,6 +234,19 @@ macro_rules! datapoint_trace {
 
 #[cfg(test)]
 mod test {
+    use super::FieldValue;
+
+    #[test]
+    fn test_parse_field_value() {
+        assert_eq!(FieldValue::parse("42i"), Some(FieldValue::Int(42)));
+        assert_eq!(FieldValue::parse("-1i"), Some(FieldValue::Int(-1)));
+        assert_eq!(FieldValue::parse("1.5"), Some(FieldValue::Float(1.5)));
+        assert_eq!(FieldValue::parse("true"), Some(FieldValue::Int(1)));
+        assert_eq!(FieldValue::parse("false"), Some(FieldValue::Int(0)));
+        assert_eq!(FieldValue::parse("\"text\""), None);
+        assert_eq!(FieldValue::parse("1.5i"), None);
+    }
+
     #[test]
     fn test_datapoint() {
         datapoint_debug!("name", ("field name", "test", String));
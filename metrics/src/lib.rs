@@ -3,6 +3,8 @@
 pub mod counter;
 pub mod datapoint;
 pub mod metrics;
+pub mod otlp;
+pub mod prometheus;
 pub use crate::metrics::{flush, query, set_host_id, set_panic_hook, submit};
 use std::sync::{
     atomic::{AtomicU64, Ordering},
@@ -1,7 +1,11 @@
-//! The `metrics` module enables sending measurements to an `InfluxDB` instance
+//! The `metrics` module enables sending measurements to an `InfluxDB` instance, serving them to
+//! Prometheus, or exporting them to an OpenTelemetry collector
 
 use {
-    crate::{counter::CounterPoint, datapoint::DataPoint},
+    crate::{
+        counter::CounterPoint, datapoint::DataPoint, otlp::OtlpMetricsWriter,
+        prometheus::PrometheusMetricsWriter,
+    },
     crossbeam_channel::{unbounded, Receiver, Sender, TryRecvError},
     gethostname::gethostname,
     log::*,
@@ -13,6 +17,7 @@ use {
         convert::Into,
         env,
         fmt::Write,
+        net::SocketAddr,
         sync::{Arc, Barrier, Mutex, Once, RwLock},
         thread,
         time::{Duration, Instant, UNIX_EPOCH},
@@ -66,6 +71,17 @@ pub trait MetricsWriter {
     // Write the points and empty the vector.  Called on the internal
     // MetricsAgent worker thread.
     fn write(&self, points: Vec<DataPoint>);
+
+    // Whether counters are passed to `write_counters()`, rather than being
+    // converted into points and passed to `write()`.
+    fn aggregates_counters(&self) -> bool {
+        false
+    }
+
+    // Write the counts accumulated since the previous write.  Called on the
+    // internal MetricsAgent worker thread, before `write()`, if
+    // `aggregates_counters()` is true.
+    fn write_counters(&self, _counters: Vec<CounterPoint>) {}
 }
 
 struct InfluxDbMetricsWriter {
@@ -85,6 +101,10 @@ impl InfluxDbMetricsWriter {
             err
         })?;
 
+        if !config.uses_influxdb() {
+            return Err(MetricsError::ConfigIncomplete);
+        }
+
         info!(
             "metrics configuration: host={} db={} username={}",
             config.host, config.db, config.username
@@ -179,11 +199,31 @@ impl Default for MetricsAgent {
             })
             .unwrap_or(4000);
 
-        Self::new(
-            Arc::new(InfluxDbMetricsWriter::new()),
-            Duration::from_secs(10),
-            max_points_per_sec,
-        )
+        let writer: Arc<dyn MetricsWriter + Send + Sync> = match get_metrics_config() {
+            Ok(MetricsConfig {
+                prometheus_addr: Some(addr),
+                ..
+            }) => match PrometheusMetricsWriter::new(addr) {
+                Ok(writer) => Arc::new(writer),
+                Err(err) => {
+                    warn!("metrics disabled: failed to serve prometheus metrics on {addr}: {err}");
+                    Arc::new(InfluxDbMetricsWriter { write_url: None })
+                }
+            },
+            Ok(MetricsConfig {
+                otlp_endpoint: Some(endpoint),
+                ..
+            }) => match OtlpMetricsWriter::new(&endpoint) {
+                Ok(writer) => Arc::new(writer),
+                Err(err) => {
+                    warn!("metrics disabled: failed to create otlp client: {err}");
+                    Arc::new(InfluxDbMetricsWriter { write_url: None })
+                }
+            },
+            _ => Arc::new(InfluxDbMetricsWriter::new()),
+        };
+
+        Self::new(writer, Duration::from_secs(10), max_points_per_sec)
     }
 }
 
@@ -270,6 +310,9 @@ impl MetricsAgent {
         let now = Instant::now();
         let secs_since_last_write = now.duration_since(last_write_time).as_secs();
 
+        if writer.aggregates_counters() {
+            writer.write_counters(counters.drain().map(|(_, counter)| counter).collect());
+        }
         writer.write(Self::combine_points(
             max_points,
             max_points_per_sec,
@@ -431,6 +474,11 @@ struct MetricsConfig {
     pub db: String,
     pub username: String,
     pub password: String,
+    /// Address to serve Prometheus metrics on, instead of writing them to InfluxDB
+    pub prometheus_addr: Option<SocketAddr>,
+    /// OTLP/HTTP endpoint of an OpenTelemetry collector to export metrics to, instead of writing
+    /// them to InfluxDB
+    pub otlp_endpoint: Option<String>,
 }
 
 impl MetricsConfig {
@@ -440,15 +488,29 @@ impl MetricsConfig {
             || self.username.is_empty()
             || self.password.is_empty())
     }
+
+    fn is_influxdb_empty(&self) -> bool {
+        self.host.is_empty()
+            && self.db.is_empty()
+            && self.username.is_empty()
+            && self.password.is_empty()
+    }
+
+    fn uses_influxdb(&self) -> bool {
+        self.prometheus_addr.is_none() && self.otlp_endpoint.is_none()
+    }
 }
 
 fn get_metrics_config() -> Result<MetricsConfig, MetricsError> {
-    let mut config = MetricsConfig::default();
     let config_var = env::var("SOLANA_METRICS_CONFIG")?;
     if config_var.is_empty() {
         Err(env::VarError::NotPresent)?;
     }
+    parse_metrics_config(&config_var)
+}
 
+fn parse_metrics_config(config_var: &str) -> Result<MetricsConfig, MetricsError> {
+    let mut config = MetricsConfig::default();
     for pair in config_var.split(',') {
         let nv: Vec<_> = pair.split('=').collect();
         if nv.len() != 2 {
@@ -460,10 +522,32 @@ fn get_metrics_config() -> Result<MetricsConfig, MetricsError> {
             "db" => config.db = v,
             "u" => config.username = v,
             "p" => config.password = v,
+            "prometheus" => {
+                config.prometheus_addr = Some(
+                    v.parse()
+                        .map_err(|_| MetricsError::ConfigInvalid(pair.to_string()))?,
+                )
+            }
+            "otlp" => config.otlp_endpoint = Some(v),
             _ => return Err(MetricsError::ConfigInvalid(pair.to_string())),
         }
     }
 
+    if !config.uses_influxdb() {
+        // Metrics are either served to Prometheus, exported to a collector or written to
+        // InfluxDB, only one at a time
+        if config.prometheus_addr.is_some() && config.otlp_endpoint.is_some() {
+            return Err(MetricsError::ConfigInvalid(
+                "prometheus cannot be combined with otlp".to_string(),
+            ));
+        }
+        if !config.is_influxdb_empty() {
+            return Err(MetricsError::ConfigInvalid(
+                "prometheus and otlp cannot be combined with InfluxDB settings".to_string(),
+            ));
+        }
+        return Ok(config);
+    }
     if !config.complete() {
         return Err(MetricsError::ConfigIncomplete);
     }
@@ -490,6 +574,9 @@ pub fn metrics_config_sanity_check(cluster_type: ClusterType) -> Result<(), Metr
 
 pub fn query(q: &str) -> Result<String, MetricsError> {
     let config = get_metrics_config()?;
+    if !config.uses_influxdb() {
+        return Err(MetricsError::ConfigIncomplete);
+    }
     let query_url = format!(
         "{}/query?u={}&p={}&q={}",
         &config.host, &config.username, &config.password, &q
@@ -750,4 +837,94 @@ mod test {
         set_host_id(test_host_id.clone());
         assert_eq!(get_host_id(), test_host_id);
     }
+
+    #[test]
+    fn test_parse_metrics_config() {
+        let config =
+            parse_metrics_config("host=http://localhost:8086,db=testnet,u=user,p=pass").unwrap();
+        assert_eq!(config.host, "http://localhost:8086");
+        assert_eq!(config.db, "testnet");
+        assert_eq!(config.prometheus_addr, None);
+        assert_eq!(config.otlp_endpoint, None);
+        assert!(config.uses_influxdb());
+
+        let config = parse_metrics_config("prometheus=127.0.0.1:9090").unwrap();
+        assert_eq!(
+            config.prometheus_addr,
+            Some("127.0.0.1:9090".parse().unwrap())
+        );
+        assert!(config.is_influxdb_empty());
+        assert!(!config.uses_influxdb());
+
+        let config = parse_metrics_config("otlp=http://localhost:4318").unwrap();
+        assert_eq!(
+            config.otlp_endpoint.as_deref(),
+            Some("http://localhost:4318")
+        );
+        assert!(!config.uses_influxdb());
+
+        assert!(matches!(
+            parse_metrics_config("host=http://localhost:8086,db=testnet"),
+            Err(MetricsError::ConfigIncomplete)
+        ));
+        assert!(matches!(
+            parse_metrics_config("prometheus=localhost"),
+            Err(MetricsError::ConfigInvalid(_))
+        ));
+        assert!(matches!(
+            parse_metrics_config("prometheus=127.0.0.1:9090,db=testnet"),
+            Err(MetricsError::ConfigInvalid(_))
+        ));
+        assert!(matches!(
+            parse_metrics_config("otlp=http://localhost:4318,db=testnet"),
+            Err(MetricsError::ConfigInvalid(_))
+        ));
+        assert!(matches!(
+            parse_metrics_config("otlp=http://localhost:4318,prometheus=127.0.0.1:9090"),
+            Err(MetricsError::ConfigInvalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_submit_counter_aggregated() {
+        struct AggregatingWriter {
+            points: Mutex<Vec<DataPoint>>,
+            counters: Mutex<Vec<CounterPoint>>,
+        }
+        impl MetricsWriter for AggregatingWriter {
+            fn write(&self, points: Vec<DataPoint>) {
+                self.points.lock().unwrap().extend(points);
+            }
+            fn aggregates_counters(&self) -> bool {
+                true
+            }
+            fn write_counters(&self, counters: Vec<CounterPoint>) {
+                self.counters.lock().unwrap().extend(counters);
+            }
+        }
+
+        let writer = Arc::new(AggregatingWriter {
+            points: Mutex::default(),
+            counters: Mutex::default(),
+        });
+        let agent = MetricsAgent::new(writer.clone(), Duration::from_secs(10), 1000);
+
+        let mut counter = CounterPoint::new("counter");
+        counter.count = 2;
+        agent.submit_counter(counter.clone(), Level::Info, 0);
+        agent.submit_counter(counter, Level::Info, 0);
+        agent.submit(
+            DataPoint::new("measurement")
+                .add_field_i64("i", 1)
+                .to_owned(),
+            Level::Info,
+        );
+        agent.flush();
+
+        // Counters bypass the points, which only hold the measurement and the stats
+        let counters = writer.counters.lock().unwrap();
+        assert_eq!(counters.len(), 1);
+        assert_eq!(counters[0].count, 4);
+        assert_eq!(writer.points.lock().unwrap().len(), 2);
+    }
 }
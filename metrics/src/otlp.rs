@@ -0,0 +1,454 @@
+//! The `otlp` module enables exporting measurements to an OpenTelemetry collector, using the OTLP
+//! protocol over HTTP with JSON encoding
+
+use {
+    crate::{
+        counter::CounterPoint,
+        datapoint::{DataPoint, FieldValue},
+        metrics::{get_host_id, MetricsWriter},
+    },
+    log::*,
+    serde::Serialize,
+    std::{
+        collections::BTreeMap,
+        sync::Mutex,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    },
+};
+
+/// Path of the metrics service, relative to the collector endpoint
+const METRICS_PATH: &str = "/v1/metrics";
+const SCOPE_NAME: &str = "solana-metrics";
+/// `AGGREGATION_TEMPORALITY_DELTA`: counts are reported since the previous export
+const AGGREGATION_TEMPORALITY_DELTA: u32 = 1;
+
+// The following types mirror the JSON encoding of the OTLP `ExportMetricsServiceRequest` message.
+// 64-bit integers are encoded as strings, as required by the protobuf JSON mapping.
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExportMetricsServiceRequest {
+    resource_metrics: Vec<ResourceMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceMetrics {
+    resource: Resource,
+    scope_metrics: Vec<ScopeMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+struct Resource {
+    attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+struct ScopeMetrics {
+    scope: InstrumentationScope,
+    metrics: Vec<Metric>,
+}
+
+#[derive(Debug, Serialize)]
+struct InstrumentationScope {
+    name: &'static str,
+    version: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct Metric {
+    name: String,
+    #[serde(flatten)]
+    data: MetricData,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum MetricData {
+    Gauge {
+        #[serde(rename = "dataPoints")]
+        data_points: Vec<NumberDataPoint>,
+    },
+    Sum {
+        #[serde(rename = "dataPoints")]
+        data_points: Vec<NumberDataPoint>,
+        #[serde(rename = "aggregationTemporality")]
+        aggregation_temporality: u32,
+        #[serde(rename = "isMonotonic")]
+        is_monotonic: bool,
+    },
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NumberDataPoint {
+    attributes: Vec<KeyValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_time_unix_nano: Option<String>,
+    time_unix_nano: String,
+    #[serde(flatten)]
+    value: NumberValue,
+}
+
+#[derive(Debug, Serialize)]
+enum NumberValue {
+    #[serde(rename = "asInt")]
+    Int(String),
+    #[serde(rename = "asDouble")]
+    Double(f64),
+}
+
+#[derive(Debug, Serialize)]
+struct KeyValue {
+    key: String,
+    value: AnyValue,
+}
+
+#[derive(Debug, Serialize)]
+enum AnyValue {
+    #[serde(rename = "stringValue")]
+    String(String),
+}
+
+impl From<FieldValue> for NumberValue {
+    fn from(value: FieldValue) -> Self {
+        match value {
+            FieldValue::Int(value) => Self::Int(value.to_string()),
+            FieldValue::Float(value) => Self::Double(value),
+        }
+    }
+}
+
+impl KeyValue {
+    fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            value: AnyValue::String(value.to_string()),
+        }
+    }
+}
+
+/// A `MetricsWriter` that exports to an OpenTelemetry collector over OTLP/HTTP.
+///
+/// Each numeric field of a point is exported as a gauge named `<point>.<field>`, with the point's
+/// tags as attributes. Each counter is exported as a monotonic sum named `<counter>`, holding the
+/// counts since the previous successful export. The host id is exported as the `host_id` resource
+/// attribute.
+pub struct OtlpMetricsWriter {
+    write_url: String,
+    client: reqwest::blocking::Client,
+    /// Time of the previous successful export, which starts the interval of exported counts
+    last_write_time: Mutex<SystemTime>,
+    /// Sums of the counters passed to `write_counters()` that have not been exported yet
+    pending_counters: Mutex<BTreeMap<&'static str, i64>>,
+}
+
+impl OtlpMetricsWriter {
+    /// Exports to the collector at `endpoint`, e.g. `http://localhost:4318`
+    pub fn new(endpoint: &str) -> reqwest::Result<Self> {
+        let write_url = format!("{}{METRICS_PATH}", endpoint.trim_end_matches('/'));
+        let client = reqwest::blocking::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        info!("metrics configuration: otlp={write_url}");
+        Ok(Self {
+            write_url,
+            client,
+            last_write_time: Mutex::new(SystemTime::now()),
+            pending_counters: Mutex::default(),
+        })
+    }
+
+    /// Sends `request` to the collector, returning whether it was accepted
+    fn export(&self, request: &ExportMetricsServiceRequest) -> bool {
+        match self.client.post(&self.write_url).json(request).send() {
+            Ok(resp) => {
+                let status = resp.status();
+                if !status.is_success() {
+                    let text = resp
+                        .text()
+                        .unwrap_or_else(|_| "[text body empty]".to_string());
+                    warn!("otlp export unsuccessful: {status} {text}");
+                }
+                status.is_success()
+            }
+            Err(err) => {
+                warn!("otlp export error: {err}");
+                false
+            }
+        }
+    }
+
+    fn build_request(
+        points: &[DataPoint],
+        counters: &BTreeMap<&'static str, i64>,
+        host_id: &str,
+        start_time: SystemTime,
+        now: SystemTime,
+    ) -> ExportMetricsServiceRequest {
+        let mut gauges = BTreeMap::<String, Vec<NumberDataPoint>>::new();
+        for point in points {
+            let attributes = || {
+                point
+                    .tags
+                    .iter()
+                    .map(|(name, value)| KeyValue::new(name, value))
+                    .collect()
+            };
+            for (field, value) in &point.fields {
+                // String fields have no numeric value to export
+                let Some(value) = FieldValue::parse(value).map(NumberValue::from) else {
+                    continue;
+                };
+                gauges
+                    .entry(format!("{}.{field}", point.name))
+                    .or_default()
+                    .push(NumberDataPoint {
+                        attributes: attributes(),
+                        start_time_unix_nano: None,
+                        time_unix_nano: unix_nanos(point.timestamp),
+                        value,
+                    });
+            }
+        }
+
+        let metrics = counters
+            .iter()
+            .map(|(name, count)| Metric {
+                name: name.to_string(),
+                data: MetricData::Sum {
+                    data_points: vec![NumberDataPoint {
+                        attributes: vec![],
+                        start_time_unix_nano: Some(unix_nanos(start_time)),
+                        time_unix_nano: unix_nanos(now),
+                        value: NumberValue::Int(count.to_string()),
+                    }],
+                    aggregation_temporality: AGGREGATION_TEMPORALITY_DELTA,
+                    is_monotonic: true,
+                },
+            })
+            .chain(gauges.into_iter().map(|(name, data_points)| Metric {
+                name,
+                data: MetricData::Gauge { data_points },
+            }))
+            .collect();
+
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Resource {
+                    attributes: vec![KeyValue::new("host_id", host_id)],
+                },
+                scope_metrics: vec![ScopeMetrics {
+                    scope: InstrumentationScope {
+                        name: SCOPE_NAME,
+                        version: env!("CARGO_PKG_VERSION"),
+                    },
+                    metrics,
+                }],
+            }],
+        }
+    }
+}
+
+impl MetricsWriter for OtlpMetricsWriter {
+    fn write(&self, points: Vec<DataPoint>) {
+        let counters = std::mem::take(&mut *self.pending_counters.lock().unwrap());
+        let start_time = *self.last_write_time.lock().unwrap();
+        let now = SystemTime::now();
+        let request = Self::build_request(&points, &counters, &get_host_id(), start_time, now);
+
+        debug!(
+            "exporting {} points and {} counters",
+            points.len(),
+            counters.len()
+        );
+
+        if self.export(&request) {
+            *self.last_write_time.lock().unwrap() = now;
+        } else {
+            // Keep the counts for the next export, whose interval then also covers this one
+            let mut pending_counters = self.pending_counters.lock().unwrap();
+            for (name, count) in counters {
+                let pending_count = pending_counters.entry(name).or_default();
+                *pending_count = pending_count.saturating_add(count);
+            }
+        }
+    }
+
+    fn aggregates_counters(&self) -> bool {
+        true
+    }
+
+    fn write_counters(&self, counters: Vec<CounterPoint>) {
+        let mut pending_counters = self.pending_counters.lock().unwrap();
+        for counter in counters {
+            let count = pending_counters.entry(counter.name).or_default();
+            *count = count.saturating_add(counter.count);
+        }
+    }
+}
+
+fn unix_nanos(time: SystemTime) -> String {
+    time.duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos()
+        .to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use {
+        super::*,
+        serde_json::{json, Value},
+        std::{
+            io::{BufRead, BufReader, Read, Write},
+            net::TcpListener,
+            thread,
+        },
+    };
+
+    #[test]
+    fn test_build_request() {
+        let start_time = UNIX_EPOCH + Duration::from_nanos(1);
+        let now = UNIX_EPOCH + Duration::from_nanos(2);
+        let mut point = DataPoint::new("bank");
+        point
+            .add_tag("kind", "replay")
+            .add_field_i64("count", 3)
+            .add_field_f64("ratio", 0.5)
+            .add_field_bool("full", true)
+            .add_field_str("message", "ignored");
+        point.timestamp = now;
+        let counters = BTreeMap::from([("txs", 4)]);
+
+        let request =
+            OtlpMetricsWriter::build_request(&[point], &counters, "host", start_time, now);
+        let attributes = json!([{"key": "kind", "value": {"stringValue": "replay"}}]);
+        assert_eq!(
+            serde_json::to_value(request).unwrap(),
+            json!({
+                "resourceMetrics": [{
+                    "resource": {
+                        "attributes": [{"key": "host_id", "value": {"stringValue": "host"}}],
+                    },
+                    "scopeMetrics": [{
+                        "scope": {"name": SCOPE_NAME, "version": env!("CARGO_PKG_VERSION")},
+                        "metrics": [
+                            {
+                                "name": "txs",
+                                "sum": {
+                                    "dataPoints": [{
+                                        "attributes": [],
+                                        "startTimeUnixNano": "1",
+                                        "timeUnixNano": "2",
+                                        "asInt": "4",
+                                    }],
+                                    "aggregationTemporality": 1,
+                                    "isMonotonic": true,
+                                },
+                            },
+                            {
+                                "name": "bank.count",
+                                "gauge": {"dataPoints": [{
+                                    "attributes": attributes.clone(),
+                                    "timeUnixNano": "2",
+                                    "asInt": "3",
+                                }]},
+                            },
+                            {
+                                "name": "bank.full",
+                                "gauge": {"dataPoints": [{
+                                    "attributes": attributes.clone(),
+                                    "timeUnixNano": "2",
+                                    "asInt": "1",
+                                }]},
+                            },
+                            {
+                                "name": "bank.ratio",
+                                "gauge": {"dataPoints": [{
+                                    "attributes": attributes.clone(),
+                                    "timeUnixNano": "2",
+                                    "asDouble": 0.5,
+                                }]},
+                            },
+                        ],
+                    }],
+                }],
+            })
+        );
+    }
+
+    /// Accepts a single request, responds with `status`, and returns the request's path and JSON
+    /// body
+    fn spawn_mock_collector(status: &'static str) -> (String, thread::JoinHandle<(String, Value)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let collector = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            write!(
+                &stream,
+                "HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            )
+            .unwrap();
+            let path = request_line.split_whitespace().nth(1).unwrap().to_string();
+            (path, serde_json::from_slice(&body).unwrap())
+        });
+        (endpoint, collector)
+    }
+
+    #[test]
+    fn test_export_to_mock_collector() {
+        let (endpoint, collector) = spawn_mock_collector("200 OK");
+        let writer = OtlpMetricsWriter::new(&format!("{endpoint}/")).unwrap();
+        assert!(writer.aggregates_counters());
+        writer.write_counters(vec![CounterPoint::new("counter")]);
+        writer.write(vec![DataPoint::new("point")
+            .add_field_i64("value", 7)
+            .to_owned()]);
+
+        let (path, body) = collector.join().unwrap();
+        assert_eq!(path, METRICS_PATH);
+        let metrics = &body["resourceMetrics"][0]["scopeMetrics"][0]["metrics"];
+        assert_eq!(metrics[0]["name"], "counter");
+        assert_eq!(metrics[1]["name"], "point.value");
+        assert_eq!(metrics[1]["gauge"]["dataPoints"][0]["asInt"], "7");
+        assert!(writer.pending_counters.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_failed_export_keeps_counters() {
+        let (endpoint, collector) = spawn_mock_collector("503 Service Unavailable");
+        let writer = OtlpMetricsWriter::new(&endpoint).unwrap();
+        let start_time = *writer.last_write_time.lock().unwrap();
+        let mut counter = CounterPoint::new("counter");
+        counter.count = 2;
+        writer.write_counters(vec![counter]);
+        writer.write(vec![]);
+        collector.join().unwrap();
+
+        // The counts are exported with the next points, over an interval covering both exports
+        assert_eq!(
+            *writer.pending_counters.lock().unwrap(),
+            BTreeMap::from([("counter", 2)])
+        );
+        assert_eq!(*writer.last_write_time.lock().unwrap(), start_time);
+    }
+}
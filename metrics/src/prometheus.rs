@@ -0,0 +1,367 @@
+//! The `prometheus` module serves measurements on a local HTTP endpoint in the Prometheus text
+//! exposition format, as an alternative to sending them to `InfluxDB`
+
+use {
+    crate::{
+        counter::CounterPoint,
+        datapoint::{DataPoint, FieldValue},
+        metrics::{get_host_id, MetricsWriter},
+    },
+    log::*,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        fmt::Write as _,
+        io::{self, BufRead, BufReader, Write},
+        net::{SocketAddr, TcpListener, TcpStream},
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    },
+};
+
+/// Prefix of every exported metric name
+const METRIC_PREFIX: &str = "solana_";
+const METRICS_PATH: &str = "/metrics";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// Requests with longer headers are rejected; scrapers send far less
+const MAX_REQUEST_HEADER_BYTES: usize = 8192;
+/// Label sets kept per metric. Tag values are arbitrary strings, so points with new label sets are
+/// dropped once a metric has this many, rather than growing the registry without bound.
+const MAX_SERIES_PER_METRIC: usize = 1024;
+/// Counter of the samples dropped because their metric reached `MAX_SERIES_PER_METRIC`
+const DROPPED_SERIES_METRIC: &str = "solana_prometheus_dropped_series_total";
+
+/// Latest value of every exported metric, keyed by metric name and then by label set
+#[derive(Debug, Default)]
+struct Registry {
+    /// Fields of `DataPoint`s, exported as gauges holding the last value written
+    gauges: BTreeMap<String, BTreeMap<String, f64>>,
+    /// `CounterPoint`s, exported as counters holding the sum of all counts written
+    counters: BTreeMap<String, i64>,
+    /// Metrics that reached `MAX_SERIES_PER_METRIC`, to only warn about each once
+    full_metrics: BTreeSet<String>,
+    /// Number of samples dropped because their metric had too many label sets
+    dropped_series: u64,
+}
+
+impl Registry {
+    fn record_point(&mut self, point: &DataPoint) {
+        let labels = point
+            .tags
+            .iter()
+            .map(|(name, value)| (sanitize_name(name), value.as_str()))
+            .collect::<BTreeMap<_, _>>();
+        let mut label_set = String::new();
+        for (name, value) in labels {
+            let _ = write!(label_set, ",{name}=\"{}\"", escape_label_value(value));
+        }
+        for (field, value) in &point.fields {
+            // String fields have no numeric value to export
+            let Some(value) = FieldValue::parse(value).map(FieldValue::as_f64) else {
+                continue;
+            };
+            let name = format!(
+                "{METRIC_PREFIX}{}_{}",
+                sanitize_name(point.name),
+                sanitize_name(field)
+            );
+            let samples = self.gauges.entry(name).or_default();
+            if let Some(sample) = samples.get_mut(&label_set) {
+                *sample = value;
+            } else if samples.len() < MAX_SERIES_PER_METRIC {
+                samples.insert(label_set.clone(), value);
+            } else {
+                self.dropped_series = self.dropped_series.saturating_add(1);
+                if self.full_metrics.insert(name.clone()) {
+                    warn!(
+                        "prometheus metric {name} reached {MAX_SERIES_PER_METRIC} label sets, \
+                         dropping points with new label sets"
+                    );
+                }
+            }
+        }
+    }
+
+    fn record_counter(&mut self, counter: &CounterPoint) {
+        let name = format!("{METRIC_PREFIX}{}_total", sanitize_name(counter.name));
+        let total = self.counters.entry(name).or_default();
+        *total = total.saturating_add(counter.count);
+    }
+
+    fn render(&self, host_id: &str) -> String {
+        let host_label = format!("host_id=\"{}\"", escape_label_value(host_id));
+        let mut text = String::new();
+        if self.dropped_series > 0 {
+            let _ = writeln!(text, "# TYPE {DROPPED_SERIES_METRIC} counter");
+            let _ = writeln!(
+                text,
+                "{DROPPED_SERIES_METRIC}{{{host_label}}} {}",
+                self.dropped_series
+            );
+        }
+        for (name, total) in &self.counters {
+            let _ = writeln!(text, "# TYPE {name} counter");
+            let _ = writeln!(text, "{name}{{{host_label}}} {total}");
+        }
+        for (name, samples) in &self.gauges {
+            let _ = writeln!(text, "# TYPE {name} gauge");
+            for (label_set, value) in samples {
+                let _ = writeln!(
+                    text,
+                    "{name}{{{host_label}{label_set}}} {}",
+                    format_value(*value)
+                );
+            }
+        }
+        text
+    }
+}
+
+/// A `MetricsWriter` that aggregates points and counters in memory, and serves them on a local
+/// `/metrics` HTTP endpoint for Prometheus to scrape.
+///
+/// Each numeric field of a point is exported as a gauge named `solana_<point>_<field>`, labeled
+/// with the point's tags, and holds the last value written. Each counter is exported as a counter
+/// named `solana_<counter>_total`, and holds the sum of all counts written.
+///
+/// Each gauge keeps at most 1024 label sets. Points adding label sets past that are dropped and
+/// counted by `solana_prometheus_dropped_series_total`.
+pub struct PrometheusMetricsWriter {
+    registry: Arc<Mutex<Registry>>,
+    local_addr: SocketAddr,
+}
+
+impl PrometheusMetricsWriter {
+    /// Binds the metrics endpoint to `addr` and starts serving it on a background thread
+    pub fn new(addr: SocketAddr) -> io::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let registry = Arc::<Mutex<Registry>>::default();
+        {
+            let registry = Arc::clone(&registry);
+            thread::Builder::new()
+                .name("solMetricsProm".into())
+                .spawn(move || Self::serve(listener, &registry))?;
+        }
+        info!("serving prometheus metrics on http://{local_addr}{METRICS_PATH}");
+        Ok(Self {
+            registry,
+            local_addr,
+        })
+    }
+
+    /// Address the metrics endpoint is bound to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        self.registry.lock().unwrap().render(&get_host_id())
+    }
+
+    fn serve(listener: TcpListener, registry: &Mutex<Registry>) {
+        for stream in listener.incoming() {
+            let result = stream.and_then(|stream| Self::handle_connection(stream, registry));
+            if let Err(err) = result {
+                debug!("prometheus metrics request failed: {err}");
+            }
+        }
+    }
+
+    fn handle_connection(stream: TcpStream, registry: &Mutex<Registry>) -> io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut reader = BufReader::new(&stream);
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Drain the headers, the request has no body
+        let mut header_bytes = 0;
+        loop {
+            let mut header = String::new();
+            let len = reader.read_line(&mut header)?;
+            header_bytes += len;
+            if len == 0 || header == "\r\n" || header == "\n" {
+                break;
+            }
+            if header_bytes > MAX_REQUEST_HEADER_BYTES {
+                return Self::respond(&stream, "431 Request Header Fields Too Large", "");
+            }
+        }
+
+        let mut parts = request_line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some("GET"), Some(METRICS_PATH)) => {
+                let body = registry.lock().unwrap().render(&get_host_id());
+                Self::respond(&stream, "200 OK", &body)
+            }
+            (Some("GET"), Some(_)) => Self::respond(&stream, "404 Not Found", ""),
+            _ => Self::respond(&stream, "405 Method Not Allowed", ""),
+        }
+    }
+
+    fn respond(mut stream: &TcpStream, status: &str, body: &str) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {CONTENT_TYPE}\r\nContent-Length: \
+             {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+impl MetricsWriter for PrometheusMetricsWriter {
+    fn write(&self, points: Vec<DataPoint>) {
+        let mut registry = self.registry.lock().unwrap();
+        for point in &points {
+            registry.record_point(point);
+        }
+    }
+
+    fn aggregates_counters(&self) -> bool {
+        true
+    }
+
+    fn write_counters(&self, counters: Vec<CounterPoint>) {
+        let mut registry = self.registry.lock().unwrap();
+        for counter in &counters {
+            registry.record_counter(counter);
+        }
+    }
+}
+
+/// Replaces characters that are invalid in Prometheus metric and label names
+fn sanitize_name(name: &str) -> String {
+    name.chars()
+        .enumerate()
+        .map(|(i, c)| {
+            if c.is_ascii_alphabetic() || c == '_' || (i > 0 && c.is_ascii_digit()) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use {super::*, std::io::Read};
+
+    #[test]
+    fn test_sanitize_name() {
+        assert_eq!(sanitize_name("bank-timing"), "bank_timing");
+        assert_eq!(sanitize_name("replay_slot.stats"), "replay_slot_stats");
+        assert_eq!(sanitize_name("9lives"), "_lives");
+        assert_eq!(sanitize_name("shred_v2"), "shred_v2");
+    }
+
+    #[test]
+    fn test_render() {
+        let mut registry = Registry::default();
+        registry.record_point(
+            DataPoint::new("bank-timing")
+                .add_tag("kind", "a\"b")
+                .add_field_i64("count", 1)
+                .add_field_f64("ratio", 0.5)
+                .add_field_str("message", "ignored"),
+        );
+        // Gauges hold the last value written
+        registry.record_point(
+            DataPoint::new("bank-timing")
+                .add_tag("kind", "a\"b")
+                .add_field_i64("count", 3),
+        );
+        registry.record_point(DataPoint::new("bank-timing").add_field_bool("count", true));
+        // Counters hold the sum of counts written
+        let mut counter = CounterPoint::new("tx-count");
+        counter.count = 2;
+        registry.record_counter(&counter);
+        registry.record_counter(&counter);
+
+        assert_eq!(
+            registry.render("host"),
+            concat!(
+                "# TYPE solana_tx_count_total counter\n",
+                "solana_tx_count_total{host_id=\"host\"} 4\n",
+                "# TYPE solana_bank_timing_count gauge\n",
+                "solana_bank_timing_count{host_id=\"host\"} 1\n",
+                "solana_bank_timing_count{host_id=\"host\",kind=\"a\\\"b\"} 3\n",
+                "# TYPE solana_bank_timing_ratio gauge\n",
+                "solana_bank_timing_ratio{host_id=\"host\",kind=\"a\\\"b\"} 0.5\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_max_series_per_metric() {
+        let mut registry = Registry::default();
+        for i in 0..MAX_SERIES_PER_METRIC + 2 {
+            registry.record_point(
+                DataPoint::new("series")
+                    .add_tag("id", &i.to_string())
+                    .add_field_i64("value", 1),
+            );
+        }
+        // Existing label sets are still updated once the metric is full
+        registry.record_point(
+            DataPoint::new("series")
+                .add_tag("id", "0")
+                .add_field_i64("value", 2),
+        );
+        let samples = &registry.gauges["solana_series_value"];
+        assert_eq!(samples.len(), MAX_SERIES_PER_METRIC);
+        assert_eq!(samples[",id=\"0\""], 2.0);
+        assert_eq!(registry.dropped_series, 2);
+        assert!(registry
+            .render("host")
+            .starts_with("# TYPE solana_prometheus_dropped_series_total counter\n"));
+    }
+
+    #[test]
+    fn test_metrics_endpoint() {
+        let writer = PrometheusMetricsWriter::new("127.0.0.1:0".parse().unwrap()).unwrap();
+        writer.write(vec![DataPoint::new("endpoint")
+            .add_field_i64("value", 7)
+            .to_owned()]);
+        assert!(writer.aggregates_counters());
+        writer.write_counters(vec![CounterPoint::new("endpoint")]);
+
+        let get = |path: &str| {
+            let mut stream = TcpStream::connect(writer.local_addr()).unwrap();
+            write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let response = get(METRICS_PATH);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with(&writer.render()));
+        assert!(response.contains("\nsolana_endpoint_value{host_id=\""));
+        assert!(response.contains("\nsolana_endpoint_total{host_id=\""));
+
+        let response = get("/other");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
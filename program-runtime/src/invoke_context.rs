@@ -24,7 +24,7 @@ use {
     solana_sdk_ids::{
         bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, loader_v4, native_loader, sysvar,
     },
-    solana_svm_callback::InvokeContextCallback,
+    solana_svm_callback::{InstructionProcessedCallback, InvokeContextCallback},
     solana_svm_feature_set::SVMFeatureSet,
     solana_svm_log_collector::{LogCollector, ic_msg},
     solana_svm_measure::measure::Measure,
@@ -144,6 +144,8 @@ pub struct EnvironmentConfig<'a> {
     pub program_runtime_environments_for_execution: &'a ProgramRuntimeEnvironments,
     pub program_runtime_environments_for_deployment: &'a ProgramRuntimeEnvironments,
     sysvar_cache: &'a SysvarCache,
+    /// Notified of the result of every instruction, if set
+    pub instruction_processed_callback: Option<&'a dyn InstructionProcessedCallback>,
 }
 impl<'a> EnvironmentConfig<'a> {
     pub fn new(
@@ -163,6 +165,7 @@ impl<'a> EnvironmentConfig<'a> {
             program_runtime_environments_for_execution,
             program_runtime_environments_for_deployment,
             sysvar_cache,
+            instruction_processed_callback: None,
         }
     }
 }
@@ -486,11 +489,27 @@ impl<'a, 'ix_data> InvokeContext<'a, 'ix_data> {
         timings: &mut ExecuteTimings,
     ) -> Result<(), InstructionError> {
         *compute_units_consumed = 0;
+        let index_in_trace = self.transaction_context.get_instruction_trace_length();
         self.push()?;
-        self.process_executable_chain(compute_units_consumed, timings)
+        let result = self
+            .process_executable_chain(compute_units_consumed, timings)
             // MUST pop if and only if `push` succeeded, independent of `result`.
             // Thus, the `.and()` instead of an `.and_then()`.
-            .and(self.pop())
+            .and(self.pop());
+        if let Some(callback) = self.environment_config.instruction_processed_callback
+            && let Ok(program_id) = self
+                .transaction_context
+                .get_instruction_context_at_index_in_trace(index_in_trace)
+                .and_then(|instruction_context| instruction_context.get_program_key().copied())
+        {
+            callback.on_instruction_processed(
+                index_in_trace,
+                &program_id,
+                *compute_units_consumed,
+                result.is_ok(),
+            );
+        }
+        result
     }
 
     /// Processes a precompile instruction
@@ -1250,6 +1269,28 @@ mod tests {
         assert_eq!(result, expected_result);
     }
 
+    #[derive(Default)]
+    struct MockInstructionProcessedCallback {
+        processed_instructions: RefCell<Vec<(usize, Pubkey, u64, bool)>>,
+    }
+
+    impl InstructionProcessedCallback for MockInstructionProcessedCallback {
+        fn on_instruction_processed(
+            &self,
+            index_in_trace: usize,
+            program_id: &Pubkey,
+            compute_units_consumed: u64,
+            succeeded: bool,
+        ) {
+            self.processed_instructions.borrow_mut().push((
+                index_in_trace,
+                *program_id,
+                compute_units_consumed,
+                succeeded,
+            ));
+        }
+    }
+
     #[test_case(Ok(()); "Ok")]
     #[test_case(Err(InstructionError::GenericError); "GenericError")]
     fn test_process_instruction_compute_unit_consumption(
@@ -1283,6 +1324,7 @@ mod tests {
                 )
             })
             .collect::<Vec<_>>();
+        let instruction_processed_callback = MockInstructionProcessedCallback::default();
         with_mock_invoke_context!(invoke_context, transaction_context, transaction_accounts);
         let mut program_cache_for_tx_batch = ProgramCacheForTxBatch::default();
         program_cache_for_tx_batch.replenish(
@@ -1290,6 +1332,9 @@ mod tests {
             Arc::new(ProgramCacheEntry::new_builtin(0, 1, MockBuiltin::vm)),
         );
         invoke_context.program_cache_for_tx_batch = &mut program_cache_for_tx_batch;
+        invoke_context
+            .environment_config
+            .instruction_processed_callback = Some(&instruction_processed_callback);
 
         // Compute unit consumption tests
         let compute_units_to_consume = 10;
@@ -1325,6 +1370,19 @@ mod tests {
         assert_eq!(result, expected_result);
 
         invoke_context.pop().unwrap();
+        drop(invoke_context);
+        // The instruction is reported once its result is final
+        assert_eq!(
+            instruction_processed_callback
+                .processed_instructions
+                .into_inner(),
+            vec![(
+                1,
+                callee_program_id,
+                compute_units_consumed,
+                expected_result.is_ok()
+            )],
+        );
     }
 
     #[test]
@@ -0,0 +1,278 @@
+//! Compute unit profiles of SBF programs.
+//!
+//! A profile is folded from the VM register traces recorded while programs
+//! execute, which requires a program runtime environment created with
+//! debugging features. Every traced instruction costs one compute unit and is
+//! attributed to the call stack it executed in, with function names taken from
+//! the program's debug symbols when present. A syscall is attributed as a leaf
+//! frame under the function that called it, by the instruction which invoked
+//! it; the compute units charged by the syscall itself are not part of the
+//! register trace.
+//!
+//! Profiles are written in the folded stack format, one line per stack with
+//! semicolon-separated frames followed by the sampled compute units, which
+//! flame graph tools and `pprof` converters accept as input.
+
+use {
+    crate::invoke_context::{InvokeContext, RegisterTrace},
+    solana_pubkey::Pubkey,
+    solana_sbpf::{elf::Executable, static_analysis::Analysis, vm::ContextObject},
+    std::{
+        cell::RefCell,
+        collections::{BTreeMap, HashMap},
+        fmt,
+        io::{self, Write},
+    },
+};
+
+/// Index of the program counter in a register trace entry
+const PC_REGISTER: usize = 11;
+
+/// Compute units sampled per call stack
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ComputeUnitProfile {
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+/// How a traced instruction transfers control
+#[derive(Debug, PartialEq, Eq)]
+enum Step {
+    /// Calls the function starting at the next traced instruction
+    Call,
+    /// Invokes the named syscall
+    Syscall(String),
+    /// Returns to the caller
+    Return,
+    /// Anything else
+    Other,
+}
+
+impl ComputeUnitProfile {
+    /// Returns true if no compute units were sampled
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Returns the number of compute units sampled over all stacks
+    pub fn total_compute_units(&self) -> u64 {
+        self.stacks
+            .values()
+            .fold(0, |total, units| total.saturating_add(*units))
+    }
+
+    /// Iterates over the sampled call stacks, from the outermost frame, and
+    /// their compute units
+    pub fn stacks(&self) -> impl Iterator<Item = (&[String], u64)> {
+        self.stacks
+            .iter()
+            .map(|(frames, units)| (frames.as_slice(), *units))
+    }
+
+    /// Adds the samples of another profile to this one
+    pub fn merge(&mut self, other: &ComputeUnitProfile) {
+        for (frames, units) in &other.stacks {
+            self.add_sample(frames.clone(), *units);
+        }
+    }
+
+    /// Folds the register trace of one execution of `program_id`
+    pub fn add_register_trace<C: ContextObject>(
+        &mut self,
+        program_id: &Pubkey,
+        executable: &Executable<C>,
+        register_trace: RegisterTrace,
+    ) {
+        let Ok(analysis) = Analysis::from_executable(executable) else {
+            return;
+        };
+        let instructions = analysis
+            .instructions
+            .iter()
+            .map(|insn| (insn.ptr, insn))
+            .collect::<HashMap<_, _>>();
+        let pcs = register_trace
+            .iter()
+            .filter_map(|registers| registers.get(PC_REGISTER).map(|pc| *pc as usize))
+            .collect::<Vec<_>>();
+        let steps = pcs.iter().enumerate().map(|(index, pc)| {
+            let step = instructions
+                .get(pc)
+                .map(|insn| {
+                    let next_pc = pcs.get(index.saturating_add(1)).copied();
+                    classify(&analysis.disassemble_instruction(insn, *pc), *pc, next_pc)
+                })
+                .unwrap_or(Step::Other);
+            (*pc, step)
+        });
+        self.fold(program_id.to_string(), steps, |pc| {
+            analysis
+                .functions
+                .range(..=pc)
+                .next_back()
+                .map(|(_, (_, name))| name.clone())
+                .unwrap_or_else(|| format!("{pc:#x}"))
+        });
+    }
+
+    /// Writes the profile in the folded stack format
+    pub fn write_folded<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        write!(output, "{self}")
+    }
+
+    fn fold(
+        &mut self,
+        root: String,
+        steps: impl Iterator<Item = (usize, Step)>,
+        function_name: impl Fn(usize) -> String,
+    ) {
+        let mut frames = vec![root];
+        let mut call_pending = false;
+        for (pc, step) in steps {
+            if call_pending || frames.len() == 1 {
+                frames.push(function_name(pc));
+                call_pending = false;
+            }
+            match step {
+                Step::Call => {
+                    self.add_sample(frames.clone(), 1);
+                    call_pending = true;
+                }
+                Step::Syscall(name) => {
+                    let mut stack = frames.clone();
+                    stack.push(name);
+                    self.add_sample(stack, 1);
+                }
+                Step::Return => {
+                    self.add_sample(frames.clone(), 1);
+                    // Keep the program and the entrypoint frames
+                    if frames.len() > 2 {
+                        frames.pop();
+                    }
+                }
+                Step::Other => self.add_sample(frames.clone(), 1),
+            }
+        }
+    }
+
+    fn add_sample(&mut self, frames: Vec<String>, units: u64) {
+        let total = self.stacks.entry(frames).or_default();
+        *total = total.saturating_add(units);
+    }
+}
+
+/// Classifies a disassembled instruction by the control flow it caused in the
+/// trace. A call which falls through to the next instruction invoked a
+/// syscall, since internal calls jump to the start of a function.
+fn classify(disassembly: &str, pc: usize, next_pc: Option<usize>) -> Step {
+    let mut tokens = disassembly.split_whitespace();
+    let mnemonic = tokens.next().unwrap_or_default();
+    let falls_through = next_pc == Some(pc.saturating_add(1));
+    match mnemonic {
+        "syscall" => Step::Syscall(tokens.next().unwrap_or(mnemonic).to_string()),
+        "call" if falls_through => Step::Syscall(tokens.next().unwrap_or(mnemonic).to_string()),
+        "call" | "callx" if next_pc.is_some() => Step::Call,
+        "exit" | "return" => Step::Return,
+        _ => Step::Other,
+    }
+}
+
+impl fmt::Display for ComputeUnitProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (frames, units) in &self.stacks {
+            writeln!(f, "{} {units}", frames.join(";"))?;
+        }
+        Ok(())
+    }
+}
+
+impl InvokeContext<'_, '_> {
+    /// Folds the VM register traces of all instructions (including CPI) into
+    /// a compute unit profile
+    pub fn compute_unit_profile(&self) -> ComputeUnitProfile {
+        let profile = RefCell::new(ComputeUnitProfile::default());
+        self.iterate_vm_traces(&|instruction_context, executable, register_trace| {
+            if let Ok(program_id) = instruction_context.get_program_key() {
+                profile
+                    .borrow_mut()
+                    .add_register_trace(program_id, executable, register_trace);
+            }
+        });
+        profile.into_inner()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frames(frames: &[&str]) -> Vec<String> {
+        frames.iter().map(|frame| frame.to_string()).collect()
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(classify("call function_foo", 4, Some(20)), Step::Call);
+        assert_eq!(
+            classify("call sol_log_", 4, Some(5)),
+            Step::Syscall("sol_log_".to_string())
+        );
+        assert_eq!(
+            classify("syscall sol_log_", 4, Some(5)),
+            Step::Syscall("sol_log_".to_string())
+        );
+        assert_eq!(classify("callx r2", 4, Some(20)), Step::Call);
+        assert_eq!(classify("callx r2", 4, None), Step::Other);
+        assert_eq!(classify("exit", 4, Some(8)), Step::Return);
+        assert_eq!(classify("return", 4, None), Step::Return);
+        assert_eq!(classify("mov64 r0, 0", 4, Some(5)), Step::Other);
+    }
+
+    #[test]
+    fn test_fold() {
+        // entrypoint: 0..10, function_foo: 10..20
+        let function_name = |pc: usize| {
+            if pc < 10 {
+                "entrypoint".to_string()
+            } else {
+                "function_foo".to_string()
+            }
+        };
+        let steps = [
+            (0, Step::Other),
+            (1, Step::Call),
+            (10, Step::Other),
+            (11, Step::Syscall("sol_log_".to_string())),
+            (12, Step::Return),
+            (2, Step::Other),
+            (3, Step::Return),
+        ];
+        let mut profile = ComputeUnitProfile::default();
+        profile.fold("program".to_string(), steps.into_iter(), function_name);
+
+        assert_eq!(profile.total_compute_units(), 7);
+        assert_eq!(
+            profile.stacks().collect::<Vec<_>>(),
+            vec![
+                (frames(&["program", "entrypoint"]).as_slice(), 4),
+                (
+                    frames(&["program", "entrypoint", "function_foo"]).as_slice(),
+                    2
+                ),
+                (
+                    frames(&["program", "entrypoint", "function_foo", "sol_log_"]).as_slice(),
+                    1
+                ),
+            ]
+        );
+
+        assert_eq!(
+            profile.to_string(),
+            "program;entrypoint 4\nprogram;entrypoint;function_foo \
+             2\nprogram;entrypoint;function_foo;sol_log_ 1\n"
+        );
+
+        let mut merged = profile.clone();
+        merged.merge(&profile);
+        assert_eq!(merged.total_compute_units(), 14);
+    }
+}
@@ -482,6 +482,7 @@ pub struct ProgramTest {
     prefer_bpf: bool,
     deactivate_feature_set: HashSet<Pubkey>,
     transaction_account_lock_limit: Option<usize>,
+    enable_compute_unit_profiling: bool,
 }
 
 impl Default for ProgramTest {
@@ -513,6 +514,7 @@ impl Default for ProgramTest {
             prefer_bpf,
             deactivate_feature_set: HashSet::default(),
             transaction_account_lock_limit: None,
+            enable_compute_unit_profiling: false,
         }
     }
 }
@@ -554,6 +556,15 @@ impl ProgramTest {
         self.transaction_account_lock_limit = Some(transaction_account_lock_limit);
     }
 
+    /// Profile the compute units of SBF programs in simulated transactions
+    ///
+    /// The profile is returned by
+    /// `BanksClient::simulate_transaction_with_compute_unit_profile`, in the
+    /// folded stack format.
+    pub fn enable_compute_unit_profiling(&mut self, enable_compute_unit_profiling: bool) {
+        self.enable_compute_unit_profiling = enable_compute_unit_profiling;
+    }
+
     /// Add an account to the test environment's genesis config.
     pub fn add_genesis_account(&mut self, address: Pubkey, account: Account) {
         self.genesis_accounts
@@ -855,6 +866,7 @@ impl ProgramTest {
                     )
                 }),
                 transaction_account_lock_limit: self.transaction_account_lock_limit,
+                enable_compute_unit_profiling: self.enable_compute_unit_profiling,
                 ..RuntimeConfig::default()
             }),
             Vec::default(),
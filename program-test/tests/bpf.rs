@@ -89,3 +89,40 @@ async fn test_max_accounts(num_accounts: u8, deactivate_feature: bool, expect_su
             .unwrap_err();
     }
 }
+
+#[tokio::test]
+async fn test_compute_unit_profile() {
+    let program_id = Pubkey::new_unique();
+
+    let mut program_test = ProgramTest::default();
+    program_test.prefer_bpf(true);
+    program_test.add_program("noop_program", program_id, None);
+    program_test.enable_compute_unit_profiling(true);
+
+    let context = program_test.start_with_context().await;
+
+    let instruction = Instruction::new_with_bytes(program_id, &[], Vec::new());
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    let simulation_result = context
+        .banks_client
+        .simulate_transaction_with_compute_unit_profile(transaction)
+        .await
+        .unwrap();
+    let simulation_details = simulation_result.simulation_details.unwrap();
+
+    // Every stack is rooted at the program, and every sampled compute unit was consumed by it
+    let compute_unit_profile = simulation_result.compute_unit_profile.unwrap();
+    let mut sampled_units = 0;
+    for line in compute_unit_profile.lines() {
+        let (stack, units) = line.rsplit_once(' ').unwrap();
+        assert!(stack.starts_with(&program_id.to_string()));
+        sampled_units += units.parse::<u64>().unwrap();
+    }
+    assert!(sampled_units > 0);
+    assert!(sampled_units <= simulation_details.units_consumed);
+}
@@ -28,6 +28,9 @@ pub const JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED: i64 = -32016;
 pub const JSON_RPC_SERVER_ERROR_EPOCH_REWARDS_PERIOD_ACTIVE: i64 = -32017;
 pub const JSON_RPC_SERVER_ERROR_SLOT_NOT_EPOCH_BOUNDARY: i64 = -32018;
 pub const JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_UNREACHABLE: i64 = -32019;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE: i64 = -32020;
+pub const JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE: i64 = -32021;
+pub const JSON_RPC_SERVER_ERROR_PROGRAM_ACCOUNTS_CURSOR_EXPIRED: i64 = -32022;
 
 #[derive(Error, Debug)]
 #[allow(clippy::large_enum_variant)]
@@ -80,6 +83,16 @@ pub enum RpcCustomError {
     SlotNotEpochBoundary { slot: Slot },
     #[error("LongTermStorageUnreachable")]
     LongTermStorageUnreachable,
+    #[error("AccountHistoryNotAvailable")]
+    AccountHistoryNotAvailable { pubkey: Option<String> },
+    #[error("AccountHistorySlotNotAvailable")]
+    AccountHistorySlotNotAvailable {
+        slot: Slot,
+        first_available_slot: Slot,
+        latest_root: Slot,
+    },
+    #[error("ProgramAccountsCursorExpired")]
+    ProgramAccountsCursorExpired { slot: Slot },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -259,6 +272,37 @@ impl From<RpcCustomError> for Error {
                 message: "Failed to query long-term storage; please try again".to_string(),
                 data: None,
             },
+            RpcCustomError::AccountHistoryNotAvailable { pubkey } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE),
+                message: if let Some(pubkey) = pubkey {
+                    format!("Account {pubkey} is not recorded in the account history of this node")
+                } else {
+                    "Account history is not available from this node".to_string()
+                },
+                data: None,
+            },
+            RpcCustomError::AccountHistorySlotNotAvailable {
+                slot,
+                first_available_slot,
+                latest_root,
+            } => Self {
+                code: ErrorCode::ServerError(
+                    JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE,
+                ),
+                message: format!(
+                    "Account history not available for slot {slot}. Available rooted slots: \
+                     {first_available_slot} to {latest_root}"
+                ),
+                data: None,
+            },
+            RpcCustomError::ProgramAccountsCursorExpired { slot } => Self {
+                code: ErrorCode::ServerError(JSON_RPC_SERVER_ERROR_PROGRAM_ACCOUNTS_CURSOR_EXPIRED),
+                message: format!(
+                    "Cursor expired: bank for slot {slot} is no longer available, restart from \
+                     the first page"
+                ),
+                data: None,
+            },
         }
     }
 }
@@ -2,9 +2,10 @@ use {
     crate::filter::RpcFilterType,
     serde::{Deserialize, Serialize},
     solana_clock::{Epoch, Slot},
+    std::collections::HashMap,
 };
 pub use {
-    solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig},
+    solana_account_decoder_client_types::{UiAccount, UiAccountEncoding, UiDataSliceConfig},
     solana_commitment_config::{CommitmentConfig, CommitmentLevel},
     solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding},
 };
@@ -47,6 +48,34 @@ pub struct RpcSimulateTransactionConfig {
     pub min_context_slot: Option<Slot>,
     #[serde(default)]
     pub inner_instructions: bool,
+    /// Return the compute unit profile of the transaction's SBF programs, which requires a node
+    /// with compute unit profiling enabled
+    #[serde(default)]
+    pub compute_unit_profile: bool,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleConfig {
+    #[serde(default)]
+    pub sig_verify: bool,
+    #[serde(default)]
+    pub replace_recent_blockhash: bool,
+    #[serde(flatten)]
+    pub commitment: Option<CommitmentConfig>,
+    pub encoding: Option<UiTransactionEncoding>,
+    /// Accounts to return the state of once the whole bundle is simulated
+    pub accounts: Option<RpcSimulateTransactionAccountsConfig>,
+    /// Accounts to simulate with in place of the node's, keyed by base-58 encoded address. The
+    /// durable nonce of a transaction is still checked against the node's nonce account
+    pub account_overrides: Option<HashMap<String, UiAccount>>,
+    pub min_context_slot: Option<Slot>,
+    #[serde(default)]
+    pub inner_instructions: bool,
+    /// Return the compute unit profile of the transaction's SBF programs, which requires a node
+    /// with compute unit profiling enabled
+    #[serde(default)]
+    pub compute_unit_profile: bool,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -147,6 +176,8 @@ pub enum RpcAccountIndex {
     ProgramId,
     SplTokenMint,
     SplTokenOwner,
+    SplTokenDelegate,
+    StakeAuthority,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -159,6 +190,13 @@ pub struct RpcAccountInfoConfig {
     pub min_context_slot: Option<Slot>,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountInfoAtSlotConfig {
+    pub encoding: Option<UiAccountEncoding>,
+    pub data_slice: Option<UiDataSliceConfig>,
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcProgramAccountsConfig {
@@ -169,6 +207,24 @@ pub struct RpcProgramAccountsConfig {
     pub sort_results: Option<bool>,
 }
 
+/// Paginated form of [`RpcProgramAccountsConfig`] accepted by `getProgramAccounts`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcProgramAccountsPageConfig {
+    #[serde(flatten)]
+    pub config: RpcProgramAccountsConfig,
+    /// Maximum number of accounts to return. If set, results are ordered by pubkey and returned a
+    /// page at a time, along with a cursor for the next page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    /// Cursor returned with the previous page. A cursor is only valid for the program and filters
+    /// it was issued for, and the next page is read from the bank at the slot the cursor was
+    /// issued at. The node keeps that bank for a limited time after each page, and the cursor
+    /// expires once the node no longer holds it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcTransactionLogsFilter {
@@ -8,6 +8,12 @@ use {
 const MAX_DATA_SIZE: usize = 128;
 const MAX_DATA_BASE58_SIZE: usize = 175;
 const MAX_DATA_BASE64_SIZE: usize = 172;
+const PUBKEY_BYTES: usize = 32;
+const MAX_BASE58_PUBKEY_SIZE: usize = 44;
+/// Maximum nesting of `And`, `Or` and `Not` filters
+pub const MAX_FILTER_DEPTH: usize = 4;
+/// Maximum number of filters, including nested ones, within a single filter
+pub const MAX_FILTER_TREE_SIZE: usize = 16;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -15,10 +21,48 @@ pub enum RpcFilterType {
     DataSize(u64),
     Memcmp(Memcmp),
     TokenAccountState,
+    /// Account data length within an inclusive range
+    DataSizeRange(RpcRange),
+    /// Account lamports within an inclusive range
+    LamportsRange(RpcRange),
+    /// Account owned by the base58 encoded program id
+    Owner(String),
+    /// Little-endian u32 at an offset is greater than or equal to a value
+    U32Gte(IntegerCompare),
+    /// Little-endian u32 at an offset is less than or equal to a value
+    U32Lte(IntegerCompare),
+    /// Little-endian u64 at an offset is greater than or equal to a value
+    U64Gte(IntegerCompare),
+    /// Little-endian u64 at an offset is less than or equal to a value
+    U64Lte(IntegerCompare),
+    /// All of the nested filters match
+    And(Vec<RpcFilterType>),
+    /// Any of the nested filters match
+    Or(Vec<RpcFilterType>),
+    /// The nested filter does not match
+    Not(Box<RpcFilterType>),
 }
 
 impl RpcFilterType {
     pub fn verify(&self) -> Result<(), RpcFilterError> {
+        if self.tree_size() > MAX_FILTER_TREE_SIZE {
+            return Err(RpcFilterError::TooManyFilters);
+        }
+        self.verify_nested(0)
+    }
+
+    /// Number of filters in this filter, including itself and all nested filters
+    pub fn tree_size(&self) -> usize {
+        match self {
+            RpcFilterType::And(filters) | RpcFilterType::Or(filters) => filters
+                .iter()
+                .fold(1, |size, filter| size.saturating_add(filter.tree_size())),
+            RpcFilterType::Not(filter) => filter.tree_size().saturating_add(1),
+            _ => 1,
+        }
+    }
+
+    fn verify_nested(&self, depth: usize) -> Result<(), RpcFilterError> {
         match self {
             RpcFilterType::DataSize(_) => Ok(()),
             RpcFilterType::Memcmp(compare) => {
@@ -55,6 +99,45 @@ impl RpcFilterType {
                 }
             }
             RpcFilterType::TokenAccountState => Ok(()),
+            RpcFilterType::DataSizeRange(range) | RpcFilterType::LamportsRange(range) => {
+                range.verify()
+            }
+            RpcFilterType::U32Gte(compare) | RpcFilterType::U32Lte(compare) => {
+                if compare.value > u64::from(u32::MAX) {
+                    Err(RpcFilterError::ValueOutOfRange)
+                } else {
+                    Ok(())
+                }
+            }
+            RpcFilterType::U64Gte(_) | RpcFilterType::U64Lte(_) => Ok(()),
+            RpcFilterType::Owner(owner) => {
+                if owner.len() > MAX_BASE58_PUBKEY_SIZE {
+                    return Err(RpcFilterError::InvalidOwner);
+                }
+                let owner = bs58::decode(owner).into_vec()?;
+                if owner.len() != PUBKEY_BYTES {
+                    Err(RpcFilterError::InvalidOwner)
+                } else {
+                    Ok(())
+                }
+            }
+            RpcFilterType::And(filters) | RpcFilterType::Or(filters) => {
+                if depth >= MAX_FILTER_DEPTH {
+                    return Err(RpcFilterError::TooDeeplyNested);
+                }
+                if filters.is_empty() {
+                    return Err(RpcFilterError::EmptyCombinator);
+                }
+                filters
+                    .iter()
+                    .try_for_each(|filter| filter.verify_nested(depth.saturating_add(1)))
+            }
+            RpcFilterType::Not(filter) => {
+                if depth >= MAX_FILTER_DEPTH {
+                    return Err(RpcFilterError::TooDeeplyNested);
+                }
+                filter.verify_nested(depth.saturating_add(1))
+            }
         }
     }
 }
@@ -67,6 +150,92 @@ pub enum RpcFilterError {
     Base58DecodeError(#[from] bs58::decode::Error),
     #[error("base64 decode error")]
     Base64DecodeError(#[from] base64::DecodeError),
+    #[error("range must have a bound and its min must not exceed its max")]
+    InvalidRange,
+    #[error("comparison value does not fit in the compared integer")]
+    ValueOutOfRange,
+    #[error("owner should be a base58 encoded pubkey")]
+    InvalidOwner,
+    #[error("and/or filters must contain at least one filter")]
+    EmptyCombinator,
+    #[error("filters should be nested at most {MAX_FILTER_DEPTH} levels deep")]
+    TooDeeplyNested,
+    #[error("filter should contain at most {MAX_FILTER_TREE_SIZE} filters")]
+    TooManyFilters,
+}
+
+/// Inclusive range; a missing bound is unbounded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RpcRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<u64>,
+}
+
+impl RpcRange {
+    pub fn new(min: Option<u64>, max: Option<u64>) -> Self {
+        Self { min, max }
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.min
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.max
+    }
+
+    pub fn verify(&self) -> Result<(), RpcFilterError> {
+        match (self.min, self.max) {
+            (None, None) => Err(RpcFilterError::InvalidRange),
+            (Some(min), Some(max)) if min > max => Err(RpcFilterError::InvalidRange),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        self.min.is_none_or(|min| value >= min) && self.max.is_none_or(|max| value <= max)
+    }
+}
+
+/// Comparison of a little-endian integer in account data against a value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct IntegerCompare {
+    /// Data offset of the integer
+    offset: usize,
+    /// Value to compare the integer against
+    value: u64,
+}
+
+impl IntegerCompare {
+    pub fn new(offset: usize, value: u64) -> Self {
+        Self { offset, value }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Reads the little-endian u32 at the offset, if the data is long enough
+    pub fn read_u32(&self, data: &[u8]) -> Option<u32> {
+        let end = self.offset.checked_add(4)?;
+        data.get(self.offset..end)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u32::from_le_bytes)
+    }
+
+    /// Reads the little-endian u64 at the offset, if the data is long enough
+    pub fn read_u64(&self, data: &[u8]) -> Option<u64> {
+        let end = self.offset.checked_add(8)?;
+        data.get(self.offset..end)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
@@ -214,6 +383,7 @@ mod tests {
         super::*,
         const_format::formatcp,
         serde_json::{json, Value},
+        solana_pubkey::Pubkey,
     };
 
     #[test]
@@ -309,6 +479,140 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_verify_compound_filters() {
+        let leaf = RpcFilterType::DataSize(0);
+
+        // Ranges need a bound, and must not be inverted
+        assert_eq!(
+            RpcFilterType::DataSizeRange(RpcRange::new(Some(1), Some(1))).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::LamportsRange(RpcRange::new(None, Some(1))).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::LamportsRange(RpcRange::new(None, None)).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+        assert_eq!(
+            RpcFilterType::DataSizeRange(RpcRange::new(Some(2), Some(1))).verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+
+        // Owners must be pubkeys
+        assert_eq!(
+            RpcFilterType::Owner(Pubkey::new_unique().to_string()).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::Owner(bs58::encode([1; 31]).into_string()).verify(),
+            Err(RpcFilterError::InvalidOwner)
+        );
+        assert_eq!(
+            RpcFilterType::Owner("1".repeat(45)).verify(),
+            Err(RpcFilterError::InvalidOwner)
+        );
+        assert!(matches!(
+            RpcFilterType::Owner("0".to_string()).verify(),
+            Err(RpcFilterError::Base58DecodeError(_))
+        ));
+
+        // u32 comparisons must use a u32 value
+        assert_eq!(
+            RpcFilterType::U32Gte(IntegerCompare::new(0, u32::MAX as u64)).verify(),
+            Ok(())
+        );
+        assert_eq!(
+            RpcFilterType::U32Lte(IntegerCompare::new(0, u32::MAX as u64 + 1)).verify(),
+            Err(RpcFilterError::ValueOutOfRange)
+        );
+        assert_eq!(
+            RpcFilterType::U64Lte(IntegerCompare::new(0, u64::MAX)).verify(),
+            Ok(())
+        );
+
+        // Combinators must not be empty
+        assert_eq!(
+            RpcFilterType::Or(vec![]).verify(),
+            Err(RpcFilterError::EmptyCombinator)
+        );
+
+        // Nested filters are verified
+        assert_eq!(
+            RpcFilterType::And(vec![
+                leaf.clone(),
+                RpcFilterType::Not(Box::new(RpcFilterType::LamportsRange(RpcRange::default())))
+            ])
+            .verify(),
+            Err(RpcFilterError::InvalidRange)
+        );
+
+        // Nesting depth is limited
+        let nested = |depth| {
+            (0..depth).fold(leaf.clone(), |filter, _| {
+                RpcFilterType::Not(Box::new(filter))
+            })
+        };
+        assert_eq!(nested(MAX_FILTER_DEPTH).verify(), Ok(()));
+        assert_eq!(
+            nested(MAX_FILTER_DEPTH + 1).verify(),
+            Err(RpcFilterError::TooDeeplyNested)
+        );
+
+        // Total number of filters is limited
+        let wide = |width| RpcFilterType::Or(vec![leaf.clone(); width]);
+        assert_eq!(
+            wide(MAX_FILTER_TREE_SIZE - 1).tree_size(),
+            MAX_FILTER_TREE_SIZE
+        );
+        assert_eq!(wide(MAX_FILTER_TREE_SIZE - 1).verify(), Ok(()));
+        assert_eq!(
+            wide(MAX_FILTER_TREE_SIZE).verify(),
+            Err(RpcFilterError::TooManyFilters)
+        );
+    }
+
+    #[test]
+    fn test_compound_filter_serde() {
+        let filter = RpcFilterType::And(vec![
+            RpcFilterType::DataSizeRange(RpcRange::new(Some(1), None)),
+            RpcFilterType::Or(vec![
+                RpcFilterType::LamportsRange(RpcRange::new(Some(2), Some(3))),
+                RpcFilterType::Not(Box::new(RpcFilterType::U64Gte(IntegerCompare::new(4, 5)))),
+            ]),
+            RpcFilterType::TokenAccountState,
+            RpcFilterType::Owner(BASE58_STR.to_string()),
+        ]);
+        let expected = json!({
+            "and": [
+                { "dataSizeRange": { "min": 1 } },
+                { "or": [
+                    { "lamportsRange": { "min": 2, "max": 3 } },
+                    { "not": { "u64Gte": { "offset": 4, "value": 5 } } },
+                ] },
+                "tokenAccountState",
+                { "owner": BASE58_STR },
+            ]
+        });
+        assert_eq!(json!(filter), expected);
+        assert_eq!(
+            serde_json::from_value::<RpcFilterType>(expected).unwrap(),
+            filter
+        );
+    }
+
+    #[test]
+    fn test_integer_compare_read() {
+        let data = [1, 0, 0, 0, 2, 0, 0, 0, 0];
+        assert_eq!(IntegerCompare::new(0, 0).read_u32(&data), Some(1));
+        assert_eq!(IntegerCompare::new(4, 0).read_u64(&data), Some(2));
+        assert_eq!(IntegerCompare::new(6, 0).read_u32(&data), None);
+        assert_eq!(IntegerCompare::new(2, 0).read_u64(&data), None);
+        assert_eq!(IntegerCompare::new(usize::MAX, 0).read_u64(&data), None);
+    }
+
     const BASE58_STR: &str = "Bpf4ERpEvSFmCSTNh1PzTWTkALrKXvMXEdthxHuwCQcf";
     const BASE64_STR: &str = "oMoycDvJzrjQpCfukbO4VW/FLGLfnbqBEc9KUEVgj2g=";
     const BYTES: [u8; 4] = [0, 1, 2, 3];
@@ -66,6 +66,33 @@ impl<T> OptionalContext<T> {
     }
 }
 
+/// A page of `getProgramAccounts` results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcKeyedAccountsPage {
+    pub accounts: Vec<RpcKeyedAccount>,
+    /// Cursor for the next page, or `None` if this is the last page
+    pub cursor: Option<String>,
+}
+
+/// Return type of `getProgramAccounts`, which is paginated if a limit or cursor is requested
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RpcProgramAccountsResponse {
+    Page(Response<RpcKeyedAccountsPage>),
+    Accounts(OptionalContext<Vec<RpcKeyedAccount>>),
+}
+
+impl RpcProgramAccountsResponse {
+    /// Returns the accounts and, if paginated, the cursor for the next page
+    pub fn parse_value(self) -> (Vec<RpcKeyedAccount>, Option<String>) {
+        match self {
+            Self::Page(response) => (response.value.accounts, response.value.cursor),
+            Self::Accounts(accounts) => (accounts.parse_value(), None),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RpcResponseContext {
@@ -445,6 +472,19 @@ pub struct RpcSimulateTransactionResult {
     pub pre_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub post_token_balances: Option<Vec<UiTransactionTokenBalance>>,
     pub loaded_addresses: Option<UiLoadedAddresses>,
+    /// Compute units sampled per call stack of the SBF programs executed, in the folded stack
+    /// format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compute_unit_profile: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    /// Results of the bundle's transactions, in order
+    pub transaction_results: Vec<RpcSimulateTransactionResult>,
+    /// State of the requested accounts once the whole bundle is simulated
+    pub accounts: Option<Vec<Option<UiAccount>>>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
@@ -20,9 +20,9 @@ use {
             Response, RpcAccountBalance, RpcBlockProduction, RpcBlockProductionRange, RpcBlockhash,
             RpcConfirmedTransactionStatusWithSignature, RpcContactInfo, RpcIdentity,
             RpcInflationGovernor, RpcInflationRate, RpcInflationReward, RpcKeyedAccount,
-            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateTransactionResult,
-            RpcSnapshotSlotInfo, RpcSupply, RpcVersionInfo, RpcVoteAccountInfo,
-            RpcVoteAccountStatus,
+            RpcPerfSample, RpcPrioritizationFee, RpcResponseContext, RpcSimulateBundleResult,
+            RpcSimulateTransactionResult, RpcSnapshotSlotInfo, RpcSupply, RpcVersionInfo,
+            RpcVoteAccountInfo, RpcVoteAccountStatus,
         },
     },
     solana_signature::Signature,
@@ -157,6 +157,10 @@ impl RpcSender for MockSender {
                 context: RpcResponseContext { slot: 1, api_version: None },
                 value: Value::Null,
             })?,
+            "getAccountInfoAtSlot" => serde_json::to_value(Response {
+                context: RpcResponseContext { slot: 1, api_version: None },
+                value: Value::Null,
+            })?,
             "getBalance" => serde_json::to_value(Response {
                 context: RpcResponseContext { slot: 1, api_version: None },
                 value: Value::Number(Number::from(50)),
@@ -363,8 +367,38 @@ impl RpcSender for MockSender {
                     pre_token_balances: None,
                     post_token_balances: None,
                     loaded_addresses: None,
+                    compute_unit_profile: None,
                 }
             })?,
+            "simulateBundle" => {
+                let transaction_count = params.as_array().unwrap()[0].as_array().unwrap().len();
+                serde_json::to_value(Response {
+                    context: RpcResponseContext { slot: 1, api_version: None },
+                    value: RpcSimulateBundleResult {
+                        transaction_results: vec![
+                            RpcSimulateTransactionResult {
+                                err: None,
+                                logs: None,
+                                accounts: None,
+                                units_consumed: None,
+                                loaded_accounts_data_size: None,
+                                return_data: None,
+                                inner_instructions: None,
+                                replacement_blockhash: None,
+                                fee: None,
+                                pre_balances: None,
+                                post_balances: None,
+                                pre_token_balances: None,
+                                post_token_balances: None,
+                                loaded_addresses: None,
+                                compute_unit_profile: None,
+                            };
+                            transaction_count
+                        ],
+                        accounts: None,
+                    }
+                })?
+            }
             "getMinimumBalanceForRentExemption" => json![20],
             "getVersion" => {
                 let version = Version::default();
@@ -21,7 +21,7 @@ use {
     },
     base64::{prelude::BASE64_STANDARD, Engine},
     bincode::serialize,
-    futures::join,
+    futures::{join, stream, Stream},
     log::*,
     serde_json::{json, Value},
     solana_account::Account,
@@ -1426,6 +1426,103 @@ impl RpcClient {
         .await
     }
 
+    /// Simulates sending a bundle of transactions.
+    ///
+    /// The transactions are simulated in order, each one observing the
+    /// account writes of the ones before it, and nothing is committed. The
+    /// returned [`RpcSimulateBundleResult`] holds the result of each
+    /// transaction, including its logs, consumed compute units and return
+    /// data.
+    ///
+    /// To verify signatures, override accounts, or query the state of
+    /// accounts once the whole bundle is simulated, call the
+    /// [`simulate_bundle_with_config`] method.
+    ///
+    /// [`simulate_bundle_with_config`]: RpcClient::simulate_bundle_with_config
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_keypair::Keypair;
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_signer::Signer;
+    /// # use solana_system_transaction as system_transaction;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// // Alice funds Bob, who then pays Carol
+    /// #     let alice = Keypair::new();
+    /// #     let bob = Keypair::new();
+    /// #     let carol = Keypair::new();
+    /// let latest_blockhash = rpc_client.get_latest_blockhash().await?;
+    /// let bundle = [
+    ///     system_transaction::transfer(&alice, &bob.pubkey(), 100, latest_blockhash),
+    ///     system_transaction::transfer(&bob, &carol.pubkey(), 50, latest_blockhash),
+    /// ];
+    /// let result = rpc_client.simulate_bundle(&bundle).await?;
+    /// assert_eq!(result.value.transaction_results.len(), 2);
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn simulate_bundle(
+        &self,
+        transactions: &[impl SerializableTransaction],
+    ) -> RpcResult<RpcSimulateBundleResult> {
+        self.simulate_bundle_with_config(
+            transactions,
+            RpcSimulateBundleConfig {
+                commitment: Some(self.commitment()),
+                ..RpcSimulateBundleConfig::default()
+            },
+        )
+        .await
+    }
+
+    /// Simulates sending a bundle of transactions.
+    ///
+    /// Accounts given in the [`account_overrides`] field of
+    /// [`RpcSimulateBundleConfig`] are used in place of the node's accounts,
+    /// which allows, for instance, replacing the binary of a program by
+    /// overriding its program data account. The accounts listed in the
+    /// [`accounts`] field are reported in the [`accounts`][accounts2] field of
+    /// the returned [`RpcSimulateBundleResult`], in their state once the
+    /// whole bundle is simulated.
+    ///
+    /// [`account_overrides`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::account_overrides
+    /// [`accounts`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::accounts
+    /// [accounts2]: solana_rpc_client_api::response::RpcSimulateBundleResult::accounts
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `simulateBundle` RPC method.
+    pub async fn simulate_bundle_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateBundleConfig,
+    ) -> RpcResult<RpcSimulateBundleResult> {
+        let encoding = config.encoding.unwrap_or(UiTransactionEncoding::Base64);
+        let commitment = config.commitment.unwrap_or_default();
+        let config = RpcSimulateBundleConfig {
+            encoding: Some(encoding),
+            commitment: Some(commitment),
+            ..config
+        };
+        let serialized_encoded = transactions
+            .iter()
+            .map(|transaction| serialize_and_encode(transaction, encoding))
+            .collect::<ClientResult<Vec<_>>>()?;
+        self.send(
+            RpcRequest::SimulateBundle,
+            json!([serialized_encoded, config]),
+        )
+        .await
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3689,6 +3786,78 @@ impl RpcClient {
             })?
     }
 
+    /// Returns the account of the provided pubkey as it was at a past rooted
+    /// slot.
+    ///
+    /// If the account did not exist at that slot, this method returns
+    /// `Ok(None)`. The node must be recording the history of the account; see
+    /// the `--account-history-owner` and `--account-history-pubkey` validator
+    /// arguments.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `getAccountInfoAtSlot` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_pubkey::Pubkey;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let alice_pubkey = Pubkey::new_unique();
+    /// let account = rpc_client.get_account_at_slot(&alice_pubkey, 42).await?;
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_account_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+    ) -> RpcResult<Option<Account>> {
+        let config = RpcAccountInfoAtSlotConfig {
+            encoding: Some(UiAccountEncoding::Base64Zstd),
+            data_slice: None,
+        };
+
+        self.get_ui_account_at_slot_with_config(pubkey, slot, config)
+            .await
+            .map(|response| Response {
+                context: response.context,
+                value: response.value.map(|ui_account| {
+                    ui_account.to_account().expect(
+                        "It should be impossible at this point for the account data not to be \
+                         decodable. Ensure that the account was fetched using a binary encoding.",
+                    )
+                }),
+            })
+    }
+
+    /// Returns the account of the provided pubkey as it was at a past rooted
+    /// slot, encoded as requested by `config`.
+    ///
+    /// If the account did not exist at that slot, this method returns
+    /// `Ok(None)`.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getAccountInfoAtSlot` RPC
+    /// method.
+    pub async fn get_ui_account_at_slot_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoAtSlotConfig,
+    ) -> RpcResult<Option<UiAccount>> {
+        self.send(
+            RpcRequest::GetAccountInfoAtSlot,
+            json!([pubkey.to_string(), slot, config]),
+        )
+        .await
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -4254,6 +4423,103 @@ impl RpcClient {
         )
     }
 
+    /// Returns a page of the accounts owned by the provided program pubkey,
+    /// along with the cursor for the next page.
+    ///
+    /// The page size is set by `config.limit`. The next page is requested by
+    /// setting `config.cursor` to the cursor returned with the previous page,
+    /// which is `None` after the last page. A cursor can only be used with the
+    /// program and filters it was returned for, and every page is read from the
+    /// bank the first page was read from. The node keeps that bank for a
+    /// limited time after each page. Once it no longer holds the bank, the
+    /// cursor expires and the walk has to be restarted from the first page.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the [`getProgramAccounts`] RPC method.
+    ///
+    /// [`getProgramAccounts`]: https://solana.com/docs/rpc/http/getprogramaccounts
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{client_error::Error, config::RpcProgramAccountsPageConfig};
+    /// # use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+    /// # use solana_keypair::Keypair;
+    /// # use solana_signer::Signer;
+    /// # futures::executor::block_on(async {
+    /// #     let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// #     let alice = Keypair::new();
+    /// let mut config = RpcProgramAccountsPageConfig {
+    ///     limit: Some(1000),
+    ///     ..RpcProgramAccountsPageConfig::default()
+    /// };
+    /// loop {
+    ///     let (ui_accounts, cursor) = rpc_client
+    ///         .get_program_ui_accounts_page(&alice.pubkey(), config.clone())
+    ///         .await?;
+    ///     if cursor.is_none() {
+    ///         break;
+    ///     }
+    ///     config.cursor = cursor;
+    /// }
+    /// #     Ok::<(), Error>(())
+    /// # })?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub async fn get_program_ui_accounts_page(
+        &self,
+        pubkey: &Pubkey,
+        mut config: RpcProgramAccountsPageConfig,
+    ) -> ClientResult<(Vec<(Pubkey, UiAccount)>, Option<String>)> {
+        let commitment = config
+            .config
+            .account_config
+            .commitment
+            .unwrap_or_else(|| self.commitment());
+        config.config.account_config.commitment = Some(commitment);
+
+        let (accounts, cursor) = self
+            .send::<RpcProgramAccountsResponse>(
+                RpcRequest::GetProgramAccounts,
+                json!([pubkey.to_string(), config]),
+            )
+            .await?
+            .parse_value();
+        let accounts = pubkey_ui_account_client_result_from_keyed_accounts(
+            accounts,
+            RpcRequest::GetProgramAccounts,
+        )?;
+        Ok((accounts, cursor))
+    }
+
+    /// Returns a stream of the pages of accounts owned by the provided
+    /// program pubkey.
+    ///
+    /// Pages are requested as the stream is polled, following the cursor
+    /// returned with each page; see [`get_program_ui_accounts_page`].
+    ///
+    /// [`get_program_ui_accounts_page`]: RpcClient::get_program_ui_accounts_page
+    pub fn get_program_ui_accounts_pages<'a>(
+        &'a self,
+        pubkey: &'a Pubkey,
+        config: RpcProgramAccountsPageConfig,
+    ) -> impl Stream<Item = ClientResult<Vec<(Pubkey, UiAccount)>>> + 'a {
+        stream::try_unfold(Some(config), move |config| async move {
+            let Some(mut config) = config else {
+                return Ok(None);
+            };
+            let (accounts, cursor) = self
+                .get_program_ui_accounts_page(pubkey, config.clone())
+                .await?;
+            let next_config = cursor.map(|cursor| {
+                config.cursor = Some(cursor);
+                config
+            });
+            Ok(Some((accounts, next_config)))
+        })
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
@@ -1236,6 +1236,78 @@ impl RpcClient {
         )
     }
 
+    /// Simulates sending a bundle of transactions.
+    ///
+    /// The transactions are simulated in order, each one observing the
+    /// account writes of the ones before it, and nothing is committed. The
+    /// returned [`RpcSimulateBundleResult`] holds the result of each
+    /// transaction, including its logs, consumed compute units and return
+    /// data.
+    ///
+    /// To verify signatures, override accounts, or query the state of
+    /// accounts once the whole bundle is simulated, call the
+    /// [`simulate_bundle_with_config`] method.
+    ///
+    /// [`simulate_bundle_with_config`]: RpcClient::simulate_bundle_with_config
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `simulateBundle` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_keypair::Keypair;
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_signer::Signer;
+    /// # use solana_system_transaction as system_transaction;
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// // Alice funds Bob, who then pays Carol
+    /// # let alice = Keypair::new();
+    /// # let bob = Keypair::new();
+    /// # let carol = Keypair::new();
+    /// let latest_blockhash = rpc_client.get_latest_blockhash()?;
+    /// let bundle = [
+    ///     system_transaction::transfer(&alice, &bob.pubkey(), 100, latest_blockhash),
+    ///     system_transaction::transfer(&bob, &carol.pubkey(), 50, latest_blockhash),
+    /// ];
+    /// let result = rpc_client.simulate_bundle(&bundle)?;
+    /// assert_eq!(result.value.transaction_results.len(), 2);
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn simulate_bundle(
+        &self,
+        transactions: &[impl SerializableTransaction],
+    ) -> RpcResult<RpcSimulateBundleResult> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundle(transactions))
+    }
+
+    /// Simulates sending a bundle of transactions.
+    ///
+    /// Accounts given in the [`account_overrides`] field of
+    /// [`RpcSimulateBundleConfig`] are used in place of the node's accounts,
+    /// which allows, for instance, replacing the binary of a program by
+    /// overriding its program data account. The accounts listed in the
+    /// [`accounts`] field are reported in the [`accounts`][accounts2] field of
+    /// the returned [`RpcSimulateBundleResult`], in their state once the
+    /// whole bundle is simulated.
+    ///
+    /// [`account_overrides`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::account_overrides
+    /// [`accounts`]: solana_rpc_client_api::config::RpcSimulateBundleConfig::accounts
+    /// [accounts2]: solana_rpc_client_api::response::RpcSimulateBundleResult::accounts
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `simulateBundle` RPC method.
+    pub fn simulate_bundle_with_config(
+        &self,
+        transactions: &[impl SerializableTransaction],
+        config: RpcSimulateBundleConfig,
+    ) -> RpcResult<RpcSimulateBundleResult> {
+        self.invoke((self.rpc_client.as_ref()).simulate_bundle_with_config(transactions, config))
+    }
+
     /// Returns the highest slot information that the node has snapshots for.
     ///
     /// This will find the highest full snapshot slot, and the highest incremental snapshot slot
@@ -3086,6 +3158,54 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_ui_account_with_config(pubkey, config))
     }
 
+    /// Returns the account of the provided pubkey as it was at a past rooted
+    /// slot.
+    ///
+    /// If the account did not exist at that slot, this method returns
+    /// `Ok(None)`. The node must be recording the history of the account; see
+    /// the `--account-history-owner` and `--account-history-pubkey` validator
+    /// arguments.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the `getAccountInfoAtSlot` RPC method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::client_error::Error;
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_pubkey::Pubkey;
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// let alice_pubkey = Pubkey::new_unique();
+    /// let account = rpc_client.get_account_at_slot(&alice_pubkey, 42)?;
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_account_at_slot(&self, pubkey: &Pubkey, slot: Slot) -> RpcResult<Option<Account>> {
+        self.invoke((self.rpc_client.as_ref()).get_account_at_slot(pubkey, slot))
+    }
+
+    /// Returns the account of the provided pubkey as it was at a past rooted
+    /// slot, encoded as requested by `config`.
+    ///
+    /// If the account did not exist at that slot, this method returns
+    /// `Ok(None)`.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method corresponds directly to the `getAccountInfoAtSlot` RPC
+    /// method.
+    pub fn get_ui_account_at_slot_with_config(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: RpcAccountInfoAtSlotConfig,
+    ) -> RpcResult<Option<UiAccount>> {
+        self.invoke(
+            (self.rpc_client.as_ref()).get_ui_account_at_slot_with_config(pubkey, slot, config),
+        )
+    }
+
     /// Get the max slot seen from retransmit stage.
     ///
     /// # RPC Reference
@@ -3478,6 +3598,76 @@ impl RpcClient {
         self.invoke((self.rpc_client.as_ref()).get_program_ui_accounts_with_config(pubkey, config))
     }
 
+    /// Returns a page of the accounts owned by the provided program pubkey,
+    /// along with the cursor for the next page.
+    ///
+    /// The page size is set by `config.limit`. The next page is requested by
+    /// setting `config.cursor` to the cursor returned with the previous page,
+    /// which is `None` after the last page. A cursor can only be used with the
+    /// program and filters it was returned for, and every page is read from the
+    /// bank the first page was read from. The node keeps that bank for a
+    /// limited time after each page. Once it no longer holds the bank, the
+    /// cursor expires and the walk has to be restarted from the first page.
+    ///
+    /// # RPC Reference
+    ///
+    /// This method is built on the [`getProgramAccounts`] RPC method.
+    ///
+    /// [`getProgramAccounts`]: https://solana.com/docs/rpc/http/getprogramaccounts
+    pub fn get_program_ui_accounts_page(
+        &self,
+        pubkey: &Pubkey,
+        config: RpcProgramAccountsPageConfig,
+    ) -> ClientResult<(Vec<(Pubkey, UiAccount)>, Option<String>)> {
+        self.invoke((self.rpc_client.as_ref()).get_program_ui_accounts_page(pubkey, config))
+    }
+
+    /// Returns an iterator over the pages of accounts owned by the provided
+    /// program pubkey.
+    ///
+    /// Pages are requested as the iterator advances, following the cursor
+    /// returned with each page; see [`get_program_ui_accounts_page`].
+    /// Iteration ends after the last page, or after the first error.
+    ///
+    /// [`get_program_ui_accounts_page`]: RpcClient::get_program_ui_accounts_page
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use solana_rpc_client_api::{client_error::Error, config::RpcProgramAccountsPageConfig};
+    /// # use solana_rpc_client::rpc_client::RpcClient;
+    /// # use solana_keypair::Keypair;
+    /// # use solana_signer::Signer;
+    /// # let rpc_client = RpcClient::new_mock("succeeds".to_string());
+    /// # let alice = Keypair::new();
+    /// let config = RpcProgramAccountsPageConfig {
+    ///     limit: Some(1000),
+    ///     ..RpcProgramAccountsPageConfig::default()
+    /// };
+    /// for ui_accounts in rpc_client.get_program_ui_accounts_pages(&alice.pubkey(), config) {
+    ///     let ui_accounts = ui_accounts?;
+    /// }
+    /// # Ok::<(), Error>(())
+    /// ```
+    pub fn get_program_ui_accounts_pages<'a>(
+        &'a self,
+        pubkey: &'a Pubkey,
+        config: RpcProgramAccountsPageConfig,
+    ) -> impl Iterator<Item = ClientResult<Vec<(Pubkey, UiAccount)>>> + 'a {
+        let mut next_config = Some(config);
+        std::iter::from_fn(move || {
+            let mut config = next_config.take()?;
+            let page = self.get_program_ui_accounts_page(pubkey, config.clone());
+            Some(page.map(|(accounts, cursor)| {
+                next_config = cursor.map(|cursor| {
+                    config.cursor = Some(cursor);
+                    config
+                });
+                accounts
+            }))
+        })
+    }
+
     /// Returns the stake minimum delegation, in lamports.
     ///
     /// # RPC Reference
@@ -4546,6 +4736,79 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_program_ui_accounts_paginated() {
+        let program_id = Pubkey::new_unique();
+        let keyed_accounts = (0..3)
+            .map(|_| RpcKeyedAccount {
+                account: UiAccount {
+                    lamports: 1_000_000,
+                    data: UiAccountData::Binary("".to_string(), UiAccountEncoding::Base64),
+                    owner: program_id.to_string(),
+                    executable: false,
+                    rent_epoch: 0,
+                    space: Some(0),
+                },
+                pubkey: Pubkey::new_unique().to_string(),
+            })
+            .collect::<Vec<_>>();
+        let expected_result = keyed_accounts
+            .iter()
+            .map(|keyed_account| {
+                (
+                    keyed_account.pubkey.parse::<Pubkey>().unwrap(),
+                    keyed_account.account.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        let page_mocks = || -> MocksMap {
+            [
+                (keyed_accounts[..2].to_vec(), Some("cursor".to_string())),
+                (keyed_accounts[2..].to_vec(), None),
+            ]
+            .into_iter()
+            .map(|(accounts, cursor)| {
+                (
+                    RpcRequest::GetProgramAccounts,
+                    serde_json::to_value(RpcProgramAccountsResponse::Page(Response {
+                        context: RpcResponseContext {
+                            slot: 1,
+                            api_version: None,
+                        },
+                        value: RpcKeyedAccountsPage { accounts, cursor },
+                    }))
+                    .unwrap(),
+                )
+            })
+            .collect()
+        };
+        let config = RpcProgramAccountsPageConfig {
+            limit: Some(2),
+            ..RpcProgramAccountsPageConfig::default()
+        };
+
+        // Test: single page
+        let rpc_client =
+            RpcClient::new_mock_with_mocks_map("mock_client".to_string(), page_mocks());
+        let (page, cursor) = rpc_client
+            .get_program_ui_accounts_page(&program_id, config.clone())
+            .unwrap();
+        assert_eq!(page, expected_result[..2]);
+        assert_eq!(cursor.as_deref(), Some("cursor"));
+
+        // Test: iterate over pages
+        let rpc_client =
+            RpcClient::new_mock_with_mocks_map("mock_client".to_string(), page_mocks());
+        let pages = rpc_client
+            .get_program_ui_accounts_pages(&program_id, config)
+            .collect::<ClientResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            pages,
+            vec![expected_result[..2].to_vec(), expected_result[2..].to_vec()]
+        );
+    }
+
     #[test_case(LegacyMessage {
         header: MessageHeader {
             num_required_signatures: 1,
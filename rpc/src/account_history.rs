@@ -0,0 +1,570 @@
+//! The `account_history` module records the versions of selected accounts at
+//! each rooted slot they are written in, so that RPC can answer for the state
+//! of those accounts at slots whose banks are no longer in `BankForks`.
+//!
+//! The history is fed by the accounts update notifier, see
+//! [`AccountHistoryNotifier`]. Versions are kept in memory until their slot is
+//! rooted or dropped, see `AccountHistoryService`, and the rooted versions are
+//! stored in the blockstore, which persists them across restarts and cleans
+//! them up with the rest of the ledger.
+
+use {
+    dashmap::DashSet,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_accounts_db::accounts_update_notifier_interface::{
+        AccountForGeyser, AccountPreState, AccountsUpdateNotifier, AccountsUpdateNotifierInterface,
+    },
+    solana_clock::Slot,
+    solana_ledger::{
+        blockstore::{Blockstore, BlockstoreError},
+        blockstore_meta::AccountHistoryVersion,
+    },
+    solana_pubkey::Pubkey,
+    solana_transaction::sanitized::SanitizedTransaction,
+    std::{
+        collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc, Mutex,
+        },
+    },
+    thiserror::Error,
+};
+
+/// Selects the accounts whose versions are recorded
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountHistoryConfig {
+    /// Record the accounts owned by these programs
+    pub owners: HashSet<Pubkey>,
+    /// Record these accounts, whatever their owner
+    pub pubkeys: HashSet<Pubkey>,
+    /// Record the selected accounts restored from the snapshot the node starts
+    /// from, so that the accounts that are not written afterwards are known
+    pub backfill: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum AccountHistoryError {
+    #[error("account {0} is not recorded in the account history")]
+    AccountNotRecorded(Pubkey),
+
+    #[error(
+        "slot {slot} is outside the account history, which covers rooted slots \
+         {first_available_slot} to {latest_root}"
+    )]
+    SlotNotAvailable {
+        slot: Slot,
+        first_available_slot: Slot,
+        latest_root: Slot,
+    },
+
+    #[error(transparent)]
+    Blockstore(#[from] BlockstoreError),
+}
+
+#[derive(Debug)]
+struct PendingVersion {
+    write_version: u64,
+    /// Whether the account was selected by the config when written in the
+    /// slot, rather than only closed or reassigned
+    selected: bool,
+    version: AccountHistoryVersion,
+}
+
+#[derive(Debug)]
+pub struct AccountHistory {
+    config: AccountHistoryConfig,
+    blockstore: Arc<Blockstore>,
+    /// The versions written in each slot that has not been rooted yet
+    unrooted_slots: Mutex<BTreeMap<Slot, HashMap<Pubkey, PendingVersion>>>,
+    /// The accounts that were selected when written or restored, and may have
+    /// a history, so that their writes are kept after they change owner
+    recorded_pubkeys: DashSet<Pubkey>,
+    /// Slots before this one were not observed, so their state is unknown
+    first_available_slot: AtomicU64,
+    latest_root: AtomicU64,
+}
+
+impl AccountHistory {
+    pub fn new(config: AccountHistoryConfig, blockstore: Arc<Blockstore>) -> Self {
+        Self {
+            config,
+            blockstore,
+            unrooted_slots: Mutex::default(),
+            recorded_pubkeys: DashSet::default(),
+            first_available_slot: AtomicU64::default(),
+            latest_root: AtomicU64::default(),
+        }
+    }
+
+    pub fn config(&self) -> &AccountHistoryConfig {
+        &self.config
+    }
+
+    /// The first slot of the history, after the slots the blockstore cleaned up
+    pub fn first_available_slot(&self) -> Slot {
+        self.first_available_slot
+            .load(Ordering::Relaxed)
+            .max(self.blockstore.lowest_cleanup_slot().saturating_add(1))
+    }
+
+    pub fn latest_root(&self) -> Slot {
+        self.latest_root.load(Ordering::Relaxed)
+    }
+
+    /// Set the root the node started from. The history recorded before a
+    /// previous restart is kept if it is continuous up to this root, otherwise
+    /// the history starts at this root.
+    pub fn set_start_root(&self, root: Slot) -> Result<(), BlockstoreError> {
+        for pubkey in self.blockstore.get_account_history_pubkeys()? {
+            self.recorded_pubkeys.insert(pubkey);
+        }
+        let first_available_slot = self
+            .blockstore
+            .get_account_history_first_available_slot(root)?
+            .unwrap_or(root);
+        self.blockstore
+            .write_account_history(root, first_available_slot, [])?;
+        self.first_available_slot
+            .store(first_available_slot, Ordering::Relaxed);
+        self.latest_root.fetch_max(root, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Whether the account is selected by the config. This does not depend on
+    /// the recorded history, see `record_account()` for the accounts that are
+    /// no longer selected.
+    fn is_selected(&self, pubkey: &Pubkey, owner: &Pubkey) -> bool {
+        self.config.pubkeys.contains(pubkey) || self.config.owners.contains(owner)
+    }
+
+    /// Record the version of an account written in `slot`, which is not
+    /// rooted yet
+    pub fn record_account(
+        &self,
+        slot: Slot,
+        pubkey: &Pubkey,
+        write_version: u64,
+        account: &AccountSharedData,
+    ) {
+        let selected = self.is_selected(pubkey, account.owner());
+        if selected {
+            if !self.recorded_pubkeys.contains(pubkey) {
+                self.recorded_pubkeys.insert(*pubkey);
+            }
+        } else if account.lamports() > 0 && !self.recorded_pubkeys.contains(pubkey) {
+            // Closing or reassigning an account can change its owner. The
+            // writes to the accounts that were selected before, and closed
+            // accounts, are kept until their slot is rooted, and then only
+            // stored for the accounts that have a history, see
+            // `write_rooted_slot()`. Other accounts never take the lock of
+            // the unrooted slots.
+            return;
+        }
+        let version = PendingVersion {
+            write_version,
+            selected,
+            version: account_history_version(account),
+        };
+        let mut unrooted_slots = self.unrooted_slots.lock().unwrap();
+        match unrooted_slots.entry(slot).or_default().entry(*pubkey) {
+            Entry::Vacant(entry) => {
+                entry.insert(version);
+            }
+            // Only keep the last write to the account within a slot
+            Entry::Occupied(mut entry) => {
+                if entry.get().write_version <= write_version {
+                    let selected = entry.get().selected || version.selected;
+                    entry.insert(PendingVersion {
+                        selected,
+                        ..version
+                    });
+                }
+            }
+        }
+    }
+
+    /// Record the version of an account restored from a snapshot, which is
+    /// rooted, if the history is backfilled
+    pub fn record_restored_account(&self, slot: Slot, account: &AccountForGeyser<'_>) {
+        if !self.config.backfill || !self.is_selected(account.pubkey, account.owner) {
+            return;
+        }
+        self.recorded_pubkeys.insert(*account.pubkey);
+        let version = AccountHistoryVersion {
+            lamports: account.lamports,
+            owner: *account.owner,
+            executable: account.executable,
+            rent_epoch: account.rent_epoch,
+            data: account.data.to_vec(),
+        };
+        if let Err(err) =
+            self.blockstore
+                .write_account_history_version(*account.pubkey, slot, &version)
+        {
+            // Without this version, the account is not recorded until it is
+            // next written, unless a continuous history already recorded it
+            warn!(
+                "Failed to record the restored account {} in the account history: {err}",
+                account.pubkey
+            );
+        }
+    }
+
+    /// Handle a new root: the versions written in unrooted slots up to `root`
+    /// are stored in the blockstore if their slot was rooted, and dropped
+    /// otherwise
+    pub fn set_root(&self, root: Slot) {
+        let mut resolved_slots = {
+            let mut unrooted_slots = self.unrooted_slots.lock().unwrap();
+            let newer_slots = unrooted_slots.split_off(&root.saturating_add(1));
+            std::mem::replace(&mut *unrooted_slots, newer_slots)
+        };
+        // Mark the root as recorded even if no account was written in it
+        resolved_slots.entry(root).or_default();
+
+        for (slot, versions) in resolved_slots {
+            if !self.blockstore.is_root(slot) {
+                continue;
+            }
+            let first_available_slot = self.first_available_slot.load(Ordering::Relaxed);
+            if let Err(err) = self.write_rooted_slot(slot, first_available_slot, versions) {
+                // The versions of this slot are lost, so the history restarts
+                // after it
+                warn!("Failed to record slot {slot} in the account history: {err}");
+                self.first_available_slot
+                    .fetch_max(slot.saturating_add(1), Ordering::Relaxed);
+            }
+        }
+        self.latest_root.fetch_max(root, Ordering::Relaxed);
+    }
+
+    fn write_rooted_slot(
+        &self,
+        slot: Slot,
+        first_available_slot: Slot,
+        versions: HashMap<Pubkey, PendingVersion>,
+    ) -> Result<(), BlockstoreError> {
+        let mut rooted_versions = Vec::with_capacity(versions.len());
+        for (pubkey, version) in versions {
+            if version.selected || self.blockstore.has_account_history(pubkey)? {
+                rooted_versions.push((pubkey, version.version));
+            }
+        }
+        self.blockstore
+            .write_account_history(slot, first_available_slot, rooted_versions)
+    }
+
+    /// Returns the state of the account at the rooted `slot`, or `None` if the
+    /// account was closed at that slot
+    pub fn get_account(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+    ) -> Result<Option<AccountSharedData>, AccountHistoryError> {
+        let first_available_slot = self.first_available_slot();
+        let latest_root = self.latest_root();
+        if slot < first_available_slot || slot > latest_root {
+            return Err(AccountHistoryError::SlotNotAvailable {
+                slot,
+                first_available_slot,
+                latest_root,
+            });
+        }
+
+        // An account that was not written since the first available slot, and
+        // was not restored from the snapshot, has an unknown state
+        let version = self
+            .blockstore
+            .get_account_history_version(*pubkey, first_available_slot, slot)?
+            .ok_or(AccountHistoryError::AccountNotRecorded(*pubkey))?;
+        Ok((version.lamports > 0).then(|| {
+            AccountSharedData::create(
+                version.lamports,
+                version.data,
+                version.owner,
+                version.executable,
+                version.rent_epoch,
+            )
+        }))
+    }
+}
+
+fn account_history_version(account: &AccountSharedData) -> AccountHistoryVersion {
+    AccountHistoryVersion {
+        lamports: account.lamports(),
+        owner: *account.owner(),
+        executable: account.executable(),
+        rent_epoch: account.rent_epoch(),
+        data: account.data().to_vec(),
+    }
+}
+
+/// Records the updates of the accounts selected by an [`AccountHistory`], and
+/// forwards all updates to the notifier of the geyser plugins, if any
+#[derive(Debug)]
+pub struct AccountHistoryNotifier {
+    account_history: Arc<AccountHistory>,
+    accounts_update_notifier: Option<AccountsUpdateNotifier>,
+}
+
+impl AccountHistoryNotifier {
+    pub fn new(
+        account_history: Arc<AccountHistory>,
+        accounts_update_notifier: Option<AccountsUpdateNotifier>,
+    ) -> Self {
+        Self {
+            account_history,
+            accounts_update_notifier,
+        }
+    }
+
+    fn snapshot_notifier(&self) -> Option<&AccountsUpdateNotifier> {
+        self.accounts_update_notifier
+            .as_ref()
+            .filter(|notifier| notifier.snapshot_notifications_enabled())
+    }
+}
+
+impl AccountsUpdateNotifierInterface for AccountHistoryNotifier {
+    fn snapshot_notifications_enabled(&self) -> bool {
+        self.account_history.config().backfill || self.snapshot_notifier().is_some()
+    }
+
+    fn account_pre_state_notifications_enabled(&self) -> bool {
+        self.accounts_update_notifier
+            .as_ref()
+            .is_some_and(|notifier| notifier.account_pre_state_notifications_enabled())
+    }
+
+    fn notify_account_update(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+    ) {
+        self.account_history
+            .record_account(slot, pubkey, write_version, account);
+        if let Some(notifier) = &self.accounts_update_notifier {
+            notifier.notify_account_update(slot, account, txn, pubkey, write_version);
+        }
+    }
+
+    fn notify_account_update_with_pre_state(
+        &self,
+        slot: Slot,
+        account: &AccountSharedData,
+        txn: &Option<&SanitizedTransaction>,
+        pubkey: &Pubkey,
+        write_version: u64,
+        pre_state: &AccountPreState,
+    ) {
+        self.account_history
+            .record_account(slot, pubkey, write_version, account);
+        if let Some(notifier) = &self.accounts_update_notifier {
+            notifier.notify_account_update_with_pre_state(
+                slot,
+                account,
+                txn,
+                pubkey,
+                write_version,
+                pre_state,
+            );
+        }
+    }
+
+    fn notify_account_restore_from_snapshot(
+        &self,
+        slot: Slot,
+        write_version: u64,
+        account: &AccountForGeyser<'_>,
+    ) {
+        self.account_history.record_restored_account(slot, account);
+        if let Some(notifier) = self.snapshot_notifier() {
+            notifier.notify_account_restore_from_snapshot(slot, write_version, account);
+        }
+    }
+
+    fn notify_end_of_restore_from_snapshot(&self) {
+        if let Some(notifier) = self.snapshot_notifier() {
+            notifier.notify_end_of_restore_from_snapshot();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_ledger::get_tmp_ledger_path_auto_delete, solana_sdk_ids::system_program,
+    };
+
+    fn account_with_lamports(lamports: u64, owner: &Pubkey) -> AccountSharedData {
+        AccountSharedData::new(lamports, 0, owner)
+    }
+
+    fn get_lamports(account_history: &AccountHistory, pubkey: &Pubkey, slot: Slot) -> Option<u64> {
+        account_history
+            .get_account(pubkey, slot)
+            .unwrap()
+            .map(|account| account.lamports())
+    }
+
+    #[test]
+    fn test_account_history_forks() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        blockstore.set_roots([1, 2, 3, 5].iter()).unwrap();
+
+        let owner = Pubkey::new_unique();
+        let pubkey = Pubkey::new_unique();
+        let other_pubkey = Pubkey::new_unique();
+        let closed_pubkey = Pubkey::new_unique();
+        let reassigned_pubkey = Pubkey::new_unique();
+        let new_owner = Pubkey::new_unique();
+        let config = AccountHistoryConfig {
+            owners: HashSet::from([owner]),
+            pubkeys: HashSet::default(),
+            backfill: true,
+        };
+        let account_history = AccountHistory::new(config.clone(), blockstore.clone());
+
+        account_history.record_restored_account(
+            2,
+            &AccountForGeyser {
+                pubkey: &pubkey,
+                lamports: 10,
+                owner: &owner,
+                executable: false,
+                rent_epoch: 0,
+                data: &[],
+            },
+        );
+        account_history.set_start_root(2).unwrap();
+
+        // Not owned by a selected program
+        account_history.record_account(
+            3,
+            &other_pubkey,
+            0,
+            &account_with_lamports(1, &Pubkey::new_unique()),
+        );
+        // Slots 3 and 5 are rooted, slot 4 is on a dropped fork
+        account_history.record_account(3, &pubkey, 0, &account_with_lamports(20, &owner));
+        account_history.record_account(3, &pubkey, 1, &account_with_lamports(30, &owner));
+        account_history.record_account(4, &pubkey, 0, &account_with_lamports(40, &owner));
+        // Closing the account changes its owner
+        account_history.record_account(
+            5,
+            &pubkey,
+            0,
+            &account_with_lamports(0, &system_program::id()),
+        );
+        // Closed, but never recorded
+        account_history.record_account(
+            5,
+            &closed_pubkey,
+            0,
+            &account_with_lamports(0, &system_program::id()),
+        );
+        // Reassigned to a program that is not selected
+        account_history.record_account(3, &reassigned_pubkey, 0, &account_with_lamports(1, &owner));
+        account_history.record_account(
+            5,
+            &reassigned_pubkey,
+            0,
+            &account_with_lamports(2, &new_owner),
+        );
+
+        assert!(matches!(
+            account_history.get_account(&pubkey, 3),
+            Err(AccountHistoryError::SlotNotAvailable {
+                slot: 3,
+                first_available_slot: 2,
+                latest_root: 2,
+            })
+        ));
+        assert_eq!(get_lamports(&account_history, &pubkey, 2), Some(10));
+
+        account_history.set_root(5);
+        assert_eq!(account_history.latest_root(), 5);
+        assert!(matches!(
+            account_history.get_account(&pubkey, 1),
+            Err(AccountHistoryError::SlotNotAvailable {
+                slot: 1,
+                first_available_slot: 2,
+                latest_root: 5,
+            })
+        ));
+        assert_eq!(get_lamports(&account_history, &pubkey, 3), Some(30));
+        assert_eq!(get_lamports(&account_history, &pubkey, 4), Some(30));
+        assert_eq!(get_lamports(&account_history, &pubkey, 5), None);
+        assert!(matches!(
+            account_history.get_account(&other_pubkey, 5),
+            Err(AccountHistoryError::AccountNotRecorded(not_recorded)) if not_recorded == other_pubkey
+        ));
+        assert!(!blockstore.has_account_history(closed_pubkey).unwrap());
+        assert_eq!(
+            get_lamports(&account_history, &reassigned_pubkey, 3),
+            Some(1)
+        );
+        let reassigned = account_history
+            .get_account(&reassigned_pubkey, 5)
+            .unwrap()
+            .unwrap();
+        assert_eq!(reassigned.lamports(), 2);
+        assert_eq!(reassigned.owner(), &new_owner);
+
+        // After a restart from a root the history is continuous up to, the
+        // history is kept
+        let account_history = AccountHistory::new(config.clone(), blockstore.clone());
+        account_history.set_start_root(3).unwrap();
+        assert_eq!(account_history.first_available_slot(), 2);
+        assert_eq!(get_lamports(&account_history, &pubkey, 3), Some(30));
+        // Writes to accounts recorded before the restart are kept after they
+        // change owner
+        blockstore.set_roots([6].iter()).unwrap();
+        account_history.record_account(
+            6,
+            &reassigned_pubkey,
+            0,
+            &account_with_lamports(3, &Pubkey::new_unique()),
+        );
+        account_history.set_root(6);
+        assert_eq!(
+            get_lamports(&account_history, &reassigned_pubkey, 6),
+            Some(3)
+        );
+
+        // After a restart from a later root, the history starts again
+        blockstore.set_roots([7].iter()).unwrap();
+        let account_history = AccountHistory::new(config, blockstore);
+        account_history.set_start_root(7).unwrap();
+        assert_eq!(account_history.first_available_slot(), 7);
+        assert!(matches!(
+            account_history.get_account(&pubkey, 7),
+            Err(AccountHistoryError::AccountNotRecorded(_))
+        ));
+    }
+
+    #[test]
+    fn test_account_history_notifier_snapshot_notifications() {
+        let ledger_path = get_tmp_ledger_path_auto_delete!();
+        let blockstore = Arc::new(Blockstore::open(ledger_path.path()).unwrap());
+        let notifier = |backfill| {
+            AccountHistoryNotifier::new(
+                Arc::new(AccountHistory::new(
+                    AccountHistoryConfig {
+                        backfill,
+                        ..AccountHistoryConfig::default()
+                    },
+                    blockstore.clone(),
+                )),
+                None,
+            )
+        };
+        assert!(!notifier(false).snapshot_notifications_enabled());
+        assert!(notifier(true).snapshot_notifications_enabled());
+    }
+}
@@ -0,0 +1,54 @@
+use {
+    crate::{
+        account_history::AccountHistory,
+        optimistically_confirmed_bank_tracker::{SlotNotification, SlotNotificationReceiver},
+    },
+    crossbeam_channel::RecvTimeoutError,
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread::{Builder, JoinHandle},
+        time::Duration,
+    },
+};
+
+pub const ACCOUNT_HISTORY_ROOT_TIMEOUT_MS: u64 = 100;
+
+/// Stores the versions recorded in the [`AccountHistory`] for rooted slots, and
+/// drops those of the slots that are not rooted, as new roots are notified
+pub struct AccountHistoryService;
+impl AccountHistoryService {
+    pub fn spawn(
+        slot_notification_receiver: SlotNotificationReceiver,
+        account_history: Arc<AccountHistory>,
+        exit: Arc<AtomicBool>,
+    ) -> JoinHandle<()> {
+        Builder::new()
+            .name("solRpcAcctHist".to_string())
+            .spawn(move || loop {
+                // received exit signal, shutdown the service
+                if exit.load(Ordering::Relaxed) {
+                    break;
+                }
+
+                match slot_notification_receiver
+                    .recv_timeout(Duration::from_millis(ACCOUNT_HISTORY_ROOT_TIMEOUT_MS))
+                {
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => {
+                        info!("AccountHistoryService channel disconnected, exiting.");
+                        break;
+                    }
+                    Ok(SlotNotification::Root((root, _parent))) => {
+                        // The blockstore roots are set before the new roots are
+                        // notified, and also cover the roots set before RPC started
+                        account_history.set_root(root);
+                    }
+                    Ok(_) => {}
+                }
+            })
+            .unwrap()
+    }
+}
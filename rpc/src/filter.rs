@@ -1,5 +1,6 @@
 use {
     solana_account::{AccountSharedData, ReadableAccount},
+    solana_pubkey::Pubkey,
     solana_rpc_client_api::filter::RpcFilterType,
     spl_generic_token::{token::GenericTokenAccount, token_2022::Account},
 };
@@ -9,5 +10,135 @@ pub fn filter_allows(filter: &RpcFilterType, account: &AccountSharedData) -> boo
         RpcFilterType::DataSize(size) => account.data().len() as u64 == *size,
         RpcFilterType::Memcmp(compare) => compare.bytes_match(account.data()),
         RpcFilterType::TokenAccountState => Account::valid_account_data(account.data()),
+        RpcFilterType::DataSizeRange(range) => range.contains(account.data().len() as u64),
+        RpcFilterType::LamportsRange(range) => range.contains(account.lamports()),
+        RpcFilterType::Owner(owner) => owner
+            .parse::<Pubkey>()
+            .is_ok_and(|owner| account.owner() == &owner),
+        RpcFilterType::U32Gte(compare) => compare
+            .read_u32(account.data())
+            .is_some_and(|value| u64::from(value) >= compare.value()),
+        RpcFilterType::U32Lte(compare) => compare
+            .read_u32(account.data())
+            .is_some_and(|value| u64::from(value) <= compare.value()),
+        RpcFilterType::U64Gte(compare) => compare
+            .read_u64(account.data())
+            .is_some_and(|value| value >= compare.value()),
+        RpcFilterType::U64Lte(compare) => compare
+            .read_u64(account.data())
+            .is_some_and(|value| value <= compare.value()),
+        RpcFilterType::And(filters) => filters.iter().all(|filter| filter_allows(filter, account)),
+        RpcFilterType::Or(filters) => filters.iter().any(|filter| filter_allows(filter, account)),
+        RpcFilterType::Not(filter) => !filter_allows(filter, account),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_rpc_client_api::filter::{IntegerCompare, Memcmp, RpcRange},
+    };
+
+    #[test]
+    fn test_filter_allows() {
+        let mut data = vec![0; 16];
+        data[0..4].copy_from_slice(&7u32.to_le_bytes());
+        data[8..16].copy_from_slice(&1_000u64.to_le_bytes());
+        let owner = Pubkey::new_unique();
+        let account = AccountSharedData::create(42, data, owner, false, 0);
+
+        // Ranges are inclusive, and a missing bound is unbounded
+        let range = |min, max| RpcRange::new(min, max);
+        assert!(filter_allows(
+            &RpcFilterType::DataSizeRange(range(Some(16), Some(16))),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::DataSizeRange(range(Some(17), None)),
+            &account
+        ));
+        assert!(filter_allows(
+            &RpcFilterType::LamportsRange(range(None, Some(42))),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::LamportsRange(range(Some(43), None)),
+            &account
+        ));
+
+        // Owners
+        assert!(filter_allows(
+            &RpcFilterType::Owner(owner.to_string()),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::Owner(Pubkey::new_unique().to_string()),
+            &account
+        ));
+
+        // Integer comparisons
+        assert!(filter_allows(
+            &RpcFilterType::U32Gte(IntegerCompare::new(0, 7)),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::U32Gte(IntegerCompare::new(0, 8)),
+            &account
+        ));
+        assert!(filter_allows(
+            &RpcFilterType::U32Lte(IntegerCompare::new(0, 7)),
+            &account
+        ));
+        assert!(filter_allows(
+            &RpcFilterType::U64Gte(IntegerCompare::new(8, 999)),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::U64Lte(IntegerCompare::new(8, 999)),
+            &account
+        ));
+
+        // Integers overrunning the data never match
+        assert!(!filter_allows(
+            &RpcFilterType::U64Gte(IntegerCompare::new(9, 0)),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::U64Lte(IntegerCompare::new(9, u64::MAX)),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::U32Lte(IntegerCompare::new(usize::MAX, u64::MAX)),
+            &account
+        ));
+
+        // Combinators
+        let matching = RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![7]));
+        let failing = RpcFilterType::DataSize(0);
+        assert!(filter_allows(
+            &RpcFilterType::And(vec![matching.clone(), matching.clone()]),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::And(vec![matching.clone(), failing.clone()]),
+            &account
+        ));
+        assert!(filter_allows(
+            &RpcFilterType::Or(vec![failing.clone(), matching.clone()]),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::Or(vec![failing.clone(), failing.clone()]),
+            &account
+        ));
+        assert!(filter_allows(
+            &RpcFilterType::Not(Box::new(failing)),
+            &account
+        ));
+        assert!(!filter_allows(
+            &RpcFilterType::Not(Box::new(matching)),
+            &account
+        ));
     }
 }
@@ -1,5 +1,7 @@
 #![cfg(feature = "agave-unstable-api")]
 #![allow(clippy::arithmetic_side_effects)]
+pub mod account_history;
+pub mod account_history_service;
 mod cluster_tpu_info;
 pub mod filter;
 pub mod max_slots;
@@ -3,9 +3,16 @@
 use solana_runtime::installed_scheduler_pool::BankWithScheduler;
 use {
     crate::{
-        filter::filter_allows, max_slots::MaxSlots,
+        account_history::{AccountHistory, AccountHistoryConfig, AccountHistoryError},
+        filter::filter_allows,
+        max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
-        parsed_token_accounts::*, rpc_cache::LargestAccountsCache, rpc_health::*,
+        parsed_token_accounts::*,
+        rpc_cache::{
+            LargestAccountsCache, ProgramAccountsCursorCache, MAX_PROGRAM_ACCOUNTS_CURSOR_BANKS,
+            PROGRAM_ACCOUNTS_CURSOR_TTL,
+        },
+        rpc_health::*,
     },
     agave_snapshots::{paths as snapshot_paths, snapshot_config::SnapshotConfig},
     base64::{prelude::BASE64_STANDARD, Engine},
@@ -28,6 +35,8 @@ use {
         accounts::AccountAddressFilter,
         accounts_index::{
             AccountIndex, AccountSecondaryIndexes, IndexKey, ScanConfig, ScanOrder, ScanResult,
+            SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET, SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
+            STAKE_ACCOUNT_STAKER_OFFSET, STAKE_ACCOUNT_WITHDRAWER_OFFSET,
         },
     },
     solana_client::connection_cache::Protocol,
@@ -39,7 +48,7 @@ use {
     solana_epoch_schedule::EpochSchedule,
     solana_faucet::faucet::request_airdrop_transaction,
     solana_gossip::cluster_info::ClusterInfo,
-    solana_hash::Hash,
+    solana_hash::{Hash, HASH_BYTES},
     solana_keypair::Keypair,
     solana_ledger::{
         blockstore::{Blockstore, BlockstoreError, SignatureInfosForAddress},
@@ -58,9 +67,10 @@ use {
         request::{
             TokenAccountsFilter, DELINQUENT_VALIDATOR_SLOT_DISTANCE,
             MAX_GET_CONFIRMED_BLOCKS_RANGE, MAX_GET_CONFIRMED_SIGNATURES_FOR_ADDRESS2_LIMIT,
-            MAX_GET_PROGRAM_ACCOUNT_FILTERS, MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS,
-            MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
-            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, NUM_LARGEST_ACCOUNTS,
+            MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT, MAX_GET_PROGRAM_ACCOUNT_FILTERS,
+            MAX_GET_SIGNATURE_STATUSES_QUERY_ITEMS, MAX_GET_SLOT_LEADERS, MAX_MULTIPLE_ACCOUNTS,
+            MAX_RPC_VOTE_ACCOUNT_INFO_EPOCH_CREDITS_HISTORY, MAX_SIMULATE_BUNDLE_TRANSACTIONS,
+            NUM_LARGEST_ACCOUNTS,
         },
         response::{Response as RpcResponse, *},
     },
@@ -74,9 +84,11 @@ use {
     },
     solana_runtime_transaction::runtime_transaction::RuntimeTransaction,
     solana_send_transaction_service::send_transaction_service::TransactionInfo,
+    solana_sha256_hasher::hashv,
     solana_signature::Signature,
     solana_signer::Signer,
     solana_storage_bigtable::Error as StorageError,
+    solana_svm::account_overrides::AccountOverrides,
     solana_transaction::{
         sanitized::{MessageHash, SanitizedTransaction, MAX_TX_ACCOUNT_LOCKS},
         versioned::VersionedTransaction,
@@ -176,6 +188,8 @@ pub struct JsonRpcConfig {
     pub max_request_body_size: Option<usize>,
     /// Disable the health check, used for tests and TestValidator
     pub disable_health_check: bool,
+    /// Record the history of the selected accounts for `getAccountInfoAtSlot`
+    pub account_history: Option<AccountHistoryConfig>,
 }
 
 impl Default for JsonRpcConfig {
@@ -196,6 +210,7 @@ impl Default for JsonRpcConfig {
             rpc_scan_and_fix_roots: Default::default(),
             max_request_body_size: Option::default(),
             disable_health_check: Default::default(),
+            account_history: Option::default(),
         }
     }
 }
@@ -217,6 +232,9 @@ pub struct RpcBigtableConfig {
     pub bigtable_app_profile_id: String,
     pub timeout: Option<Duration>,
     pub max_message_size: usize,
+    /// Stores the ledger in an object store instead of BigTable, see
+    /// `solana_storage_bigtable::LedgerStorageConfig::storage_url`
+    pub storage_url: Option<String>,
 }
 
 impl Default for RpcBigtableConfig {
@@ -229,6 +247,7 @@ impl Default for RpcBigtableConfig {
             bigtable_app_profile_id,
             timeout: None,
             max_message_size: solana_storage_bigtable::DEFAULT_MAX_MESSAGE_SIZE,
+            storage_url: None,
         }
     }
 }
@@ -249,10 +268,12 @@ pub struct JsonRpcRequestProcessor {
     bigtable_ledger_storage: Option<solana_storage_bigtable::LedgerStorage>,
     optimistically_confirmed_bank: Arc<RwLock<OptimisticallyConfirmedBank>>,
     largest_accounts_cache: Arc<RwLock<LargestAccountsCache>>,
+    program_accounts_cursor_cache: Arc<ProgramAccountsCursorCache>,
     max_slots: Arc<MaxSlots>,
     leader_schedule_cache: Arc<LeaderScheduleCache>,
     max_complete_transaction_status_slot: Arc<AtomicU64>,
     prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+    account_history: Option<Arc<AccountHistory>>,
     runtime: Arc<Runtime>,
 }
 impl Metadata for JsonRpcRequestProcessor {}
@@ -309,6 +330,27 @@ impl JsonRpcRequestProcessor {
         program_id: &Pubkey,
         filters: Vec<RpcFilterType>,
         sort_results: bool,
+    ) -> ScanResult<Vec<KeyedAccountSharedData>> {
+        self.get_filtered_indexed_accounts_page(
+            bank,
+            index_key,
+            program_id,
+            filters,
+            sort_results,
+            None,
+        )
+        .await
+    }
+
+    /// If `page` is set, only the accounts of that page are loaded, in pubkey order.
+    async fn get_filtered_indexed_accounts_page(
+        &self,
+        bank: &Arc<Bank>,
+        index_key: &IndexKey,
+        program_id: &Pubkey,
+        filters: Vec<RpcFilterType>,
+        sort_results: bool,
+        page: Option<ProgramAccountsPage>,
     ) -> ScanResult<Vec<KeyedAccountSharedData>> {
         let scan_order = if sort_results {
             ScanOrder::Sorted
@@ -320,22 +362,31 @@ impl JsonRpcRequestProcessor {
         let program_id = program_id.to_owned();
         self.runtime
             .spawn_blocking(move || {
-                bank.get_filtered_indexed_accounts(
-                    &index_key,
-                    |account| {
-                        // The program-id account index checks for Account owner on inclusion.
-                        // However, due to the current AccountsDb implementation, an account may
-                        // remain in storage as a zero-lamport AccountSharedData::Default() after
-                        // being wiped and reinitialized in later updates. We include the redundant
-                        // filters here to avoid returning these accounts.
-                        account.owner().eq(&program_id)
-                            && filters
-                                .iter()
-                                .all(|filter_type| filter_allows(filter_type, account))
-                    },
-                    &ScanConfig::new(scan_order),
-                    bank.byte_limit_for_scans(),
-                )
+                // The program-id account index checks for Account owner on inclusion.
+                // However, due to the current AccountsDb implementation, an account may
+                // remain in storage as a zero-lamport AccountSharedData::Default() after
+                // being wiped and reinitialized in later updates. We include the redundant
+                // filters here to avoid returning these accounts.
+                let filter_closure = |account: &AccountSharedData| {
+                    account.owner().eq(&program_id)
+                        && filters
+                            .iter()
+                            .all(|filter_type| filter_allows(filter_type, account))
+                };
+                match page {
+                    Some(page) => bank.get_filtered_indexed_accounts_after(
+                        &index_key,
+                        filter_closure,
+                        page.after.as_ref(),
+                        page.limit,
+                    ),
+                    None => bank.get_filtered_indexed_accounts(
+                        &index_key,
+                        filter_closure,
+                        &ScanConfig::new(scan_order),
+                        bank.byte_limit_for_scans(),
+                    ),
+                }
             })
             .await
             .expect("Failed to spawn blocking task")
@@ -416,6 +467,7 @@ impl JsonRpcRequestProcessor {
         leader_schedule_cache: Arc<LeaderScheduleCache>,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+        account_history: Option<Arc<AccountHistory>>,
         runtime: Arc<Runtime>,
     ) -> (Self, Receiver<TransactionInfo>) {
         let (transaction_sender, transaction_receiver) = unbounded();
@@ -434,10 +486,15 @@ impl JsonRpcRequestProcessor {
                 bigtable_ledger_storage,
                 optimistically_confirmed_bank,
                 largest_accounts_cache,
+                program_accounts_cursor_cache: Arc::new(ProgramAccountsCursorCache::new(
+                    PROGRAM_ACCOUNTS_CURSOR_TTL,
+                    MAX_PROGRAM_ACCOUNTS_CURSOR_BANKS,
+                )),
                 max_slots,
                 leader_schedule_cache,
                 max_complete_transaction_status_slot,
                 prioritization_fee_cache,
+                account_history,
                 runtime,
             },
             transaction_receiver,
@@ -517,10 +574,15 @@ impl JsonRpcRequestProcessor {
             bigtable_ledger_storage: None,
             optimistically_confirmed_bank,
             largest_accounts_cache: Arc::new(RwLock::new(LargestAccountsCache::new(30))),
+            program_accounts_cursor_cache: Arc::new(ProgramAccountsCursorCache::new(
+                PROGRAM_ACCOUNTS_CURSOR_TTL,
+                MAX_PROGRAM_ACCOUNTS_CURSOR_BANKS,
+            )),
             max_slots: Arc::new(MaxSlots::default()),
             leader_schedule_cache,
             max_complete_transaction_status_slot: Arc::new(AtomicU64::default()),
             prioritization_fee_cache: Some(Arc::new(PrioritizationFeeCache::default())),
+            account_history: None,
             runtime,
         }
     }
@@ -585,6 +647,54 @@ impl JsonRpcRequestProcessor {
         Ok(new_response(&bank, accounts))
     }
 
+    pub fn get_account_info_at_slot(
+        &self,
+        pubkey: &Pubkey,
+        slot: Slot,
+        config: Option<RpcAccountInfoAtSlotConfig>,
+    ) -> Result<RpcResponse<Option<UiAccount>>> {
+        let Some(account_history) = &self.account_history else {
+            return Err(RpcCustomError::AccountHistoryNotAvailable { pubkey: None }.into());
+        };
+        let RpcAccountInfoAtSlotConfig {
+            encoding,
+            data_slice,
+        } = config.unwrap_or_default();
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Base64);
+
+        let account = account_history
+            .get_account(pubkey, slot)
+            .map_err(|err| match err {
+                AccountHistoryError::AccountNotRecorded(pubkey) => {
+                    RpcCustomError::AccountHistoryNotAvailable {
+                        pubkey: Some(pubkey.to_string()),
+                    }
+                    .into()
+                }
+                AccountHistoryError::SlotNotAvailable {
+                    slot,
+                    first_available_slot,
+                    latest_root,
+                } => RpcCustomError::AccountHistorySlotNotAvailable {
+                    slot,
+                    first_available_slot,
+                    latest_root,
+                }
+                .into(),
+                AccountHistoryError::Blockstore(err) => {
+                    warn!("get_account_info_at_slot failed to read the account history: {err}");
+                    Error::internal_error()
+                }
+            })?;
+        let value = account
+            .map(|account| encode_account(&account, pubkey, encoding, data_slice))
+            .transpose()?;
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value,
+        })
+    }
+
     pub fn get_minimum_balance_for_rent_exemption(
         &self,
         data_len: usize,
@@ -601,19 +711,64 @@ impl JsonRpcRequestProcessor {
         mut filters: Vec<RpcFilterType>,
         with_context: bool,
         sort_results: bool,
-    ) -> Result<OptionalContext<Vec<RpcKeyedAccount>>> {
+        limit: Option<usize>,
+        cursor: Option<String>,
+    ) -> Result<RpcProgramAccountsResponse> {
         let RpcAccountInfoConfig {
             encoding,
             data_slice: data_slice_config,
             commitment,
             min_context_slot,
         } = config.unwrap_or_default();
-        let bank = self.get_bank_with_config(RpcContextConfig {
-            commitment,
-            min_context_slot,
-        })?;
-        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let cursor = cursor
+            .as_deref()
+            .map(ProgramAccountsCursor::decode)
+            .transpose()?;
+        let page_limit = match limit {
+            Some(limit) if limit == 0 || limit > MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT => {
+                return Err(Error::invalid_params(format!(
+                    "Invalid param: limit must be between 1 and \
+                     {MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT}"
+                )));
+            }
+            Some(limit) => Some(limit),
+            None => cursor.map(|_| MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT),
+        };
         optimize_filters(&mut filters);
+        let query_hash = ProgramAccountsCursor::query_hash(&program_id, &filters);
+        if cursor.is_some_and(|cursor| cursor.query_hash != query_hash) {
+            return Err(Error::invalid_params(
+                "Invalid param: cursor was issued for a different program or filters",
+            ));
+        }
+        // Later pages are read from the bank of the first page. The bank is kept in the cursor
+        // cache, as BankForks prunes it once a newer root is set.
+        let bank = match cursor {
+            Some(cursor) => {
+                let bank = self
+                    .program_accounts_cursor_cache
+                    .get(cursor.slot)
+                    .or_else(|| self.bank_forks.read().unwrap().get(cursor.slot))
+                    .ok_or(RpcCustomError::ProgramAccountsCursorExpired { slot: cursor.slot })?;
+                if min_context_slot.is_some_and(|min_context_slot| bank.slot() < min_context_slot) {
+                    return Err(RpcCustomError::MinContextSlotNotReached {
+                        context_slot: bank.slot(),
+                    }
+                    .into());
+                }
+                bank
+            }
+            None => self.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?,
+        };
+        let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
+        let page = page_limit.map(|limit| ProgramAccountsPage {
+            after: cursor.map(|cursor| cursor.last_pubkey),
+            // One more account than requested tells whether another page follows
+            limit: limit.saturating_add(1),
+        });
         let keyed_accounts = {
             if let Some(owner) = get_spl_token_owner_filter(&program_id, &filters)? {
                 self.get_filtered_spl_token_accounts_by_owner(
@@ -622,6 +777,7 @@ impl JsonRpcRequestProcessor {
                     owner,
                     filters,
                     sort_results,
+                    page,
                 )
                 .await?
             } else if let Some(mint) = get_spl_token_mint_filter(&program_id, &filters)? {
@@ -631,6 +787,17 @@ impl JsonRpcRequestProcessor {
                     mint,
                     filters,
                     sort_results,
+                    page,
+                )
+                .await?
+            } else if let Some(delegate) = get_spl_token_delegate_filter(&program_id, &filters)? {
+                self.get_filtered_spl_token_accounts_by_delegate(
+                    Arc::clone(&bank),
+                    program_id,
+                    delegate,
+                    filters,
+                    sort_results,
+                    page,
                 )
                 .await?
             } else {
@@ -639,10 +806,30 @@ impl JsonRpcRequestProcessor {
                     program_id,
                     filters,
                     sort_results,
+                    page,
                 )
                 .await?
             }
         };
+        let (keyed_accounts, next_cursor) = match page_limit {
+            Some(limit) => {
+                let (keyed_accounts, last_pubkey) = paginate_keyed_accounts(
+                    keyed_accounts,
+                    limit,
+                    cursor.map(|cursor| cursor.last_pubkey),
+                );
+                let next_cursor = last_pubkey.map(|last_pubkey| {
+                    self.program_accounts_cursor_cache.insert(Arc::clone(&bank));
+                    ProgramAccountsCursor {
+                        slot: bank.slot(),
+                        query_hash,
+                        last_pubkey,
+                    }
+                });
+                (keyed_accounts, next_cursor)
+            }
+            None => (keyed_accounts, None),
+        };
         let accounts = if is_known_spl_token_id(&program_id)
             && encoding == UiAccountEncoding::JsonParsed
         {
@@ -658,10 +845,19 @@ impl JsonRpcRequestProcessor {
                 })
                 .collect::<Result<Vec<_>>>()?
         };
-        Ok(match with_context {
+        if page_limit.is_some() {
+            return Ok(RpcProgramAccountsResponse::Page(new_response(
+                &bank,
+                RpcKeyedAccountsPage {
+                    accounts,
+                    cursor: next_cursor.as_ref().map(ProgramAccountsCursor::encode),
+                },
+            )));
+        }
+        Ok(RpcProgramAccountsResponse::Accounts(match with_context {
             true => OptionalContext::Context(new_response(&bank, accounts)),
             false => OptionalContext::NoContext(accounts),
-        })
+        }))
     }
 
     fn filter_map_rewards<'a, F>(
@@ -1254,7 +1450,9 @@ impl JsonRpcRequestProcessor {
         if let Err(BlockstoreError::SlotCleanedUp) = result {
             return Err(err);
         }
-        if slot < first_available_block {
+        // A slot below the first available block can still be read from the
+        // blockstore archive
+        if result.is_err() && slot < first_available_block {
             return Err(err);
         }
         Ok(())
@@ -1468,12 +1666,14 @@ impl JsonRpcRequestProcessor {
         }
 
         // Finalized blocks
-        let mut blocks: Vec<_> = self
+        let mut blocks = self
             .blockstore
-            .rooted_slot_iterator(max(start_slot, lowest_blockstore_slot))
-            .map_err(|_| Error::internal_error())?
-            .filter(|&slot| slot <= end_slot && slot <= highest_super_majority_root)
-            .collect();
+            .get_rooted_slots(
+                max(start_slot, lowest_blockstore_slot),
+                min(end_slot, highest_super_majority_root),
+                usize::MAX,
+            )
+            .map_err(|_| Error::internal_error())?;
         let last_element = blocks
             .last()
             .cloned()
@@ -1547,9 +1747,9 @@ impl JsonRpcRequestProcessor {
         // Finalized blocks
         let mut blocks: Vec<_> = self
             .blockstore
-            .rooted_slot_iterator(max(start_slot, lowest_blockstore_slot))
+            .get_rooted_slots(max(start_slot, lowest_blockstore_slot), Slot::MAX, limit)
             .map_err(|_| Error::internal_error())?
-            .take(limit)
+            .into_iter()
             .filter(|&slot| slot <= highest_super_majority_root)
             .collect();
 
@@ -2041,6 +2241,7 @@ impl JsonRpcRequestProcessor {
                 mint,
                 vec![],
                 true,
+                None,
             )
             .await?
         {
@@ -2111,6 +2312,7 @@ impl JsonRpcRequestProcessor {
                 owner,
                 filters,
                 sort_results,
+                None,
             )
             .await?;
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
@@ -2149,35 +2351,66 @@ impl JsonRpcRequestProcessor {
         let encoding = encoding.unwrap_or(UiAccountEncoding::Binary);
         let (token_program_id, mint) = get_token_program_id_and_mint(&bank, token_account_filter)?;
 
-        let mut filters = vec![
-            // Filter on Delegate is_some()
-            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
-                72,
-                bincode::serialize(&1u32).unwrap(),
-            )),
-            // Filter on Delegate address
-            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(76, delegate.to_bytes().into())),
-        ];
-        // Optional filter on Mint address, uses mint account index for scan
-        let keyed_accounts = if let Some(mint) = mint {
-            self.get_filtered_spl_token_accounts_by_mint(
+        let keyed_accounts = if self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::SplTokenDelegate)
+        {
+            // Optional filter on Mint address, the scan uses the delegate account index
+            let filters = mint
+                .map(|mint| {
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                        SPL_TOKEN_ACCOUNT_MINT_OFFSET,
+                        mint.to_bytes().into(),
+                    ))
+                })
+                .into_iter()
+                .collect();
+            self.get_filtered_spl_token_accounts_by_delegate(
                 Arc::clone(&bank),
                 token_program_id,
-                mint,
+                delegate,
                 filters,
                 sort_results,
+                None,
             )
             .await?
         } else {
-            // Filter on Token Account state
-            filters.push(RpcFilterType::TokenAccountState);
-            self.get_filtered_program_accounts(
-                Arc::clone(&bank),
-                token_program_id,
-                filters,
-                sort_results,
-            )
-            .await?
+            let mut filters = vec![
+                // Filter on Delegate is_some()
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
+                    bincode::serialize(&1u32).unwrap(),
+                )),
+                // Filter on Delegate address
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                    SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET,
+                    delegate.to_bytes().into(),
+                )),
+            ];
+            // Optional filter on Mint address, uses mint account index for scan
+            if let Some(mint) = mint {
+                self.get_filtered_spl_token_accounts_by_mint(
+                    Arc::clone(&bank),
+                    token_program_id,
+                    mint,
+                    filters,
+                    sort_results,
+                    None,
+                )
+                .await?
+            } else {
+                // Filter on Token Account state
+                filters.push(RpcFilterType::TokenAccountState);
+                self.get_filtered_program_accounts(
+                    Arc::clone(&bank),
+                    token_program_id,
+                    filters,
+                    sort_results,
+                    None,
+                )
+                .await?
+            }
         };
         let accounts = if encoding == UiAccountEncoding::JsonParsed {
             get_parsed_token_accounts(bank.clone(), keyed_accounts.into_iter()).collect()
@@ -2196,14 +2429,41 @@ impl JsonRpcRequestProcessor {
     }
 
     /// Use a set of filters to get an iterator of keyed program accounts from a bank
+    ///
+    /// If `page` is set, only the accounts of that page are returned, in pubkey order.
     async fn get_filtered_program_accounts(
         &self,
         bank: Arc<Bank>,
         program_id: Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
+        page: Option<ProgramAccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         optimize_filters(&mut filters);
+        if let Some(authority) = get_stake_authority_filter(&program_id, &filters) {
+            // The stake authority index is narrower than the program-id index, so prefer it if
+            // the key was indexed. Otherwise fall through to the other strategies.
+            if self
+                .config
+                .account_indexes
+                .contains(&AccountIndex::StakeAuthority)
+                && self.config.account_indexes.include_key(&authority)
+            {
+                return self
+                    .get_filtered_indexed_accounts_page(
+                        &bank,
+                        &IndexKey::StakeAuthority(authority),
+                        &program_id,
+                        filters,
+                        sort_results,
+                        page,
+                    )
+                    .await
+                    .map_err(|e| RpcCustomError::ScanError {
+                        message: e.to_string(),
+                    });
+            }
+        }
         if self
             .config
             .account_indexes
@@ -2214,12 +2474,13 @@ impl JsonRpcRequestProcessor {
                     index_key: program_id.to_string(),
                 });
             }
-            self.get_filtered_indexed_accounts(
+            self.get_filtered_indexed_accounts_page(
                 &bank,
                 &IndexKey::ProgramId(program_id),
                 &program_id,
                 filters,
                 sort_results,
+                page,
             )
             .await
             .map_err(|e| RpcCustomError::ScanError {
@@ -2234,15 +2495,26 @@ impl JsonRpcRequestProcessor {
             };
             self.runtime
                 .spawn_blocking(move || {
-                    bank.get_filtered_program_accounts(
-                        &program_id,
-                        |account: &AccountSharedData| {
-                            filters
-                                .iter()
-                                .all(|filter_type| filter_allows(filter_type, account))
-                        },
-                        &ScanConfig::new(scan_order),
-                    )
+                    let filter_closure = |account: &AccountSharedData| {
+                        filters
+                            .iter()
+                            .all(|filter_type| filter_allows(filter_type, account))
+                    };
+                    match page {
+                        // Pages are read in pubkey order, so the scan can start after the last
+                        // account of the previous page and stop once the page is full
+                        Some(page) => bank.get_filtered_program_accounts_after(
+                            &program_id,
+                            filter_closure,
+                            page.after.as_ref(),
+                            page.limit,
+                        ),
+                        None => bank.get_filtered_program_accounts(
+                            &program_id,
+                            filter_closure,
+                            &ScanConfig::new(scan_order),
+                        ),
+                    }
                     .map_err(|e| RpcCustomError::ScanError {
                         message: e.to_string(),
                     })
@@ -2260,6 +2532,7 @@ impl JsonRpcRequestProcessor {
         owner_key: Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
+        page: Option<ProgramAccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-owner accounts index checks for Token Account state and Owner address on
         // inclusion. However, due to the current AccountsDb implementation, an account may remain
@@ -2284,19 +2557,20 @@ impl JsonRpcRequestProcessor {
                     index_key: owner_key.to_string(),
                 });
             }
-            self.get_filtered_indexed_accounts(
+            self.get_filtered_indexed_accounts_page(
                 &bank,
                 &IndexKey::SplTokenOwner(owner_key),
                 &program_id,
                 filters,
                 sort_results,
+                page,
             )
             .await
             .map_err(|e| RpcCustomError::ScanError {
                 message: e.to_string(),
             })
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters, sort_results)
+            self.get_filtered_program_accounts(bank, program_id, filters, sort_results, page)
                 .await
         }
     }
@@ -2309,6 +2583,7 @@ impl JsonRpcRequestProcessor {
         mint_key: Pubkey,
         mut filters: Vec<RpcFilterType>,
         sort_results: bool,
+        page: Option<ProgramAccountsPage>,
     ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
         // The by-mint accounts index checks for Token Account state and Mint address on inclusion.
         // However, due to the current AccountsDb implementation, an account may remain in storage
@@ -2332,19 +2607,77 @@ impl JsonRpcRequestProcessor {
                     index_key: mint_key.to_string(),
                 });
             }
-            self.get_filtered_indexed_accounts(
+            self.get_filtered_indexed_accounts_page(
                 &bank,
                 &IndexKey::SplTokenMint(mint_key),
                 &program_id,
                 filters,
                 sort_results,
+                page,
+            )
+            .await
+            .map_err(|e| RpcCustomError::ScanError {
+                message: e.to_string(),
+            })
+        } else {
+            self.get_filtered_program_accounts(bank, program_id, filters, sort_results, page)
+                .await
+        }
+    }
+
+    /// Get an iterator of spl-token accounts by delegate address
+    async fn get_filtered_spl_token_accounts_by_delegate(
+        &self,
+        bank: Arc<Bank>,
+        program_id: Pubkey,
+        delegate_key: Pubkey,
+        mut filters: Vec<RpcFilterType>,
+        sort_results: bool,
+        page: Option<ProgramAccountsPage>,
+    ) -> RpcCustomResult<Vec<(Pubkey, AccountSharedData)>> {
+        // The by-delegate accounts index checks for Token Account state and Delegate address on
+        // inclusion. However, the index is not updated when a delegate is revoked, and an account
+        // may remain in storage as a zero-lamport AccountSharedData::Default() after being wiped
+        // and reinitialized in later updates. We include the redundant filters here to avoid
+        // returning these accounts.
+        //
+        // Filter on Token Account state
+        filters.push(RpcFilterType::TokenAccountState);
+        // Filter on Delegate is_some()
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET,
+            bincode::serialize(&1u32).unwrap(),
+        )));
+        // Filter on Delegate address
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+            SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET,
+            delegate_key.to_bytes().into(),
+        )));
+
+        if self
+            .config
+            .account_indexes
+            .contains(&AccountIndex::SplTokenDelegate)
+        {
+            if !self.config.account_indexes.include_key(&delegate_key) {
+                return Err(RpcCustomError::KeyExcludedFromSecondaryIndex {
+                    index_key: delegate_key.to_string(),
+                });
+            }
+            self.get_filtered_indexed_accounts_page(
+                &bank,
+                &IndexKey::SplTokenDelegate(delegate_key),
+                &program_id,
+                filters,
+                sort_results,
+                page,
             )
             .await
             .map_err(|e| RpcCustomError::ScanError {
                 message: e.to_string(),
             })
         } else {
-            self.get_filtered_program_accounts(bank, program_id, filters, sort_results)
+            self.get_filtered_program_accounts(bank, program_id, filters, sort_results, page)
                 .await
         }
     }
@@ -2403,15 +2736,99 @@ impl JsonRpcRequestProcessor {
     }
 }
 
+/// Accounts requested for a page of `getProgramAccounts` results: the first `limit` accounts in
+/// pubkey order following `after`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProgramAccountsPage {
+    after: Option<Pubkey>,
+    limit: usize,
+}
+
+/// Position of a paginated `getProgramAccounts` request: the slot of the bank that was read, a hash
+/// of the program and filters the cursor is valid for, and the last account returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ProgramAccountsCursor {
+    slot: Slot,
+    query_hash: Hash,
+    last_pubkey: Pubkey,
+}
+
+impl ProgramAccountsCursor {
+    const VERSION: u8 = 1;
+
+    /// Hashes the program and the optimized filters of a request
+    fn query_hash(program_id: &Pubkey, filters: &[RpcFilterType]) -> Hash {
+        let filters = serde_json::to_vec(filters).expect("filters serialize to json");
+        hashv(&[program_id.as_ref(), &filters])
+    }
+
+    fn encode(&self) -> String {
+        let mut bytes = vec![Self::VERSION];
+        bytes.extend_from_slice(&self.slot.to_le_bytes());
+        bytes.extend_from_slice(self.query_hash.as_ref());
+        bytes.extend_from_slice(self.last_pubkey.as_ref());
+        bs58::encode(bytes).into_string()
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        let invalid_cursor = || Error::invalid_params("Invalid param: cursor");
+        let bytes = bs58::decode(cursor)
+            .into_vec()
+            .map_err(|_| invalid_cursor())?;
+        let Some((&Self::VERSION, bytes)) = bytes.split_first() else {
+            return Err(invalid_cursor());
+        };
+        if bytes.len()
+            != size_of::<Slot>()
+                .saturating_add(HASH_BYTES)
+                .saturating_add(PUBKEY_BYTES)
+        {
+            return Err(invalid_cursor());
+        }
+        let (slot, bytes) = bytes.split_at(size_of::<Slot>());
+        let (query_hash, last_pubkey) = bytes.split_at(HASH_BYTES);
+        Ok(Self {
+            slot: Slot::from_le_bytes(slot.try_into().unwrap()),
+            query_hash: Hash::try_from(query_hash).unwrap(),
+            last_pubkey: Pubkey::try_from(last_pubkey).unwrap(),
+        })
+    }
+}
+
+/// Returns up to `limit` accounts following `last_pubkey` in pubkey order, along with the pubkey
+/// of the last account returned if more accounts follow it
+fn paginate_keyed_accounts(
+    mut keyed_accounts: Vec<(Pubkey, AccountSharedData)>,
+    limit: usize,
+    last_pubkey: Option<Pubkey>,
+) -> (Vec<(Pubkey, AccountSharedData)>, Option<Pubkey>) {
+    keyed_accounts.sort_unstable_by_key(|(pubkey, _)| *pubkey);
+    let start = last_pubkey.map_or(0, |last_pubkey| {
+        keyed_accounts.partition_point(|(pubkey, _)| *pubkey <= last_pubkey)
+    });
+    let end = start.saturating_add(limit);
+    let next_last_pubkey = (end < keyed_accounts.len())
+        .then(|| keyed_accounts[..end].last().map(|(pubkey, _)| *pubkey))
+        .flatten();
+    keyed_accounts.truncate(end);
+    keyed_accounts.drain(..start);
+    (keyed_accounts, next_last_pubkey)
+}
+
 pub(crate) fn optimize_filters(filters: &mut [RpcFilterType]) {
-    filters.iter_mut().for_each(|filter_type| {
-        if let RpcFilterType::Memcmp(compare) = filter_type {
-            if let Err(err) = compare.convert_to_raw_bytes() {
-                // All filters should have been previously verified
-                warn!("Invalid filter: bytes could not be decoded, {err}");
+    filters
+        .iter_mut()
+        .for_each(|filter_type| match filter_type {
+            RpcFilterType::Memcmp(compare) => {
+                if let Err(err) = compare.convert_to_raw_bytes() {
+                    // All filters should have been previously verified
+                    warn!("Invalid filter: bytes could not be decoded, {err}");
+                }
             }
-        }
-    })
+            RpcFilterType::And(filters) | RpcFilterType::Or(filters) => optimize_filters(filters),
+            RpcFilterType::Not(filter) => optimize_filters(std::slice::from_mut(filter.as_mut())),
+            _ => {}
+        })
 }
 
 pub(crate) fn verify_filters(filters: &[RpcFilterType]) -> Result<()> {
@@ -2584,6 +3001,8 @@ fn get_spl_token_owner_filter(
                 }
             }
             RpcFilterType::TokenAccountState => token_account_state_filter = true,
+            // Only top-level filters are considered when choosing an index
+            _ => {}
         }
     }
     if data_size_filter == Some(account_packed_len as u64)
@@ -2635,6 +3054,8 @@ fn get_spl_token_mint_filter(
                 }
             }
             RpcFilterType::TokenAccountState => token_account_state_filter = true,
+            // Only top-level filters are considered when choosing an index
+            _ => {}
         }
     }
     if data_size_filter == Some(account_packed_len as u64)
@@ -2648,6 +3069,82 @@ fn get_spl_token_mint_filter(
     }
 }
 
+/// Analyze custom filters to determine if the result will be a subset of spl-token accounts by
+/// delegate.
+/// NOTE: `optimize_filters()` should almost always be called before using this method because of
+/// the requirement that `Memcmp::raw_bytes_as_ref().is_some()`.
+fn get_spl_token_delegate_filter(
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+) -> Result<Option<Pubkey>> {
+    if !is_known_spl_token_id(program_id) {
+        return Ok(None);
+    }
+    let mut data_size_filter: Option<u64> = None;
+    let mut memcmp_filter: Option<&[u8]> = None;
+    let mut delegate_is_some_filter = false;
+    let mut delegate_key: Option<Pubkey> = None;
+    let mut token_account_state_filter = false;
+    let account_packed_len = TokenAccount::get_packed_len();
+    for filter in filters {
+        match filter {
+            RpcFilterType::DataSize(size) => data_size_filter = Some(*size),
+            RpcFilterType::Memcmp(memcmp) => {
+                let offset = memcmp.offset();
+                if let Some(bytes) = memcmp.raw_bytes_as_ref() {
+                    if offset == account_packed_len && *program_id == token_2022::id() {
+                        memcmp_filter = Some(bytes);
+                    } else if offset == SPL_TOKEN_ACCOUNT_DELEGATE_TAG_OFFSET {
+                        delegate_is_some_filter = bytes == 1u32.to_le_bytes();
+                    } else if offset == SPL_TOKEN_ACCOUNT_DELEGATE_OFFSET {
+                        // A partial delegate prefix is left to the normal scan
+                        delegate_key = Pubkey::try_from(bytes).ok();
+                    }
+                }
+            }
+            RpcFilterType::TokenAccountState => token_account_state_filter = true,
+            // Only top-level filters are considered when choosing an index
+            _ => {}
+        }
+    }
+    // Only accounts with a delegate are indexed, so the filters must require one to be set
+    if delegate_is_some_filter
+        && (data_size_filter == Some(account_packed_len as u64)
+            || memcmp_filter == Some(&[ACCOUNTTYPE_ACCOUNT])
+            || token_account_state_filter)
+    {
+        Ok(delegate_key)
+    } else {
+        debug!("spl_token program filters do not match by-delegate index requisites");
+        Ok(None)
+    }
+}
+
+/// Analyze custom filters to determine if the result will be a subset of stake accounts by staker
+/// or withdrawer authority.
+/// NOTE: `optimize_filters()` should almost always be called before using this method because of
+/// the requirement that `Memcmp::raw_bytes_as_ref().is_some()`.
+fn get_stake_authority_filter(program_id: &Pubkey, filters: &[RpcFilterType]) -> Option<Pubkey> {
+    if *program_id != solana_sdk_ids::stake::id() {
+        return None;
+    }
+    filters
+        .iter()
+        .filter_map(|filter| match filter {
+            RpcFilterType::Memcmp(memcmp)
+                if memcmp.offset() == STAKE_ACCOUNT_STAKER_OFFSET
+                    || memcmp.offset() == STAKE_ACCOUNT_WITHDRAWER_OFFSET =>
+            {
+                memcmp
+                    .raw_bytes_as_ref()
+                    .and_then(|bytes| Pubkey::try_from(bytes).ok())
+            }
+            _ => None,
+        })
+        // Uninitialized stake accounts are not indexed, and their authorities are all zeroes
+        .find(|authority| *authority != Pubkey::default())
+}
+
 /// Analyze a passed Pubkey that may be a Token program id or Mint address to determine the program
 /// id and optional Mint
 fn get_token_program_id_and_mint(
@@ -3178,6 +3675,15 @@ pub mod rpc_accounts {
             config: Option<RpcAccountInfoConfig>,
         ) -> BoxFuture<Result<RpcResponse<Vec<Option<UiAccount>>>>>;
 
+        #[rpc(meta, name = "getAccountInfoAtSlot")]
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>>;
+
         #[rpc(meta, name = "getBlockCommitment")]
         fn get_block_commitment(
             &self,
@@ -3253,6 +3759,18 @@ pub mod rpc_accounts {
             .boxed()
         }
 
+        fn get_account_info_at_slot(
+            &self,
+            meta: Self::Metadata,
+            pubkey_str: String,
+            slot: Slot,
+            config: Option<RpcAccountInfoAtSlotConfig>,
+        ) -> Result<RpcResponse<Option<UiAccount>>> {
+            debug!("get_account_info_at_slot rpc request received: {pubkey_str:?} {slot}");
+            let pubkey = verify_pubkey(&pubkey_str)?;
+            meta.get_account_info_at_slot(&pubkey, slot, config)
+        }
+
         fn get_block_commitment(
             &self,
             meta: Self::Metadata,
@@ -3300,8 +3818,8 @@ pub mod rpc_accounts_scan {
             &self,
             meta: Self::Metadata,
             program_id_str: String,
-            config: Option<RpcProgramAccountsConfig>,
-        ) -> BoxFuture<Result<OptionalContext<Vec<RpcKeyedAccount>>>>;
+            config: Option<RpcProgramAccountsPageConfig>,
+        ) -> BoxFuture<Result<RpcProgramAccountsResponse>>;
 
         #[rpc(meta, name = "getLargestAccounts")]
         fn get_largest_accounts(
@@ -3356,25 +3874,41 @@ pub mod rpc_accounts_scan {
             &self,
             meta: Self::Metadata,
             program_id_str: String,
-            config: Option<RpcProgramAccountsConfig>,
-        ) -> BoxFuture<Result<OptionalContext<Vec<RpcKeyedAccount>>>> {
+            config: Option<RpcProgramAccountsPageConfig>,
+        ) -> BoxFuture<Result<RpcProgramAccountsResponse>> {
             debug!("get_program_accounts rpc request received: {program_id_str:?}");
             async move {
                 let program_id = verify_pubkey(&program_id_str)?;
-                let (config, filters, with_context, sort_results) = if let Some(config) = config {
-                    (
-                        Some(config.account_config),
-                        config.filters.unwrap_or_default(),
-                        config.with_context.unwrap_or_default(),
-                        config.sort_results.unwrap_or(true),
-                    )
-                } else {
-                    (None, vec![], false, true)
-                };
+                let (config, filters, with_context, sort_results, limit, cursor) =
+                    if let Some(RpcProgramAccountsPageConfig {
+                        config,
+                        limit,
+                        cursor,
+                    }) = config
+                    {
+                        (
+                            Some(config.account_config),
+                            config.filters.unwrap_or_default(),
+                            config.with_context.unwrap_or_default(),
+                            config.sort_results.unwrap_or(true),
+                            limit,
+                            cursor,
+                        )
+                    } else {
+                        (None, vec![], false, true, None, None)
+                    };
                 verify_filters(&filters)?;
-                meta.get_program_accounts(program_id, config, filters, with_context, sort_results)
-                    .await
-            }
+                meta.get_program_accounts(
+                    program_id,
+                    config,
+                    filters,
+                    with_context,
+                    sort_results,
+                    limit,
+                    cursor,
+                )
+                .await
+            }
             .boxed()
         }
 
@@ -3515,6 +4049,14 @@ pub mod rpc_full {
             config: Option<RpcSimulateTransactionConfig>,
         ) -> Result<RpcResponse<RpcSimulateTransactionResult>>;
 
+        #[rpc(meta, name = "simulateBundle")]
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>>;
+
         #[rpc(meta, name = "minimumLedgerSlot")]
         fn minimum_ledger_slot(&self, meta: Self::Metadata) -> Result<Slot>;
 
@@ -3890,6 +4432,7 @@ pub mod rpc_full {
                     result: Err(err),
                     logs,
                     post_simulation_accounts: _,
+                    rollback_accounts: _,
                     units_consumed,
                     loaded_accounts_data_size,
                     return_data,
@@ -3899,6 +4442,7 @@ pub mod rpc_full {
                     post_balances: _,
                     pre_token_balances: _,
                     post_token_balances: _,
+                    compute_unit_profile: _,
                 } = simulation_result
                 {
                     match err {
@@ -3926,6 +4470,7 @@ pub mod rpc_full {
                             pre_token_balances: None,
                             post_token_balances: None,
                             loaded_addresses: None,
+                            compute_unit_profile: None,
                         },
                     }
                     .into());
@@ -3959,6 +4504,7 @@ pub mod rpc_full {
                 accounts: config_accounts,
                 min_context_slot,
                 inner_instructions: enable_cpi_recording,
+                compute_unit_profile: enable_compute_unit_profile,
             } = config.unwrap_or_default();
             let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
             let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
@@ -3973,6 +4519,11 @@ pub mod rpc_full {
                 commitment,
                 min_context_slot,
             })?;
+            if enable_compute_unit_profile && !bank.compute_unit_profiling_enabled() {
+                return Err(Error::invalid_params(
+                    "computeUnitProfile requires a node with compute unit profiling enabled",
+                ));
+            }
             let mut blockhash: Option<RpcBlockhash> = None;
             if replace_recent_blockhash {
                 if sig_verify {
@@ -4017,6 +4568,7 @@ pub mod rpc_full {
                 result,
                 logs,
                 post_simulation_accounts,
+                rollback_accounts: _,
                 units_consumed,
                 loaded_accounts_data_size,
                 return_data,
@@ -4026,6 +4578,7 @@ pub mod rpc_full {
                 post_balances,
                 pre_token_balances,
                 post_token_balances,
+                compute_unit_profile,
             } = simulation_result;
 
             let account_keys = transaction.message().account_keys();
@@ -4104,6 +4657,237 @@ pub mod rpc_full {
                         balances.into_iter().map(|balance| solana_ledger::transaction_balances::svm_token_info_to_token_balance(balance).into()).collect()
                     }),
                     loaded_addresses: Some(UiLoadedAddresses::from(&transaction.get_loaded_addresses())),
+                    compute_unit_profile: compute_unit_profile
+                        .filter(|_| enable_compute_unit_profile)
+                        .map(|profile| profile.to_string()),
+                },
+            ))
+        }
+
+        fn simulate_bundle(
+            &self,
+            meta: Self::Metadata,
+            data: Vec<String>,
+            config: Option<RpcSimulateBundleConfig>,
+        ) -> Result<RpcResponse<RpcSimulateBundleResult>> {
+            debug!("simulate_bundle rpc request received");
+            let RpcSimulateBundleConfig {
+                sig_verify,
+                replace_recent_blockhash,
+                commitment,
+                encoding,
+                accounts: config_accounts,
+                account_overrides: config_account_overrides,
+                min_context_slot,
+                inner_instructions: enable_cpi_recording,
+                compute_unit_profile: enable_compute_unit_profile,
+            } = config.unwrap_or_default();
+            if data.is_empty() {
+                return Err(Error::invalid_params("No transactions provided"));
+            }
+            if data.len() > MAX_SIMULATE_BUNDLE_TRANSACTIONS {
+                return Err(Error::invalid_params(format!(
+                    "Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"
+                )));
+            }
+            if replace_recent_blockhash && sig_verify {
+                return Err(Error::invalid_params(
+                    "sigVerify may not be used with replaceRecentBlockhash",
+                ));
+            }
+            let tx_encoding = encoding.unwrap_or(UiTransactionEncoding::Base58);
+            let binary_encoding = tx_encoding.into_binary_encoding().ok_or_else(|| {
+                Error::invalid_params(format!(
+                    "unsupported encoding: {tx_encoding}. Supported encodings: base58, base64"
+                ))
+            })?;
+            let accounts_encoding = config_accounts
+                .as_ref()
+                .map(|config_accounts| {
+                    let accounts_encoding = config_accounts
+                        .encoding
+                        .unwrap_or(UiAccountEncoding::Base64);
+                    if accounts_encoding == UiAccountEncoding::Binary
+                        || accounts_encoding == UiAccountEncoding::Base58
+                    {
+                        return Err(Error::invalid_params("base58 encoding not supported"));
+                    }
+                    if config_accounts.addresses.len() > MAX_MULTIPLE_ACCOUNTS {
+                        return Err(Error::invalid_params(format!(
+                            "Too many accounts provided; max {MAX_MULTIPLE_ACCOUNTS}"
+                        )));
+                    }
+                    Ok(accounts_encoding)
+                })
+                .transpose()?;
+
+            let mut account_overrides = AccountOverrides::default();
+            for (address, ui_account) in config_account_overrides.unwrap_or_default() {
+                let pubkey = verify_pubkey(&address)?;
+                let account = ui_account.to_account_shared_data().ok_or_else(|| {
+                    Error::invalid_params(format!("Invalid account override for {address}"))
+                })?;
+                account_overrides.set_account(&pubkey, Some(account));
+            }
+
+            let bank = &*meta.get_bank_with_config(RpcContextConfig {
+                commitment,
+                min_context_slot,
+            })?;
+            if enable_compute_unit_profile && !bank.compute_unit_profiling_enabled() {
+                return Err(Error::invalid_params(
+                    "computeUnitProfile requires a node with compute unit profiling enabled",
+                ));
+            }
+            let blockhash = replace_recent_blockhash.then(|| {
+                let recent_blockhash = bank.last_blockhash();
+                let last_valid_block_height = bank
+                    .get_blockhash_last_valid_block_height(&recent_blockhash)
+                    .expect("bank blockhash queue should contain blockhash");
+                (recent_blockhash, last_valid_block_height)
+            });
+
+            let transactions = data
+                .into_iter()
+                .map(|data| {
+                    let (_, mut unsanitized_tx) =
+                        decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)?;
+                    if let Some((recent_blockhash, _)) = blockhash {
+                        unsanitized_tx
+                            .message
+                            .set_recent_blockhash(recent_blockhash);
+                    }
+                    let transaction = sanitize_transaction(
+                        unsanitized_tx,
+                        bank,
+                        bank.get_reserved_account_keys(),
+                        bank.feature_set
+                            .is_active(&agave_feature_set::static_instruction_limit::id()),
+                    )?;
+                    if sig_verify {
+                        transaction.verify().map_err(|_| {
+                            Error::from(RpcCustomError::TransactionSignatureVerificationFailure)
+                        })?;
+                    }
+                    Ok(transaction)
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            // The post-state of the bundle is the overridden accounts updated, in order, with the
+            // writes of every transaction that succeeded and the fee payer and nonce accounts of
+            // every transaction that failed but was still processed.
+            let mut post_simulation_accounts_map: HashMap<Pubkey, AccountSharedData> =
+                account_overrides
+                    .iter()
+                    .map(|(pubkey, account)| (*pubkey, account.clone()))
+                    .collect();
+            let simulation_results = bank.simulate_transaction_bundle(
+                &transactions,
+                account_overrides,
+                enable_cpi_recording,
+            );
+            let transaction_results = transactions
+                .iter()
+                .zip(simulation_results)
+                .map(|(transaction, simulation_result)| {
+                    let TransactionSimulationResult {
+                        result,
+                        logs,
+                        post_simulation_accounts,
+                        rollback_accounts,
+                        units_consumed,
+                        loaded_accounts_data_size,
+                        return_data,
+                        inner_instructions,
+                        fee,
+                        pre_balances,
+                        post_balances,
+                        pre_token_balances,
+                        post_token_balances,
+                        compute_unit_profile,
+                    } = simulation_result;
+
+                    if result.is_ok() {
+                        post_simulation_accounts_map.extend(post_simulation_accounts);
+                    } else {
+                        post_simulation_accounts_map.extend(rollback_accounts);
+                    }
+
+                    let account_keys = transaction.message().account_keys();
+                    let inner_instructions = inner_instructions.map(|info| {
+                        map_inner_instructions(info)
+                            .map(|converted| parse_ui_inner_instructions(converted, &account_keys))
+                            .collect()
+                    });
+
+                    RpcSimulateTransactionResult {
+                        err: result.err().map(Into::into),
+                        logs: Some(logs),
+                        accounts: None,
+                        units_consumed: Some(units_consumed),
+                        loaded_accounts_data_size: Some(loaded_accounts_data_size),
+                        return_data: return_data.map(|return_data| return_data.into()),
+                        inner_instructions,
+                        replacement_blockhash: blockhash.map(
+                            |(recent_blockhash, last_valid_block_height)| RpcBlockhash {
+                                blockhash: recent_blockhash.to_string(),
+                                last_valid_block_height,
+                            },
+                        ),
+                        fee,
+                        pre_balances,
+                        post_balances,
+                        pre_token_balances: pre_token_balances.map(|balances| {
+                            balances
+                                .into_iter()
+                                .map(|balance| {
+                                    solana_ledger::transaction_balances::svm_token_info_to_token_balance(balance).into()
+                                })
+                                .collect()
+                        }),
+                        post_token_balances: post_token_balances.map(|balances| {
+                            balances
+                                .into_iter()
+                                .map(|balance| {
+                                    solana_ledger::transaction_balances::svm_token_info_to_token_balance(balance).into()
+                                })
+                                .collect()
+                        }),
+                        loaded_addresses: Some(UiLoadedAddresses::from(
+                            &transaction.get_loaded_addresses(),
+                        )),
+                        compute_unit_profile: compute_unit_profile
+                            .filter(|_| enable_compute_unit_profile)
+                            .map(|profile| profile.to_string()),
+                    }
+                })
+                .collect();
+
+            let accounts = config_accounts
+                .zip(accounts_encoding)
+                .map(|(config_accounts, accounts_encoding)| {
+                    config_accounts
+                        .addresses
+                        .iter()
+                        .map(|address_str| {
+                            let pubkey = verify_pubkey(address_str)?;
+                            get_encoded_account(
+                                bank,
+                                &pubkey,
+                                accounts_encoding,
+                                None,
+                                Some(&post_simulation_accounts_map),
+                            )
+                        })
+                        .collect::<Result<Vec<_>>>()
+                })
+                .transpose()?;
+
+            Ok(new_response(
+                bank,
+                RpcSimulateBundleResult {
+                    transaction_results,
+                    accounts,
                 },
             ))
         }
@@ -4561,7 +5345,11 @@ pub mod tests {
         },
         solana_rpc_client_api::{
             custom_error::{
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE,
+                JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+                JSON_RPC_SERVER_ERROR_PROGRAM_ACCOUNTS_CURSOR_EXPIRED,
                 JSON_RPC_SERVER_ERROR_TRANSACTION_HISTORY_NOT_AVAILABLE,
                 JSON_RPC_SERVER_ERROR_UNSUPPORTED_TRANSACTION_VERSION,
             },
@@ -4816,6 +5604,15 @@ pub mod tests {
             // depending on whether the full API is enabled or not. Since this
             // is test code, always pass it and just .unwrap() as needed
             let prioritization_fee_cache = Some(Arc::new(PrioritizationFeeCache::default()));
+            let account_history = config
+                .account_history
+                .clone()
+                .map(|account_history_config| {
+                    Arc::new(AccountHistory::new(
+                        account_history_config,
+                        blockstore.clone(),
+                    ))
+                });
 
             let JsonRpcConfig {
                 rpc_threads,
@@ -4840,6 +5637,7 @@ pub mod tests {
                 Arc::new(LeaderScheduleCache::new_from_bank(&bank)),
                 max_complete_transaction_status_slot.clone(),
                 prioritization_fee_cache,
+                account_history,
                 service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
             )
             .0;
@@ -5732,6 +6530,66 @@ pub mod tests {
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_rpc_get_account_info_at_slot() {
+        let pubkey = Pubkey::new_unique();
+        let address = pubkey.to_string();
+
+        let rpc = RpcHandler::start();
+        let request = create_test_request("getAccountInfoAtSlot", Some(json!([address, 0])));
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE);
+
+        let rpc = RpcHandler::start_with_config(JsonRpcConfig {
+            account_history: Some(AccountHistoryConfig {
+                owners: HashSet::default(),
+                pubkeys: HashSet::from([pubkey]),
+                backfill: false,
+            }),
+            ..JsonRpcConfig::default()
+        });
+        let account_history = rpc.meta.account_history.as_ref().unwrap();
+        rpc.meta.blockstore.set_roots([1, 2, 3].iter()).unwrap();
+        account_history.set_start_root(1).unwrap();
+        let data = vec![1, 2, 3, 4, 5];
+        let account = AccountSharedData::create(42, data.clone(), Pubkey::default(), false, 0);
+        account_history.record_account(2, &pubkey, 0, &account);
+        account_history.set_root(3);
+        // Not rooted yet
+        account_history.record_account(4, &pubkey, 0, &AccountSharedData::default());
+
+        // Not written since the history started
+        let request = create_test_request("getAccountInfoAtSlot", Some(json!([address, 1])));
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE);
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([address, 3, {"dataSlice": {"length": 2, "offset": 1}}])),
+        );
+        let result: Value = parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result["context"]["slot"], 3);
+        assert_eq!(result["value"]["lamports"], 42);
+        assert_eq!(
+            result["value"]["data"],
+            json!([BASE64_STANDARD.encode(&data[1..3]), "base64"])
+        );
+
+        let request = create_test_request("getAccountInfoAtSlot", Some(json!([address, 4])));
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(
+            code,
+            JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_SLOT_NOT_AVAILABLE
+        );
+
+        let request = create_test_request(
+            "getAccountInfoAtSlot",
+            Some(json!([Pubkey::new_unique().to_string(), 3])),
+        );
+        let (code, _message) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_ACCOUNT_HISTORY_NOT_AVAILABLE);
+    }
+
     #[test]
     fn test_rpc_get_multiple_accounts() {
         let rpc = RpcHandler::start();
@@ -6006,6 +6864,218 @@ pub mod tests {
         assert_eq!(result.len(), 0);
     }
 
+    #[test]
+    fn test_rpc_get_program_accounts_paginated() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+
+        let program_id = Pubkey::new_unique();
+        let mut keys = (0..5)
+            .map(|_| {
+                let pubkey = Pubkey::new_unique();
+                bank.store_account(&pubkey, &AccountSharedData::new(42, 0, &program_id));
+                pubkey.to_string()
+            })
+            .collect::<Vec<_>>();
+
+        // Pages are returned in pubkey order until the cursor runs out
+        let mut page_keys = vec![];
+        let mut page_lens = vec![];
+        let mut cursor: Option<String> = None;
+        loop {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([
+                    program_id.to_string(),
+                    {"limit": 2, "cursor": cursor},
+                ])),
+            );
+            let result: RpcResponse<RpcKeyedAccountsPage> =
+                parse_success_result(rpc.handle_request_sync(request));
+            assert_eq!(result.context.slot, bank.slot());
+            page_lens.push(result.value.accounts.len());
+            page_keys.extend(
+                result
+                    .value
+                    .accounts
+                    .into_iter()
+                    .map(|keyed_account| keyed_account.pubkey),
+            );
+            cursor = result.value.cursor;
+            if cursor.is_none() {
+                break;
+            }
+            // Accounts stored in a newer bank are not seen by later pages
+            if page_lens.len() == 1 {
+                let new_bank = rpc.advance_bank_to_confirmed_slot(bank.slot() + 1);
+                new_bank.store_account(
+                    &Pubkey::new_from_array([u8::MAX; 32]),
+                    &AccountSharedData::new(42, 0, &program_id),
+                );
+            }
+        }
+        keys.sort_by_key(|key| key.parse::<Pubkey>().unwrap());
+        assert_eq!(page_keys, keys);
+        assert_eq!(page_lens, vec![2, 2, 1]);
+
+        // Later pages are still served once BankForks has pruned the bank of the first page
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([program_id.to_string(), {"limit": 2}])),
+        );
+        let result: RpcResponse<RpcKeyedAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        let cursor_slot = result.context.slot;
+        let new_bank = rpc.advance_bank_to_confirmed_slot(cursor_slot + 1);
+        rpc.bank_forks
+            .write()
+            .unwrap()
+            .set_root(new_bank.slot(), None, Some(0));
+        assert!(rpc.bank_forks.read().unwrap().get(cursor_slot).is_none());
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"limit": 2, "cursor": result.value.cursor.unwrap()},
+            ])),
+        );
+        let result: RpcResponse<RpcKeyedAccountsPage> =
+            parse_success_result(rpc.handle_request_sync(request));
+        assert_eq!(result.context.slot, cursor_slot);
+        assert_eq!(result.value.accounts.len(), 2);
+
+        // Test invalid limits
+        for limit in [0, MAX_GET_PROGRAM_ACCOUNTS_PAGE_LIMIT + 1] {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([program_id.to_string(), {"limit": limit}])),
+            );
+            let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+        }
+
+        // Test invalid cursors, and cursors issued for another program or other filters
+        let other_program_cursor = ProgramAccountsCursor {
+            slot: bank.slot(),
+            query_hash: ProgramAccountsCursor::query_hash(&Pubkey::new_unique(), &[]),
+            last_pubkey: Pubkey::new_unique(),
+        }
+        .encode();
+        let other_filters_cursor = ProgramAccountsCursor {
+            slot: bank.slot(),
+            query_hash: ProgramAccountsCursor::query_hash(
+                &program_id,
+                &[RpcFilterType::DataSize(0)],
+            ),
+            last_pubkey: Pubkey::new_unique(),
+        }
+        .encode();
+        for cursor in [
+            "invalid".to_string(),
+            other_program_cursor,
+            other_filters_cursor,
+        ] {
+            let request = create_test_request(
+                "getProgramAccounts",
+                Some(json!([program_id.to_string(), {"limit": 2, "cursor": cursor}])),
+            );
+            let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+            assert_eq!(code, ErrorCode::InvalidParams.code());
+        }
+
+        // Test cursor whose bank the node does not hold
+        let expired_cursor = ProgramAccountsCursor {
+            slot: bank.slot() + 1000,
+            query_hash: ProgramAccountsCursor::query_hash(&program_id, &[]),
+            last_pubkey: Pubkey::new_unique(),
+        }
+        .encode();
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([program_id.to_string(), {"limit": 2, "cursor": expired_cursor}])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_PROGRAM_ACCOUNTS_CURSOR_EXPIRED);
+
+        // Test cursor whose bank is older than the minimum context slot
+        let cursor = ProgramAccountsCursor {
+            slot: bank.slot(),
+            query_hash: ProgramAccountsCursor::query_hash(&program_id, &[]),
+            last_pubkey: Pubkey::new_unique(),
+        }
+        .encode();
+        let request = create_test_request(
+            "getProgramAccounts",
+            Some(json!([
+                program_id.to_string(),
+                {"limit": 2, "cursor": cursor, "minContextSlot": bank.slot() + 1},
+            ])),
+        );
+        let (code, _) = parse_failure_response(rpc.handle_request_sync(request));
+        assert_eq!(code, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED);
+    }
+
+    #[test]
+    fn test_paginate_keyed_accounts() {
+        let mut keyed_accounts = (0..5)
+            .map(|_| (Pubkey::new_unique(), AccountSharedData::default()))
+            .collect::<Vec<_>>();
+        let shuffled_accounts = keyed_accounts.iter().rev().cloned().collect::<Vec<_>>();
+        keyed_accounts.sort_by_key(|(pubkey, _)| *pubkey);
+        let keys = keyed_accounts
+            .iter()
+            .map(|(pubkey, _)| *pubkey)
+            .collect::<Vec<_>>();
+        let page_keys = |(page, _): &(Vec<(Pubkey, AccountSharedData)>, _)| {
+            page.iter().map(|(pubkey, _)| *pubkey).collect::<Vec<_>>()
+        };
+
+        // First page
+        let page = paginate_keyed_accounts(shuffled_accounts.clone(), 2, None);
+        assert_eq!(page_keys(&page), keys[..2]);
+        assert_eq!(page.1, Some(keys[1]));
+
+        // Middle page
+        let page = paginate_keyed_accounts(shuffled_accounts.clone(), 2, Some(keys[1]));
+        assert_eq!(page_keys(&page), keys[2..4]);
+        assert_eq!(page.1, Some(keys[3]));
+
+        // Last page
+        let page = paginate_keyed_accounts(shuffled_accounts.clone(), 2, Some(keys[3]));
+        assert_eq!(page_keys(&page), keys[4..]);
+        assert_eq!(page.1, None);
+
+        // A page ending on the last account has no next page
+        let page = paginate_keyed_accounts(shuffled_accounts.clone(), 5, None);
+        assert_eq!(page_keys(&page), keys);
+        assert_eq!(page.1, None);
+
+        // The last pubkey need not be one of the accounts, e.g. if it was closed
+        let page = paginate_keyed_accounts(shuffled_accounts, 5, Some(Pubkey::default()));
+        assert_eq!(page_keys(&page), keys);
+    }
+
+    #[test]
+    fn test_program_accounts_cursor() {
+        let cursor = ProgramAccountsCursor {
+            slot: 42,
+            query_hash: Hash::new_from_array([7; HASH_BYTES]),
+            last_pubkey: Pubkey::new_unique(),
+        };
+        assert_eq!(
+            ProgramAccountsCursor::decode(&cursor.encode()).unwrap(),
+            cursor
+        );
+        assert!(ProgramAccountsCursor::decode("").is_err());
+        assert!(ProgramAccountsCursor::decode("0OIl").is_err());
+        let mut bytes = bs58::decode(cursor.encode()).into_vec().unwrap();
+        bytes.pop();
+        assert!(ProgramAccountsCursor::decode(&bs58::encode(&bytes).into_string()).is_err());
+        bytes[0] = 0;
+        bytes.push(0);
+        assert!(ProgramAccountsCursor::decode(&bs58::encode(&bytes).into_string()).is_err());
+    }
+
     #[test]
     fn test_rpc_simulate_transaction() {
         let rpc = RpcHandler::start();
@@ -6348,6 +7418,216 @@ pub mod tests {
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_rpc_simulate_bundle() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta, ref io, ..
+        } = rpc;
+
+        // Alice only exists through an override, funds Bob, who then pays Carol
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+        let carol_pubkey = solana_pubkey::new_rand();
+        let tx1 = system_transaction::transfer(
+            &alice,
+            &bob.pubkey(),
+            2 * rent_exempt_amount + 5000,
+            recent_blockhash,
+        );
+        let tx2 =
+            system_transaction::transfer(&bob, &carol_pubkey, rent_exempt_amount, recent_blockhash);
+        let tx1_serialized_encoded = bs58::encode(serialize(&tx1).unwrap()).into_string();
+        let tx2_serialized_encoded = bs58::encode(serialize(&tx2).unwrap()).into_string();
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0",
+                 "id":1,
+                 "method":"simulateBundle",
+                 "params":[
+                   ["{tx1_serialized_encoded}", "{tx2_serialized_encoded}"],
+                   {{
+                     "sigVerify": true,
+                     "accounts": {{
+                       "addresses": ["{}", "{}", "{carol_pubkey}"]
+                     }},
+                     "accountOverrides": {{
+                       "{}": {{
+                         "lamports": 1000000000,
+                         "data": ["", "base64"],
+                         "owner": "11111111111111111111111111111111",
+                         "executable": false,
+                         "rentEpoch": {},
+                         "space": 0
+                       }}
+                     }}
+                   }}
+                 ]
+            }}"#,
+            alice.pubkey(),
+            bob.pubkey(),
+            alice.pubkey(),
+            u64::MAX,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let value = &result["result"]["value"];
+        let transaction_results = value["transactionResults"].as_array().unwrap();
+        assert_eq!(transaction_results.len(), 2);
+        for transaction_result in transaction_results {
+            assert_eq!(transaction_result["err"], Value::Null);
+            assert_eq!(transaction_result["unitsConsumed"], 150);
+        }
+        let lamports = |index: usize| value["accounts"][index]["lamports"].as_u64().unwrap();
+        assert_eq!(lamports(0), 1_000_000_000 - 2 * rent_exempt_amount - 10_000);
+        assert_eq!(lamports(1), rent_exempt_amount);
+        assert_eq!(lamports(2), rent_exempt_amount);
+
+        // Nothing is committed to the bank
+        assert!(bank.get_account(&alice.pubkey()).is_none());
+        assert!(bank.get_account(&bob.pubkey()).is_none());
+        assert!(bank.get_account(&carol_pubkey).is_none());
+
+        // Too many transactions...
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"simulateBundle","params":[[{}]]}}"#,
+            vec![format!("\"{tx1_serialized_encoded}\""); MAX_SIMULATE_BUNDLE_TRANSACTIONS + 1]
+                .join(","),
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["message"],
+            format!("Too many transactions provided; max {MAX_SIMULATE_BUNDLE_TRANSACTIONS}"),
+        );
+    }
+
+    #[test]
+    fn test_rpc_simulate_bundle_failed_transaction() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let rent_exempt_amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta, ref io, ..
+        } = rpc;
+
+        // Alice funds Bob, Bob fails to pay Carol more than he has, then Alice pays Carol
+        let alice = Keypair::new();
+        let bob = Keypair::new();
+        let carol_pubkey = solana_pubkey::new_rand();
+        let tx1 = system_transaction::transfer(
+            &alice,
+            &bob.pubkey(),
+            2 * rent_exempt_amount + 5000,
+            recent_blockhash,
+        );
+        let tx2 = system_transaction::transfer(
+            &bob,
+            &carol_pubkey,
+            3 * rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx3 = system_transaction::transfer(
+            &alice,
+            &carol_pubkey,
+            rent_exempt_amount,
+            recent_blockhash,
+        );
+        let tx1_serialized_encoded = bs58::encode(serialize(&tx1).unwrap()).into_string();
+        let tx2_serialized_encoded = bs58::encode(serialize(&tx2).unwrap()).into_string();
+        let tx3_serialized_encoded = bs58::encode(serialize(&tx3).unwrap()).into_string();
+
+        // Simulation bank must be frozen
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0",
+                 "id":1,
+                 "method":"simulateBundle",
+                 "params":[
+                   ["{tx1_serialized_encoded}", "{tx2_serialized_encoded}", "{tx3_serialized_encoded}"],
+                   {{
+                     "accounts": {{
+                       "addresses": ["{}", "{}", "{carol_pubkey}"]
+                     }},
+                     "accountOverrides": {{
+                       "{}": {{
+                         "lamports": 1000000000,
+                         "data": ["", "base64"],
+                         "owner": "11111111111111111111111111111111",
+                         "executable": false,
+                         "rentEpoch": {},
+                         "space": 0
+                       }}
+                     }}
+                   }}
+                 ]
+            }}"#,
+            alice.pubkey(),
+            bob.pubkey(),
+            alice.pubkey(),
+            u64::MAX,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        let value = &result["result"]["value"];
+        let transaction_results = value["transactionResults"].as_array().unwrap();
+        assert_eq!(transaction_results.len(), 3);
+        assert_eq!(transaction_results[0]["err"], Value::Null);
+        assert_ne!(transaction_results[1]["err"], Value::Null);
+        assert_eq!(transaction_results[2]["err"], Value::Null);
+
+        // Bob still pays the fee of the failed transfer
+        let lamports = |index: usize| value["accounts"][index]["lamports"].as_u64().unwrap();
+        assert_eq!(lamports(0), 1_000_000_000 - 3 * rent_exempt_amount - 15_000);
+        assert_eq!(lamports(1), 2 * rent_exempt_amount);
+        assert_eq!(lamports(2), rent_exempt_amount);
+    }
+
+    #[test]
+    fn test_rpc_simulate_transaction_compute_unit_profile_disabled() {
+        let rpc = RpcHandler::start();
+        let bank = rpc.working_bank();
+        let recent_blockhash = bank.confirmed_last_blockhash();
+        let RpcHandler {
+            ref meta, ref io, ..
+        } = rpc;
+
+        let tx = system_transaction::transfer(
+            &rpc.mint_keypair,
+            &solana_pubkey::new_rand(),
+            bank.get_minimum_balance_for_rent_exemption(0),
+            recent_blockhash,
+        );
+        let tx_serialized_encoded = bs58::encode(serialize(&tx).unwrap()).into_string();
+        bank.freeze();
+
+        let req = format!(
+            r#"{{"jsonrpc":"2.0",
+                 "id":1,
+                 "method":"simulateTransaction",
+                 "params":["{tx_serialized_encoded}", {{"computeUnitProfile": true}}]
+            }}"#,
+        );
+        let res = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&res.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["message"],
+            "computeUnitProfile requires a node with compute unit profiling enabled",
+        );
+    }
+
     #[test]
     fn test_rpc_simulate_transaction_with_parsing_token_accounts() {
         let rpc = RpcHandler::start();
@@ -6905,6 +8185,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            None,
             runtime.clone(),
         );
 
@@ -7229,6 +8510,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            None,
             runtime,
         );
 
@@ -8913,6 +10195,142 @@ pub mod tests {
         }));
     }
 
+    #[test]
+    fn test_get_spl_token_delegate_filter() {
+        let delegate = Pubkey::new_unique();
+        let delegate_is_some =
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(72, 1u32.to_le_bytes().to_vec()));
+        let delegate_filter =
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(76, delegate.to_bytes().to_vec()));
+
+        // Filtering on token-v3 length
+        assert_eq!(
+            get_spl_token_delegate_filter(
+                &spl_generic_token::token::id(),
+                &[
+                    delegate_is_some.clone(),
+                    delegate_filter.clone(),
+                    RpcFilterType::DataSize(165)
+                ],
+            )
+            .unwrap()
+            .unwrap(),
+            delegate
+        );
+
+        // Filtering on token account state
+        assert_eq!(
+            get_spl_token_delegate_filter(
+                &token_2022::id(),
+                &[
+                    delegate_is_some.clone(),
+                    delegate_filter.clone(),
+                    RpcFilterType::TokenAccountState,
+                ],
+            )
+            .unwrap()
+            .unwrap(),
+            delegate
+        );
+
+        // Filtering on token-2022 account type
+        assert_eq!(
+            get_spl_token_delegate_filter(
+                &token_2022::id(),
+                &[
+                    delegate_is_some.clone(),
+                    delegate_filter.clone(),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(165, vec![ACCOUNTTYPE_ACCOUNT])),
+                ],
+            )
+            .unwrap()
+            .unwrap(),
+            delegate
+        );
+
+        // Missing delegate is_some() filter
+        assert!(get_spl_token_delegate_filter(
+            &spl_generic_token::token::id(),
+            &[delegate_filter.clone(), RpcFilterType::DataSize(165)],
+        )
+        .unwrap()
+        .is_none());
+
+        // Missing token account filter
+        assert!(get_spl_token_delegate_filter(
+            &spl_generic_token::token::id(),
+            &[delegate_is_some.clone(), delegate_filter.clone()],
+        )
+        .unwrap()
+        .is_none());
+
+        // Wrong program id
+        assert!(get_spl_token_delegate_filter(
+            &Pubkey::new_unique(),
+            &[
+                delegate_is_some.clone(),
+                delegate_filter,
+                RpcFilterType::DataSize(165)
+            ],
+        )
+        .unwrap()
+        .is_none());
+
+        // Partial delegate prefix
+        let mut first_half_bytes = delegate.to_bytes().to_vec();
+        first_half_bytes.resize(16, 0);
+        assert!(get_spl_token_delegate_filter(
+            &spl_generic_token::token::id(),
+            &[
+                delegate_is_some,
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(76, first_half_bytes)),
+                RpcFilterType::DataSize(165)
+            ],
+        )
+        .unwrap()
+        .is_none());
+    }
+
+    #[test]
+    fn test_get_stake_authority_filter() {
+        let authority = Pubkey::new_unique();
+        let stake_program_id = solana_sdk_ids::stake::id();
+        let memcmp_filter = |offset: usize, key: &Pubkey| {
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec()))
+        };
+
+        // Filtering on staker or withdrawer
+        assert_eq!(
+            get_stake_authority_filter(&stake_program_id, &[memcmp_filter(12, &authority)]),
+            Some(authority)
+        );
+        assert_eq!(
+            get_stake_authority_filter(
+                &stake_program_id,
+                &[RpcFilterType::DataSize(200), memcmp_filter(44, &authority)],
+            ),
+            Some(authority)
+        );
+
+        // Filtering on other offsets
+        assert_eq!(
+            get_stake_authority_filter(&stake_program_id, &[memcmp_filter(124, &authority)]),
+            None
+        );
+
+        // Filtering on the default pubkey
+        assert_eq!(
+            get_stake_authority_filter(&stake_program_id, &[memcmp_filter(44, &Pubkey::default())]),
+            None
+        );
+
+        // Wrong program id
+        assert_eq!(
+            get_stake_authority_filter(&Pubkey::new_unique(), &[memcmp_filter(44, &authority)]),
+            None
+        );
+    }
+
     #[test]
     fn test_rpc_single_gossip() {
         let exit = Arc::new(AtomicBool::new(false));
@@ -8973,6 +10391,7 @@ pub mod tests {
             Arc::new(LeaderScheduleCache::default()),
             max_complete_transaction_status_slot,
             prioritization_fee_cache_inner.clone(),
+            None,
             service_runtime(rpc_threads, rpc_blocking_threads, rpc_niceness_adj),
         );
 
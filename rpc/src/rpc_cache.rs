@@ -1,11 +1,19 @@
 use {
+    solana_clock::Slot,
     solana_rpc_client_api::{config::RpcLargestAccountsFilter, response::RpcAccountBalance},
+    solana_runtime::bank::Bank,
     std::{
         collections::HashMap,
-        time::{Duration, SystemTime},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant, SystemTime},
     },
 };
 
+/// How long the bank of a `getProgramAccounts` cursor is kept after its last use
+pub const PROGRAM_ACCOUNTS_CURSOR_TTL: Duration = Duration::from_secs(60);
+/// Maximum number of banks kept for `getProgramAccounts` cursors
+pub const MAX_PROGRAM_ACCOUNTS_CURSOR_BANKS: usize = 16;
+
 #[derive(Debug, Clone)]
 pub struct LargestAccountsCache {
     duration: u64,
@@ -58,9 +66,72 @@ impl LargestAccountsCache {
     }
 }
 
+/// Banks read by the first page of paginated `getProgramAccounts` requests, keyed by the slot of
+/// the cursors issued for them. Later pages are read from these banks after BankForks has pruned
+/// them.
+pub struct ProgramAccountsCursorCache {
+    ttl: Duration,
+    max_banks: usize,
+    banks: Mutex<HashMap<Slot, ProgramAccountsCursorCacheValue>>,
+}
+
+struct ProgramAccountsCursorCacheValue {
+    bank: Arc<Bank>,
+    last_used: Instant,
+}
+
+impl ProgramAccountsCursorCache {
+    pub(crate) fn new(ttl: Duration, max_banks: usize) -> Self {
+        Self {
+            ttl,
+            max_banks,
+            banks: Mutex::default(),
+        }
+    }
+
+    /// Returns the bank of a cursor, and extends the time it is kept
+    pub(crate) fn get(&self, slot: Slot) -> Option<Arc<Bank>> {
+        let now = Instant::now();
+        let mut banks = self.banks.lock().unwrap();
+        banks.retain(|_, value| now.duration_since(value.last_used) < self.ttl);
+        banks.get_mut(&slot).map(|value| {
+            value.last_used = now;
+            Arc::clone(&value.bank)
+        })
+    }
+
+    /// Keeps the bank of a newly issued cursor, evicting the least recently used bank if the
+    /// cache is full
+    pub(crate) fn insert(&self, bank: Arc<Bank>) {
+        let now = Instant::now();
+        let mut banks = self.banks.lock().unwrap();
+        banks.retain(|_, value| now.duration_since(value.last_used) < self.ttl);
+        if !banks.contains_key(&bank.slot()) && banks.len() >= self.max_banks {
+            let least_recently_used = banks
+                .iter()
+                .min_by_key(|(_, value)| value.last_used)
+                .map(|(slot, _)| *slot);
+            if let Some(slot) = least_recently_used {
+                banks.remove(&slot);
+            }
+        }
+        banks.insert(
+            bank.slot(),
+            ProgramAccountsCursorCacheValue {
+                bank,
+                last_used: now,
+            },
+        );
+    }
+}
+
 #[cfg(test)]
 pub mod test {
-    use super::*;
+    use {
+        super::*,
+        solana_ledger::genesis_utils::{create_genesis_config, GenesisConfigInfo},
+        solana_pubkey::Pubkey,
+    };
 
     #[test]
     fn test_old_entries_expire() {
@@ -74,4 +145,34 @@ pub mod test {
         std::thread::sleep(Duration::from_secs(1));
         assert_eq!(cache.get_largest_accounts(&filter), None);
     }
+
+    #[test]
+    fn test_program_accounts_cursor_cache() {
+        let GenesisConfigInfo { genesis_config, .. } = create_genesis_config(100);
+        let bank0 = Arc::new(Bank::new_for_tests(&genesis_config));
+        let new_bank = |slot| {
+            Arc::new(Bank::new_from_parent(
+                Arc::clone(&bank0),
+                &Pubkey::default(),
+                slot,
+            ))
+        };
+        let (bank1, bank2) = (new_bank(1), new_bank(2));
+
+        let cache = ProgramAccountsCursorCache::new(Duration::from_secs(60), 2);
+        assert!(cache.get(0).is_none());
+        cache.insert(Arc::clone(&bank0));
+        cache.insert(Arc::clone(&bank1));
+        assert_eq!(cache.get(0).unwrap().slot(), 0);
+        // Bank 1 is the least recently used one
+        cache.insert(Arc::clone(&bank2));
+        assert!(cache.get(1).is_none());
+        assert_eq!(cache.get(0).unwrap().slot(), 0);
+        assert_eq!(cache.get(2).unwrap().slot(), 2);
+
+        let cache = ProgramAccountsCursorCache::new(Duration::from_millis(10), 2);
+        cache.insert(bank0);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(cache.get(0).is_none());
+    }
 }
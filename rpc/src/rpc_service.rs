@@ -2,6 +2,7 @@
 
 use {
     crate::{
+        account_history::AccountHistory,
         cluster_tpu_info::ClusterTpuInfo,
         max_slots::MaxSlots,
         optimistically_confirmed_bank_tracker::OptimisticallyConfirmedBank,
@@ -488,6 +489,7 @@ pub struct JsonRpcServiceConfig<'a> {
     pub leader_schedule_cache: Arc<LeaderScheduleCache>,
     pub max_complete_transaction_status_slot: Arc<AtomicU64>,
     pub prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+    pub account_history: Option<Arc<AccountHistory>>,
     pub rpc_tpu_client_args: RpcTpuClientArgs<'a>,
 }
 
@@ -551,6 +553,7 @@ impl JsonRpcService {
             client,
             config.max_complete_transaction_status_slot,
             config.prioritization_fee_cache,
+            config.account_history,
             runtime,
         )?;
         Ok(json_rpc_service)
@@ -584,6 +587,7 @@ impl JsonRpcService {
         client: Client,
         max_complete_transaction_status_slot: Arc<AtomicU64>,
         prioritization_fee_cache: Option<Arc<PrioritizationFeeCache>>,
+        account_history: Option<Arc<AccountHistory>>,
         runtime: Arc<TokioRuntime>,
     ) -> Result<Self, String> {
         info!("rpc bound to {rpc_addr:?}");
@@ -610,6 +614,7 @@ impl JsonRpcService {
                 ref bigtable_app_profile_id,
                 timeout,
                 max_message_size,
+                ref storage_url,
             }) = config.rpc_bigtable_config
             {
                 let bigtable_config = solana_storage_bigtable::LedgerStorageConfig {
@@ -619,6 +624,7 @@ impl JsonRpcService {
                     instance_name: bigtable_instance_name.clone(),
                     app_profile_id: bigtable_app_profile_id.clone(),
                     max_message_size,
+                    storage_url: storage_url.clone(),
                 };
                 runtime
                     .block_on(solana_storage_bigtable::LedgerStorage::new_with_config(
@@ -675,6 +681,7 @@ impl JsonRpcService {
             leader_schedule_cache,
             max_complete_transaction_status_slot,
             prioritization_fee_cache,
+            account_history,
             Arc::clone(&runtime),
         );
 
@@ -903,6 +910,7 @@ mod tests {
             client,
             Arc::new(AtomicU64::default()),
             Some(Arc::new(PrioritizationFeeCache::default())),
+            None,
             runtime,
         )
         .expect("assume successful JsonRpcService start");
@@ -82,8 +82,9 @@ use {
     rayon::{ThreadPool, ThreadPoolBuilder},
     serde::{Deserialize, Serialize},
     solana_account::{
-        create_account_shared_data_with_fields as create_account, from_account, Account,
-        AccountSharedData, InheritableAccountFields, ReadableAccount, WritableAccount,
+        create_account_shared_data_with_fields as create_account, from_account,
+        state_traits::StateMut, Account, AccountSharedData, InheritableAccountFields,
+        ReadableAccount, WritableAccount,
     },
     solana_accounts_db::{
         account_locks::validate_account_locks,
@@ -119,6 +120,7 @@ use {
     solana_inflation::Inflation,
     solana_keypair::Keypair,
     solana_lattice_hash::lt_hash::LtHash,
+    solana_loader_v3_interface::state::UpgradeableLoaderState,
     solana_measure::{measure::Measure, measure_time, measure_us},
     solana_message::{inner_instruction::InnerInstructions, AccountKeys, SanitizedMessage},
     solana_packet::PACKET_DATA_SIZE,
@@ -126,6 +128,7 @@ use {
     solana_program_runtime::{
         invoke_context::BuiltinFunctionWithContext,
         loaded_programs::{ProgramCacheEntry, ProgramRuntimeEnvironments},
+        profiler::ComputeUnitProfile,
     },
     solana_pubkey::{Pubkey, PubkeyHasherBuilder},
     solana_runtime_transaction::{
@@ -322,6 +325,9 @@ pub struct TransactionSimulationResult {
     pub result: Result<()>,
     pub logs: TransactionLogMessages,
     pub post_simulation_accounts: Vec<KeyedAccountSharedData>,
+    /// Accounts committed by a processed transaction that failed: the fee payer after paying
+    /// the fee, and the advanced nonce account if any
+    pub rollback_accounts: Vec<KeyedAccountSharedData>,
     pub units_consumed: u64,
     pub loaded_accounts_data_size: u32,
     pub return_data: Option<TransactionReturnData>,
@@ -331,6 +337,7 @@ pub struct TransactionSimulationResult {
     pub post_balances: Option<Vec<u64>>,
     pub pre_token_balances: Option<Vec<SvmTokenInfo>>,
     pub post_token_balances: Option<Vec<SvmTokenInfo>>,
+    pub compute_unit_profile: Option<ComputeUnitProfile>,
 }
 
 impl TransactionSimulationResult {
@@ -346,8 +353,10 @@ impl TransactionSimulationResult {
             pre_balances: None,
             pre_token_balances: None,
             result: Err(err),
+            rollback_accounts: vec![],
             return_data: None,
             units_consumed: 0,
+            compute_unit_profile: None,
         }
     }
 }
@@ -577,9 +586,13 @@ impl PartialEq for Bank {
             collector_fee_details: _,
             compute_budget: _,
             transaction_account_lock_limit: _,
+            enable_compute_unit_profiling: _,
+            compute_unit_profiling_environments: _,
             fee_structure: _,
             cache_for_accounts_lt_hash: _,
             stats_for_accounts_lt_hash: _,
+            record_account_deltas_slots: _,
+            account_writers: _,
             block_id,
             bank_hash_stats: _,
             epoch_rewards_calculation_cache: _,
@@ -893,6 +906,15 @@ pub struct Bank {
     /// The max number of accounts that a transaction may lock.
     transaction_account_lock_limit: Option<usize>,
 
+    /// Whether simulated transactions load their SBF programs into program
+    /// runtime environments which record VM register traces, and return the
+    /// traces folded into compute unit profiles.
+    enable_compute_unit_profiling: bool,
+
+    /// Program runtime environments with debugging features for simulated transactions, shared
+    /// with the descendants of the bank and created again once the epoch changes
+    compute_unit_profiling_environments: Arc<RwLock<Option<(Epoch, ProgramRuntimeEnvironments)>>>,
+
     /// Fee structure to use for assessing transaction fees.
     fee_structure: FeeStructure,
 
@@ -918,6 +940,14 @@ pub struct Bank {
     /// Stats related to the accounts lt hash
     stats_for_accounts_lt_hash: AccountsLtHashStats,
 
+    /// Slots for which the accounts written are recorded in a bank hash details file
+    record_account_deltas_slots: Arc<HashSet<Slot>>,
+
+    /// The transactions that wrote each account in this slot, in commit order
+    ///
+    /// Only recorded if this slot is in `record_account_deltas_slots`.
+    account_writers: Option<DashMap<Pubkey, Vec<Signature>, ahash::RandomState>>,
+
     /// The unique identifier for the corresponding block for this bank.
     /// None for banks that have not yet completed replay or for leader banks as we cannot populate block_id
     /// until bankless leader. Can be computed directly from shreds without needing to execute transactions.
@@ -1129,12 +1159,16 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: None,
             transaction_account_lock_limit: None,
+            enable_compute_unit_profiling: false,
+            compute_unit_profiling_environments: Arc::default(),
             fee_structure: FeeStructure::default(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: Arc::new(Mutex::new(HashOverrides::default())),
             accounts_lt_hash: Mutex::new(AccountsLtHash(LtHash::identity())),
             cache_for_accounts_lt_hash: DashMap::default(),
             stats_for_accounts_lt_hash: AccountsLtHashStats::default(),
+            record_account_deltas_slots: Arc::default(),
+            account_writers: None,
             block_id: RwLock::new(None),
             bank_hash_stats: AtomicBankHashStats::default(),
             epoch_rewards_calculation_cache: Arc::new(Mutex::new(HashMap::default())),
@@ -1172,6 +1206,13 @@ impl Bank {
                 .set_execution_cost(compute_budget.to_cost());
         }
         bank.transaction_account_lock_limit = runtime_config.transaction_account_lock_limit;
+        bank.enable_compute_unit_profiling = runtime_config.enable_compute_unit_profiling;
+        bank.record_account_deltas_slots =
+            Arc::new(runtime_config.record_account_deltas_slots.clone());
+        bank.account_writers = bank
+            .record_account_deltas_slots
+            .contains(&bank.slot())
+            .then(DashMap::default);
         bank.transaction_debug_keys = debug_keys;
         bank.cluster_type = Some(genesis_config.cluster_type);
 
@@ -1376,12 +1417,19 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: parent.compute_budget,
             transaction_account_lock_limit: parent.transaction_account_lock_limit,
+            enable_compute_unit_profiling: parent.enable_compute_unit_profiling,
+            compute_unit_profiling_environments: parent.compute_unit_profiling_environments.clone(),
             fee_structure: parent.fee_structure.clone(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: parent.hash_overrides.clone(),
             accounts_lt_hash: Mutex::new(parent.accounts_lt_hash.lock().unwrap().clone()),
             cache_for_accounts_lt_hash: DashMap::default(),
             stats_for_accounts_lt_hash: AccountsLtHashStats::default(),
+            record_account_deltas_slots: parent.record_account_deltas_slots.clone(),
+            account_writers: parent
+                .record_account_deltas_slots
+                .contains(&slot)
+                .then(DashMap::default),
             block_id: RwLock::new(None),
             bank_hash_stats: AtomicBankHashStats::default(),
             epoch_rewards_calculation_cache: parent.epoch_rewards_calculation_cache.clone(),
@@ -1566,7 +1614,10 @@ impl Bank {
         } else if slot_index.saturating_add(slots_in_recompilation_phase) >= slots_in_epoch {
             // Anticipate the upcoming program runtime environment for the next epoch,
             // so we can try to recompile loaded programs before the feature transition hits.
-            let new_environments = self.create_program_runtime_environments(&upcoming_feature_set);
+            let new_environments = self.create_program_runtime_environments(
+                &upcoming_feature_set,
+                false, /* debugging_features */
+            );
             let mut upcoming_environments = self.transaction_processor.environments.clone();
             let changed_program_runtime_v1 =
                 *upcoming_environments.program_runtime_v1 != *new_environments.program_runtime_v1;
@@ -1754,7 +1805,10 @@ impl Bank {
             rewards_metrics,
         );
 
-        let new_environments = self.create_program_runtime_environments(&self.feature_set);
+        let new_environments = self.create_program_runtime_environments(
+            &self.feature_set,
+            false, /* debugging_features */
+        );
         self.transaction_processor
             .set_environments(new_environments);
     }
@@ -1918,12 +1972,19 @@ impl Bank {
             collector_fee_details: RwLock::new(CollectorFeeDetails::default()),
             compute_budget: runtime_config.compute_budget,
             transaction_account_lock_limit: runtime_config.transaction_account_lock_limit,
+            enable_compute_unit_profiling: runtime_config.enable_compute_unit_profiling,
+            compute_unit_profiling_environments: Arc::default(),
             fee_structure: FeeStructure::default(),
             #[cfg(feature = "dev-context-only-utils")]
             hash_overrides: Arc::new(Mutex::new(HashOverrides::default())),
             accounts_lt_hash: Mutex::new(fields.accounts_lt_hash),
             cache_for_accounts_lt_hash: DashMap::default(),
             stats_for_accounts_lt_hash: AccountsLtHashStats::default(),
+            record_account_deltas_slots: Arc::new(
+                runtime_config.record_account_deltas_slots.clone(),
+            ),
+            // a bank loaded from a snapshot is already frozen
+            account_writers: None,
             block_id: RwLock::new(None),
             bank_hash_stats: AtomicBankHashStats::new(&fields.bank_hash_stats),
             epoch_rewards_calculation_cache: Arc::new(Mutex::new(HashMap::default())),
@@ -2566,6 +2627,15 @@ impl Bank {
             self.update_accounts_lt_hash();
             *hash = self.hash_internal_state();
             self.rc.accounts.accounts_db.mark_slot_frozen(self.slot());
+            drop(hash);
+
+            if self.account_writers.is_some() {
+                bank_hash_details::write_bank_hash_details_file(self)
+                    .map_err(|err| {
+                        warn!("Unable to write bank hash details file: {err}");
+                    })
+                    .ok();
+            }
         }
     }
 
@@ -2934,6 +3004,19 @@ impl Bank {
         self.update_recent_blockhashes_locked(&w_blockhash_queue);
     }
 
+    /// Register a blockhash in the bank's recent blockhash queue with the given lamports per
+    /// signature, without advancing the tick height. Used to recreate the blockhash queue of a
+    /// bank from outside of replay, such as from a test fixture.
+    pub fn register_recent_blockhash_with_lamports_per_signature(
+        &self,
+        blockhash: &Hash,
+        lamports_per_signature: u64,
+    ) {
+        let mut w_blockhash_queue = self.blockhash_queue.write().unwrap();
+        w_blockhash_queue.register_hash(blockhash, lamports_per_signature);
+        self.update_recent_blockhashes_locked(&w_blockhash_queue);
+    }
+
     // gating this under #[cfg(feature = "dev-context-only-utils")] isn't easy due to
     // solana-program-test's usage...
     pub fn register_unique_recent_blockhash_for_test(&self) {
@@ -3183,14 +3266,106 @@ impl Bank {
         transaction: &impl TransactionWithMeta,
         enable_cpi_recording: bool,
     ) -> TransactionSimulationResult {
-        let account_keys = transaction.account_keys();
-        let number_of_accounts = account_keys.len();
-        let account_overrides = self.get_account_overrides_for_simulation(&account_keys);
-        let batch = self.prepare_unlocked_batch_from_single_tx(transaction);
+        let account_overrides =
+            self.get_account_overrides_for_simulation(&transaction.account_keys());
+        self.simulate_transactions_unchecked(
+            slice::from_ref(transaction),
+            &account_overrides,
+            enable_cpi_recording,
+        )
+        .pop()
+        .unwrap_or_else(|| {
+            TransactionSimulationResult::new_error(TransactionError::InvalidProgramForExecution)
+        })
+    }
+
+    /// Run a bundle of transactions against a frozen bank without committing the results.
+    ///
+    /// The transactions are executed in order, so each one observes the writes of the ones before
+    /// it, and accounts found in `account_overrides` take the place of the bank's accounts.
+    pub fn simulate_transaction_bundle(
+        &self,
+        transactions: &[impl TransactionWithMeta],
+        mut account_overrides: AccountOverrides,
+        enable_cpi_recording: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        assert!(self.is_frozen(), "simulation bank must be frozen");
+
+        let slot_history_id = sysvar::slot_history::id();
+        if account_overrides.get(&slot_history_id).is_none()
+            && transactions.iter().any(|transaction| {
+                transaction
+                    .account_keys()
+                    .iter()
+                    .any(|pubkey| *pubkey == slot_history_id)
+            })
+        {
+            self.override_slot_history_for_simulation(&mut account_overrides);
+        }
+
+        // Programs are only reloaded from overridden accounts when their program account is
+        // overridden, so upgradeable programs whose program data is overridden are overridden too.
+        let overridden_programdata: HashSet<Pubkey> = account_overrides
+            .iter()
+            .filter(|(_, account)| bpf_loader_upgradeable::check_id(account.owner()))
+            .map(|(pubkey, _)| *pubkey)
+            .collect();
+        if !overridden_programdata.is_empty() {
+            for transaction in transactions {
+                for pubkey in transaction.account_keys().iter() {
+                    if account_overrides.get(pubkey).is_some() {
+                        continue;
+                    }
+                    let Some(account) = self.get_account(pubkey) else {
+                        continue;
+                    };
+                    if let Ok(UpgradeableLoaderState::Program {
+                        programdata_address,
+                    }) = account.state()
+                    {
+                        if bpf_loader_upgradeable::check_id(account.owner())
+                            && overridden_programdata.contains(&programdata_address)
+                        {
+                            account_overrides.set_account(pubkey, Some(account));
+                        }
+                    }
+                }
+            }
+        }
+
+        self.simulate_transactions_unchecked(transactions, &account_overrides, enable_cpi_recording)
+    }
+
+    /// Returns true if simulated transactions return compute unit profiles
+    pub fn compute_unit_profiling_enabled(&self) -> bool {
+        self.enable_compute_unit_profiling
+    }
+
+    fn simulate_transactions_unchecked(
+        &self,
+        transactions: &[impl TransactionWithMeta],
+        account_overrides: &AccountOverrides,
+        enable_cpi_recording: bool,
+    ) -> Vec<TransactionSimulationResult> {
+        let tx_account_lock_limit = self.get_transaction_account_lock_limit();
+        let lock_results = transactions
+            .iter()
+            .map(|transaction| {
+                validate_account_locks(transaction.account_keys(), tx_account_lock_limit)
+            })
+            .collect();
+        let mut batch =
+            TransactionBatch::new(lock_results, self, OwnedOrBorrowed::Borrowed(transactions));
+        batch.set_needs_unlock(false);
         let mut timings = ExecuteTimings::default();
+        // Only simulated transactions are traced, so the environments of the
+        // bank and the global program cache are left without debugging features
+        let compute_unit_profiling_environments = self
+            .enable_compute_unit_profiling
+            .then(|| self.compute_unit_profiling_environments());
 
         let LoadAndExecuteTransactionsOutput {
-            mut processing_results,
+            processing_results,
             balance_collector,
             ..
         } = self.load_and_execute_transactions(
@@ -3202,7 +3377,7 @@ impl Bank {
             &mut timings,
             &mut TransactionErrorMetrics::default(),
             TransactionProcessingConfig {
-                account_overrides: Some(&account_overrides),
+                account_overrides: Some(account_overrides),
                 check_program_deployment_slot: self.check_program_deployment_slot,
                 log_messages_bytes_limit: None,
                 limit_to_load_programs: true,
@@ -3214,115 +3389,159 @@ impl Bank {
                 },
                 drop_on_failure: false,
                 all_or_nothing: false,
+                compute_unit_profiling_environments: compute_unit_profiling_environments.as_ref(),
+                instruction_processed_callback: None,
             },
         );
 
-        debug!("simulate_transaction: {timings:?}");
-
-        let processing_result = processing_results
-            .pop()
-            .unwrap_or(Err(TransactionError::InvalidProgramForExecution));
-        let (
-            post_simulation_accounts,
-            result,
-            fee,
-            logs,
-            return_data,
-            inner_instructions,
-            units_consumed,
-            loaded_accounts_data_size,
-        ) = match processing_result {
-            Ok(processed_tx) => {
-                let executed_units = processed_tx.executed_units();
-                let loaded_accounts_data_size = processed_tx.loaded_accounts_data_size();
-
-                match processed_tx {
-                    ProcessedTransaction::Executed(executed_tx) => {
-                        let details = executed_tx.execution_details;
-                        let post_simulation_accounts = executed_tx
-                            .loaded_transaction
-                            .accounts
-                            .into_iter()
-                            .take(number_of_accounts)
-                            .collect::<Vec<_>>();
-                        (
-                            post_simulation_accounts,
-                            details.status,
-                            Some(executed_tx.loaded_transaction.fee_details.total_fee()),
-                            details.log_messages,
-                            details.return_data,
-                            details.inner_instructions,
-                            executed_units,
-                            loaded_accounts_data_size,
-                        )
+        debug!("simulate_transactions: {timings:?}");
+
+        let mut balances = balance_collector.map(|balance_collector| {
+            let (native_pre, native_post, token_pre, token_post) = balance_collector.into_vecs();
+            native_pre
+                .into_iter()
+                .zip(native_post)
+                .zip(token_pre.into_iter().zip(token_post))
+        });
+
+        transactions
+            .iter()
+            .zip(processing_results)
+            .map(|(transaction, processing_result)| {
+                let number_of_accounts = transaction.account_keys().len();
+                let (
+                    post_simulation_accounts,
+                    rollback_accounts,
+                    result,
+                    fee,
+                    logs,
+                    return_data,
+                    inner_instructions,
+                    units_consumed,
+                    loaded_accounts_data_size,
+                    compute_unit_profile,
+                ) = match processing_result {
+                    Ok(processed_tx) => {
+                        let executed_units = processed_tx.executed_units();
+                        let loaded_accounts_data_size = processed_tx.loaded_accounts_data_size();
+
+                        match processed_tx {
+                            ProcessedTransaction::Executed(executed_tx) => {
+                                let details = executed_tx.execution_details;
+                                let rollback_accounts = if details.status.is_err() {
+                                    executed_tx
+                                        .loaded_transaction
+                                        .rollback_accounts
+                                        .iter()
+                                        .cloned()
+                                        .collect()
+                                } else {
+                                    vec![]
+                                };
+                                let post_simulation_accounts = executed_tx
+                                    .loaded_transaction
+                                    .accounts
+                                    .into_iter()
+                                    .take(number_of_accounts)
+                                    .collect::<Vec<_>>();
+                                (
+                                    post_simulation_accounts,
+                                    rollback_accounts,
+                                    details.status,
+                                    Some(executed_tx.loaded_transaction.fee_details.total_fee()),
+                                    details.log_messages,
+                                    details.return_data,
+                                    details.inner_instructions,
+                                    executed_units,
+                                    loaded_accounts_data_size,
+                                    details.compute_unit_profile,
+                                )
+                            }
+                            ProcessedTransaction::FeesOnly(fees_only_tx) => (
+                                vec![],
+                                fees_only_tx.rollback_accounts.iter().cloned().collect(),
+                                Err(fees_only_tx.load_error),
+                                Some(fees_only_tx.fee_details.total_fee()),
+                                None,
+                                None,
+                                None,
+                                executed_units,
+                                loaded_accounts_data_size,
+                                None,
+                            ),
+                        }
                     }
-                    ProcessedTransaction::FeesOnly(fees_only_tx) => (
+                    Err(error) => (
                         vec![],
-                        Err(fees_only_tx.load_error),
-                        Some(fees_only_tx.fee_details.total_fee()),
+                        vec![],
+                        Err(error),
                         None,
                         None,
                         None,
-                        executed_units,
-                        loaded_accounts_data_size,
+                        None,
+                        0,
+                        0,
+                        None,
                     ),
+                };
+                let logs = logs.unwrap_or_default();
+
+                let (pre_balances, post_balances, pre_token_balances, post_token_balances) =
+                    match balances.as_mut().and_then(Iterator::next) {
+                        Some(((native_pre, native_post), (token_pre, token_post))) => (
+                            Some(native_pre),
+                            Some(native_post),
+                            Some(token_pre),
+                            Some(token_post),
+                        ),
+                        None => (None, None, None, None),
+                    };
+
+                TransactionSimulationResult {
+                    result,
+                    logs,
+                    post_simulation_accounts,
+                    rollback_accounts,
+                    units_consumed,
+                    loaded_accounts_data_size,
+                    return_data,
+                    inner_instructions,
+                    fee,
+                    pre_balances,
+                    post_balances,
+                    pre_token_balances,
+                    post_token_balances,
+                    compute_unit_profile,
                 }
-            }
-            Err(error) => (vec![], Err(error), None, None, None, None, 0, 0),
-        };
-        let logs = logs.unwrap_or_default();
-
-        let (pre_balances, post_balances, pre_token_balances, post_token_balances) =
-            match balance_collector {
-                Some(balance_collector) => {
-                    let (mut native_pre, mut native_post, mut token_pre, mut token_post) =
-                        balance_collector.into_vecs();
-
-                    (
-                        native_pre.pop(),
-                        native_post.pop(),
-                        token_pre.pop(),
-                        token_post.pop(),
-                    )
-                }
-                None => (None, None, None, None),
-            };
-
-        TransactionSimulationResult {
-            result,
-            logs,
-            post_simulation_accounts,
-            units_consumed,
-            loaded_accounts_data_size,
-            return_data,
-            inner_instructions,
-            fee,
-            pre_balances,
-            post_balances,
-            pre_token_balances,
-            post_token_balances,
-        }
+            })
+            .collect()
     }
 
     fn get_account_overrides_for_simulation(&self, account_keys: &AccountKeys) -> AccountOverrides {
         let mut account_overrides = AccountOverrides::default();
+        if account_keys
+            .iter()
+            .any(|pubkey| *pubkey == sysvar::slot_history::id())
+        {
+            self.override_slot_history_for_simulation(&mut account_overrides);
+        }
+        account_overrides
+    }
+
+    fn override_slot_history_for_simulation(&self, account_overrides: &mut AccountOverrides) {
         let slot_history_id = sysvar::slot_history::id();
-        if account_keys.iter().any(|pubkey| *pubkey == slot_history_id) {
-            let current_account = self.get_account_with_fixed_root(&slot_history_id);
-            let slot_history = current_account
-                .as_ref()
-                .map(|account| from_account::<SlotHistory, _>(account).unwrap())
-                .unwrap_or_default();
-            if slot_history.check(self.slot()) == Check::Found {
-                let ancestors = Ancestors::from(self.proper_ancestors().collect::<Vec<_>>());
-                if let Some((account, _)) =
-                    self.load_slow_with_fixed_root(&ancestors, &slot_history_id)
-                {
-                    account_overrides.set_slot_history(Some(account));
-                }
+        let current_account = self.get_account_with_fixed_root(&slot_history_id);
+        let slot_history = current_account
+            .as_ref()
+            .map(|account| from_account::<SlotHistory, _>(account).unwrap())
+            .unwrap_or_default();
+        if slot_history.check(self.slot()) == Check::Found {
+            let ancestors = Ancestors::from(self.proper_ancestors().collect::<Vec<_>>());
+            if let Some((account, _)) = self.load_slow_with_fixed_root(&ancestors, &slot_history_id)
+            {
+                account_overrides.set_slot_history(Some(account));
             }
         }
-        account_overrides
     }
 
     pub fn unlock_accounts<'a, Tx: SVMMessage + 'a>(
@@ -3362,31 +3581,15 @@ impl Bank {
         balances
     }
 
-    pub fn load_and_execute_transactions(
-        &self,
-        batch: &TransactionBatch<impl TransactionWithMeta>,
-        max_age: usize,
-        timings: &mut ExecuteTimings,
-        error_counters: &mut TransactionErrorMetrics,
-        processing_config: TransactionProcessingConfig,
-    ) -> LoadAndExecuteTransactionsOutput {
-        let sanitized_txs = batch.sanitized_transactions();
-
-        let (check_results, check_us) = measure_us!(self.check_transactions(
-            sanitized_txs,
-            batch.lock_results(),
-            max_age,
-            error_counters,
-        ));
-        timings.saturating_add_in_place(ExecuteTimingType::CheckUs, check_us);
-
+    /// Returns the environment that transactions are processed in by this bank
+    pub fn transaction_processing_environment(&self) -> TransactionProcessingEnvironment {
         let (blockhash, blockhash_lamports_per_signature) =
             self.last_blockhash_and_lamports_per_signature();
         let effective_epoch_of_deployments =
             self.epoch_schedule().get_epoch(self.slot.saturating_add(
                 solana_program_runtime::loaded_programs::DELAY_VISIBILITY_SLOT_OFFSET,
             ));
-        let processing_environment = TransactionProcessingEnvironment {
+        TransactionProcessingEnvironment {
             blockhash,
             blockhash_lamports_per_signature,
             epoch_total_stake: self.get_current_epoch_total_stake(),
@@ -3399,7 +3602,28 @@ impl Bank {
                 .transaction_processor
                 .get_environments_for_epoch(effective_epoch_of_deployments),
             rent: self.rent_collector.rent.clone(),
-        };
+        }
+    }
+
+    pub fn load_and_execute_transactions(
+        &self,
+        batch: &TransactionBatch<impl TransactionWithMeta>,
+        max_age: usize,
+        timings: &mut ExecuteTimings,
+        error_counters: &mut TransactionErrorMetrics,
+        processing_config: TransactionProcessingConfig,
+    ) -> LoadAndExecuteTransactionsOutput {
+        let sanitized_txs = batch.sanitized_transactions();
+
+        let (check_results, check_us) = measure_us!(self.check_transactions(
+            sanitized_txs,
+            batch.lock_results(),
+            max_age,
+            error_counters,
+        ));
+        timings.saturating_add_in_place(ExecuteTimingType::CheckUs, check_us);
+
+        let processing_environment = self.transaction_processing_environment();
 
         let sanitized_output = self
             .transaction_processor
@@ -3699,11 +3923,11 @@ impl Bank {
         let ((), store_accounts_us) = measure_us!({
             // If geyser is present, we must collect `SanitizedTransaction`
             // references in order to comply with that interface - until it
-            // is changed.
-            let maybe_transaction_refs = self
-                .accounts()
-                .accounts_db
-                .has_accounts_update_notifier()
+            // is changed. They are also needed to record the writer of each
+            // account.
+            let maybe_transaction_refs =
+                (self.accounts().accounts_db.has_accounts_update_notifier()
+                    || self.account_writers.is_some())
                 .then(|| {
                     sanitized_txs
                         .iter()
@@ -3717,13 +3941,27 @@ impl Bank {
                 &processing_results,
             );
 
+            if let (Some(account_writers), Some(transactions)) =
+                (&self.account_writers, &transactions)
+            {
+                for ((pubkey, _account), transaction) in accounts_to_store.iter().zip(transactions)
+                {
+                    account_writers
+                        .entry(**pubkey)
+                        .or_default()
+                        .push(*transaction.signature());
+                }
+            }
+
             let to_store = (self.slot(), accounts_to_store.as_slice());
             self.update_bank_hash_stats(&to_store);
             // See https://github.com/solana-labs/solana/pull/31455 for discussion
             // on *not* updating the index within a threadpool.
-            self.rc
-                .accounts
-                .store_accounts_seq(to_store, transactions.as_deref());
+            self.rc.accounts.store_accounts_seq(
+                to_store,
+                transactions.as_deref(),
+                Some(&self.ancestors),
+            );
         });
 
         // Cached vote and stake accounts are synchronized with accounts-db
@@ -3951,6 +4189,8 @@ impl Bank {
                 recording_config,
                 drop_on_failure: false,
                 all_or_nothing: false,
+                compute_unit_profiling_environments: None,
+                instruction_processed_callback: None,
             },
         );
 
@@ -4103,7 +4343,9 @@ impl Bank {
             })
         });
         self.update_bank_hash_stats(&accounts);
-        self.rc.accounts.store_accounts_par(accounts, None);
+        self.rc
+            .accounts
+            .store_accounts_par(accounts, None, Some(&self.ancestors));
         m.stop();
         self.rc
             .accounts
@@ -4242,7 +4484,10 @@ impl Bank {
             self.apply_simd_0339_invoke_cost_changes();
         }
 
-        let environments = self.create_program_runtime_environments(&self.feature_set);
+        let environments = self.create_program_runtime_environments(
+            &self.feature_set,
+            false, /* debugging_features */
+        );
         self.transaction_processor
             .global_program_cache
             .write()
@@ -4256,9 +4501,30 @@ impl Bank {
         self.transaction_processor.environments = environments;
     }
 
+    /// Returns the program runtime environments with debugging features for
+    /// simulated transactions, which are created once per epoch
+    fn compute_unit_profiling_environments(&self) -> ProgramRuntimeEnvironments {
+        let epoch = self.epoch();
+        if let Some((environments_epoch, environments)) =
+            &*self.compute_unit_profiling_environments.read().unwrap()
+        {
+            if *environments_epoch == epoch {
+                return environments.clone();
+            }
+        }
+        let environments = self.create_program_runtime_environments(
+            &self.feature_set,
+            true, /* debugging_features */
+        );
+        *self.compute_unit_profiling_environments.write().unwrap() =
+            Some((epoch, environments.clone()));
+        environments
+    }
+
     fn create_program_runtime_environments(
         &self,
         feature_set: &FeatureSet,
+        debugging_features: bool,
     ) -> ProgramRuntimeEnvironments {
         let simd_0268_active = feature_set.is_active(&raise_cpi_nesting_limit_to_8::id());
         let simd_0339_active = feature_set.is_active(&increase_cpi_account_info_limit::id());
@@ -4276,13 +4542,13 @@ impl Bank {
                     &feature_set.runtime_features(),
                     &compute_budget,
                     false, /* deployment */
-                    false, /* debugging_features */
+                    debugging_features,
                 )
                 .unwrap(),
             ),
             program_runtime_v2: Arc::new(create_program_runtime_environment_v2(
                 &compute_budget,
-                false, /* debugging_features */
+                debugging_features,
             )),
         }
     }
@@ -4419,6 +4685,25 @@ impl Bank {
         )
     }
 
+    /// Returns up to `limit` accounts owned by `program_id` that pass `filter`, in pubkey order,
+    /// starting after `after` if set
+    pub fn get_filtered_program_accounts_after<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        program_id: &Pubkey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<KeyedAccountSharedData>> {
+        self.rc.accounts.load_by_program_with_filter_after(
+            &self.ancestors,
+            self.bank_id,
+            program_id,
+            filter,
+            after,
+            limit,
+        )
+    }
+
     pub fn get_filtered_indexed_accounts<F: Fn(&AccountSharedData) -> bool>(
         &self,
         index_key: &IndexKey,
@@ -4436,6 +4721,25 @@ impl Bank {
         )
     }
 
+    /// Returns up to `limit` accounts of `index_key` that pass `filter`, in pubkey order,
+    /// starting after `after` if set
+    pub fn get_filtered_indexed_accounts_after<F: Fn(&AccountSharedData) -> bool>(
+        &self,
+        index_key: &IndexKey,
+        filter: F,
+        after: Option<&Pubkey>,
+        limit: usize,
+    ) -> ScanResult<Vec<KeyedAccountSharedData>> {
+        self.rc.accounts.load_by_index_key_with_filter_after(
+            &self.ancestors,
+            self.bank_id,
+            index_key,
+            filter,
+            after,
+            limit,
+        )
+    }
+
     pub fn account_indexes_include_key(&self, key: &Pubkey) -> bool {
         self.rc.accounts.account_indexes_include_key(key)
     }
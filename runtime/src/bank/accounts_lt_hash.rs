@@ -2,7 +2,7 @@ use {
     super::Bank,
     rayon::prelude::*,
     solana_account::{accounts_equal, AccountSharedData},
-    solana_accounts_db::accounts_db::AccountsDb,
+    solana_accounts_db::{accounts_db::AccountsDb, ancestors::Ancestors},
     solana_hash::Hash,
     solana_lattice_hash::lt_hash::LtHash,
     solana_measure::{meas_dur, measure::Measure},
@@ -44,13 +44,7 @@ impl Bank {
         let slot = self.slot();
 
         // If we don't find the account in the cache, we need to go load it.
-        // We want the version of the account *before* it was written in this slot.
-        // Bank::ancestors *includes* this slot, so we need to remove it before loading.
-        let strictly_ancestors = {
-            let mut ancestors = self.ancestors.clone();
-            ancestors.remove(&self.slot());
-            ancestors
-        };
+        let strictly_ancestors = self.strictly_ancestors();
 
         if slot == 0 {
             // Slot 0 is special when calculating the accounts lt hash.
@@ -112,39 +106,10 @@ impl Bank {
                     || (LtHash::identity(), Stats::default()),
                     |mut accum, (pubkey, curr_account)| {
                         // load the initial state of the account
-                        let (initial_state_of_account, measure_load) = meas_dur!({
-                            let cache_value = self
-                                .cache_for_accounts_lt_hash
-                                .get(pubkey)
-                                .map(|entry| entry.value().clone());
-                            match cache_value {
-                                Some(CacheValue::InspectAccount(initial_state_of_account)) => {
-                                    initial_state_of_account
-                                }
-                                Some(CacheValue::BankNew) | None => {
-                                    accum.1.num_cache_misses += 1;
-                                    // If the initial state of the account is not in the accounts
-                                    // lt hash cache, or is explicitly unknown, then it is likely
-                                    // this account was stored *outside* of transaction processing
-                                    // (e.g. creating a new bank).
-                                    // Do not populate the read cache, as this account likely will
-                                    // not be accessed again soon.
-                                    let account_slot = self
-                                        .rc
-                                        .accounts
-                                        .load_with_fixed_root_do_not_populate_read_cache(
-                                            &strictly_ancestors,
-                                            pubkey,
-                                        );
-                                    match account_slot {
-                                        Some((account, _slot)) => {
-                                            InitialStateOfAccount::Alive(account)
-                                        }
-                                        None => InitialStateOfAccount::Dead,
-                                    }
-                                }
-                            }
-                        });
+                        let ((initial_state_of_account, is_cache_miss), measure_load) = meas_dur!(
+                            self.get_initial_state_of_account(pubkey, &strictly_ancestors)
+                        );
+                        accum.1.num_cache_misses += usize::from(is_cache_miss);
                         accum.1.time_loading_accounts_prev += measure_load;
 
                         // mix out the previous version of the account
@@ -276,6 +241,53 @@ impl Bank {
         delta_lt_hash
     }
 
+    /// Returns the ancestors of this bank, excluding this bank itself
+    ///
+    /// Bank::ancestors *includes* this slot, so it must be removed before loading the version of
+    /// an account *before* it was written in this slot.
+    pub(super) fn strictly_ancestors(&self) -> Ancestors {
+        let mut ancestors = self.ancestors.clone();
+        ancestors.remove(&self.slot());
+        ancestors
+    }
+
+    /// Returns the state of an account *before* it was first written in this slot
+    ///
+    /// Also returns whether the initial state was missing from the accounts lt hash cache, and
+    /// thus had to be loaded from accounts db.
+    pub(super) fn get_initial_state_of_account(
+        &self,
+        pubkey: &Pubkey,
+        strictly_ancestors: &Ancestors,
+    ) -> (InitialStateOfAccount, bool) {
+        let cache_value = self
+            .cache_for_accounts_lt_hash
+            .get(pubkey)
+            .map(|entry| entry.value().clone());
+        match cache_value {
+            Some(CacheValue::InspectAccount(initial_state_of_account)) => {
+                (initial_state_of_account, false)
+            }
+            Some(CacheValue::BankNew) | None => {
+                // If the initial state of the account is not in the accounts
+                // lt hash cache, or is explicitly unknown, then it is likely
+                // this account was stored *outside* of transaction processing
+                // (e.g. creating a new bank).
+                // Do not populate the read cache, as this account likely will
+                // not be accessed again soon.
+                let account_slot = self
+                    .rc
+                    .accounts
+                    .load_with_fixed_root_do_not_populate_read_cache(strictly_ancestors, pubkey);
+                let initial_state_of_account = match account_slot {
+                    Some((account, _slot)) => InitialStateOfAccount::Alive(account),
+                    None => InitialStateOfAccount::Dead,
+                };
+                (initial_state_of_account, true)
+            }
+        }
+    }
+
     /// Caches initial state of writeable accounts
     ///
     /// If a transaction account is writeable, cache its initial account state.
@@ -390,7 +402,7 @@ mod tests {
     use {
         super::*,
         crate::{runtime_config::RuntimeConfig, snapshot_bank_utils, snapshot_utils},
-        agave_snapshots::snapshot_config::SnapshotConfig,
+        agave_snapshots::{manifest::ManifestVerification, snapshot_config::SnapshotConfig},
         solana_account::{ReadableAccount as _, WritableAccount as _},
         solana_accounts_db::{
             accounts_db::{AccountsDbConfig, MarkObsoleteAccounts, ACCOUNTS_DB_CONFIG_FOR_TESTING},
@@ -889,6 +901,7 @@ mod tests {
             false,
             false,
             accounts_db_config,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -986,6 +999,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1,7 +1,7 @@
 //! Container to capture information relevant to computing a bank hash
 
 use {
-    super::Bank,
+    super::{accounts_lt_hash::InitialStateOfAccount, Bank},
     base64::{prelude::BASE64_STANDARD, Engine},
     log::*,
     serde::{
@@ -9,9 +9,11 @@ use {
         ser::{SerializeSeq, Serializer},
         Deserialize, Serialize,
     },
-    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_account::{accounts_equal, Account, AccountSharedData, ReadableAccount},
+    solana_accounts_db::accounts_db::AccountsDb,
     solana_clock::Slot,
     solana_fee_structure::FeeDetails,
+    solana_lattice_hash::lt_hash::LtHash,
     solana_message::inner_instruction::InnerInstructionsList,
     solana_pubkey::Pubkey,
     solana_svm::transaction_commit_result::CommittedTransaction,
@@ -116,6 +118,93 @@ pub struct BankHashComponents {
     pub last_blockhash: String,
     pub accounts_lt_hash_checksum: String,
     pub accounts: AccountsDetails,
+    /// Only present if the bank recorded the accounts written in its slot
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub account_deltas: Vec<AccountDeltaDetails>,
+}
+
+/// An account written in a bank, with enough detail to pinpoint how it
+/// contributed to the bank hash
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AccountDeltaDetails {
+    pub pubkey: String,
+    /// The state of the account before it was first written in this slot,
+    /// or None if the account did not exist
+    pub pre_state: Option<AccountStateDetails>,
+    pub post_state: AccountStateDetails,
+    /// Signatures of the transactions that wrote the account, in commit order
+    pub writers: Vec<String>,
+    /// Checksum of the account's contribution to the accounts lt hash
+    pub lt_hash_checksum: String,
+}
+
+/// The fields of an account that contribute to its lt hash
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct AccountStateDetails {
+    pub owner: String,
+    pub lamports: u64,
+    pub executable: bool,
+    pub data: String,
+}
+
+impl From<&AccountSharedData> for AccountStateDetails {
+    fn from(account: &AccountSharedData) -> Self {
+        Self {
+            owner: account.owner().to_string(),
+            lamports: account.lamports(),
+            executable: account.executable(),
+            data: BASE64_STANDARD.encode(account.data()),
+        }
+    }
+}
+
+impl AccountDeltaDetails {
+    /// Returns the details of every account written in `bank`, sorted by pubkey
+    ///
+    /// Returns an empty Vec if the bank did not record the accounts written in its slot.
+    fn new_from_bank(bank: &Bank) -> Vec<Self> {
+        let Some(account_writers) = &bank.account_writers else {
+            return Vec::new();
+        };
+        let strictly_ancestors = bank.strictly_ancestors();
+
+        bank.get_accounts_for_bank_hash_details()
+            .into_iter()
+            .map(|(pubkey, post_account)| {
+                let (initial_state_of_account, _is_cache_miss) =
+                    bank.get_initial_state_of_account(&pubkey, &strictly_ancestors);
+                let pre_account = match initial_state_of_account {
+                    InitialStateOfAccount::Dead => None,
+                    InitialStateOfAccount::Alive(account) => Some(account),
+                };
+
+                // Mirror Bank::calculate_delta_lt_hash(): unmodified accounts do not contribute
+                let mut lt_hash = LtHash::identity();
+                if !pre_account
+                    .as_ref()
+                    .is_some_and(|pre_account| accounts_equal(pre_account, &post_account))
+                {
+                    if let Some(pre_account) = &pre_account {
+                        lt_hash.mix_out(&AccountsDb::lt_hash_account(pre_account, &pubkey).0);
+                    }
+                    lt_hash.mix_in(&AccountsDb::lt_hash_account(&post_account, &pubkey).0);
+                }
+
+                let writers = account_writers
+                    .get(&pubkey)
+                    .map(|writers| writers.iter().map(ToString::to_string).collect())
+                    .unwrap_or_default();
+
+                Self {
+                    pubkey: pubkey.to_string(),
+                    pre_state: pre_account.as_ref().map(AccountStateDetails::from),
+                    post_state: AccountStateDetails::from(&post_account),
+                    writers,
+                    lt_hash_checksum: lt_hash.checksum().to_string(),
+                }
+            })
+            .collect()
+    }
 }
 
 impl SlotDetails {
@@ -142,6 +231,7 @@ impl SlotDetails {
                     .checksum()
                     .to_string(),
                 accounts: AccountsDetails { accounts },
+                account_deltas: AccountDeltaDetails::new_from_bank(bank),
             })
         } else {
             None
@@ -276,7 +366,17 @@ pub fn write_bank_hash_details_file(bank: &Bank) -> std::result::Result<(), Stri
 
 #[cfg(test)]
 pub mod tests {
-    use super::*;
+    use {
+        super::*,
+        crate::{bank::BankTestConfig, runtime_config::RuntimeConfig},
+        solana_accounts_db::accounts_db::{AccountsDbConfig, ACCOUNTS_DB_CONFIG_FOR_TESTING},
+        solana_fee_calculator::FeeRateGovernor,
+        solana_native_token::LAMPORTS_PER_SOL,
+        solana_sdk_ids::sysvar,
+        solana_signer::Signer as _,
+        std::{collections::HashSet, sync::Arc},
+        tempfile::TempDir,
+    };
 
     fn build_details(num_slots: usize) -> BankHashDetails {
         let slot_details: Vec<_> = (0..num_slots)
@@ -304,6 +404,18 @@ pub mod tests {
                         last_blockhash: "last_blockhash".into(),
                         accounts_lt_hash_checksum: "accounts_lt_hash_checksum".into(),
                         accounts,
+                        account_deltas: vec![AccountDeltaDetails {
+                            pubkey: account_pubkey.to_string(),
+                            pre_state: None,
+                            post_state: AccountStateDetails {
+                                owner: "owner".into(),
+                                lamports: 123_456_789,
+                                executable: true,
+                                data: "AAkBCAIHAwYEBQ==".into(),
+                            },
+                            writers: vec!["signature".into()],
+                            lt_hash_checksum: "lt_hash_checksum".into(),
+                        }],
                     }),
                     transactions: vec![],
                 }
@@ -324,4 +436,76 @@ pub mod tests {
 
         assert_eq!(bank_hash_details, deserialized_bank_hash_details);
     }
+
+    #[test]
+    fn test_record_account_deltas() {
+        let bank_hash_details_dir = TempDir::new().unwrap();
+        let (mut genesis_config, mint_keypair) =
+            solana_genesis_config::create_genesis_config(LAMPORTS_PER_SOL);
+        genesis_config.fee_rate_governor = FeeRateGovernor::new(0, 0);
+        let runtime_config = RuntimeConfig {
+            record_account_deltas_slots: HashSet::from([1]),
+            ..RuntimeConfig::default()
+        };
+        let test_config = BankTestConfig {
+            accounts_db_config: AccountsDbConfig {
+                bank_hash_details_dir: bank_hash_details_dir.path().to_path_buf(),
+                ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+            },
+        };
+        let bank = Bank::new_with_paths_for_tests(
+            &genesis_config,
+            Arc::new(runtime_config),
+            test_config,
+            Vec::new(),
+        );
+        let (bank, bank_forks) = bank.wrap_with_bank_forks_for_tests();
+        let bank = Bank::new_from_parent_with_bank_forks(&bank_forks, bank, &Pubkey::default(), 1);
+
+        let recipient = Pubkey::new_unique();
+        let amount = bank.get_minimum_balance_for_rent_exemption(0);
+        let signature = bank
+            .transfer(amount, &mint_keypair, &recipient)
+            .unwrap()
+            .to_string();
+        bank.freeze();
+
+        let details = SlotDetails::new_from_bank(&bank, true).unwrap();
+        let account_deltas = &details
+            .bank_hash_components
+            .as_ref()
+            .unwrap()
+            .account_deltas;
+        let find_delta = |pubkey: &Pubkey| {
+            account_deltas
+                .iter()
+                .find(|delta| delta.pubkey == pubkey.to_string())
+                .unwrap()
+        };
+
+        let recipient_delta = find_delta(&recipient);
+        assert_eq!(recipient_delta.pre_state, None);
+        assert_eq!(recipient_delta.post_state.lamports, amount);
+        assert_eq!(recipient_delta.writers, vec![signature.clone()]);
+
+        let mint_delta = find_delta(&mint_keypair.pubkey());
+        assert_eq!(
+            mint_delta.pre_state.as_ref().unwrap().lamports,
+            mint_delta.post_state.lamports + amount,
+        );
+        assert_eq!(mint_delta.writers, vec![signature]);
+
+        // Sysvars are written outside of transaction processing
+        let clock_delta = find_delta(&sysvar::clock::id());
+        assert!(clock_delta.pre_state.is_some());
+        assert!(clock_delta.writers.is_empty());
+
+        // Freezing a recorded slot also writes its bank hash details file
+        let filename = BankHashDetails::new(vec![details]).filename().unwrap();
+        assert!(bank_hash_details_dir
+            .path()
+            .join("bank_hash_details")
+            .join(filename)
+            .exists());
+    }
 }
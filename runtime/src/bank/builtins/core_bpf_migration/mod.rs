@@ -509,7 +509,7 @@ pub(crate) mod tests {
             snapshot_utils::create_tmp_accounts_dir_for_tests,
         },
         agave_feature_set::FeatureSet,
-        agave_snapshots::snapshot_config::SnapshotConfig,
+        agave_snapshots::{manifest::ManifestVerification, snapshot_config::SnapshotConfig},
         assert_matches::assert_matches,
         solana_account::{
             state_traits::StateMut, AccountSharedData, ReadableAccount, WritableAccount,
@@ -2147,6 +2147,7 @@ pub(crate) mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -11,7 +11,7 @@ mod tests {
             snapshot_utils::{create_tmp_accounts_dir_for_tests, StorageAndNextAccountsFileId},
             stakes::{SerdeStakesToStakeFormat, Stakes},
         },
-        agave_snapshots::snapshot_config::SnapshotConfig,
+        agave_snapshots::{manifest::ManifestVerification, snapshot_config::SnapshotConfig},
         solana_accounts_db::{
             account_storage::AccountStorageMap,
             account_storage_entry::AccountStorageEntry,
@@ -301,6 +301,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -249,6 +249,7 @@ fn new_executed_processing_result(
                 return_data: None,
                 executed_units: 0,
                 accounts_data_len_delta: 0,
+                compute_unit_profile: None,
             },
             programs_modified_by_tx: HashMap::new(),
         },
@@ -3519,6 +3520,67 @@ fn test_get_filtered_indexed_accounts_limit_exceeded() {
         .is_err());
 }
 
+#[test]
+fn test_get_filtered_indexed_accounts_after() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(500);
+    let mut account_indexes = AccountSecondaryIndexes::default();
+    account_indexes.indexes.insert(AccountIndex::ProgramId);
+    let bank_config = BankTestConfig {
+        accounts_db_config: AccountsDbConfig {
+            account_indexes: Some(account_indexes),
+            ..ACCOUNTS_DB_CONFIG_FOR_TESTING
+        },
+    };
+    let bank = Arc::new(Bank::new_with_config_for_tests(
+        &genesis_config,
+        bank_config,
+    ));
+
+    let program_id = Pubkey::new_unique();
+    let mut addresses = (0..5)
+        .map(|lamports| {
+            let address = Pubkey::new_unique();
+            bank.store_account(
+                &address,
+                &AccountSharedData::new(lamports + 1, 0, &program_id),
+            );
+            address
+        })
+        .collect::<Vec<_>>();
+    addresses.sort_unstable();
+
+    let get_page = |after: Option<&Pubkey>, limit| {
+        bank.get_filtered_indexed_accounts_after(
+            &IndexKey::ProgramId(program_id),
+            |_| true,
+            after,
+            limit,
+        )
+        .unwrap()
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect::<Vec<_>>()
+    };
+
+    // Pages are in pubkey order and stop at the limit
+    assert_eq!(get_page(None, 2), addresses[..2]);
+    assert_eq!(get_page(Some(&addresses[1]), 2), addresses[2..4]);
+    assert_eq!(get_page(Some(&addresses[3]), 2), addresses[4..]);
+    assert_eq!(get_page(Some(&addresses[4]), 2), Vec::<Pubkey>::new());
+    assert_eq!(get_page(None, 0), Vec::<Pubkey>::new());
+
+    // The filter is applied before the limit
+    let indexed_accounts = bank
+        .get_filtered_indexed_accounts_after(
+            &IndexKey::ProgramId(program_id),
+            |account| account.lamports() > 1,
+            None,
+            10,
+        )
+        .unwrap();
+    assert_eq!(indexed_accounts.len(), 4);
+}
+
 #[test]
 fn test_get_filtered_indexed_accounts() {
     let (genesis_config, _mint_keypair) = create_genesis_config(500);
@@ -11590,6 +11652,7 @@ fn test_failed_simulation_load_error() {
             result: Err(TransactionError::ProgramAccountNotFound),
             logs: vec![],
             post_simulation_accounts: vec![],
+            rollback_accounts: vec![],
             units_consumed: 0,
             loaded_accounts_data_size: 0,
             return_data: None,
@@ -11599,10 +11662,61 @@ fn test_failed_simulation_load_error() {
             post_balances: Some(vec![mint_balance, 0]),
             pre_token_balances: Some(vec![]),
             post_token_balances: Some(vec![]),
+            compute_unit_profile: None,
         }
     );
 }
 
+/// Test that bundle simulations carry writes forward and use the account overrides
+#[test]
+fn test_simulate_transaction_bundle() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(LAMPORTS_PER_SOL);
+    let (bank, _bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+    bank.freeze();
+
+    let alice = Keypair::new();
+    let bob = Keypair::new();
+    let carol = Pubkey::new_unique();
+    let rent_exempt_minimum = bank.get_minimum_balance_for_rent_exemption(0);
+
+    // Alice only exists in the account overrides, and Bob is funded by the first transaction.
+    let mut account_overrides = AccountOverrides::default();
+    account_overrides.set_account(
+        &alice.pubkey(),
+        Some(AccountSharedData::new(
+            LAMPORTS_PER_SOL,
+            0,
+            &system_program::id(),
+        )),
+    );
+    let transactions = [
+        system_transaction::transfer(
+            &alice,
+            &bob.pubkey(),
+            3 * rent_exempt_minimum,
+            bank.last_blockhash(),
+        ),
+        system_transaction::transfer(&bob, &carol, rent_exempt_minimum, bank.last_blockhash()),
+    ]
+    .map(RuntimeTransaction::from_transaction_for_tests);
+
+    let simulations = bank.simulate_transaction_bundle(&transactions, account_overrides, false);
+    assert_eq!(simulations.len(), 2);
+    assert_eq!(simulations[0].result, Ok(()));
+    assert_eq!(simulations[1].result, Ok(()));
+    let (_, carol_account) = simulations[1]
+        .post_simulation_accounts
+        .iter()
+        .find(|(pubkey, _)| *pubkey == carol)
+        .unwrap();
+    assert_eq!(carol_account.lamports(), rent_exempt_minimum);
+
+    // Nothing is committed to the bank.
+    assert_eq!(bank.get_account(&alice.pubkey()), None);
+    assert_eq!(bank.get_account(&bob.pubkey()), None);
+    assert_eq!(bank.get_account(&carol), None);
+}
+
 #[test]
 fn test_filter_program_errors_and_collect_fee_details() {
     // TX  | PROCESSING RESULT           | COLLECT            | COLLECT
@@ -12549,3 +12663,45 @@ fn test_bpf_loader_upgradeable_deploy_with_more_than_255_accounts() {
         )
         .is_err());
 }
+
+#[test]
+fn test_compute_unit_profiling_environments() {
+    let (genesis_config, _mint_keypair) = create_genesis_config(500);
+    let (bank0, _bank_forks) = Bank::new_with_bank_forks_for_tests(&genesis_config);
+    let environments = bank0.compute_unit_profiling_environments();
+    assert!(
+        environments
+            .program_runtime_v1
+            .get_config()
+            .enable_register_tracing
+    );
+    // The environments of the bank executing transactions do not trace
+    assert!(
+        !bank0
+            .transaction_processor
+            .environments
+            .program_runtime_v1
+            .get_config()
+            .enable_register_tracing
+    );
+
+    // Shared with the banks of the same epoch, and created again in the next epoch
+    let bank1 = Arc::new(new_from_parent(bank0));
+    assert!(Arc::ptr_eq(
+        &bank1
+            .compute_unit_profiling_environments()
+            .program_runtime_v1,
+        &environments.program_runtime_v1,
+    ));
+    let bank2 = Bank::new_from_parent(
+        bank1,
+        &Pubkey::default(),
+        genesis_config.epoch_schedule.get_first_slot_in_epoch(1),
+    );
+    assert!(!Arc::ptr_eq(
+        &bank2
+            .compute_unit_profiling_environments()
+            .program_runtime_v1,
+        &environments.program_runtime_v1,
+    ));
+}
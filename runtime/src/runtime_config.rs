@@ -1,4 +1,7 @@
-use solana_compute_budget::compute_budget::ComputeBudget;
+use {
+    solana_clock::Slot, solana_compute_budget::compute_budget::ComputeBudget,
+    std::collections::HashSet,
+};
 
 #[cfg(feature = "frozen-abi")]
 impl ::solana_frozen_abi::abi_example::AbiExample for RuntimeConfig {
@@ -14,4 +17,12 @@ pub struct RuntimeConfig {
     pub compute_budget: Option<ComputeBudget>,
     pub log_messages_bytes_limit: Option<usize>,
     pub transaction_account_lock_limit: Option<usize>,
+    /// Slots for which every account written, along with its prior state and the
+    /// transactions that wrote it, is recorded in the bank hash details file
+    pub record_account_deltas_slots: HashSet<Slot>,
+    /// Return compute unit profiles of simulated transactions. The SBF
+    /// programs of each simulation are then loaded anew with VM register
+    /// tracing, which slows down simulation, so it is meant for development
+    /// and debugging only. Other transactions are not traced.
+    pub enable_compute_unit_profiling: bool,
 }
@@ -30,6 +30,7 @@ use {
         error::{
             SnapshotError, VerifyEpochStakesError, VerifySlotDeltasError, VerifySlotHistoryError,
         },
+        manifest::ManifestVerification,
         paths::{
             self as snapshot_paths, get_highest_full_snapshot_archive_info,
             get_highest_incremental_snapshot_archive_info,
@@ -95,6 +96,7 @@ pub fn bank_fields_from_snapshot_archives(
         incremental_snapshot_archive_info.as_ref(),
         &account_paths,
         accounts_db_config,
+        ManifestVerification::Skip,
     )?;
 
     bank_fields_from_snapshots(
@@ -145,6 +147,7 @@ pub fn bank_from_snapshot_archives(
     accounts_db_force_initial_clean: bool,
     verify_index: bool,
     accounts_db_config: AccountsDbConfig,
+    manifest_verification: ManifestVerification,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: Arc<AtomicBool>,
 ) -> agave_snapshots::Result<Bank> {
@@ -180,6 +183,7 @@ pub fn bank_from_snapshot_archives(
         incremental_snapshot_archive_info,
         account_paths,
         &accounts_db_config,
+        manifest_verification,
     )?;
 
     if let Some(incremental_storage) = incremental_storage {
@@ -288,6 +292,7 @@ pub fn bank_from_latest_snapshot_archives(
     accounts_db_force_initial_clean: bool,
     verify_index: bool,
     accounts_db_config: AccountsDbConfig,
+    manifest_verification: ManifestVerification,
     accounts_update_notifier: Option<AccountsUpdateNotifier>,
     exit: Arc<AtomicBool>,
 ) -> agave_snapshots::Result<(
@@ -318,6 +323,7 @@ pub fn bank_from_latest_snapshot_archives(
         accounts_db_force_initial_clean,
         verify_index,
         accounts_db_config,
+        manifest_verification,
         accounts_update_notifier,
         exit,
     )?;
@@ -922,6 +928,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1004,6 +1011,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1103,6 +1111,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1220,6 +1229,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1327,6 +1337,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1455,6 +1466,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1512,6 +1524,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
@@ -1935,6 +1948,7 @@ mod tests {
             false,
             false,
             ACCOUNTS_DB_CONFIG_FOR_TESTING,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
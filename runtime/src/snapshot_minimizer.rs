@@ -376,7 +376,7 @@ mod tests {
             snapshot_minimizer::SnapshotMinimizer,
             snapshot_utils,
         },
-        agave_snapshots::snapshot_config::SnapshotConfig,
+        agave_snapshots::{manifest::ManifestVerification, snapshot_config::SnapshotConfig},
         dashmap::DashSet,
         solana_account::{AccountSharedData, ReadableAccount, WritableAccount},
         solana_accounts_db::accounts_db::{AccountsDbConfig, ACCOUNTS_DB_CONFIG_FOR_TESTING},
@@ -666,6 +666,7 @@ mod tests {
             false,
             false,
             accounts_db_config,
+            ManifestVerification::Skip,
             None,
             Arc::default(),
         )
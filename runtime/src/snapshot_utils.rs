@@ -21,6 +21,7 @@ use {
             HardLinkStoragesToSnapshotError, SnapshotError, SnapshotFastbootError,
             SnapshotNewFromDirError,
         },
+        manifest::ManifestVerification,
         paths::{self as snapshot_paths, get_incremental_snapshot_archives},
         snapshot_archive_info::{
             FullSnapshotArchiveInfo, IncrementalSnapshotArchiveInfo, SnapshotArchiveInfo,
@@ -478,6 +479,7 @@ pub fn archive_snapshot_package(
         &bank_snapshot_dir,
         snapshot_archive_path,
         snapshot_config.archive_format,
+        snapshot_config.archive_manifest,
     )?;
 
     Ok(snapshot_archive_info)
@@ -1020,6 +1022,7 @@ pub fn verify_and_unarchive_snapshots(
     incremental_snapshot_archive_info: Option<&IncrementalSnapshotArchiveInfo>,
     account_paths: &[PathBuf],
     accounts_db_config: &AccountsDbConfig,
+    manifest_verification: ManifestVerification,
 ) -> Result<(UnarchivedSnapshots, UnarchivedSnapshotsGuard)> {
     check_are_snapshots_compatible(
         full_snapshot_archive_info,
@@ -1044,6 +1047,7 @@ pub fn verify_and_unarchive_snapshots(
         next_append_vec_id.clone(),
         None,
         accounts_db_config,
+        manifest_verification,
     )?;
 
     let (
@@ -1071,6 +1075,7 @@ pub fn verify_and_unarchive_snapshots(
             next_append_vec_id.clone(),
             Some(incremental_snapshot_archive_info.base_slot()),
             accounts_db_config,
+            manifest_verification,
         )?;
         (
             Some(unpack_dir),
@@ -1262,6 +1267,7 @@ fn unarchive_snapshot(
     next_append_vec_id: Arc<AtomicAccountsFileId>,
     base_slot: Option<Slot>,
     accounts_db_config: &AccountsDbConfig,
+    manifest_verification: ManifestVerification,
 ) -> Result<UnarchivedSnapshot> {
     let unpack_dir = tempfile::Builder::new()
         .prefix(unpacked_snapshots_dir_prefix)
@@ -1280,6 +1286,7 @@ fn unarchive_snapshot(
         snapshot_archive_path.as_ref().to_path_buf(),
         archive_format,
         io_setup,
+        manifest_verification,
     );
 
     let num_rebuilder_threads = num_cpus::get_physical().saturating_sub(1).max(1);
@@ -52,5 +52,6 @@ DCOU_TAINTED_PACKAGES=(
   solana-bench-tps
   solana-dos
   solana-local-cluster
+  solana-svm-test-harness-block
   solana-transaction-dos
 )
@@ -1,7 +1,11 @@
 use {
     crate::{
-        error::ArchiveSnapshotPackageError, paths, snapshot_archive_info::SnapshotArchiveInfo,
-        snapshot_hash::SnapshotHash, ArchiveFormat, Result, SnapshotArchiveKind,
+        error::ArchiveSnapshotPackageError,
+        manifest::{HashingReader, ManifestEntry, SnapshotManifest, MAX_MANIFEST_SIZE},
+        paths,
+        snapshot_archive_info::SnapshotArchiveInfo,
+        snapshot_hash::SnapshotHash,
+        ArchiveFormat, Result, SnapshotArchiveKind,
     },
     agave_fs::buffered_writer::large_file_buf_writer,
     log::info,
@@ -12,7 +16,12 @@ use {
     solana_clock::Slot,
     solana_measure::measure::Measure,
     solana_metrics::datapoint_info,
-    std::{fs, io::Write, path::Path, sync::Arc},
+    std::{
+        fs,
+        io::{self, Write},
+        path::Path,
+        sync::Arc,
+    },
 };
 
 // Balance large and small files order in snapshot tar with bias towards small (4 small + 1 large),
@@ -20,7 +29,47 @@ use {
 // and towards the end of archive (sizes equalize) writes are >256KiB / file.
 const INTERLEAVE_TAR_ENTRIES_SMALL_TO_LARGE_RATIO: (usize, usize) = (4, 1);
 
+/// Appends the data read from `reader` to `archive` under `header`, and lists it in `manifest`
+/// if there is one
+fn append_to_archive(
+    archive: &mut tar::Builder<impl Write>,
+    manifest: Option<&mut SnapshotManifest>,
+    header: &mut tar::Header,
+    path_in_archive: &str,
+    reader: impl io::Read,
+) -> io::Result<()> {
+    let Some(manifest) = manifest else {
+        return archive.append_data(header, path_in_archive, reader);
+    };
+    let mut reader = HashingReader::new(reader);
+    archive.append_data(header, path_in_archive, &mut reader)?;
+    let (size, hash) = reader.finish();
+    manifest.push(ManifestEntry {
+        path: path_in_archive.to_string(),
+        size,
+        hash,
+    });
+    Ok(())
+}
+
+/// Appends the file at `src_path` to `archive` as `path_in_archive`, and lists it in `manifest`
+/// if there is one
+fn append_file_to_archive(
+    archive: &mut tar::Builder<impl Write>,
+    manifest: Option<&mut SnapshotManifest>,
+    src_path: &Path,
+    path_in_archive: &str,
+) -> io::Result<()> {
+    let file = fs::File::open(src_path)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_metadata(&file.metadata()?);
+    append_to_archive(archive, manifest, &mut header, path_in_archive, file)
+}
+
 /// Archives a snapshot into `archive_path`
+///
+/// If `include_manifest` is set, an integrity manifest is appended as the last entry of the
+/// archive.  Validators prior to v4.0 reject archives containing a manifest.
 pub fn archive_snapshot(
     snapshot_archive_kind: SnapshotArchiveKind,
     snapshot_slot: Slot,
@@ -29,11 +78,16 @@ pub fn archive_snapshot(
     bank_snapshot_dir: impl AsRef<Path>,
     archive_path: impl AsRef<Path>,
     archive_format: ArchiveFormat,
+    include_manifest: bool,
 ) -> Result<SnapshotArchiveInfo> {
     use ArchiveSnapshotPackageError as E;
     const ACCOUNTS_DIR: &str = "accounts";
     info!("Generating snapshot archive for slot {snapshot_slot}, kind: {snapshot_archive_kind:?}");
 
+    if !archive_format.is_writable() {
+        return Err(E::UnsupportedArchiveFormat(archive_format).into());
+    }
+
     let mut timer = Measure::start("snapshot_package-package_snapshots");
     let tar_dir = archive_path
         .as_ref()
@@ -63,14 +117,15 @@ pub fn archive_snapshot(
     let staging_snapshot_file = staging_snapshot_dir.join(&slot_str);
     let src_snapshot_file = src_snapshot_dir.join(slot_str);
     symlink::symlink_file(&src_snapshot_file, &staging_snapshot_file)
-        .map_err(|err| E::SymlinkSnapshot(err, src_snapshot_file, staging_snapshot_file))?;
+        .map_err(|err| E::SymlinkSnapshot(err, src_snapshot_file, staging_snapshot_file.clone()))?;
 
     // Following the existing archive format, the status cache is under snapshots/, not under <slot>/
     // like in the snapshot dir.
     let staging_status_cache = staging_snapshots_dir.join(paths::SNAPSHOT_STATUS_CACHE_FILENAME);
     let src_status_cache = src_snapshot_dir.join(paths::SNAPSHOT_STATUS_CACHE_FILENAME);
-    symlink::symlink_file(&src_status_cache, &staging_status_cache)
-        .map_err(|err| E::SymlinkStatusCache(err, src_status_cache, staging_status_cache))?;
+    symlink::symlink_file(&src_status_cache, &staging_status_cache).map_err(|err| {
+        E::SymlinkStatusCache(err, src_status_cache, staging_status_cache.clone())
+    })?;
 
     // The bank snapshot has the version file, so symlink it to the correct staging path
     let staging_version_file = staging_dir.path().join(paths::SNAPSHOT_VERSION_FILENAME);
@@ -102,36 +157,87 @@ pub fn archive_snapshot(
             // [^1] https://github.com/alexcrichton/tar-rs/pull/375
             // [^2] https://github.com/alexcrichton/tar-rs/issues/403
             archive.sparse(false);
+            // If requested, every file appended to the archive is hashed as it is written and
+            // listed in the manifest, which goes last
+            let mut manifest = include_manifest.then(SnapshotManifest::default);
             // Serialize the version and snapshots files before accounts so we can quickly determine the version
             // and other bank fields. This is necessary if we want to interleave unpacking with reconstruction
+            append_file_to_archive(
+                &mut archive,
+                manifest.as_mut(),
+                &staging_version_file,
+                paths::SNAPSHOT_VERSION_FILENAME,
+            )
+            .map_err(E::ArchiveVersionFile)?;
+            let snapshot_dir_in_archive = format!("{}/{snapshot_slot}", paths::BANK_SNAPSHOTS_DIR);
             archive
-                .append_path_with_name(&staging_version_file, paths::SNAPSHOT_VERSION_FILENAME)
-                .map_err(E::ArchiveVersionFile)?;
+                .append_dir(paths::BANK_SNAPSHOTS_DIR, &staging_snapshots_dir)
+                .map_err(E::ArchiveSnapshotsDir)?;
+            append_file_to_archive(
+                &mut archive,
+                manifest.as_mut(),
+                &staging_status_cache,
+                &format!(
+                    "{}/{}",
+                    paths::BANK_SNAPSHOTS_DIR,
+                    paths::SNAPSHOT_STATUS_CACHE_FILENAME,
+                ),
+            )
+            .map_err(E::ArchiveSnapshotsDir)?;
             archive
-                .append_dir_all(paths::BANK_SNAPSHOTS_DIR, &staging_snapshots_dir)
+                .append_dir(&snapshot_dir_in_archive, &staging_snapshot_dir)
                 .map_err(E::ArchiveSnapshotsDir)?;
+            append_file_to_archive(
+                &mut archive,
+                manifest.as_mut(),
+                &staging_snapshot_file,
+                &format!("{snapshot_dir_in_archive}/{snapshot_slot}"),
+            )
+            .map_err(E::ArchiveSnapshotsDir)?;
 
             let storages_orderer = AccountStoragesOrderer::with_small_to_large_ratio(
                 snapshot_storages,
                 INTERLEAVE_TAR_ENTRIES_SMALL_TO_LARGE_RATIO,
             );
             for storage in storages_orderer.iter() {
-                let path_in_archive = Path::new(ACCOUNTS_DIR)
-                    .join(AccountsFile::file_name(storage.slot(), storage.id()));
+                let path_in_archive = format!(
+                    "{ACCOUNTS_DIR}/{}",
+                    AccountsFile::file_name(storage.slot(), storage.id())
+                );
 
                 let reader =
                     AccountStorageReader::new(storage, Some(snapshot_slot)).map_err(|err| {
                         E::AccountStorageReaderError(err, storage.path().to_path_buf())
                     })?;
                 let mut header = tar::Header::new_gnu();
-                header.set_path(path_in_archive).map_err(|err| {
-                    E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
-                })?;
                 header.set_size(reader.len() as u64);
-                header.set_cksum();
-                archive.append(&header, reader).map_err(|err| {
-                    E::ArchiveAccountStorageFile(err, storage.path().to_path_buf())
-                })?;
+                append_to_archive(
+                    &mut archive,
+                    manifest.as_mut(),
+                    &mut header,
+                    &path_in_archive,
+                    reader,
+                )
+                .map_err(|err| E::ArchiveAccountStorageFile(err, storage.path().to_path_buf()))?;
+            }
+
+            if let Some(manifest) = manifest {
+                let manifest = manifest.to_bytes();
+                if manifest.len() as u64 > MAX_MANIFEST_SIZE {
+                    return Err(E::ArchiveManifest(io::Error::other(format!(
+                        "manifest is larger than {MAX_MANIFEST_SIZE} bytes"
+                    ))));
+                }
+                let mut header = tar::Header::new_gnu();
+                header.set_size(manifest.len() as u64);
+                header.set_mode(0o644);
+                archive
+                    .append_data(
+                        &mut header,
+                        paths::SNAPSHOT_MANIFEST_FILENAME,
+                        manifest.as_slice(),
+                    )
+                    .map_err(E::ArchiveManifest)?;
             }
 
             archive.into_inner().map_err(E::FinishArchive)?;
@@ -155,6 +261,14 @@ pub fn archive_snapshot(
                 let (_output, result) = encoder.finish();
                 result.map_err(E::FinishEncoder)?;
             }
+            ArchiveFormat::Tar => {
+                let mut archive_writer = archive_writer;
+                do_archive_files(&mut archive_writer)?;
+                archive_writer.flush().map_err(E::FinishArchive)?;
+            }
+            ArchiveFormat::TarGzip | ArchiveFormat::TarBzip2 => {
+                unreachable!("read-only archive formats are rejected before archiving")
+            }
         };
     }
 
@@ -5,17 +5,30 @@ use {
 
 // SUPPORTED_ARCHIVE_COMPRESSION lists the compression types that can be
 // specified on the command line.
-pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4"];
+//
+// Note that "none" creates uncompressed tar archives, which trade disk space
+// for faster archiving and unpacking when compression is the bottleneck.
+pub const SUPPORTED_ARCHIVE_COMPRESSION: &[&str] = &["zstd", "lz4", "none"];
 pub const DEFAULT_ARCHIVE_COMPRESSION: &str = "zstd";
 
 pub const TAR_ZSTD_EXTENSION: &str = "tar.zst";
 pub const TAR_LZ4_EXTENSION: &str = "tar.lz4";
+pub const TAR_EXTENSION: &str = "tar";
+pub const TAR_GZIP_EXTENSION: &str = "tar.gz";
+pub const TAR_BZIP2_EXTENSION: &str = "tar.bz2";
 
 /// The different archive formats used for snapshots
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Display)]
 pub enum ArchiveFormat {
-    TarZstd { config: ZstdConfig },
+    TarZstd {
+        config: ZstdConfig,
+    },
     TarLz4,
+    Tar,
+    /// Legacy format that is only supported when reading archives
+    TarGzip,
+    /// Legacy format that is only supported when reading archives
+    TarBzip2,
 }
 
 impl ArchiveFormat {
@@ -24,6 +37,19 @@ impl ArchiveFormat {
         match self {
             ArchiveFormat::TarZstd { .. } => TAR_ZSTD_EXTENSION,
             ArchiveFormat::TarLz4 => TAR_LZ4_EXTENSION,
+            ArchiveFormat::Tar => TAR_EXTENSION,
+            ArchiveFormat::TarGzip => TAR_GZIP_EXTENSION,
+            ArchiveFormat::TarBzip2 => TAR_BZIP2_EXTENSION,
+        }
+    }
+
+    /// Returns true if snapshot archives can be created with the ArchiveFormat
+    ///
+    /// Legacy formats can still be unpacked, but new archives are never created with them.
+    pub fn is_writable(&self) -> bool {
+        match self {
+            ArchiveFormat::TarZstd { .. } | ArchiveFormat::TarLz4 | ArchiveFormat::Tar => true,
+            ArchiveFormat::TarGzip | ArchiveFormat::TarBzip2 => false,
         }
     }
 
@@ -33,6 +59,7 @@ impl ArchiveFormat {
                 config: ZstdConfig::default(),
             }),
             "lz4" => Some(ArchiveFormat::TarLz4),
+            "none" => Some(ArchiveFormat::Tar),
             _ => None,
         }
     }
@@ -49,6 +76,9 @@ impl TryFrom<&str> for ArchiveFormat {
                 config: ZstdConfig::default(),
             }),
             TAR_LZ4_EXTENSION => Ok(ArchiveFormat::TarLz4),
+            TAR_EXTENSION => Ok(ArchiveFormat::Tar),
+            TAR_GZIP_EXTENSION => Ok(ArchiveFormat::TarGzip),
+            TAR_BZIP2_EXTENSION => Ok(ArchiveFormat::TarBzip2),
             _ => Err(ParseError::InvalidExtension(extension.to_string())),
         }
     }
@@ -65,6 +95,9 @@ impl FromStr for ArchiveFormat {
 pub enum ArchiveFormatDecompressor<R> {
     Zstd(zstd::stream::read::Decoder<'static, R>),
     Lz4(lz4::Decoder<R>),
+    None(R),
+    Gzip(flate2::bufread::MultiGzDecoder<R>),
+    Bzip2(bzip2::bufread::MultiBzDecoder<R>),
 }
 
 impl<R: std::io::BufRead> ArchiveFormatDecompressor<R> {
@@ -76,6 +109,9 @@ impl<R: std::io::BufRead> ArchiveFormatDecompressor<R> {
             ArchiveFormat::TarLz4 => {
                 Self::Lz4(lz4::Decoder::new(input).map_err(std::io::Error::other)?)
             }
+            ArchiveFormat::Tar => Self::None(input),
+            ArchiveFormat::TarGzip => Self::Gzip(flate2::bufread::MultiGzDecoder::new(input)),
+            ArchiveFormat::TarBzip2 => Self::Bzip2(bzip2::bufread::MultiBzDecoder::new(input)),
         })
     }
 }
@@ -85,6 +121,9 @@ impl<R: std::io::BufRead> std::io::Read for ArchiveFormatDecompressor<R> {
         match self {
             Self::Zstd(decoder) => decoder.read(buf),
             Self::Lz4(decoder) => decoder.read(buf),
+            Self::None(reader) => reader.read(buf),
+            Self::Gzip(decoder) => decoder.read(buf),
+            Self::Bzip2(decoder) => decoder.read(buf),
         }
     }
 }
@@ -126,6 +165,21 @@ mod tests {
             TAR_ZSTD_EXTENSION
         );
         assert_eq!(ArchiveFormat::TarLz4.extension(), TAR_LZ4_EXTENSION);
+        assert_eq!(ArchiveFormat::Tar.extension(), TAR_EXTENSION);
+        assert_eq!(ArchiveFormat::TarGzip.extension(), TAR_GZIP_EXTENSION);
+        assert_eq!(ArchiveFormat::TarBzip2.extension(), TAR_BZIP2_EXTENSION);
+    }
+
+    #[test]
+    fn test_is_writable() {
+        assert!(ArchiveFormat::TarZstd {
+            config: ZstdConfig::default(),
+        }
+        .is_writable());
+        assert!(ArchiveFormat::TarLz4.is_writable());
+        assert!(ArchiveFormat::Tar.is_writable());
+        assert!(!ArchiveFormat::TarGzip.is_writable());
+        assert!(!ArchiveFormat::TarBzip2.is_writable());
     }
 
     #[test]
@@ -140,6 +194,18 @@ mod tests {
             ArchiveFormat::try_from(TAR_LZ4_EXTENSION),
             Ok(ArchiveFormat::TarLz4)
         );
+        assert_eq!(
+            ArchiveFormat::try_from(TAR_EXTENSION),
+            Ok(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::try_from(TAR_GZIP_EXTENSION),
+            Ok(ArchiveFormat::TarGzip)
+        );
+        assert_eq!(
+            ArchiveFormat::try_from(TAR_BZIP2_EXTENSION),
+            Ok(ArchiveFormat::TarBzip2)
+        );
         assert_eq!(
             ArchiveFormat::try_from(INVALID_EXTENSION),
             Err(ParseError::InvalidExtension(INVALID_EXTENSION.to_string()))
@@ -171,7 +237,9 @@ mod tests {
                 config: ZstdConfig::default(),
             }),
             Some(ArchiveFormat::TarLz4),
+            Some(ArchiveFormat::Tar),
         ];
+        assert_eq!(golden.len(), SUPPORTED_ARCHIVE_COMPRESSION.len());
 
         for (arg, expected) in zip(SUPPORTED_ARCHIVE_COMPRESSION.iter(), golden.into_iter()) {
             assert_eq!(ArchiveFormat::from_cli_arg(arg), expected);
@@ -179,4 +247,43 @@ mod tests {
 
         assert_eq!(ArchiveFormat::from_cli_arg("bad"), None);
     }
+
+    #[test]
+    fn test_decompressor() {
+        use std::io::{Read, Write};
+
+        let data: Vec<u8> = (0..64 * 1024).map(|i| (i % 251) as u8).collect();
+
+        let gzip_data = {
+            let mut encoder =
+                flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&data).unwrap();
+            encoder.finish().unwrap()
+        };
+        let bzip2_data = {
+            let mut encoder =
+                bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+            encoder.write_all(&data).unwrap();
+            encoder.finish().unwrap()
+        };
+        let zstd_data = zstd::stream::encode_all(data.as_slice(), 0).unwrap();
+
+        for (format, input) in [
+            (ArchiveFormat::Tar, data.clone()),
+            (ArchiveFormat::TarGzip, gzip_data),
+            (ArchiveFormat::TarBzip2, bzip2_data),
+            (
+                ArchiveFormat::TarZstd {
+                    config: ZstdConfig::default(),
+                },
+                zstd_data,
+            ),
+        ] {
+            let mut decompressor =
+                ArchiveFormatDecompressor::new(format, input.as_slice()).unwrap();
+            let mut output = Vec::new();
+            decompressor.read_to_end(&mut output).unwrap();
+            assert_eq!(output, data, "format: {format}");
+        }
+    }
 }
@@ -1,5 +1,5 @@
 use {
-    crate::{hardened_unpack::UnpackError, snapshot_hash::SnapshotHash},
+    crate::{hardened_unpack::UnpackError, snapshot_hash::SnapshotHash, ArchiveFormat},
     agave_fs::FileInfo,
     crossbeam_channel::SendError,
     semver::Version,
@@ -215,6 +215,9 @@ pub enum AddBankSnapshotError {
 /// Errors that can happen in `archive_snapshot_package()`
 #[derive(Error, Debug)]
 pub enum ArchiveSnapshotPackageError {
+    #[error("archive format '{0}' is not supported when creating snapshot archives")]
+    UnsupportedArchiveFormat(ArchiveFormat),
+
     #[error("failed to create archive path '{1}': {0}")]
     CreateArchiveDir(#[source] io::Error, PathBuf),
 
@@ -248,6 +251,9 @@ pub enum ArchiveSnapshotPackageError {
     #[error("failed to archive account storage file '{1}': {0}")]
     ArchiveAccountStorageFile(#[source] io::Error, PathBuf),
 
+    #[error("failed to archive manifest: {0}")]
+    ArchiveManifest(#[source] io::Error),
+
     #[error("failed to archive snapshot: {0}")]
     FinishArchive(#[source] io::Error),
 
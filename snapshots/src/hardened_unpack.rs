@@ -1,4 +1,5 @@
 use {
+    crate::manifest::{HashingReader, ManifestError, ManifestVerification, ManifestVerifier},
     agave_fs::file_io::{self, FileCreator},
     log::*,
     rand::{rng, Rng},
@@ -28,6 +29,8 @@ pub enum UnpackError {
     Archive(String),
     #[error("Unpacking '{1}' failed: {0}")]
     Unpack(Box<UnpackError>, PathBuf),
+    #[error("Manifest error: {0}")]
+    Manifest(#[from] ManifestError),
 }
 
 pub type Result<T> = std::result::Result<T, UnpackError>;
@@ -91,6 +94,7 @@ fn unpack_archive<'a, C>(
     apparent_limit_size: u64,
     actual_limit_size: u64,
     limit_count: u64,
+    mut manifest_verifier: Option<ManifestVerifier>,
     mut entry_checker: C, // checks if entry is valid
 ) -> Result<()>
 where
@@ -105,7 +109,7 @@ where
 
     let mut archive = Archive::new(input);
     for entry in archive.entries()? {
-        let entry = entry?;
+        let mut entry = entry?;
         let path = entry.path()?;
         let path_str = path.display().to_string();
 
@@ -133,6 +137,13 @@ where
             )));
         };
 
+        if let Some(manifest_verifier) = manifest_verifier.as_mut() {
+            if ManifestVerifier::is_manifest_entry(parts.as_slice(), kind) {
+                manifest_verifier.check_manifest(&mut entry)?;
+                continue;
+            }
+        }
+
         let unpack_dir = match entry_checker(parts.as_slice(), kind) {
             UnpackPath::Invalid => {
                 return Err(UnpackError::Archive(format!(
@@ -176,12 +187,24 @@ where
             continue; // skip it
         };
 
-        let unpack = unpack_entry(&mut file_creator, entry, entry_path, open_dir);
+        let manifest_verifier = manifest_verifier
+            .as_mut()
+            .map(|verifier| (verifier, path_str.as_str()));
+        let unpack = unpack_entry(
+            &mut file_creator,
+            entry,
+            entry_path,
+            open_dir,
+            manifest_verifier,
+        );
         check_unpack_result(unpack, path_str)?;
 
         total_entries += 1;
     }
     file_creator.drain()?;
+    if let Some(manifest_verifier) = manifest_verifier {
+        manifest_verifier.finish()?;
+    }
 
     info!("unpacked {total_entries} entries total");
     Ok(())
@@ -192,8 +215,10 @@ fn unpack_entry<'a, R: Read>(
     mut entry: tar::Entry<'_, R>,
     dst: PathBuf,
     dst_open_dir: Arc<File>,
+    manifest_verifier: Option<(&mut ManifestVerifier, &str)>,
 ) -> Result<()> {
-    let mode = match entry.header().entry_type() {
+    let kind = entry.header().entry_type();
+    let mode = match kind {
         GNUSparse | Regular => 0o644,
         _ => 0o755,
     };
@@ -210,7 +235,17 @@ fn unpack_entry<'a, R: Read>(
         }
         return Ok(());
     }
-    files_creator.schedule_create_at_dir(dst, mode, dst_open_dir, &mut entry)?;
+    match manifest_verifier {
+        Some((manifest_verifier, path)) if ManifestVerifier::is_listed_entry(kind) => {
+            // Hash the contents as they are streamed into the file, so they can be checked
+            // against the manifest once it is reached
+            let mut reader = HashingReader::new(&mut entry);
+            files_creator.schedule_create_at_dir(dst, mode, dst_open_dir, &mut reader)?;
+            let (size, hash) = reader.finish();
+            manifest_verifier.record(path.to_string(), size, hash)?;
+        }
+        _ => files_creator.schedule_create_at_dir(dst, mode, dst_open_dir, &mut entry)?,
+    }
 
     Ok(())
 }
@@ -313,13 +348,23 @@ fn validate_inside_dst(dst: &Path, file_dst: &Path) -> Result<PathBuf> {
 
 /// Unpacks snapshot from (potentially partial) `archive` and
 /// sends entry file paths through the `sender` channel
+///
+/// Entries are checked against the archive's manifest according to `manifest_verification`.
 pub(super) fn streaming_unpack_snapshot(
     input: impl Read,
     file_creator: Box<dyn FileCreator>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    manifest_verification: ManifestVerification,
 ) -> Result<()> {
-    unpack_snapshot_with_processors(input, file_creator, ledger_dir, account_paths, |_, _| {})
+    unpack_snapshot_with_processors(
+        input,
+        file_creator,
+        ledger_dir,
+        account_paths,
+        manifest_verification,
+        |_, _| {},
+    )
 }
 
 fn unpack_snapshot_with_processors<F>(
@@ -327,6 +372,7 @@ fn unpack_snapshot_with_processors<F>(
     file_creator: Box<dyn FileCreator>,
     ledger_dir: &Path,
     account_paths: &[PathBuf],
+    manifest_verification: ManifestVerification,
     mut accounts_path_processor: F,
 ) -> Result<()>
 where
@@ -340,8 +386,12 @@ where
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_APPARENT_SIZE,
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_ACTUAL_SIZE,
         MAX_SNAPSHOT_ARCHIVE_UNPACKED_COUNT,
+        ManifestVerifier::new(manifest_verification),
         |parts, kind| {
-            if is_valid_snapshot_archive_entry(parts, kind) {
+            if ManifestVerifier::is_manifest_entry(parts, kind) {
+                // The manifest is only needed while unpacking, don't write it out
+                UnpackPath::Ignore
+            } else if is_valid_snapshot_archive_entry(parts, kind) {
                 if let ["accounts", file] = parts {
                     // Randomly distribute the accounts files about the available `account_paths`,
                     let path_index = rng().random_range(0..account_paths.len());
@@ -427,6 +477,7 @@ pub(super) fn unpack_genesis(
         max_genesis_archive_unpacked_size,
         max_genesis_archive_unpacked_size,
         MAX_GENESIS_ARCHIVE_UNPACKED_COUNT,
+        None,
         |p, k| is_valid_genesis_archive_entry(unpack_dir, p, k),
     )
 }
@@ -455,6 +506,7 @@ fn is_valid_genesis_archive_entry<'a>(
 mod tests {
     use {
         super::*,
+        crate::manifest::{ManifestEntry, SnapshotManifest},
         agave_fs::{file_io::file_creator, io_setup::IoSetupState},
         assert_matches::assert_matches,
         std::io::BufReader,
@@ -702,15 +754,54 @@ mod tests {
     }
 
     fn finalize_and_unpack_snapshot(archive: tar::Builder<Vec<u8>>) -> Result<()> {
+        finalize_and_unpack_snapshot_with_manifest(archive, ManifestVerification::Skip)
+    }
+
+    fn finalize_and_unpack_snapshot_with_manifest(
+        archive: tar::Builder<Vec<u8>>,
+        manifest_verification: ManifestVerification,
+    ) -> Result<()> {
         let file_creator = file_creator(256, &IoSetupState::default(), |file_info| {
             Some(file_info.file)
         })?;
         with_finalize_and_unpack(archive, move |a, b| {
-            unpack_snapshot_with_processors(a, file_creator, b, &[PathBuf::new()], |_, _| {})
-                .map(|_| ())
+            unpack_snapshot_with_processors(
+                a,
+                file_creator,
+                b,
+                &[PathBuf::new()],
+                manifest_verification,
+                |_, _| {},
+            )
+            .map(|_| ())
         })
     }
 
+    fn append_version_and_manifest(
+        archive: &mut tar::Builder<Vec<u8>>,
+        version: &[u8],
+        manifest_version: &[u8],
+    ) {
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(ManifestEntry {
+            path: "version".to_string(),
+            size: manifest_version.len() as u64,
+            hash: blake3::hash(manifest_version),
+        });
+        let manifest = manifest.to_bytes();
+
+        let mut header = Header::new_gnu();
+        header.set_size(version.len() as u64);
+        archive
+            .append_data(&mut header, "version", version)
+            .unwrap();
+        let mut header = Header::new_gnu();
+        header.set_size(manifest.len() as u64);
+        archive
+            .append_data(&mut header, "manifest", manifest.as_slice())
+            .unwrap();
+    }
+
     fn finalize_and_unpack_genesis(archive: tar::Builder<Vec<u8>>) -> Result<()> {
         let file_creator = file_creator(0, &IoSetupState::default(), |file_info| {
             Some(file_info.file)
@@ -736,6 +827,63 @@ mod tests {
         assert_matches!(result, Ok(()));
     }
 
+    #[test]
+    fn test_archive_unpack_snapshot_manifest_ok() {
+        for manifest_verification in [
+            ManifestVerification::Skip,
+            ManifestVerification::IfPresent,
+            ManifestVerification::Required,
+        ] {
+            let mut archive = Builder::new(Vec::new());
+            append_version_and_manifest(&mut archive, &[1, 2, 3, 4], &[1, 2, 3, 4]);
+            let result = finalize_and_unpack_snapshot_with_manifest(archive, manifest_verification);
+            assert_matches!(result, Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_archive_unpack_snapshot_manifest_mismatch() {
+        let mut archive = Builder::new(Vec::new());
+        append_version_and_manifest(&mut archive, &[1, 2, 3, 4], &[1, 2, 3, 5]);
+        let result =
+            finalize_and_unpack_snapshot_with_manifest(archive, ManifestVerification::Required);
+        assert_matches!(
+            result,
+            Err(UnpackError::Manifest(ManifestError::HashMismatch { .. }))
+        );
+
+        // the mismatch goes unnoticed when not verifying
+        let mut archive = Builder::new(Vec::new());
+        append_version_and_manifest(&mut archive, &[1, 2, 3, 4], &[1, 2, 3, 5]);
+        let result =
+            finalize_and_unpack_snapshot_with_manifest(archive, ManifestVerification::Skip);
+        assert_matches!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_archive_unpack_snapshot_manifest_missing() {
+        let mut header = Header::new_gnu();
+        header.set_path("version").unwrap();
+        header.set_size(4);
+        header.set_cksum();
+        let data: &[u8] = &[1, 2, 3, 4];
+
+        let mut archive = Builder::new(Vec::new());
+        archive.append(&header, data).unwrap();
+        let result =
+            finalize_and_unpack_snapshot_with_manifest(archive, ManifestVerification::Required);
+        assert_matches!(
+            result,
+            Err(UnpackError::Manifest(ManifestError::MissingManifest))
+        );
+
+        let mut archive = Builder::new(Vec::new());
+        archive.append(&header, data).unwrap();
+        let result =
+            finalize_and_unpack_snapshot_with_manifest(archive, ManifestVerification::IfPresent);
+        assert_matches!(result, Ok(()));
+    }
+
     #[test]
     fn test_archive_unpack_genesis_ok() {
         let mut header = Header::new_gnu();
@@ -1008,6 +1156,7 @@ mod tests {
                 file_creator,
                 tmp,
                 &[tmp.join("accounts_dest")],
+                ManifestVerification::Skip,
                 |_, _| {},
             )
         });
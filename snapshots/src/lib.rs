@@ -12,6 +12,7 @@ mod archive_format;
 pub mod error;
 pub mod hardened_unpack;
 mod kind;
+pub mod manifest;
 pub mod paths;
 pub mod snapshot_archive_info;
 pub mod snapshot_config;
@@ -0,0 +1,550 @@
+//! Integrity manifest for snapshot archives
+//!
+//! If enabled, every file appended to a snapshot archive is hashed as it is written, and the
+//! resulting list of (path, size, hash) records is appended as the final entry of the archive.
+//! The manifest ends with a checksum line holding the number of entries and a hash over
+//! everything preceding it, so a truncated or corrupted manifest is rejected as well.  The
+//! manifest is not signed: it detects corruption, not deliberate tampering by whoever serves the
+//! archive.
+//!
+//! The manifest is plain text:
+//!
+//! ```text
+//! agave-snapshot-manifest v1
+//! <blake3 hex> <size> <path>
+//! ...
+//! end <num entries> <blake3 hex of the preceding lines>
+//! ```
+use {
+    crate::{paths::SNAPSHOT_MANIFEST_FILENAME, ArchiveFormat, ArchiveFormatDecompressor},
+    std::{
+        collections::HashMap,
+        fs::File,
+        io::{self, BufReader, Read},
+        path::Path,
+    },
+    tar::EntryType::{GNUSparse, Regular},
+    thiserror::Error,
+};
+
+const MANIFEST_HEADER: &str = "agave-snapshot-manifest v1";
+const MANIFEST_CHECKSUM: &str = "end";
+
+// The manifest is read into memory in one piece, so bound its size.  Archives are refused a
+// manifest when writing rather than producing one that cannot be read back.
+pub(crate) const MAX_MANIFEST_SIZE: u64 = 8 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum ManifestError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("malformed manifest: {0}")]
+    Malformed(String),
+
+    #[error("manifest checksum does not match its contents")]
+    ChecksumMismatch,
+
+    #[error("archive does not contain a manifest")]
+    MissingManifest,
+
+    #[error("archive contains more than one manifest")]
+    DuplicateManifest,
+
+    #[error("entry '{0}' found after the manifest")]
+    EntryAfterManifest(String),
+
+    #[error("entry '{0}' is not listed in the manifest")]
+    UnlistedEntry(String),
+
+    #[error("entry '{0}' is listed in the manifest but missing from the archive")]
+    MissingEntry(String),
+
+    #[error("entry '{path}' size mismatch: manifest: {expected}, archive: {actual}")]
+    SizeMismatch {
+        path: String,
+        expected: u64,
+        actual: u64,
+    },
+
+    #[error("entry '{path}' hash mismatch: manifest: {expected}, archive: {actual}")]
+    HashMismatch {
+        path: String,
+        expected: blake3::Hash,
+        actual: blake3::Hash,
+    },
+}
+
+/// How unpacking treats the manifest of a snapshot archive
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestVerification {
+    /// Do not check entries against the manifest
+    #[default]
+    Skip,
+    /// Check entries against the manifest, if the archive has one
+    IfPresent,
+    /// Check entries against the manifest, and reject archives without one
+    Required,
+}
+
+/// A single file in a snapshot archive
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub hash: blake3::Hash,
+}
+
+/// The list of files in a snapshot archive, in archive order
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SnapshotManifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl SnapshotManifest {
+    pub fn push(&mut self, entry: ManifestEntry) {
+        self.entries.push(entry);
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    /// Total size of all the files listed in the manifest
+    pub fn total_size(&self) -> u64 {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+
+    /// Serializes the manifest, including its checksum line
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut body = format!("{MANIFEST_HEADER}\n");
+        for entry in &self.entries {
+            body.push_str(&format!(
+                "{} {} {}\n",
+                entry.hash.to_hex(),
+                entry.size,
+                entry.path
+            ));
+        }
+        let checksum = blake3::hash(body.as_bytes());
+        body.push_str(&format!(
+            "{MANIFEST_CHECKSUM} {} {}\n",
+            self.entries.len(),
+            checksum.to_hex()
+        ));
+        body.into_bytes()
+    }
+
+    /// Deserializes a manifest, checking its checksum line
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ManifestError> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|err| ManifestError::Malformed(format!("not utf-8: {err}")))?;
+        let body_len = text
+            .trim_end_matches('\n')
+            .rfind('\n')
+            .map(|pos| pos.saturating_add(1))
+            .ok_or_else(|| ManifestError::Malformed("missing checksum".to_string()))?;
+        let (body, checksum) = text.split_at(body_len);
+
+        let mut lines = body.lines();
+        if lines.next() != Some(MANIFEST_HEADER) {
+            return Err(ManifestError::Malformed("unknown header".to_string()));
+        }
+        let entries = lines
+            .map(|line| {
+                let mut fields = line.splitn(3, ' ');
+                let (Some(hash), Some(size), Some(path)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(ManifestError::Malformed(format!("invalid entry: {line}")));
+                };
+                Ok(ManifestEntry {
+                    path: path.to_string(),
+                    size: size
+                        .parse()
+                        .map_err(|_| ManifestError::Malformed(format!("invalid size: {line}")))?,
+                    hash: blake3::Hash::from_hex(hash)
+                        .map_err(|_| ManifestError::Malformed(format!("invalid hash: {line}")))?,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut fields = checksum.trim_end_matches('\n').split(' ');
+        let (Some(MANIFEST_CHECKSUM), Some(num_entries), Some(hash), None) =
+            (fields.next(), fields.next(), fields.next(), fields.next())
+        else {
+            return Err(ManifestError::Malformed(format!(
+                "invalid checksum: {checksum}"
+            )));
+        };
+        let num_entries_matches = num_entries
+            .parse::<usize>()
+            .is_ok_and(|num_entries| num_entries == entries.len());
+        let hash_matches =
+            blake3::Hash::from_hex(hash).is_ok_and(|hash| hash == blake3::hash(body.as_bytes()));
+        if !num_entries_matches || !hash_matches {
+            return Err(ManifestError::ChecksumMismatch);
+        }
+
+        Ok(Self { entries })
+    }
+}
+
+/// Hashes and counts the bytes read through it
+pub(crate) struct HashingReader<R> {
+    inner: R,
+    hasher: blake3::Hasher,
+    len: u64,
+}
+
+impl<R> HashingReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: blake3::Hasher::new(),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of bytes read so far, and their hash
+    pub(crate) fn finish(&self) -> (u64, blake3::Hash) {
+        (self.len, self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.len = self.len.saturating_add(n as u64);
+        Ok(n)
+    }
+}
+
+/// Checks the entries of a snapshot archive against its manifest while the archive is streamed
+///
+/// Since the manifest is the final entry, entries are recorded as they are seen and compared
+/// once the manifest is reached.
+pub(crate) struct ManifestVerifier {
+    required: bool,
+    observed: Vec<ManifestEntry>,
+    manifest: Option<SnapshotManifest>,
+}
+
+impl ManifestVerifier {
+    pub(crate) fn new(verification: ManifestVerification) -> Option<Self> {
+        let required = match verification {
+            ManifestVerification::Skip => return None,
+            ManifestVerification::IfPresent => false,
+            ManifestVerification::Required => true,
+        };
+        Some(Self {
+            required,
+            observed: Vec::new(),
+            manifest: None,
+        })
+    }
+
+    /// Returns true if this archive entry is the manifest
+    pub(crate) fn is_manifest_entry(parts: &[&str], kind: tar::EntryType) -> bool {
+        matches!((parts, kind), ([SNAPSHOT_MANIFEST_FILENAME], Regular))
+    }
+
+    /// Returns true if this archive entry is a file that should be listed in the manifest
+    pub(crate) fn is_listed_entry(kind: tar::EntryType) -> bool {
+        matches!(kind, GNUSparse | Regular)
+    }
+
+    /// Records a file entry seen in the archive
+    pub(crate) fn record(
+        &mut self,
+        path: String,
+        size: u64,
+        hash: blake3::Hash,
+    ) -> Result<(), ManifestError> {
+        if self.manifest.is_some() {
+            return Err(ManifestError::EntryAfterManifest(path));
+        }
+        self.observed.push(ManifestEntry { path, size, hash });
+        Ok(())
+    }
+
+    /// Reads the manifest entry and checks all entries recorded so far against it
+    pub(crate) fn check_manifest(&mut self, contents: impl Read) -> Result<(), ManifestError> {
+        if self.manifest.is_some() {
+            return Err(ManifestError::DuplicateManifest);
+        }
+        let mut bytes = Vec::new();
+        contents
+            .take(MAX_MANIFEST_SIZE.saturating_add(1))
+            .read_to_end(&mut bytes)?;
+        if bytes.len() as u64 > MAX_MANIFEST_SIZE {
+            return Err(ManifestError::Malformed(format!(
+                "larger than {MAX_MANIFEST_SIZE} bytes"
+            )));
+        }
+        let manifest = SnapshotManifest::from_bytes(&bytes)?;
+
+        let mut expected: HashMap<_, _> = manifest
+            .entries()
+            .iter()
+            .map(|entry| (entry.path.as_str(), entry))
+            .collect();
+        for observed in self.observed.drain(..) {
+            let expected = expected
+                .remove(observed.path.as_str())
+                .ok_or_else(|| ManifestError::UnlistedEntry(observed.path.clone()))?;
+            if expected.size != observed.size {
+                return Err(ManifestError::SizeMismatch {
+                    path: observed.path,
+                    expected: expected.size,
+                    actual: observed.size,
+                });
+            }
+            if expected.hash != observed.hash {
+                return Err(ManifestError::HashMismatch {
+                    path: observed.path,
+                    expected: expected.hash,
+                    actual: observed.hash,
+                });
+            }
+        }
+        if let Some(missing) = expected.into_keys().next() {
+            return Err(ManifestError::MissingEntry(missing.to_string()));
+        }
+
+        self.manifest = Some(manifest);
+        Ok(())
+    }
+
+    /// Completes verification once the whole archive has been streamed
+    ///
+    /// Returns the manifest, if the archive had one.
+    pub(crate) fn finish(self) -> Result<Option<SnapshotManifest>, ManifestError> {
+        if self.manifest.is_none() {
+            if self.required {
+                return Err(ManifestError::MissingManifest);
+            }
+            if let Some(unchecked) = self.observed.first() {
+                log::warn!(
+                    "snapshot archive has no manifest, entry '{}' and {} others were not verified",
+                    unchecked.path,
+                    self.observed.len().saturating_sub(1),
+                );
+            }
+        }
+        Ok(self.manifest)
+    }
+}
+
+/// Verifies the snapshot archive at `archive_path` against its manifest
+///
+/// The archive is decompressed and every file is hashed in a single streaming pass, without
+/// writing anything to disk.  Returns the verified manifest.
+pub fn verify_snapshot_archive(
+    archive_path: impl AsRef<Path>,
+    archive_format: ArchiveFormat,
+) -> Result<SnapshotManifest, ManifestError> {
+    let file = File::open(archive_path)?;
+    let decompressor = ArchiveFormatDecompressor::new(archive_format, BufReader::new(file))?;
+    let mut verifier = ManifestVerifier::new(ManifestVerification::Required)
+        .expect("verifier is created for required verification");
+
+    let mut archive = tar::Archive::new(decompressor);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.display().to_string();
+        let kind = entry.header().entry_type();
+        if ManifestVerifier::is_manifest_entry(&[path.as_str()], kind) {
+            verifier.check_manifest(&mut entry)?;
+        } else if ManifestVerifier::is_listed_entry(kind) {
+            let mut reader = HashingReader::new(&mut entry);
+            io::copy(&mut reader, &mut io::sink())?;
+            let (size, hash) = reader.finish();
+            verifier.record(path, size, hash)?;
+        }
+    }
+
+    Ok(verifier
+        .finish()?
+        .expect("manifest is present for required verification"))
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        assert_matches::assert_matches,
+        tar::{Builder, Header},
+    };
+
+    fn manifest_entry(path: &str, data: &[u8]) -> ManifestEntry {
+        ManifestEntry {
+            path: path.to_string(),
+            size: data.len() as u64,
+            hash: blake3::hash(data),
+        }
+    }
+
+    fn append_file(archive: &mut Builder<Vec<u8>>, path: &str, data: &[u8]) {
+        let mut header = Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o644);
+        archive.append_data(&mut header, path, data).unwrap();
+    }
+
+    fn write_archive(dir: &Path, archive: Builder<Vec<u8>>) -> std::path::PathBuf {
+        let path = dir.join("snapshot.tar");
+        std::fs::write(&path, archive.into_inner().unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_manifest_roundtrip() {
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(manifest_entry("version", b"1.2.0"));
+        manifest.push(manifest_entry("accounts/123.456", &[7; 100]));
+
+        let bytes = manifest.to_bytes();
+        assert_eq!(SnapshotManifest::from_bytes(&bytes).unwrap(), manifest);
+        assert_eq!(manifest.total_size(), 105);
+
+        let empty = SnapshotManifest::default();
+        assert_eq!(
+            SnapshotManifest::from_bytes(&empty.to_bytes()).unwrap(),
+            empty
+        );
+    }
+
+    #[test]
+    fn test_manifest_corrupted() {
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(manifest_entry("version", b"1.2.0"));
+        manifest.push(manifest_entry("accounts/123.456", &[7; 100]));
+        let text = String::from_utf8(manifest.to_bytes()).unwrap();
+
+        // edited entry
+        let edited = text.replace(" 100 ", " 101 ");
+        assert_matches!(
+            SnapshotManifest::from_bytes(edited.as_bytes()),
+            Err(ManifestError::ChecksumMismatch)
+        );
+
+        // dropped entry
+        let mut lines: Vec<_> = text.lines().collect();
+        lines.remove(2);
+        let dropped = lines.join("\n") + "\n";
+        assert_matches!(
+            SnapshotManifest::from_bytes(dropped.as_bytes()),
+            Err(ManifestError::ChecksumMismatch)
+        );
+
+        // truncated
+        let truncated = text.lines().take(2).collect::<Vec<_>>().join("\n");
+        assert_matches!(
+            SnapshotManifest::from_bytes(truncated.as_bytes()),
+            Err(ManifestError::Malformed(_))
+        );
+    }
+
+    #[test]
+    fn test_verify_snapshot_archive() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let version = b"1.2.0";
+        let storage = [7; 100];
+
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(manifest_entry("version", version));
+        manifest.push(manifest_entry("accounts/123.456", &storage));
+
+        let mut archive = Builder::new(Vec::new());
+        append_file(&mut archive, "version", version);
+        append_file(&mut archive, "accounts/123.456", &storage);
+        append_file(
+            &mut archive,
+            SNAPSHOT_MANIFEST_FILENAME,
+            &manifest.to_bytes(),
+        );
+        let archive_path = write_archive(temp_dir.path(), archive);
+
+        let verified = verify_snapshot_archive(archive_path, ArchiveFormat::Tar).unwrap();
+        assert_eq!(verified, manifest);
+    }
+
+    #[test]
+    fn test_verify_snapshot_archive_corrupt() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let version = b"1.2.0";
+
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(manifest_entry("version", version));
+
+        let mut archive = Builder::new(Vec::new());
+        append_file(&mut archive, "version", b"1.2.1");
+        append_file(
+            &mut archive,
+            SNAPSHOT_MANIFEST_FILENAME,
+            &manifest.to_bytes(),
+        );
+        let archive_path = write_archive(temp_dir.path(), archive);
+
+        assert_matches!(
+            verify_snapshot_archive(archive_path, ArchiveFormat::Tar),
+            Err(ManifestError::HashMismatch { path, .. }) if path == "version"
+        );
+    }
+
+    #[test]
+    fn test_verify_snapshot_archive_missing_manifest() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let mut archive = Builder::new(Vec::new());
+        append_file(&mut archive, "version", b"1.2.0");
+        let archive_path = write_archive(temp_dir.path(), archive);
+
+        assert_matches!(
+            verify_snapshot_archive(archive_path, ArchiveFormat::Tar),
+            Err(ManifestError::MissingManifest)
+        );
+    }
+
+    #[test]
+    fn test_manifest_verifier_entries() {
+        let mut manifest = SnapshotManifest::default();
+        manifest.push(manifest_entry("version", b"1.2.0"));
+        let bytes = manifest.to_bytes();
+
+        // unlisted entry
+        let mut verifier = ManifestVerifier::new(ManifestVerification::Required).unwrap();
+        let entry = manifest_entry("version", b"1.2.0");
+        verifier.record(entry.path, entry.size, entry.hash).unwrap();
+        let entry = manifest_entry("accounts/1.1", b"data");
+        verifier.record(entry.path, entry.size, entry.hash).unwrap();
+        assert_matches!(
+            verifier.check_manifest(bytes.as_slice()),
+            Err(ManifestError::UnlistedEntry(path)) if path == "accounts/1.1"
+        );
+
+        // missing entry
+        let mut verifier = ManifestVerifier::new(ManifestVerification::Required).unwrap();
+        assert_matches!(
+            verifier.check_manifest(bytes.as_slice()),
+            Err(ManifestError::MissingEntry(path)) if path == "version"
+        );
+
+        // entry after manifest
+        let mut verifier = ManifestVerifier::new(ManifestVerification::Required).unwrap();
+        let entry = manifest_entry("version", b"1.2.0");
+        verifier.record(entry.path, entry.size, entry.hash).unwrap();
+        verifier.check_manifest(bytes.as_slice()).unwrap();
+        let entry = manifest_entry("accounts/1.1", b"data");
+        assert_matches!(
+            verifier.record(entry.path, entry.size, entry.hash),
+            Err(ManifestError::EntryAfterManifest(_))
+        );
+
+        // optional manifest
+        assert!(ManifestVerifier::new(ManifestVerification::Skip).is_none());
+        let verifier = ManifestVerifier::new(ManifestVerification::IfPresent).unwrap();
+        assert_matches!(verifier.finish(), Ok(None));
+    }
+}
@@ -20,6 +20,7 @@ use {
 
 pub const SNAPSHOT_STATUS_CACHE_FILENAME: &str = "status_cache";
 pub const SNAPSHOT_VERSION_FILENAME: &str = "version";
+pub const SNAPSHOT_MANIFEST_FILENAME: &str = "manifest";
 pub const SNAPSHOT_FASTBOOT_VERSION_FILENAME: &str = "fastboot_version";
 pub const SNAPSHOT_ACCOUNTS_HARDLINKS: &str = "accounts_hardlinks";
 pub const SNAPSHOT_ARCHIVE_DOWNLOAD_DIR: &str = "remote";
@@ -29,9 +30,8 @@ pub const SNAPSHOT_OBSOLETE_ACCOUNTS_FILENAME: &str = "obsolete_accounts";
 /// This is also where the bank state is located in the snapshot archive.
 pub const BANK_SNAPSHOTS_DIR: &str = "snapshots";
 pub const TMP_SNAPSHOT_ARCHIVE_PREFIX: &str = "tmp-snapshot-archive-";
-pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str =
-    r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
-pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4)$";
+pub const FULL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^snapshot-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|tar\.gz|tar\.bz2|tar)$";
+pub const INCREMENTAL_SNAPSHOT_ARCHIVE_FILENAME_REGEX: &str = r"^incremental-snapshot-(?P<base>[[:digit:]]+)-(?P<slot>[[:digit:]]+)-(?P<hash>[[:alnum:]]+)\.(?P<ext>tar\.zst|tar\.lz4|tar\.gz|tar\.bz2|tar)$";
 
 /// Get the `&str` from a `&Path`
 pub fn path_to_file_name_str(path: &Path) -> Result<&str> {
@@ -287,6 +287,27 @@ mod tests {
             .unwrap(),
             (45, SnapshotHash(Hash::default()), ArchiveFormat::TarLz4)
         );
+        assert_eq!(
+            parse_full_snapshot_archive_filename(&format!("snapshot-46-{}.tar", Hash::default()))
+                .unwrap(),
+            (46, SnapshotHash(Hash::default()), ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            parse_full_snapshot_archive_filename(&format!(
+                "snapshot-47-{}.tar.gz",
+                Hash::default()
+            ))
+            .unwrap(),
+            (47, SnapshotHash(Hash::default()), ArchiveFormat::TarGzip)
+        );
+        assert_eq!(
+            parse_full_snapshot_archive_filename(&format!(
+                "snapshot-48-{}.tar.bz2",
+                Hash::default()
+            ))
+            .unwrap(),
+            (48, SnapshotHash(Hash::default()), ArchiveFormat::TarBzip2)
+        );
 
         assert!(parse_full_snapshot_archive_filename("invalid").is_err());
         assert!(
@@ -364,6 +385,27 @@ mod tests {
                 ArchiveFormat::TarLz4
             )
         );
+        assert_eq!(
+            parse_incremental_snapshot_archive_filename(&format!(
+                "incremental-snapshot-46-567-{}.tar",
+                Hash::default()
+            ))
+            .unwrap(),
+            (46, 567, SnapshotHash(Hash::default()), ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            parse_incremental_snapshot_archive_filename(&format!(
+                "incremental-snapshot-47-678-{}.tar.gz",
+                Hash::default()
+            ))
+            .unwrap(),
+            (
+                47,
+                678,
+                SnapshotHash(Hash::default()),
+                ArchiveFormat::TarGzip
+            )
+        );
 
         assert!(parse_incremental_snapshot_archive_filename("invalid").is_err());
         assert!(parse_incremental_snapshot_archive_filename(&format!(
@@ -1,5 +1,8 @@
 use {
-    super::{ArchiveFormat, SnapshotInterval, SnapshotVersion, ZstdConfig},
+    super::{
+        manifest::ManifestVerification, ArchiveFormat, SnapshotInterval, SnapshotVersion,
+        ZstdConfig,
+    },
     std::{
         num::{NonZeroU64, NonZeroUsize},
         path::PathBuf,
@@ -42,6 +45,13 @@ pub struct SnapshotConfig {
     /// Snapshot version to generate
     pub snapshot_version: SnapshotVersion,
 
+    /// Append an integrity manifest to generated snapshot archives
+    /// NOTE: Validators prior to v4.0 reject archives containing a manifest
+    pub archive_manifest: bool,
+
+    /// How the manifest of snapshot archives is checked when loading them at startup
+    pub manifest_verification: ManifestVerification,
+
     /// Maximum number of full snapshot archives to retain
     pub maximum_full_snapshot_archives_to_retain: NonZeroUsize,
 
@@ -70,6 +80,8 @@ impl Default for SnapshotConfig {
                 config: ZstdConfig::default(),
             },
             snapshot_version: SnapshotVersion::default(),
+            archive_manifest: false,
+            manifest_verification: ManifestVerification::default(),
             maximum_full_snapshot_archives_to_retain: DEFAULT_MAX_FULL_SNAPSHOT_ARCHIVES_TO_RETAIN,
             maximum_incremental_snapshot_archives_to_retain:
                 DEFAULT_MAX_INCREMENTAL_SNAPSHOT_ARCHIVES_TO_RETAIN,
@@ -1,6 +1,7 @@
 use {
     crate::{
         hardened_unpack::{self, UnpackError},
+        manifest::ManifestVerification,
         ArchiveFormat, ArchiveFormatDecompressor,
     },
     agave_fs::{buffered_reader, file_io::file_creator, io_setup::IoSetupState, FileInfo},
@@ -25,6 +26,8 @@ const MAX_SNAPSHOT_READER_BUF_SIZE: usize = 128 * 1024 * 1024;
 const MAX_UNPACK_WRITE_BUF_SIZE: usize = 512 * 1024 * 1024;
 
 /// Streams unpacked files across channel
+///
+/// Entries are checked against the archive's manifest according to `manifest_verification`.
 pub fn streaming_unarchive_snapshot(
     file_sender: Sender<FileInfo>,
     account_paths: Vec<PathBuf>,
@@ -32,6 +35,7 @@ pub fn streaming_unarchive_snapshot(
     snapshot_archive_path: PathBuf,
     archive_format: ArchiveFormat,
     io_setup: IoSetupState,
+    manifest_verification: ManifestVerification,
 ) -> JoinHandle<Result<(), UnpackError>> {
     let do_unpack = move |archive_path: &Path| {
         let (decompressor, file_creator) = {
@@ -64,6 +68,7 @@ pub fn streaming_unarchive_snapshot(
             file_creator,
             ledger_dir.as_path(),
             &account_paths,
+            manifest_verification,
         )
     };
 
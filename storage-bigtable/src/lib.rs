@@ -4,6 +4,7 @@
 use {
     crate::bigtable::RowKey,
     agave_reserved_account_keys::ReservedAccountKeys,
+    async_trait::async_trait,
     log::*,
     serde::{Deserialize, Serialize},
     solana_clock::{Slot, UnixTimestamp},
@@ -40,16 +41,28 @@ use {
 #[macro_use]
 extern crate solana_metrics;
 
+pub use {
+    adapter::LedgerStorageAdapter,
+    object_store::{FileObjectStore, ObjectStore, ObjectStoreLedgerStorage},
+    s3::S3ObjectStore,
+};
+
 mod access_token;
+mod adapter;
 mod bigtable;
 mod compression;
+pub mod object_store;
 mod root_ca_certificate;
+mod s3;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("BigTable: {0}")]
     BigTableError(bigtable::Error),
 
+    #[error("Object store: {0}")]
+    ObjectStoreError(object_store::Error),
+
     #[error("I/O Error: {0}")]
     IoError(std::io::Error),
 
@@ -72,6 +85,12 @@ impl std::convert::From<bigtable::Error> for Error {
     }
 }
 
+impl std::convert::From<object_store::Error> for Error {
+    fn from(err: object_store::Error) -> Self {
+        Self::ObjectStoreError(err)
+    }
+}
+
 impl std::convert::From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
         Self::IoError(err)
@@ -381,6 +400,237 @@ impl From<LegacyTransactionByAddrInfo> for TransactionByAddrInfo {
     }
 }
 
+// The rows written to each table when uploading a block
+struct BlockRows {
+    tx: Vec<(RowKey, TransactionInfo)>,
+    tx_by_addr: Vec<(RowKey, tx_by_addr::TransactionByAddr)>,
+    entries: Option<(RowKey, entries::Entries)>,
+    block: (RowKey, generated::ConfirmedBlock),
+    num_transactions: usize,
+    num_entries: usize,
+}
+
+impl BlockRows {
+    fn new(slot: Slot, confirmed_block: VersionedConfirmedBlockWithEntries) -> Self {
+        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
+        let VersionedConfirmedBlockWithEntries {
+            block: confirmed_block,
+            entries,
+        } = confirmed_block;
+
+        let reserved_account_keys = ReservedAccountKeys::new_all_activated();
+        let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
+        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
+            let err = meta.status.clone().err();
+            let index = index as u32;
+            let signature = transaction.signatures[0];
+            let memo = extract_and_fmt_memos(transaction_with_meta);
+
+            for address in transaction_with_meta.account_keys().iter() {
+                // Historical note that previously only a set of sysvar ids were
+                // skipped from being uploaded. Now we skip uploaded for the set
+                // of all reserved account keys which will continue to grow in
+                // the future.
+                if !reserved_account_keys.is_reserved(address) {
+                    by_addr
+                        .entry(address)
+                        .or_default()
+                        .push(TransactionByAddrInfo {
+                            signature,
+                            err: err.clone(),
+                            index,
+                            memo: memo.clone(),
+                            block_time: confirmed_block.block_time,
+                        });
+                }
+            }
+
+            tx_cells.push((
+                signature.to_string(),
+                TransactionInfo {
+                    slot,
+                    index,
+                    err,
+                    memo,
+                },
+            ));
+        }
+
+        let tx_by_addr_cells: Vec<_> = by_addr
+            .into_iter()
+            .map(|(address, transaction_info_by_addr)| {
+                (
+                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
+                    tx_by_addr::TransactionByAddr {
+                        tx_by_addrs: transaction_info_by_addr
+                            .into_iter()
+                            .map(|by_addr| by_addr.into())
+                            .collect(),
+                    },
+                )
+            })
+            .collect();
+
+        let num_entries = entries.len();
+        let entry_cell = (num_entries > 0).then(|| {
+            (
+                slot_to_entries_key(slot),
+                entries::Entries {
+                    entries: entries.into_iter().enumerate().map(Into::into).collect(),
+                },
+            )
+        });
+
+        Self {
+            tx: tx_cells,
+            tx_by_addr: tx_by_addr_cells,
+            entries: entry_cell,
+            num_transactions: confirmed_block.transactions.len(),
+            num_entries,
+            block: (slot_to_blocks_key(slot), confirmed_block.into()),
+        }
+    }
+}
+
+// The `tx-by-addr` row keys that may have been written when uploading a block, and the `tx` rows
+// that are expected to have been written, by signature
+fn uploaded_transaction_rows(
+    slot: Slot,
+    confirmed_block: &ConfirmedBlock,
+) -> (Vec<RowKey>, HashMap<String, UploadedTransaction>) {
+    let mut addresses: HashSet<&Pubkey> = HashSet::new();
+    let mut expected_tx_infos: HashMap<String, UploadedTransaction> = HashMap::new();
+    for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
+        match transaction_with_meta {
+            TransactionWithStatusMeta::MissingMetadata(transaction) => {
+                let signature = transaction.signatures[0];
+                let index = index as u32;
+                let err = None;
+
+                for address in transaction.message.account_keys.iter() {
+                    // We could skip deleting addresses that are known
+                    // reserved keys but it's hard to be sure whether we
+                    // previously uploaded rows for reserved keys or not. So
+                    // to ensure everything is deleted properly, we attempt
+                    // to delete rows for all addresses even if they might
+                    // not have been uploaded.
+                    addresses.insert(address);
+                }
+
+                expected_tx_infos.insert(
+                    signature.to_string(),
+                    UploadedTransaction { slot, index, err },
+                );
+            }
+            TransactionWithStatusMeta::Complete(tx_with_meta) => {
+                let VersionedTransactionWithStatusMeta { transaction, meta } = tx_with_meta;
+                let signature = transaction.signatures[0];
+                let index = index as u32;
+                let err = meta.status.clone().err();
+
+                for address in tx_with_meta.account_keys().iter() {
+                    // We could skip deleting addresses that are known
+                    // reserved keys but it's hard to be sure whether we
+                    // previously uploaded rows for reserved keys or not. So
+                    // to ensure everything is deleted properly, we attempt
+                    // to delete rows for all addresses even if they might
+                    // not have been uploaded.
+                    addresses.insert(address);
+                }
+
+                expected_tx_infos.insert(
+                    signature.to_string(),
+                    UploadedTransaction { slot, index, err },
+                );
+            }
+        }
+    }
+
+    let address_slot_rows = addresses
+        .into_iter()
+        .map(|address| format!("{}/{}", address, slot_to_tx_by_addr_key(slot)))
+        .collect();
+    (address_slot_rows, expected_tx_infos)
+}
+
+// Find the transaction described by a `tx` row in its block
+fn confirmed_transaction_in_block(
+    signature: &Signature,
+    slot: Slot,
+    index: u32,
+    block: ConfirmedBlock,
+) -> Option<ConfirmedTransactionWithStatusMeta> {
+    match block.transactions.into_iter().nth(index as usize) {
+        None => {
+            // report this somewhere actionable?
+            warn!("Transaction info for {signature} is corrupt");
+            None
+        }
+        Some(tx_with_meta) => {
+            if tx_with_meta.transaction_signature() != signature {
+                warn!("Transaction info or confirmed block for {signature} is corrupt");
+                None
+            } else {
+                Some(ConfirmedTransactionWithStatusMeta {
+                    slot,
+                    tx_with_meta,
+                    block_time: block.block_time,
+                    index,
+                })
+            }
+        }
+    }
+}
+
+// The bounds of a `get_confirmed_signatures_for_address()` query
+struct SignaturesForAddressRange {
+    first_slot: Slot,
+    before_transaction_index: u32,
+    last_slot: Slot,
+    until_transaction_index: u32,
+}
+
+impl SignaturesForAddressRange {
+    // Append the signatures of the `tx-by-addr` row for `slot` to `infos`, most recent first.
+    // Returns true once `limit` signatures have been collected
+    fn push_row(
+        &self,
+        infos: &mut Vec<(ConfirmedTransactionStatusWithSignature, u32)>,
+        slot: Slot,
+        mut cell_data: Vec<TransactionByAddrInfo>,
+        limit: usize,
+    ) -> bool {
+        cell_data.reverse();
+        for tx_by_addr_info in cell_data.into_iter() {
+            // Filter out records before `before_transaction_index`
+            if slot == self.first_slot && tx_by_addr_info.index >= self.before_transaction_index {
+                continue;
+            }
+            // Filter out records after `until_transaction_index`
+            if slot == self.last_slot && tx_by_addr_info.index <= self.until_transaction_index {
+                continue;
+            }
+            infos.push((
+                ConfirmedTransactionStatusWithSignature {
+                    signature: tx_by_addr_info.signature,
+                    slot,
+                    err: tx_by_addr_info.err,
+                    memo: tx_by_addr_info.memo,
+                    block_time: tx_by_addr_info.block_time,
+                    index: tx_by_addr_info.index,
+                },
+                tx_by_addr_info.index,
+            ));
+            // Respect limit
+            if infos.len() >= limit {
+                return true;
+            }
+        }
+        false
+    }
+}
+
 pub const DEFAULT_INSTANCE_NAME: &str = "solana-ledger";
 pub const DEFAULT_APP_PROFILE_ID: &str = "default";
 pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024; // 64MB
@@ -399,6 +649,9 @@ pub struct LedgerStorageConfig {
     pub instance_name: String,
     pub app_profile_id: String,
     pub max_message_size: usize,
+    /// Stores the ledger in an object store instead of BigTable, see
+    /// `ObjectStoreLedgerStorage::open()`. The BigTable settings are ignored when this is set
+    pub storage_url: Option<String>,
 }
 
 impl Default for LedgerStorageConfig {
@@ -410,6 +663,7 @@ impl Default for LedgerStorageConfig {
             instance_name: DEFAULT_INSTANCE_NAME.to_string(),
             app_profile_id: DEFAULT_APP_PROFILE_ID.to_string(),
             max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            storage_url: None,
         }
     }
 }
@@ -442,10 +696,11 @@ impl LedgerStorageStats {
     }
 }
 
+/// Handle to the historical ledger storage, backed by BigTable or by any other
+/// `LedgerStorageAdapter`
 #[derive(Clone)]
 pub struct LedgerStorage {
-    connection: bigtable::BigTableConnection,
-    stats: Arc<LedgerStorageStats>,
+    adapter: Arc<dyn LedgerStorageAdapter>,
 }
 
 impl LedgerStorage {
@@ -463,6 +718,146 @@ impl LedgerStorage {
         .await
     }
 
+    pub fn new_for_emulator(
+        instance_name: &str,
+        app_profile_id: &str,
+        endpoint: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Self> {
+        BigTableLedgerStorage::new_for_emulator(instance_name, app_profile_id, endpoint, timeout)
+            .map(Self::new_with_adapter)
+    }
+
+    pub async fn new_with_config(config: LedgerStorageConfig) -> Result<Self> {
+        if let Some(storage_url) = &config.storage_url {
+            return ObjectStoreLedgerStorage::open(storage_url, config.read_only)
+                .map(Self::new_with_adapter);
+        }
+        BigTableLedgerStorage::new_with_config(config)
+            .await
+            .map(Self::new_with_adapter)
+    }
+
+    pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
+        Self::new_with_config(LedgerStorageConfig {
+            credential_type: CredentialType::Stringified(credential),
+            ..LedgerStorageConfig::default()
+        })
+        .await
+    }
+
+    pub fn new_with_adapter(adapter: impl LedgerStorageAdapter + 'static) -> Self {
+        Self {
+            adapter: Arc::new(adapter),
+        }
+    }
+
+    /// Return the available slot that contains a block
+    pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        self.adapter.get_first_available_block().await
+    }
+
+    /// Fetch the next slots after the provided slot that contains a block
+    ///
+    /// start_slot: slot to start the search from (inclusive)
+    /// limit: stop after this many slots have been found
+    pub async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        self.adapter.get_confirmed_blocks(start_slot, limit).await
+    }
+
+    /// Fetch the confirmed block from the desired slot
+    pub async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        self.adapter.get_confirmed_block(slot).await
+    }
+
+    /// Does the confirmed block exist in the ledger storage
+    pub async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        self.adapter.confirmed_block_exists(slot).await
+    }
+
+    /// Fetch the entries of the block at the desired slot
+    pub async fn get_entries(
+        &self,
+        slot: Slot,
+    ) -> Result<impl Iterator<Item = EntrySummary> + use<>> {
+        Ok(self.adapter.get_entries(slot).await?.into_iter())
+    }
+
+    pub async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        self.adapter.get_signature_status(signature).await
+    }
+
+    /// Fetch a confirmed transaction
+    pub async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        self.adapter.get_confirmed_transaction(signature).await
+    }
+
+    /// Get confirmed signatures for the provided address, in descending ledger order
+    ///
+    /// address: address to search for
+    /// before_signature: start with the first signature older than this one
+    /// until_signature: end with the last signature more recent than this one
+    /// limit: stop after this many signatures
+    pub async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<
+        Vec<(
+            ConfirmedTransactionStatusWithSignature,
+            u32, /*slot index*/
+        )>,
+    > {
+        self.adapter
+            .get_confirmed_signatures_for_address(address, before_signature, until_signature, limit)
+            .await
+    }
+
+    /// Upload a new confirmed block and associated meta data.
+    pub async fn upload_confirmed_block(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlock,
+    ) -> Result<()> {
+        self.upload_confirmed_block_with_entries(
+            slot,
+            VersionedConfirmedBlockWithEntries {
+                block: confirmed_block,
+                entries: vec![],
+            },
+        )
+        .await
+    }
+
+    pub async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        self.adapter
+            .upload_confirmed_block_with_entries(slot, confirmed_block)
+            .await
+    }
+
+    // Delete a confirmed block and associated meta data.
+    pub async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
+        self.adapter.delete_confirmed_block(slot, dry_run).await
+    }
+}
+
+/// Ledger storage in a BigTable instance
+#[derive(Clone)]
+pub struct BigTableLedgerStorage {
+    connection: bigtable::BigTableConnection,
+    stats: Arc<LedgerStorageStats>,
+}
+
+impl BigTableLedgerStorage {
     pub fn new_for_emulator(
         instance_name: &str,
         app_profile_id: &str,
@@ -491,6 +886,7 @@ impl LedgerStorage {
             app_profile_id,
             credential_type,
             max_message_size,
+            storage_url: _,
         } = config;
         let connection = bigtable::BigTableConnection::new(
             instance_name.as_str(),
@@ -504,14 +900,6 @@ impl LedgerStorage {
         Ok(Self { stats, connection })
     }
 
-    pub async fn new_with_stringified_credential(credential: String) -> Result<Self> {
-        Self::new_with_config(LedgerStorageConfig {
-            credential_type: CredentialType::Stringified(credential),
-            ..LedgerStorageConfig::default()
-        })
-        .await
-    }
-
     /// Return the available slot that contains a block
     pub async fn get_first_available_block(&self) -> Result<Option<Slot>> {
         trace!("LedgerStorage::get_first_available_block request received");
@@ -719,26 +1107,9 @@ impl LedgerStorage {
 
         // Load the block and return the transaction
         let block = self.get_confirmed_block(slot).await?;
-        match block.transactions.into_iter().nth(index as usize) {
-            None => {
-                // report this somewhere actionable?
-                warn!("Transaction info for {signature} is corrupt");
-                Ok(None)
-            }
-            Some(tx_with_meta) => {
-                if tx_with_meta.transaction_signature() != signature {
-                    warn!("Transaction info or confirmed block for {signature} is corrupt");
-                    Ok(None)
-                } else {
-                    Ok(Some(ConfirmedTransactionWithStatusMeta {
-                        slot,
-                        tx_with_meta,
-                        block_time: block.block_time,
-                        index,
-                    }))
-                }
-            }
-        }
+        Ok(confirmed_transaction_in_block(
+            signature, slot, index, block,
+        ))
     }
 
     /// Get confirmed signatures for the provided address, in descending ledger order
@@ -796,6 +1167,12 @@ impl LedgerStorage {
             }
         };
 
+        let range = SignaturesForAddressRange {
+            first_slot,
+            before_transaction_index,
+            last_slot,
+            until_transaction_index,
+        };
         let mut infos = vec![];
 
         let starting_slot_tx_len = bigtable
@@ -831,7 +1208,7 @@ impl LedgerStorage {
             )
             .await?;
 
-        'outer: for (row_key, data) in tx_by_addr_data {
+        for (row_key, data) in tx_by_addr_data {
             let slot = !key_to_slot(&row_key[address_prefix.len()..]).ok_or_else(|| {
                 bigtable::Error::ObjectCorrupt(format!(
                     "Failed to convert key to slot: tx-by-addr/{row_key}"
@@ -843,7 +1220,7 @@ impl LedgerStorage {
                 tx_by_addr::TransactionByAddr,
             >(&data, "tx-by-addr", row_key.clone())?;
 
-            let mut cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
+            let cell_data: Vec<TransactionByAddrInfo> = match deserialized_cell_data {
                 bigtable::CellData::Bincode(tx_by_addr) => {
                     tx_by_addr.into_iter().map(|legacy| legacy.into()).collect()
                 }
@@ -858,31 +1235,8 @@ impl LedgerStorage {
                 }
             };
 
-            cell_data.reverse();
-            for tx_by_addr_info in cell_data.into_iter() {
-                // Filter out records before `before_transaction_index`
-                if slot == first_slot && tx_by_addr_info.index >= before_transaction_index {
-                    continue;
-                }
-                // Filter out records after `until_transaction_index`
-                if slot == last_slot && tx_by_addr_info.index <= until_transaction_index {
-                    continue;
-                }
-                infos.push((
-                    ConfirmedTransactionStatusWithSignature {
-                        signature: tx_by_addr_info.signature,
-                        slot,
-                        err: tx_by_addr_info.err,
-                        memo: tx_by_addr_info.memo,
-                        block_time: tx_by_addr_info.block_time,
-                        index: tx_by_addr_info.index,
-                    },
-                    tx_by_addr_info.index,
-                ));
-                // Respect limit
-                if infos.len() >= limit {
-                    break 'outer;
-                }
+            if range.push_row(&mut infos, slot, cell_data, limit) {
+                break;
             }
         }
         Ok(infos)
@@ -911,73 +1265,14 @@ impl LedgerStorage {
         confirmed_block: VersionedConfirmedBlockWithEntries,
     ) -> Result<()> {
         trace!("LedgerStorage::upload_confirmed_block_with_entries request received: {slot:?}");
-        let mut by_addr: HashMap<&Pubkey, Vec<TransactionByAddrInfo>> = HashMap::new();
-        let VersionedConfirmedBlockWithEntries {
-            block: confirmed_block,
-            entries,
-        } = confirmed_block;
-
-        let reserved_account_keys = ReservedAccountKeys::new_all_activated();
-        let mut tx_cells = Vec::with_capacity(confirmed_block.transactions.len());
-        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
-            let VersionedTransactionWithStatusMeta { meta, transaction } = transaction_with_meta;
-            let err = meta.status.clone().err();
-            let index = index as u32;
-            let signature = transaction.signatures[0];
-            let memo = extract_and_fmt_memos(transaction_with_meta);
-
-            for address in transaction_with_meta.account_keys().iter() {
-                // Historical note that previously only a set of sysvar ids were
-                // skipped from being uploaded. Now we skip uploaded for the set
-                // of all reserved account keys which will continue to grow in
-                // the future.
-                if !reserved_account_keys.is_reserved(address) {
-                    by_addr
-                        .entry(address)
-                        .or_default()
-                        .push(TransactionByAddrInfo {
-                            signature,
-                            err: err.clone(),
-                            index,
-                            memo: memo.clone(),
-                            block_time: confirmed_block.block_time,
-                        });
-                }
-            }
-
-            tx_cells.push((
-                signature.to_string(),
-                TransactionInfo {
-                    slot,
-                    index,
-                    err,
-                    memo,
-                },
-            ));
-        }
-
-        let tx_by_addr_cells: Vec<_> = by_addr
-            .into_iter()
-            .map(|(address, transaction_info_by_addr)| {
-                (
-                    format!("{}/{}", address, slot_to_tx_by_addr_key(slot)),
-                    tx_by_addr::TransactionByAddr {
-                        tx_by_addrs: transaction_info_by_addr
-                            .into_iter()
-                            .map(|by_addr| by_addr.into())
-                            .collect(),
-                    },
-                )
-            })
-            .collect();
-
-        let num_entries = entries.len();
-        let entry_cell = (
-            slot_to_entries_key(slot),
-            entries::Entries {
-                entries: entries.into_iter().enumerate().map(Into::into).collect(),
-            },
-        );
+        let BlockRows {
+            tx: tx_cells,
+            tx_by_addr: tx_by_addr_cells,
+            entries: entry_cell,
+            block: block_cell,
+            num_transactions,
+            num_entries,
+        } = BlockRows::new(slot, confirmed_block);
 
         let mut tasks = vec![];
 
@@ -1000,7 +1295,7 @@ impl LedgerStorage {
             }));
         }
 
-        if num_entries > 0 {
+        if let Some(entry_cell) = entry_cell {
             let conn = self.connection.clone();
             tasks.push(tokio::spawn(async move {
                 conn.put_protobuf_cells_with_retry::<entries::Entries>("entries", &[entry_cell])
@@ -1034,12 +1329,10 @@ impl LedgerStorage {
             return Err(err);
         }
 
-        let num_transactions = confirmed_block.transactions.len();
-
         // Store the block itself last, after all other metadata about the block has been
         // successfully stored.  This avoids partial uploaded blocks from becoming visible to
         // `get_confirmed_block()` and `get_confirmed_blocks()`
-        let blocks_cells = [(slot_to_blocks_key(slot), confirmed_block.into())];
+        let blocks_cells = [block_cell];
         bytes_written += self
             .connection
             .put_protobuf_cells_with_retry::<generated::ConfirmedBlock>("blocks", &blocks_cells)
@@ -1056,59 +1349,9 @@ impl LedgerStorage {
 
     // Delete a confirmed block and associated meta data.
     pub async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
-        let mut addresses: HashSet<&Pubkey> = HashSet::new();
-        let mut expected_tx_infos: HashMap<String, UploadedTransaction> = HashMap::new();
         let confirmed_block = self.get_confirmed_block(slot).await?;
-        for (index, transaction_with_meta) in confirmed_block.transactions.iter().enumerate() {
-            match transaction_with_meta {
-                TransactionWithStatusMeta::MissingMetadata(transaction) => {
-                    let signature = transaction.signatures[0];
-                    let index = index as u32;
-                    let err = None;
-
-                    for address in transaction.message.account_keys.iter() {
-                        // We could skip deleting addresses that are known
-                        // reserved keys but it's hard to be sure whether we
-                        // previously uploaded rows for reserved keys or not. So
-                        // to ensure everything is deleted properly, we attempt
-                        // to delete rows for all addresses even if they might
-                        // not have been uploaded.
-                        addresses.insert(address);
-                    }
-
-                    expected_tx_infos.insert(
-                        signature.to_string(),
-                        UploadedTransaction { slot, index, err },
-                    );
-                }
-                TransactionWithStatusMeta::Complete(tx_with_meta) => {
-                    let VersionedTransactionWithStatusMeta { transaction, meta } = tx_with_meta;
-                    let signature = transaction.signatures[0];
-                    let index = index as u32;
-                    let err = meta.status.clone().err();
-
-                    for address in tx_with_meta.account_keys().iter() {
-                        // We could skip deleting addresses that are known
-                        // reserved keys but it's hard to be sure whether we
-                        // previously uploaded rows for reserved keys or not. So
-                        // to ensure everything is deleted properly, we attempt
-                        // to delete rows for all addresses even if they might
-                        // not have been uploaded.
-                        addresses.insert(address);
-                    }
-
-                    expected_tx_infos.insert(
-                        signature.to_string(),
-                        UploadedTransaction { slot, index, err },
-                    );
-                }
-            }
-        }
-
-        let address_slot_rows: Vec<_> = addresses
-            .into_iter()
-            .map(|address| format!("{}/{}", address, slot_to_tx_by_addr_key(slot)))
-            .collect();
+        let (address_slot_rows, expected_tx_infos) =
+            uploaded_transaction_rows(slot, &confirmed_block);
 
         let tx_deletion_rows = if !expected_tx_infos.is_empty() {
             let signatures = expected_tx_infos.keys().cloned().collect::<Vec<_>>();
@@ -1194,6 +1437,72 @@ impl LedgerStorage {
     }
 }
 
+#[async_trait]
+impl LedgerStorageAdapter for BigTableLedgerStorage {
+    async fn get_first_available_block(&self) -> Result<Option<Slot>> {
+        BigTableLedgerStorage::get_first_available_block(self).await
+    }
+
+    async fn get_confirmed_blocks(&self, start_slot: Slot, limit: usize) -> Result<Vec<Slot>> {
+        BigTableLedgerStorage::get_confirmed_blocks(self, start_slot, limit).await
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> Result<ConfirmedBlock> {
+        BigTableLedgerStorage::get_confirmed_block(self, slot).await
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> Result<bool> {
+        BigTableLedgerStorage::confirmed_block_exists(self, slot).await
+    }
+
+    async fn get_entries(&self, slot: Slot) -> Result<Vec<EntrySummary>> {
+        Ok(BigTableLedgerStorage::get_entries(self, slot)
+            .await?
+            .collect())
+    }
+
+    async fn get_signature_status(&self, signature: &Signature) -> Result<TransactionStatus> {
+        BigTableLedgerStorage::get_signature_status(self, signature).await
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        BigTableLedgerStorage::get_confirmed_transaction(self, signature).await
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        BigTableLedgerStorage::get_confirmed_signatures_for_address(
+            self,
+            address,
+            before_signature,
+            until_signature,
+            limit,
+        )
+        .await
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> Result<()> {
+        BigTableLedgerStorage::upload_confirmed_block_with_entries(self, slot, confirmed_block)
+            .await
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> Result<()> {
+        BigTableLedgerStorage::delete_confirmed_block(self, slot, dry_run).await
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
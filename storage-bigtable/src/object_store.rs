@@ -0,0 +1,906 @@
+//! Ledger storage in an object store, such as a local directory or an S3-compatible bucket.
+//!
+//! Each BigTable row is stored as an object named `<table>/<row key>`, holding the same
+//! compressed protobuf or bincode encoding as the row's cell. Row keys are ordered like BigTable
+//! rows when object names are listed in lexicographic order.
+
+use {
+    crate::{
+        compression::{compress_best, decompress},
+        confirmed_transaction_in_block, key_to_slot, slot_to_blocks_key, slot_to_entries_key,
+        slot_to_tx_by_addr_key, uploaded_transaction_rows, BlockRows, LedgerStorageAdapter,
+        S3ObjectStore, SignaturesForAddressRange, TransactionInfo, UploadedTransaction,
+    },
+    async_trait::async_trait,
+    futures::{StreamExt, TryStreamExt},
+    log::*,
+    solana_clock::Slot,
+    solana_pubkey::Pubkey,
+    solana_signature::Signature,
+    solana_storage_proto::convert::{entries, generated, tx_by_addr},
+    solana_transaction_status::{
+        ConfirmedBlock, ConfirmedTransactionStatusWithSignature,
+        ConfirmedTransactionWithStatusMeta, EntrySummary, TransactionByAddrInfo, TransactionStatus,
+        VersionedConfirmedBlockWithEntries,
+    },
+    std::{
+        convert::TryInto,
+        io,
+        path::{Path, PathBuf},
+        sync::Arc,
+    },
+    thiserror::Error,
+};
+
+/// Most object names requested in a single listing
+const LIST_PAGE_SIZE: usize = 1000;
+/// Most objects written or deleted concurrently
+const MAX_CONCURRENT_REQUESTS: usize = 64;
+/// Suffix of files being written by `FileObjectStore`, before they are renamed into place
+const PARTIAL_FILE_SUFFIX: &str = ".partial";
+/// Length of the slot keys of the `blocks`, `entries` and `tx-by-addr` tables, in hex digits
+const SLOT_KEY_LEN: usize = 16;
+/// `FileObjectStore` stores each slot key in a subdirectory named by its first
+/// `SLOT_KEY_SHARD_LEN` digits, which holds at most 65536 consecutive slots
+const SLOT_KEY_SHARD_LEN: usize = 12;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("I/O: {0}")]
+    Io(io::Error),
+
+    #[error("HTTP: {0}")]
+    Http(reqwest::Error),
+
+    #[error("HTTP status {0}: {1}")]
+    HttpStatus(u16, String),
+
+    #[error("Invalid storage URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Environment variable {0} is not set")]
+    MissingEnvVar(&'static str),
+
+    #[error("Object is corrupt: {0}")]
+    ObjectCorrupt(String),
+
+    #[error("Ledger storage is read-only")]
+    ReadOnly,
+}
+
+impl std::convert::From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl std::convert::From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A store of named objects, listable in lexicographic order of their names
+#[async_trait]
+pub trait ObjectStore: Send + Sync {
+    /// Fetch the object named `key`, or None if there is no such object
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Does an object named `key` exist
+    async fn exists(&self, key: &str) -> Result<bool>;
+
+    /// Store `data` as the object named `key`, replacing any existing object
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()>;
+
+    /// Delete the object named `key`. Deleting an object that does not exist is not an error
+    async fn delete(&self, key: &str) -> Result<()>;
+
+    /// List up to `limit` object names that start with `prefix` and sort after `start_after`, in
+    /// lexicographic order
+    async fn list(
+        &self,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+}
+
+/// An `ObjectStore` in a local directory, where each object is a file and each `/` in an object
+/// name is a subdirectory.
+///
+/// Objects named by a slot key are stored in a further subdirectory named by the key's first 12
+/// hex digits, e.g. `blocks/000000000016/00000000000016a8`. Directories of slot keys hold one
+/// subdirectory per 65536 slots, so listing them from a slot only reads the subdirectories from
+/// that slot on, rather than every object.
+pub struct FileObjectStore {
+    root: PathBuf,
+}
+
+impl FileObjectStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        let (dir, name) = key.rsplit_once('/').unwrap_or(("", key));
+        match slot_key_shard(name) {
+            Some(shard) => self.root.join(dir).join(shard).join(name),
+            None => self.root.join(key),
+        }
+    }
+
+    /// Names of the objects in `dir` that start with `name_prefix` and sort after `start_after`
+    async fn list_dir(
+        dir: &Path,
+        name_prefix: &str,
+        start_after: Option<&str>,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let mut read_dir = match tokio::fs::read_dir(dir).await {
+            Ok(read_dir) => read_dir,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok((vec![], vec![])),
+            Err(err) => return Err(err.into()),
+        };
+        let mut names = vec![];
+        let mut shards = vec![];
+        while let Some(entry) = read_dir.next_entry().await? {
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() && is_slot_key_shard(&name) {
+                shards.push(name);
+            } else if file_type.is_file()
+                && name.starts_with(name_prefix)
+                && !name.ends_with(PARTIAL_FILE_SUFFIX)
+                && start_after.is_none_or(|start_after| name.as_str() > start_after)
+            {
+                names.push(name);
+            }
+        }
+        Ok((names, shards))
+    }
+}
+
+/// The subdirectory `FileObjectStore` stores the object named `name` in, if `name` is a slot key
+fn slot_key_shard(name: &str) -> Option<&str> {
+    (name.len() == SLOT_KEY_LEN && name.bytes().all(|byte| byte.is_ascii_hexdigit()))
+        .then(|| &name[..SLOT_KEY_SHARD_LEN])
+}
+
+fn is_slot_key_shard(name: &str) -> bool {
+    name.len() == SLOT_KEY_SHARD_LEN && name.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+#[async_trait]
+impl ObjectStore for FileObjectStore {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(tokio::fs::try_exists(self.path(key)).await?)
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        // Write to a partial file first, so that readers never see a partially written object
+        let mut partial_path = path.clone().into_os_string();
+        partial_path.push(PARTIAL_FILE_SUFFIX);
+        tokio::fs::write(&partial_path, data).await?;
+        tokio::fs::rename(&partial_path, &path).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match tokio::fs::remove_file(self.path(key)).await {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Only objects in the directory named by `prefix` are listed, not objects in its
+    /// subdirectories. The ledger layout never nests objects below the prefixes it lists
+    async fn list(
+        &self,
+        prefix: &str,
+        start_after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let (dir, name_prefix) = prefix.rsplit_once('/').unwrap_or(("", prefix));
+        let dir_path = self.root.join(dir);
+        // Every key listed is in `dir`, so keys sort after `start_after` if their names sort
+        // after the rest of it
+        let dir_prefix = format!("{dir}/");
+        let start_after = match start_after {
+            Some(start_after) if dir.is_empty() => Some(start_after),
+            Some(start_after) => match start_after.strip_prefix(&dir_prefix) {
+                Some(start_after) => Some(start_after),
+                None if start_after < dir_prefix.as_str() => None,
+                None => return Ok(vec![]),
+            },
+            None => None,
+        };
+        let (mut names, mut shards) = Self::list_dir(&dir_path, name_prefix, start_after).await?;
+
+        // The names in a shard all start with the shard's name, so shards are read in order, and
+        // only until they hold `limit` names
+        shards.sort_unstable();
+        let mut shard_names = vec![];
+        for shard in shards {
+            if shard_names.len() >= limit {
+                break;
+            }
+            let has_prefix = shard.starts_with(name_prefix) || name_prefix.starts_with(&shard);
+            let is_after_start = start_after.is_none_or(|start_after| {
+                shard.as_bytes()
+                    >= &start_after.as_bytes()[..start_after.len().min(SLOT_KEY_SHARD_LEN)]
+            });
+            if !has_prefix || !is_after_start {
+                continue;
+            }
+            let (mut names, _) =
+                Self::list_dir(&dir_path.join(&shard), name_prefix, start_after).await?;
+            names.retain(|name| slot_key_shard(name) == Some(shard.as_str()));
+            names.sort_unstable();
+            shard_names.extend(names);
+        }
+
+        names.extend(shard_names);
+        names.sort_unstable();
+        names.truncate(limit);
+        Ok(names
+            .into_iter()
+            .map(|name| {
+                if dir.is_empty() {
+                    name
+                } else {
+                    format!("{dir_prefix}{name}")
+                }
+            })
+            .collect())
+    }
+}
+
+fn object_key(table: &str, row_key: &str) -> String {
+    format!("{table}/{row_key}")
+}
+
+fn encode_protobuf<P: prost::Message>(
+    table: &str,
+    rows: impl IntoIterator<Item = (String, P)>,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    rows.into_iter()
+        .map(|(row_key, data)| {
+            let data = compress_best(&data.encode_to_vec())?;
+            Ok((object_key(table, &row_key), data))
+        })
+        .collect()
+}
+
+fn encode_bincode<T: serde::Serialize>(
+    table: &str,
+    rows: impl IntoIterator<Item = (String, T)>,
+) -> Result<Vec<(String, Vec<u8>)>> {
+    rows.into_iter()
+        .map(|(row_key, data)| {
+            let data = compress_best(&bincode::serialize(&data).unwrap())?;
+            Ok((object_key(table, &row_key), data))
+        })
+        .collect()
+}
+
+/// Ledger storage in an `ObjectStore`, laid out like the BigTable tables
+#[derive(Clone)]
+pub struct ObjectStoreLedgerStorage {
+    store: Arc<dyn ObjectStore>,
+    read_only: bool,
+}
+
+impl ObjectStoreLedgerStorage {
+    pub fn new(store: Arc<dyn ObjectStore>, read_only: bool) -> Self {
+        Self { store, read_only }
+    }
+
+    /// Open the object store at `storage_url`, which is one of:
+    ///
+    /// * `file://<path>`: a local directory, see `FileObjectStore`
+    /// * `s3://<bucket>[/<prefix>]`: an S3-compatible bucket, see `S3ObjectStore::from_env()`
+    pub fn open(storage_url: &str, read_only: bool) -> crate::Result<Self> {
+        let store: Arc<dyn ObjectStore> = if let Some(path) = storage_url.strip_prefix("file://") {
+            Arc::new(FileObjectStore::new(Path::new(path)))
+        } else if let Some(location) = storage_url.strip_prefix("s3://") {
+            let (bucket, prefix) = location.split_once('/').unwrap_or((location, ""));
+            if bucket.is_empty() {
+                return Err(Error::InvalidUrl(storage_url.to_string()).into());
+            }
+            Arc::new(S3ObjectStore::from_env(bucket, prefix)?)
+        } else {
+            return Err(Error::InvalidUrl(storage_url.to_string()).into());
+        };
+        info!("Opened ledger storage at {storage_url}");
+        Ok(Self::new(store, read_only))
+    }
+
+    async fn get_protobuf<P: prost::Message + Default>(&self, key: &str) -> Result<Option<P>> {
+        let Some(data) = self.store.get(key).await? else {
+            return Ok(None);
+        };
+        let data = decompress(&data)?;
+        P::decode(&data[..]).map(Some).map_err(|err| {
+            warn!("Failed to deserialize {key}: {err}");
+            Error::ObjectCorrupt(key.to_string())
+        })
+    }
+
+    async fn get_bincode<T: serde::de::DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let Some(data) = self.store.get(key).await? else {
+            return Ok(None);
+        };
+        let data = decompress(&data)?;
+        bincode::deserialize(&data).map(Some).map_err(|err| {
+            warn!("Failed to deserialize {key}: {err}");
+            Error::ObjectCorrupt(key.to_string())
+        })
+    }
+
+    async fn get_transaction_info(&self, signature: &Signature) -> crate::Result<TransactionInfo> {
+        self.get_bincode(&object_key("tx", &signature.to_string()))
+            .await?
+            .ok_or(crate::Error::SignatureNotFound)
+    }
+
+    async fn put_objects(&self, objects: Vec<(String, Vec<u8>)>) -> Result<usize> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        let bytes_written = objects.iter().map(|(_key, data)| data.len()).sum();
+        futures::stream::iter(objects)
+            .map(|(key, data)| async move { self.store.put(&key, data).await })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_collect::<()>()
+            .await?;
+        Ok(bytes_written)
+    }
+
+    async fn delete_objects(&self, keys: Vec<String>) -> Result<()> {
+        if self.read_only {
+            return Err(Error::ReadOnly);
+        }
+        futures::stream::iter(keys)
+            .map(|key| async move { self.store.delete(&key).await })
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .try_collect::<()>()
+            .await
+    }
+}
+
+#[async_trait]
+impl LedgerStorageAdapter for ObjectStoreLedgerStorage {
+    async fn get_first_available_block(&self) -> crate::Result<Option<Slot>> {
+        trace!("ObjectStoreLedgerStorage::get_first_available_block request received");
+        let blocks = self.store.list("blocks/", None, 1).await?;
+        Ok(blocks
+            .first()
+            .and_then(|key| key.strip_prefix("blocks/"))
+            .and_then(key_to_slot))
+    }
+
+    async fn get_confirmed_blocks(
+        &self,
+        start_slot: Slot,
+        limit: usize,
+    ) -> crate::Result<Vec<Slot>> {
+        trace!(
+            "ObjectStoreLedgerStorage::get_confirmed_blocks request received: {start_slot:?} \
+             {limit:?}"
+        );
+        let start_after = start_slot
+            .checked_sub(1)
+            .map(|slot| object_key("blocks", &slot_to_blocks_key(slot)));
+        let blocks = self
+            .store
+            .list("blocks/", start_after.as_deref(), limit)
+            .await?;
+        Ok(blocks
+            .iter()
+            .filter_map(|key| key.strip_prefix("blocks/").and_then(key_to_slot))
+            .collect())
+    }
+
+    async fn get_confirmed_block(&self, slot: Slot) -> crate::Result<ConfirmedBlock> {
+        trace!("ObjectStoreLedgerStorage::get_confirmed_block request received: {slot:?}");
+        let key = object_key("blocks", &slot_to_blocks_key(slot));
+        let block = self
+            .get_protobuf::<generated::ConfirmedBlock>(&key)
+            .await?
+            .ok_or(crate::Error::BlockNotFound(slot))?;
+        block
+            .try_into()
+            .map_err(|_err| Error::ObjectCorrupt(key).into())
+    }
+
+    async fn confirmed_block_exists(&self, slot: Slot) -> crate::Result<bool> {
+        trace!("ObjectStoreLedgerStorage::confirmed_block_exists request received: {slot:?}");
+        Ok(self
+            .store
+            .exists(&object_key("blocks", &slot_to_blocks_key(slot)))
+            .await?)
+    }
+
+    async fn get_entries(&self, slot: Slot) -> crate::Result<Vec<EntrySummary>> {
+        trace!("ObjectStoreLedgerStorage::get_entries request received: {slot:?}");
+        let entries = self
+            .get_protobuf::<entries::Entries>(&object_key("entries", &slot_to_entries_key(slot)))
+            .await?
+            .ok_or(crate::Error::BlockNotFound(slot))?;
+        Ok(entries.entries.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> crate::Result<TransactionStatus> {
+        trace!("ObjectStoreLedgerStorage::get_signature_status request received: {signature:?}");
+        Ok(self.get_transaction_info(signature).await?.into())
+    }
+
+    async fn get_confirmed_transaction(
+        &self,
+        signature: &Signature,
+    ) -> crate::Result<Option<ConfirmedTransactionWithStatusMeta>> {
+        trace!(
+            "ObjectStoreLedgerStorage::get_confirmed_transaction request received: {signature:?}"
+        );
+        let TransactionInfo { slot, index, .. } = self.get_transaction_info(signature).await?;
+        let block = self.get_confirmed_block(slot).await?;
+        Ok(confirmed_transaction_in_block(
+            signature, slot, index, block,
+        ))
+    }
+
+    async fn get_confirmed_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> crate::Result<Vec<(ConfirmedTransactionStatusWithSignature, u32)>> {
+        trace!(
+            "ObjectStoreLedgerStorage::get_confirmed_signatures_for_address request received: \
+             {address:?}"
+        );
+        let (first_slot, before_transaction_index) = match before_signature {
+            None => (Slot::MAX, 0),
+            Some(before_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(before_signature).await?;
+                (slot, index)
+            }
+        };
+        let (last_slot, until_transaction_index) = match until_signature {
+            None => (0, u32::MAX),
+            Some(until_signature) => {
+                let TransactionInfo { slot, index, .. } =
+                    self.get_transaction_info(until_signature).await?;
+                (slot, index)
+            }
+        };
+        let range = SignaturesForAddressRange {
+            first_slot,
+            before_transaction_index,
+            last_slot,
+            until_transaction_index,
+        };
+
+        // Rows are keyed by inverted slot, so listing them in order walks down from `first_slot`
+        // to `last_slot`
+        let address_prefix = format!("tx-by-addr/{address}/");
+        let last_key = format!("{address_prefix}{}", slot_to_tx_by_addr_key(last_slot));
+        let mut start_after = first_slot
+            .checked_add(1)
+            .map(|slot| format!("{address_prefix}{}", slot_to_tx_by_addr_key(slot)));
+        // Every row holds at least one signature, and the row for `first_slot` may be filtered out
+        let page_size = limit.saturating_add(1).clamp(1, LIST_PAGE_SIZE);
+
+        let mut infos = vec![];
+        loop {
+            let keys = self
+                .store
+                .list(&address_prefix, start_after.as_deref(), page_size)
+                .await?;
+            let is_last_page = keys.len() < page_size || keys.last() >= Some(&last_key);
+            let keys: Vec<_> = keys.into_iter().filter(|key| *key <= last_key).collect();
+            let rows = futures::future::try_join_all(
+                keys.iter()
+                    .map(|key| self.get_protobuf::<tx_by_addr::TransactionByAddr>(key)),
+            )
+            .await?;
+            for (key, row) in keys.iter().zip(rows) {
+                // The row was deleted after it was listed
+                let Some(row) = row else {
+                    continue;
+                };
+                let slot = !key
+                    .strip_prefix(&address_prefix)
+                    .and_then(key_to_slot)
+                    .ok_or_else(|| {
+                        Error::ObjectCorrupt(format!("Failed to convert key to slot: {key}"))
+                    })?;
+                let cell_data: Vec<TransactionByAddrInfo> = row.try_into().map_err(|error| {
+                    Error::ObjectCorrupt(format!("Failed to deserialize: {error}: {key}"))
+                })?;
+                if range.push_row(&mut infos, slot, cell_data, limit) {
+                    return Ok(infos);
+                }
+            }
+            if is_last_page {
+                return Ok(infos);
+            }
+            start_after = keys.into_iter().last();
+        }
+    }
+
+    async fn upload_confirmed_block_with_entries(
+        &self,
+        slot: Slot,
+        confirmed_block: VersionedConfirmedBlockWithEntries,
+    ) -> crate::Result<()> {
+        trace!(
+            "ObjectStoreLedgerStorage::upload_confirmed_block_with_entries request received: \
+             {slot:?}"
+        );
+        let BlockRows {
+            tx,
+            tx_by_addr,
+            entries,
+            block,
+            num_transactions,
+            num_entries,
+        } = BlockRows::new(slot, confirmed_block);
+
+        let mut objects = encode_bincode("tx", tx)?;
+        objects.extend(encode_protobuf("tx-by-addr", tx_by_addr)?);
+        objects.extend(encode_protobuf("entries", entries)?);
+        let mut bytes_written = self.put_objects(objects).await?;
+
+        // Store the block itself last, after all other metadata about the block has been
+        // successfully stored.  This avoids partial uploaded blocks from becoming visible to
+        // `get_confirmed_block()` and `get_confirmed_blocks()`
+        bytes_written += self
+            .put_objects(encode_protobuf("blocks", [block])?)
+            .await?;
+        datapoint_info!(
+            "storage-object-store-upload-block",
+            ("slot", slot, i64),
+            ("transactions", num_transactions, i64),
+            ("entries", num_entries, i64),
+            ("bytes", bytes_written, i64),
+        );
+        Ok(())
+    }
+
+    async fn delete_confirmed_block(&self, slot: Slot, dry_run: bool) -> crate::Result<()> {
+        let confirmed_block = self.get_confirmed_block(slot).await?;
+        let (address_slot_rows, expected_tx_infos) =
+            uploaded_transaction_rows(slot, &confirmed_block);
+
+        let signatures: Vec<_> = expected_tx_infos.keys().cloned().collect();
+        let fetched_tx_infos =
+            futures::future::join_all(signatures.iter().map(|signature| async move {
+                self.get_bincode::<TransactionInfo>(&object_key("tx", signature))
+                    .await
+            }))
+            .await;
+        let mut tx_deletion_rows = Vec::with_capacity(signatures.len());
+        for (signature, fetched_tx_info) in signatures.into_iter().zip(fetched_tx_infos) {
+            let expected_tx_info = &expected_tx_infos[&signature];
+            match fetched_tx_info.map(|tx_info| tx_info.map(UploadedTransaction::from)) {
+                Ok(Some(fetched_tx_info)) if fetched_tx_info == *expected_tx_info => {
+                    tx_deletion_rows.push(signature);
+                }
+                Ok(Some(fetched_tx_info)) => {
+                    warn!(
+                        "skipped tx object {signature} because it ({fetched_tx_info:?}) did not \
+                         match to {expected_tx_info:?}"
+                    );
+                }
+                Ok(None) => {
+                    warn!("skipped tx object {signature} because it was not found");
+                }
+                Err(err) => {
+                    warn!("skipped tx object {signature} because it was corrupted: {err:?}");
+                }
+            }
+        }
+
+        let entries_key = object_key("entries", &slot_to_entries_key(slot));
+        let entries_exist = self.store.exists(&entries_key).await?;
+
+        if !dry_run {
+            self.delete_objects(
+                address_slot_rows
+                    .iter()
+                    .map(|row_key| object_key("tx-by-addr", row_key))
+                    .chain(
+                        tx_deletion_rows
+                            .iter()
+                            .map(|signature| object_key("tx", signature)),
+                    )
+                    .chain(entries_exist.then_some(entries_key))
+                    .collect(),
+            )
+            .await?;
+            self.delete_objects(vec![object_key("blocks", &slot_to_blocks_key(slot))])
+                .await?;
+        }
+
+        info!(
+            "{}deleted ledger data for slot {}: {} transaction objects, {} address slot objects, \
+             {} entry object",
+            if dry_run { "[dry run] " } else { "" },
+            slot,
+            tx_deletion_rows.len(),
+            address_slot_rows.len(),
+            if entries_exist { "with" } else { "WITHOUT" }
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        solana_hash::Hash,
+        solana_keypair::Keypair,
+        solana_system_transaction as system_transaction,
+        solana_transaction::versioned::VersionedTransaction,
+        solana_transaction_status::{
+            TransactionStatusMeta, TransactionWithStatusMeta, VersionedConfirmedBlock,
+            VersionedTransactionWithStatusMeta,
+        },
+    };
+
+    fn confirmed_block(
+        parent_slot: Slot,
+        transactions: Vec<VersionedTransaction>,
+    ) -> VersionedConfirmedBlockWithEntries {
+        VersionedConfirmedBlockWithEntries {
+            block: VersionedConfirmedBlock {
+                previous_blockhash: Hash::default().to_string(),
+                blockhash: Hash::new_unique().to_string(),
+                parent_slot,
+                transactions: transactions
+                    .into_iter()
+                    .map(|transaction| VersionedTransactionWithStatusMeta {
+                        transaction,
+                        meta: TransactionStatusMeta::default(),
+                    })
+                    .collect(),
+                rewards: vec![],
+                num_partitions: None,
+                block_time: Some(1_234_567_890),
+                block_height: Some(parent_slot + 1),
+            },
+            entries: vec![],
+        }
+    }
+
+    async fn signatures_for_address(
+        storage: &ObjectStoreLedgerStorage,
+        address: &Pubkey,
+        before_signature: Option<&Signature>,
+        until_signature: Option<&Signature>,
+        limit: usize,
+    ) -> Vec<Signature> {
+        storage
+            .get_confirmed_signatures_for_address(address, before_signature, until_signature, limit)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|(info, _index)| info.signature)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_file_object_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileObjectStore::new(dir.path());
+
+        assert_eq!(store.get("a/1").await.unwrap(), None);
+        assert!(!store.exists("a/1").await.unwrap());
+        assert!(store.list("a/", None, 10).await.unwrap().is_empty());
+
+        for key in ["a/3", "a/1", "a/2", "a/b/4", "b/1"] {
+            store.put(key, key.as_bytes().to_vec()).await.unwrap();
+        }
+        assert_eq!(store.get("a/1").await.unwrap(), Some(b"a/1".to_vec()));
+        assert!(store.exists("a/1").await.unwrap());
+        assert_eq!(
+            store.list("a/", None, 10).await.unwrap(),
+            vec!["a/1", "a/2", "a/3"]
+        );
+        assert_eq!(store.list("a/", Some("a/1"), 1).await.unwrap(), vec!["a/2"]);
+        assert_eq!(store.list("a/2", None, 10).await.unwrap(), vec!["a/2"]);
+
+        store.delete("a/2").await.unwrap();
+        store.delete("a/2").await.unwrap();
+        assert_eq!(
+            store.list("a/", None, 10).await.unwrap(),
+            vec!["a/1", "a/3"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_object_store_slot_keys() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileObjectStore::new(dir.path());
+
+        let slots = [0x10000, 3, 0x1ffff, 1, 0x30000];
+        for slot in slots {
+            let key = object_key("blocks", &slot_to_blocks_key(slot));
+            store.put(&key, vec![]).await.unwrap();
+        }
+        // Slot keys are sharded by their first 12 digits
+        assert!(dir
+            .path()
+            .join("blocks/000000000001/000000000001ffff")
+            .is_file());
+        assert!(store.exists("blocks/000000000001ffff").await.unwrap());
+
+        let store = &store;
+        let list = |start_after: Option<Slot>, limit| {
+            let start_after =
+                start_after.map(|slot| object_key("blocks", &slot_to_blocks_key(slot)));
+            async move {
+                store
+                    .list("blocks/", start_after.as_deref(), limit)
+                    .await
+                    .unwrap()
+                    .into_iter()
+                    .map(|key| key_to_slot(key.strip_prefix("blocks/").unwrap()).unwrap())
+                    .collect::<Vec<_>>()
+            }
+        };
+        assert_eq!(list(None, 10).await, vec![1, 3, 0x10000, 0x1ffff, 0x30000]);
+        assert_eq!(list(None, 1).await, vec![1]);
+        assert_eq!(list(Some(1), 3).await, vec![3, 0x10000, 0x1ffff]);
+        assert_eq!(list(Some(0x10000), 10).await, vec![0x1ffff, 0x30000]);
+        assert_eq!(list(Some(0x20000), 10).await, vec![0x30000]);
+        assert_eq!(list(Some(0x30000), 10).await, Vec::<Slot>::new());
+        assert_eq!(
+            store.list("blocks/0000000000000", None, 10).await.unwrap(),
+            vec!["blocks/0000000000000001", "blocks/0000000000000003"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_object_store_ledger_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage =
+            ObjectStoreLedgerStorage::new(Arc::new(FileObjectStore::new(dir.path())), false);
+
+        let from = Keypair::new();
+        let recipient = solana_pubkey::new_rand();
+        let transactions: Vec<_> = (0..3)
+            .map(|lamports| {
+                VersionedTransaction::from(system_transaction::transfer(
+                    &from,
+                    &recipient,
+                    lamports,
+                    Hash::default(),
+                ))
+            })
+            .collect();
+        let signatures: Vec<_> = transactions
+            .iter()
+            .map(|transaction| transaction.signatures[0])
+            .collect();
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), None);
+        for (slot, transactions) in [
+            (3, vec![transactions[0].clone()]),
+            (5, vec![transactions[1].clone(), transactions[2].clone()]),
+            (7, vec![]),
+        ] {
+            storage
+                .upload_confirmed_block_with_entries(slot, confirmed_block(slot - 1, transactions))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(storage.get_first_available_block().await.unwrap(), Some(3));
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![3, 5, 7]
+        );
+        assert_eq!(storage.get_confirmed_blocks(4, 1).await.unwrap(), vec![5]);
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        assert!(!storage.confirmed_block_exists(6).await.unwrap());
+        assert!(matches!(
+            storage.get_confirmed_block(6).await,
+            Err(crate::Error::BlockNotFound(6))
+        ));
+
+        let block = storage.get_confirmed_block(5).await.unwrap();
+        assert_eq!(block.parent_slot, 4);
+        assert_eq!(block.transactions.len(), 2);
+        assert_eq!(
+            storage
+                .get_signature_status(&signatures[2])
+                .await
+                .unwrap()
+                .slot,
+            5
+        );
+        let transaction = storage
+            .get_confirmed_transaction(&signatures[2])
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((transaction.slot, transaction.index), (5, 1));
+        assert!(matches!(
+            storage.get_signature_status(&Signature::default()).await,
+            Err(crate::Error::SignatureNotFound)
+        ));
+
+        assert_eq!(
+            signatures_for_address(&storage, &recipient, None, None, 10).await,
+            vec![signatures[2], signatures[1], signatures[0]]
+        );
+        assert_eq!(
+            signatures_for_address(&storage, &recipient, None, None, 2).await,
+            vec![signatures[2], signatures[1]]
+        );
+        assert_eq!(
+            signatures_for_address(&storage, &recipient, Some(&signatures[2]), None, 10).await,
+            vec![signatures[1], signatures[0]]
+        );
+        assert_eq!(
+            signatures_for_address(&storage, &recipient, None, Some(&signatures[0]), 10).await,
+            vec![signatures[2], signatures[1]]
+        );
+
+        storage.delete_confirmed_block(5, true).await.unwrap();
+        assert!(storage.confirmed_block_exists(5).await.unwrap());
+        storage.delete_confirmed_block(5, false).await.unwrap();
+        assert_eq!(
+            storage.get_confirmed_blocks(0, 10).await.unwrap(),
+            vec![3, 7]
+        );
+        assert!(storage
+            .get_confirmed_transaction(&signatures[1])
+            .await
+            .is_err());
+        assert_eq!(
+            signatures_for_address(&storage, &recipient, None, None, 10).await,
+            vec![signatures[0]]
+        );
+
+        let read_only =
+            ObjectStoreLedgerStorage::new(Arc::new(FileObjectStore::new(dir.path())), true);
+        assert!(read_only.delete_confirmed_block(3, false).await.is_err());
+        assert!(read_only.confirmed_block_exists(3).await.unwrap());
+    }
+
+    #[test]
+    fn test_open() {
+        assert!(ObjectStoreLedgerStorage::open("file:///tmp/ledger", true).is_ok());
+        for storage_url in ["/tmp/ledger", "gs://bucket", "s3://", "s3:///prefix"] {
+            assert!(matches!(
+                ObjectStoreLedgerStorage::open(storage_url, true),
+                Err(crate::Error::ObjectStoreError(Error::InvalidUrl(_)))
+            ));
+        }
+    }
+}
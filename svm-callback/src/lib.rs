@@ -32,6 +32,24 @@ pub trait InvokeContextCallback {
     }
 }
 
+/// Callback used by InvokeContext to report every instruction it processes,
+/// including instructions invoked through CPI. Precompiles are not reported.
+pub trait InstructionProcessedCallback {
+    /// Called once the result of an instruction is final. `index_in_trace` is
+    /// the index of the instruction in the transaction's instruction trace.
+    ///
+    /// `compute_units_consumed` includes the compute units consumed by the
+    /// instructions the instruction invoked through CPI, which are also
+    /// reported on their own, before it.
+    fn on_instruction_processed(
+        &self,
+        index_in_trace: usize,
+        program_id: &Pubkey,
+        compute_units_consumed: u64,
+        succeeded: bool,
+    );
+}
+
 /// Runtime callbacks for transaction processing.
 pub trait TransactionProcessingCallback: InvokeContextCallback {
     fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)>;
@@ -0,0 +1,452 @@
+use {
+    agave_feature_set::{raise_cpi_nesting_limit_to_8, FeatureSet},
+    agave_syscalls::create_program_runtime_environment_v1,
+    solana_account::{AccountSharedData, ReadableAccount},
+    solana_builtins::BUILTINS,
+    solana_clock::{Clock, Slot, UnixTimestamp},
+    solana_compute_budget_instruction::instructions_processor::process_compute_budget_instructions,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee::{calculate_fee_details, FeeFeatures},
+    solana_fee_structure::{FeeBudgetLimits, FeeStructure},
+    solana_hash::Hash,
+    solana_program_runtime::{
+        execution_budget::SVMTransactionExecutionBudget,
+        loaded_programs::{BlockRelation, ForkGraph, ProgramCacheEntry},
+    },
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_svm::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        transaction_processing_result::{ProcessedTransaction, TransactionProcessingResult},
+        transaction_processor::{
+            ExecutionRecordingConfig, TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    solana_svm_transaction::svm_message::{SVMMessage, SVMStaticMessage},
+    solana_transaction::sanitized::SanitizedTransaction,
+    std::{
+        cmp::Ordering,
+        collections::HashMap,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Fork graph of a single fork, where every older slot is an ancestor.
+struct SimpleForkGraph;
+
+impl ForkGraph for SimpleForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+}
+
+/// Callback serving the accounts of the session, falling back to the
+/// account loader for accounts the session has not touched.
+struct SimpleSvmCallback<'a, F> {
+    accounts: &'a HashMap<Pubkey, AccountSharedData>,
+    account_loader: &'a F,
+}
+
+impl<F> InvokeContextCallback for SimpleSvmCallback<'_, F> {}
+
+impl<F: Fn(&Pubkey) -> Option<AccountSharedData>> TransactionProcessingCallback
+    for SimpleSvmCallback<'_, F>
+{
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        let account = match self.accounts.get(pubkey) {
+            Some(account) => account.clone(),
+            None => (self.account_loader)(pubkey)?,
+        };
+        // Accounts without lamports do not exist, as in accounts-db.
+        (account.lamports() > 0).then_some((account, 0))
+    }
+}
+
+/// Configuration of a [`SimpleSvmEnvironment`].
+#[derive(Debug, Clone)]
+pub struct SimpleSvmConfig {
+    pub feature_set: FeatureSet,
+    pub slot: Slot,
+    pub unix_timestamp: UnixTimestamp,
+    pub epoch_schedule: EpochSchedule,
+    pub rent: Rent,
+    /// Blockhash handed to transactions, e.g. to initialize or advance
+    /// durable nonces.
+    pub blockhash: Hash,
+    /// Lamports per signature of `blockhash`. Zero disables fees.
+    pub lamports_per_signature: u64,
+}
+
+impl Default for SimpleSvmConfig {
+    fn default() -> Self {
+        Self {
+            feature_set: FeatureSet::all_enabled(),
+            slot: 0,
+            unix_timestamp: 0,
+            epoch_schedule: EpochSchedule::default(),
+            rent: Rent::default(),
+            blockhash: Hash::default(),
+            lamports_per_signature: FeeStructure::default().lamports_per_signature,
+        }
+    }
+}
+
+/// A minimal environment for simulating transactions without a bank.
+///
+/// Accounts are looked up in the state of the session first and then fetched
+/// through `account_loader`, which is expected to return `None` for accounts
+/// that do not exist. The session state holds the builtin program accounts,
+/// the clock, rent and epoch schedule sysvars, any account set with
+/// [`Self::set_account`], and every account committed by
+/// [`Self::process_transactions`].
+///
+/// Transactions are not checked for the age of their recent blockhash, nor
+/// for duplicate signatures, and durable nonce transactions are executed as
+/// regular ones. Precompiles are not supported.
+pub struct SimpleSvmEnvironment<F> {
+    config: SimpleSvmConfig,
+    account_loader: F,
+    accounts: HashMap<Pubkey, AccountSharedData>,
+    processor: TransactionBatchProcessor<SimpleForkGraph>,
+    // The program cache only holds a weak reference to the fork graph.
+    _fork_graph: Arc<RwLock<SimpleForkGraph>>,
+}
+
+impl<F: Fn(&Pubkey) -> Option<AccountSharedData>> SimpleSvmEnvironment<F> {
+    pub fn new(config: SimpleSvmConfig, account_loader: F) -> Self {
+        let epoch = config.epoch_schedule.get_epoch(config.slot);
+        let processor = TransactionBatchProcessor::new_uninitialized(config.slot, epoch);
+        let fork_graph = Arc::new(RwLock::new(SimpleForkGraph));
+        processor
+            .global_program_cache
+            .write()
+            .unwrap()
+            .set_fork_graph(Arc::downgrade(&fork_graph));
+
+        let mut environment = Self {
+            config,
+            account_loader,
+            accounts: HashMap::new(),
+            processor,
+            _fork_graph: fork_graph,
+        };
+        environment.add_builtins();
+        environment.add_sysvars(epoch);
+        environment
+    }
+
+    fn add_builtins(&mut self) {
+        let feature_set = &self.config.feature_set;
+        let runtime_features = feature_set.runtime_features();
+        self.processor.environments.program_runtime_v1 = Arc::new(
+            create_program_runtime_environment_v1(
+                &runtime_features,
+                &SVMTransactionExecutionBudget::new_with_defaults(
+                    runtime_features.raise_cpi_nesting_limit_to_8,
+                ),
+                false, /* deployment */
+                false, /* debugging_features */
+            )
+            .unwrap(),
+        );
+
+        for builtin in BUILTINS {
+            let is_enabled = builtin
+                .enable_feature_id
+                .is_none_or(|feature_id| feature_set.is_active(&feature_id));
+            let is_migrated = builtin
+                .core_bpf_migration_config
+                .as_ref()
+                .is_some_and(|config| feature_set.is_active(&config.feature_id));
+            if !is_enabled || is_migrated {
+                continue;
+            }
+
+            self.accounts.insert(
+                builtin.program_id,
+                AccountSharedData::create(
+                    1,
+                    builtin.name.as_bytes().to_vec(),
+                    solana_sdk_ids::native_loader::id(),
+                    true,
+                    0,
+                ),
+            );
+            self.processor.add_builtin(
+                builtin.program_id,
+                ProgramCacheEntry::new_builtin(0, builtin.name.len(), builtin.entrypoint),
+            );
+        }
+    }
+
+    fn add_sysvars(&mut self, epoch: u64) {
+        let clock = Clock {
+            slot: self.config.slot,
+            epoch_start_timestamp: self.config.unix_timestamp,
+            epoch,
+            leader_schedule_epoch: self
+                .config
+                .epoch_schedule
+                .get_leader_schedule_epoch(self.config.slot),
+            unix_timestamp: self.config.unix_timestamp,
+        };
+        let sysvars = [
+            (
+                solana_sdk_ids::sysvar::clock::id(),
+                bincode::serialize(&clock).unwrap(),
+            ),
+            (
+                solana_sdk_ids::sysvar::epoch_schedule::id(),
+                bincode::serialize(&self.config.epoch_schedule).unwrap(),
+            ),
+            (
+                solana_sdk_ids::sysvar::rent::id(),
+                bincode::serialize(&self.config.rent).unwrap(),
+            ),
+        ];
+        for (pubkey, data) in sysvars {
+            let lamports = self.config.rent.minimum_balance(data.len()).max(1);
+            self.accounts.insert(
+                pubkey,
+                AccountSharedData::create(lamports, data, solana_sdk_ids::sysvar::id(), false, 0),
+            );
+        }
+    }
+
+    fn callback(&self) -> SimpleSvmCallback<'_, F> {
+        SimpleSvmCallback {
+            accounts: &self.accounts,
+            account_loader: &self.account_loader,
+        }
+    }
+
+    /// Returns the account as seen by the next transaction, if it exists.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<AccountSharedData> {
+        self.callback()
+            .get_account_shared_data(pubkey)
+            .map(|(account, _slot)| account)
+    }
+
+    /// Sets the account in the session state, overriding the account loader.
+    /// Setting an account without lamports deletes it.
+    pub fn set_account(&mut self, pubkey: Pubkey, account: AccountSharedData) {
+        self.accounts.insert(pubkey, account);
+    }
+
+    /// Advances the environment to the given slot, keeping the session state
+    /// and the program cache. The sysvars are rewritten for the new slot.
+    pub fn warp_to_slot(&mut self, slot: Slot) {
+        assert!(
+            slot > self.config.slot,
+            "slot {slot} is not after the current slot {}",
+            self.config.slot,
+        );
+        let epoch = self.config.epoch_schedule.get_epoch(slot);
+        self.config.slot = slot;
+        self.processor = self.processor.new_from(slot, epoch);
+        self.add_sysvars(epoch);
+    }
+
+    /// Checks the compute budget limits of the transaction and calculates its
+    /// fee, producing the check result the bank would pass to the SVM.
+    fn check_transaction(&self, transaction: &SanitizedTransaction) -> TransactionCheckResult {
+        let feature_set = &self.config.feature_set;
+        let compute_budget_limits = process_compute_budget_instructions(
+            SVMStaticMessage::program_instructions_iter(transaction),
+            feature_set,
+        )?;
+        let fee_budget = FeeBudgetLimits::from(compute_budget_limits);
+        let fee_details = calculate_fee_details(
+            transaction,
+            self.config.lamports_per_signature == 0,
+            self.config.lamports_per_signature,
+            fee_budget.prioritization_fee,
+            FeeFeatures::from(feature_set),
+        );
+
+        Ok(CheckedTransactionDetails::new(
+            None,
+            compute_budget_limits.get_compute_budget_and_limits(
+                fee_budget.loaded_accounts_data_size_limit,
+                fee_details,
+                feature_set.is_active(&raise_cpi_nesting_limit_to_8::id()),
+            ),
+        ))
+    }
+
+    fn load_and_execute(
+        &self,
+        transactions: &[SanitizedTransaction],
+    ) -> Vec<TransactionProcessingResult> {
+        let callback = self.callback();
+        // Sysvar accounts may have been replaced since the last batch.
+        self.processor.reset_sysvar_cache();
+        self.processor.fill_missing_sysvar_cache_entries(&callback);
+
+        let environment = TransactionProcessingEnvironment {
+            blockhash: self.config.blockhash,
+            blockhash_lamports_per_signature: self.config.lamports_per_signature,
+            feature_set: self.config.feature_set.runtime_features(),
+            program_runtime_environments_for_execution: self.processor.environments.clone(),
+            program_runtime_environments_for_deployment: self.processor.environments.clone(),
+            rent: self.config.rent.clone(),
+            ..TransactionProcessingEnvironment::default()
+        };
+        let config = TransactionProcessingConfig {
+            recording_config: ExecutionRecordingConfig {
+                enable_log_recording: true,
+                enable_return_data_recording: true,
+                ..ExecutionRecordingConfig::default()
+            },
+            ..TransactionProcessingConfig::default()
+        };
+
+        let check_results = transactions
+            .iter()
+            .map(|transaction| self.check_transaction(transaction))
+            .collect();
+        self.processor
+            .load_and_execute_sanitized_transactions(
+                &callback,
+                transactions,
+                check_results,
+                &environment,
+                &config,
+            )
+            .processing_results
+    }
+
+    /// Executes the transactions in order without committing their results,
+    /// so the session state is left untouched. Each transaction still
+    /// observes the results of the ones before it in the batch.
+    pub fn simulate_transactions(
+        &self,
+        transactions: &[SanitizedTransaction],
+    ) -> Vec<TransactionProcessingResult> {
+        self.load_and_execute(transactions)
+    }
+
+    /// Executes the transactions in order and commits their results to the
+    /// session state. Failed transactions only commit their fee payer and
+    /// nonce accounts. Programs deployed or upgraded by a transaction become
+    /// executable after [`Self::warp_to_slot`].
+    pub fn process_transactions(
+        &mut self,
+        transactions: &[SanitizedTransaction],
+    ) -> Vec<TransactionProcessingResult> {
+        let processing_results = self.load_and_execute(transactions);
+        for (transaction, processing_result) in transactions.iter().zip(&processing_results) {
+            let Ok(processed_transaction) = processing_result else {
+                continue;
+            };
+            match processed_transaction {
+                ProcessedTransaction::Executed(executed_transaction)
+                    if executed_transaction.was_successful() =>
+                {
+                    let accounts = executed_transaction
+                        .loaded_transaction
+                        .accounts
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| transaction.is_writable(*index));
+                    for (_, (pubkey, account)) in accounts {
+                        self.accounts.insert(*pubkey, account.clone());
+                    }
+                    self.processor.global_program_cache.write().unwrap().merge(
+                        &self.processor.environments,
+                        self.config.slot,
+                        &executed_transaction.programs_modified_by_tx,
+                    );
+                }
+                ProcessedTransaction::Executed(executed_transaction) => {
+                    let rollback_accounts =
+                        &executed_transaction.loaded_transaction.rollback_accounts;
+                    for (pubkey, account) in rollback_accounts.iter() {
+                        self.accounts.insert(*pubkey, account.clone());
+                    }
+                }
+                ProcessedTransaction::FeesOnly(fees_only_transaction) => {
+                    for (pubkey, account) in fees_only_transaction.rollback_accounts.iter() {
+                        self.accounts.insert(*pubkey, account.clone());
+                    }
+                }
+            }
+        }
+        processing_results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, solana_keypair::Keypair, solana_signer::Signer,
+        solana_system_interface::program as system_program, solana_transaction::Transaction,
+        std::collections::HashSet,
+    };
+
+    const PAYER_LAMPORTS: u64 = 1_000_000_000;
+
+    fn sanitize(transaction: Transaction) -> SanitizedTransaction {
+        SanitizedTransaction::try_from_legacy_transaction(transaction, &HashSet::new()).unwrap()
+    }
+
+    #[test]
+    fn test_process_transactions_carries_state() {
+        let payer = Keypair::new();
+        let payer_pubkey = payer.pubkey();
+        let recipient = Pubkey::new_unique();
+        let mut environment =
+            SimpleSvmEnvironment::new(SimpleSvmConfig::default(), move |pubkey: &Pubkey| {
+                (*pubkey == payer_pubkey)
+                    .then(|| AccountSharedData::new(PAYER_LAMPORTS, 0, &system_program::id()))
+            });
+        let lamports_per_signature = FeeStructure::default().lamports_per_signature;
+        let amount = Rent::default().minimum_balance(0);
+        let transfer = |lamports| {
+            sanitize(solana_system_transaction::transfer(
+                &payer,
+                &recipient,
+                lamports,
+                Hash::default(),
+            ))
+        };
+
+        let results = environment.simulate_transactions(&[transfer(amount)]);
+        assert_eq!(results[0].as_ref().unwrap().status(), Ok(()));
+        assert_eq!(environment.get_account(&recipient), None);
+        assert_eq!(
+            environment.get_account(&payer_pubkey).unwrap().lamports(),
+            PAYER_LAMPORTS
+        );
+
+        let results = environment.process_transactions(&[transfer(amount), transfer(amount + 1)]);
+        for result in &results {
+            assert_eq!(result.as_ref().unwrap().status(), Ok(()));
+        }
+        let results = environment.process_transactions(&[transfer(amount + 2)]);
+        assert_eq!(results[0].as_ref().unwrap().status(), Ok(()));
+
+        assert_eq!(
+            environment.get_account(&recipient).unwrap().lamports(),
+            3 * amount + 3
+        );
+        assert_eq!(
+            environment.get_account(&payer_pubkey).unwrap().lamports(),
+            PAYER_LAMPORTS - (3 * amount + 3) - 3 * lamports_per_signature
+        );
+
+        // A failed transaction still pays its fee.
+        let results = environment.process_transactions(&[transfer(PAYER_LAMPORTS)]);
+        assert!(results[0].as_ref().unwrap().status().is_err());
+        assert_eq!(
+            environment.get_account(&payer_pubkey).unwrap().lamports(),
+            PAYER_LAMPORTS - (3 * amount + 3) - 4 * lamports_per_signature
+        );
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg(feature = "agave-unstable-api")]
+//! A standalone transaction simulator built on the SVM.
+//!
+//! [`SimpleSvmEnvironment`] wires together what a bank would otherwise
+//! provide to the [`TransactionBatchProcessor`]: the builtin programs, a
+//! program cache on a single fork, the clock, rent and epoch schedule
+//! sysvars, and fee calculation. Accounts are fetched on demand through a
+//! caller-provided closure, and the results of processed transactions are
+//! kept in the environment so that later transactions observe them.
+//!
+//! [`TransactionBatchProcessor`]: solana_svm::transaction_processor::TransactionBatchProcessor
+
+mod environment;
+
+pub use environment::{SimpleSvmConfig, SimpleSvmEnvironment};
@@ -0,0 +1,42 @@
+use {
+    clap::Parser,
+    solana_svm_test_harness::block::{execute_block, file::read_fixture},
+    std::path::PathBuf,
+};
+
+#[derive(Parser)]
+#[command(version, about, long_about = None)]
+struct Cli {
+    inputs: Vec<PathBuf>,
+}
+
+fn exec(input: &PathBuf) -> bool {
+    let fixture = read_fixture(input);
+    let effects = match execute_block(&fixture.input) {
+        Ok(effects) => effects,
+        Err(err) => {
+            println!("FAIL: Block failed with {err} for input: {input:?}");
+            return false;
+        }
+    };
+
+    let ok = effects == fixture.output;
+
+    if ok {
+        println!("OK: {input:?}");
+    } else {
+        println!("FAIL: {input:?}");
+    }
+    ok
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let mut fail_cnt: i32 = 0;
+    for input in cli.inputs {
+        if !exec(&input) {
+            fail_cnt = fail_cnt.saturating_add(1);
+        }
+    }
+    std::process::exit(fail_cnt);
+}
@@ -0,0 +1,86 @@
+//! Module for reading and writing block fixtures on the local filesystem.
+//!
+//! A block fixture is stored as a bincode-serialized `BlockFixture`, pairing
+//! a block context with the effects it is expected to produce.
+//!
+//! Since these functions are intended for the local filesystem and for testing
+//! purposes, they will panic if the file cannot be read, written or decoded.
+
+#![cfg(feature = "serde")]
+
+use {
+    crate::{
+        execute_block,
+        fixture::{block_context::BlockContext, block_fixture::BlockFixture},
+    },
+    solana_ledger::blockstore_processor::BlockstoreProcessorError,
+    std::{fs, path::Path},
+};
+
+/// Read a block fixture from a file.
+pub fn read_fixture<P: AsRef<Path>>(path: P) -> BlockFixture {
+    let path = path.as_ref();
+    let blob =
+        fs::read(path).unwrap_or_else(|e| panic!("Failed to read file {}: {}", path.display(), e));
+    bincode::deserialize(&blob)
+        .unwrap_or_else(|e| panic!("Failed to decode fixture {}: {}", path.display(), e))
+}
+
+/// Write a block fixture to a file.
+pub fn write_fixture<P: AsRef<Path>>(path: P, fixture: &BlockFixture) {
+    let path = path.as_ref();
+    let blob = bincode::serialize(fixture).unwrap();
+    fs::write(path, blob)
+        .unwrap_or_else(|e| panic!("Failed to write file {}: {}", path.display(), e));
+}
+
+/// Execute the given block context and pair it with its effects, producing a
+/// fixture that can be written with `write_fixture`.
+pub fn create_fixture(input: BlockContext) -> Result<BlockFixture, BlockstoreProcessorError> {
+    let output = execute_block(&input)?;
+    Ok(BlockFixture { input, output })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, agave_feature_set::FeatureSet, solana_account::Account,
+        solana_entry::entry::next_entry, solana_hash::Hash, solana_keypair::Keypair,
+        solana_pubkey::Pubkey, solana_rent::Rent, solana_signer::Signer,
+        solana_system_interface::program as system_program,
+    };
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let from_keypair = Keypair::new();
+        let blockhash = Hash::new_unique();
+        let transaction = solana_system_transaction::transfer(
+            &from_keypair,
+            &Pubkey::new_unique(),
+            Rent::default().minimum_balance(0),
+            blockhash,
+        );
+        let entry = next_entry(&blockhash, 1, vec![transaction]);
+        let context = BlockContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(
+                from_keypair.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            )],
+            blockhash_queue: vec![(blockhash, 5000)],
+            slot: 1,
+            entries: vec![entry],
+        };
+        let fixture = create_fixture(context).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("block.fix");
+        write_fixture(&path, &fixture);
+        let read = read_fixture(&path);
+
+        assert_eq!(read.input.feature_set, fixture.input.feature_set);
+        assert_eq!(read.input.entries, fixture.input.entries);
+        assert_eq!(read.output, fixture.output);
+        assert_eq!(execute_block(&read.input).unwrap(), fixture.output);
+    }
+}
@@ -0,0 +1,248 @@
+//! Block harness.
+
+use {
+    crate::fixture::{block_context::BlockContext, block_effects::BlockEffects},
+    solana_account::Account,
+    solana_accounts_db::accounts_db::AccountsDbConfig,
+    solana_entry::entry::{self, EntrySlice, EntryType},
+    solana_genesis_config::GenesisConfig,
+    solana_ledger::{
+        block_error::BlockError,
+        blockstore_processor::{
+            process_entries, BatchExecutionTiming, BlockstoreProcessorError, ReplayEntry,
+        },
+    },
+    solana_pubkey::Pubkey,
+    solana_runtime::{
+        bank::Bank, bank_forks::BankForks, genesis_utils::activate_feature,
+        runtime_config::RuntimeConfig,
+    },
+    solana_transaction::{versioned::VersionedTransaction, TransactionVerificationMode},
+    std::sync::Arc,
+};
+
+/// Execute the entries of the given block context on top of a parent bank
+/// holding its accounts.
+///
+/// Like replay, the entry hashes are verified against the parent's last
+/// blockhash, transactions are fully verified, and the entries are then
+/// processed with `blockstore_processor::process_entries`. Unlike replay,
+/// the tick count and hashes per tick are not checked, so the entries do not
+/// need to form a complete slot.
+///
+/// A block with an invalid transaction is reported as dead. Any other
+/// failure, such as an entry hash mismatch, is returned as an error.
+pub fn execute_block(input: &BlockContext) -> Result<BlockEffects, BlockstoreProcessorError> {
+    let mut genesis_config = GenesisConfig {
+        accounts: input.accounts.iter().cloned().collect(),
+        // Pinned so that the parent bank hash is reproducible.
+        creation_time: 0,
+        ..GenesisConfig::default()
+    };
+    for feature_id in input.feature_set.active().keys() {
+        activate_feature(&mut genesis_config, *feature_id);
+    }
+
+    let parent = Bank::new_from_genesis(
+        &genesis_config,
+        Arc::<RuntimeConfig>::default(),
+        Vec::new(),
+        None,
+        AccountsDbConfig::default(),
+        None,
+        None,
+        Arc::default(),
+        None,
+        None,
+    );
+    for (blockhash, lamports_per_signature) in &input.blockhash_queue {
+        parent.register_recent_blockhash_with_lamports_per_signature(
+            blockhash,
+            *lamports_per_signature,
+        );
+    }
+    let bank_forks = BankForks::new_rw_arc(parent);
+    let parent = bank_forks.read().unwrap().root_bank();
+    let bank = bank_forks.write().unwrap().insert(Bank::new_from_parent(
+        parent.clone(),
+        &Pubkey::default(),
+        input.slot,
+    ));
+
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .thread_name(|i| format!("solBlockTx{i:02}"))
+        .build()
+        .expect("new rayon threadpool");
+
+    if !input
+        .entries
+        .verify(&parent.last_blockhash(), &thread_pool)
+        .status()
+    {
+        return Err(BlockError::InvalidEntryHash.into());
+    }
+
+    let verify_transaction = {
+        let bank = bank.clone_with_scheduler();
+        move |versioned_tx: VersionedTransaction| {
+            bank.verify_transaction(versioned_tx, TransactionVerificationMode::FullVerification)
+        }
+    };
+    let replay_entries = match entry::verify_transactions(
+        input.entries.clone(),
+        &thread_pool,
+        Arc::new(verify_transaction),
+    ) {
+        Ok(entries) => entries,
+        Err(err) => return Ok(BlockEffects::dead(err)),
+    };
+    let mut entry_starting_index: usize = 0;
+    let replay_entries = replay_entries
+        .into_iter()
+        .map(|entry| {
+            let starting_index = entry_starting_index;
+            if let EntryType::Transactions(ref transactions) = entry {
+                entry_starting_index = entry_starting_index.saturating_add(transactions.len());
+            }
+            ReplayEntry {
+                entry,
+                starting_index,
+            }
+        })
+        .collect();
+
+    match process_entries(
+        &bank,
+        &thread_pool,
+        replay_entries,
+        None,
+        None,
+        &mut BatchExecutionTiming::default(),
+        None,
+        None,
+    ) {
+        Ok(()) => {}
+        Err(BlockstoreProcessorError::InvalidTransaction(err)) => {
+            return Ok(BlockEffects::dead(err));
+        }
+        Err(err) => return Err(err),
+    }
+    bank.freeze();
+
+    let mut modified_accounts: Vec<(Pubkey, Account)> = bank
+        .get_all_accounts_modified_since_parent()
+        .into_iter()
+        .map(|(pubkey, account)| (pubkey, Account::from(account)))
+        .collect();
+    modified_accounts.sort_by_key(|(pubkey, _)| *pubkey);
+
+    let cost_tracker = bank.read_cost_tracker().unwrap();
+    Ok(BlockEffects {
+        result: None,
+        bank_hash: bank.hash(),
+        parent_bank_hash: bank.parent_hash(),
+        signature_count: bank.signature_count(),
+        last_blockhash: bank.last_blockhash(),
+        accounts_lt_hash_checksum: bank.get_snapshot_hash().0.to_bytes(),
+        block_cost: cost_tracker.block_cost(),
+        vote_cost: cost_tracker.vote_cost(),
+        transaction_count: cost_tracker.transaction_count(),
+        modified_accounts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, agave_feature_set::FeatureSet, solana_entry::entry::next_entry,
+        solana_hash::Hash, solana_keypair::Keypair, solana_rent::Rent, solana_signer::Signer,
+        solana_system_interface::program as system_program,
+        solana_transaction_error::TransactionError,
+    };
+
+    const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+    fn transfer_block(blockhash: Hash, transaction_blockhash: Hash) -> (BlockContext, Pubkey) {
+        let from_keypair = Keypair::new();
+        let to_pubkey = Pubkey::new_unique();
+        let transaction = solana_system_transaction::transfer(
+            &from_keypair,
+            &to_pubkey,
+            Rent::default().minimum_balance(0),
+            transaction_blockhash,
+        );
+        let entry = next_entry(&blockhash, 1, vec![transaction]);
+        let tick = next_entry(&entry.hash, 1, vec![]);
+
+        let context = BlockContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![(
+                from_keypair.pubkey(),
+                Account::new(1_000_000_000, 0, &system_program::id()),
+            )],
+            blockhash_queue: vec![(blockhash, LAMPORTS_PER_SIGNATURE)],
+            slot: 1,
+            entries: vec![entry, tick],
+        };
+        (context, to_pubkey)
+    }
+
+    #[test]
+    fn test_execute_block_transfer() {
+        let blockhash = Hash::new_unique();
+        let (context, to_pubkey) = transfer_block(blockhash, blockhash);
+
+        let effects = execute_block(&context).unwrap();
+        assert_eq!(effects.result, None);
+        assert_eq!(effects.signature_count, 1);
+        assert_eq!(effects.transaction_count, 1);
+        assert!(effects.block_cost > 0);
+        assert_eq!(effects.vote_cost, 0);
+        // A single tick does not reach the slot boundary, so no new blockhash
+        // is registered.
+        assert_eq!(effects.last_blockhash, blockhash);
+        assert_eq!(
+            effects.get_account(&to_pubkey).unwrap().lamports,
+            Rent::default().minimum_balance(0),
+        );
+        assert!(effects
+            .modified_accounts
+            .is_sorted_by_key(|(pubkey, _)| *pubkey));
+
+        // Executing the same block again yields the same bank hash.
+        let effects_again = execute_block(&context).unwrap();
+        assert_eq!(effects.parent_bank_hash, effects_again.parent_bank_hash);
+        assert_eq!(effects.bank_hash, effects_again.bank_hash);
+        assert_eq!(
+            effects.accounts_lt_hash_checksum,
+            effects_again.accounts_lt_hash_checksum,
+        );
+    }
+
+    #[test]
+    fn test_execute_block_dead() {
+        let (context, _) = transfer_block(Hash::new_unique(), Hash::new_unique());
+
+        let effects = execute_block(&context).unwrap();
+        assert_eq!(effects.result, Some(TransactionError::BlockhashNotFound));
+        assert!(effects.modified_accounts.is_empty());
+    }
+
+    #[test]
+    fn test_execute_block_invalid_entry_hash() {
+        let blockhash = Hash::new_unique();
+        let (mut context, _) = transfer_block(blockhash, blockhash);
+        // The entries no longer chain from the parent's last blockhash.
+        context
+            .blockhash_queue
+            .push((Hash::new_unique(), LAMPORTS_PER_SIGNATURE));
+
+        assert!(matches!(
+            execute_block(&context),
+            Err(BlockstoreProcessorError::InvalidBlock(
+                BlockError::InvalidEntryHash
+            )),
+        ));
+    }
+}
@@ -0,0 +1,10 @@
+//! Solana SVM test harness for block execution.
+//!
+//! This crate replays a set of entries as a block on top of a minimal parent
+//! bank, using the validator's entry processing, and reports the resulting
+//! bank hash components, cost tracker totals and account deltas.
+
+pub mod file;
+mod harness;
+
+pub use {harness::execute_block, solana_svm_test_harness_fixture as fixture};
@@ -0,0 +1,34 @@
+//! Feature set serialization for serde support.
+//!
+//! A feature set is stored as its active feature ids with their activation
+//! slots, sorted by feature id. Use with `#[serde(with = "...")]`.
+
+#![cfg(feature = "serde")]
+
+use {
+    agave_feature_set::FeatureSet,
+    serde::{Deserialize, Deserializer, Serialize, Serializer},
+    solana_pubkey::Pubkey,
+};
+
+pub fn serialize<S: Serializer>(
+    feature_set: &FeatureSet,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut active: Vec<(Pubkey, u64)> = feature_set
+        .active()
+        .iter()
+        .map(|(feature_id, slot)| (*feature_id, *slot))
+        .collect();
+    active.sort_unstable();
+    active.serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<FeatureSet, D::Error> {
+    let active = Vec::<(Pubkey, u64)>::deserialize(deserializer)?;
+    let mut feature_set = FeatureSet::default();
+    for (feature_id, slot) in active {
+        feature_set.activate(&feature_id, slot);
+    }
+    Ok(feature_set)
+}
@@ -0,0 +1,25 @@
+//! Block context (input).
+
+use {
+    agave_feature_set::FeatureSet, solana_account::Account, solana_entry::entry::Entry,
+    solana_hash::Hash, solana_pubkey::Pubkey,
+};
+
+/// Block context fixture.
+///
+/// Sysvars are maintained by the bank, so any sysvar accounts provided in
+/// `accounts` are overwritten.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockContext {
+    #[cfg_attr(feature = "serde", serde(with = "crate::active_features"))]
+    pub feature_set: FeatureSet,
+    /// Accounts of the parent bank.
+    pub accounts: Vec<(Pubkey, Account)>,
+    /// Recent blockhashes of the parent bank with their lamports per
+    /// signature, oldest first. The last entry is the blockhash the block
+    /// starts from.
+    pub blockhash_queue: Vec<(Hash, u64)>,
+    pub slot: u64,
+    pub entries: Vec<Entry>,
+}
@@ -0,0 +1,51 @@
+//! Block effects (output).
+
+use {
+    solana_account::Account, solana_hash::Hash, solana_pubkey::Pubkey,
+    solana_transaction_error::TransactionError,
+};
+
+/// Represents the effects of a single block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockEffects {
+    /// Error that marked the block as dead. A dead block is not frozen, so the
+    /// remaining fields are left empty.
+    pub result: Option<TransactionError>,
+    pub bank_hash: Hash,
+    pub parent_bank_hash: Hash,
+    pub signature_count: u64,
+    pub last_blockhash: Hash,
+    pub accounts_lt_hash_checksum: [u8; 32],
+    pub block_cost: u64,
+    pub vote_cost: u64,
+    pub transaction_count: u64,
+    /// Accounts stored in the block's slot, sorted by pubkey.
+    pub modified_accounts: Vec<(Pubkey, Account)>,
+}
+
+impl BlockEffects {
+    /// Returns the effects of a block that was marked as dead.
+    pub fn dead(err: TransactionError) -> Self {
+        Self {
+            result: Some(err),
+            bank_hash: Hash::default(),
+            parent_bank_hash: Hash::default(),
+            signature_count: 0,
+            last_blockhash: Hash::default(),
+            accounts_lt_hash_checksum: [0; 32],
+            block_cost: 0,
+            vote_cost: 0,
+            transaction_count: 0,
+            modified_accounts: vec![],
+        }
+    }
+
+    /// Returns the modified account for the given pubkey, if it exists.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.modified_accounts
+            .iter()
+            .find(|(pk, _)| pk == pubkey)
+            .map(|(_, acc)| acc)
+    }
+}
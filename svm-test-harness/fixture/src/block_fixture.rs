@@ -0,0 +1,15 @@
+//! Block fixture, pairing a block context with its expected effects.
+
+#![cfg(feature = "serde")]
+
+use {
+    crate::{block_context::BlockContext, block_effects::BlockEffects},
+    serde::{Deserialize, Serialize},
+};
+
+/// Block fixture, stored on disk as bincode.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockFixture {
+    pub input: BlockContext,
+    pub output: BlockEffects,
+}
@@ -2,13 +2,22 @@
 //!
 //! This module provides native Rust types for testing program execution.
 //! When the `fuzz` feature is enabled, it also includes conversions
-//! between Firedancer's protobuf payloads and Solana SDK types.
+//! between Firedancer's protobuf payloads and Solana SDK types. When the
+//! `serde` feature is enabled, transaction and block fixtures can be
+//! serialized, so that they can be stored and exchanged as files.
 
 pub mod account_state;
+pub mod active_features;
+pub mod block_context;
+pub mod block_effects;
+pub mod block_fixture;
 pub mod error;
 pub mod feature_set;
 pub mod instr_context;
 pub mod instr_effects;
+pub mod txn_context;
+pub mod txn_effects;
+pub mod txn_fixture;
 
 #[cfg(feature = "fuzz")]
 pub mod proto {
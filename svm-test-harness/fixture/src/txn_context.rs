@@ -0,0 +1,23 @@
+//! Transaction context (input).
+
+use {
+    agave_feature_set::FeatureSet, solana_account::Account, solana_hash::Hash,
+    solana_pubkey::Pubkey, solana_transaction::Transaction,
+};
+
+/// Transaction context fixture.
+///
+/// Sysvars are provided through `accounts`, the same way as for instruction
+/// fixtures.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxnContext {
+    #[cfg_attr(feature = "serde", serde(with = "crate::active_features"))]
+    pub feature_set: FeatureSet,
+    pub accounts: Vec<(Pubkey, Account)>,
+    /// Recent blockhashes with their lamports per signature, oldest first.
+    /// The last entry is the blockhash of the executing slot.
+    pub blockhash_queue: Vec<(Hash, u64)>,
+    pub slot: u64,
+    pub transaction: Transaction,
+}
@@ -0,0 +1,76 @@
+//! Transaction effects (output).
+
+use {
+    solana_account::Account, solana_fee_structure::FeeDetails, solana_pubkey::Pubkey,
+    solana_transaction_error::TransactionError,
+};
+
+/// Rent state of an account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RentState {
+    /// account.lamports == 0
+    Uninitialized,
+    /// 0 < account.lamports < rent-exempt-minimum
+    RentPaying { lamports: u64, data_size: usize },
+    /// account.lamports >= rent-exempt-minimum
+    RentExempt,
+}
+
+/// Rent state of an account before and after a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RentStateTransition {
+    pub pubkey: Pubkey,
+    pub pre: RentState,
+    pub post: RentState,
+}
+
+/// Represents the effects of a single transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxnEffects {
+    pub result: Option<TransactionError>,
+    /// Whether the transaction was processed, meaning its fee was charged and
+    /// its resulting accounts are committed, even if it failed.
+    pub is_processed: bool,
+    /// Whether the transaction was executed, as opposed to failing to load
+    /// its accounts or being rejected before loading.
+    pub is_executed: bool,
+    pub fee_details: FeeDetails,
+    pub executed_units: u64,
+    pub loaded_accounts_data_size: u32,
+    /// Accounts to commit: the writable accounts of a successful transaction,
+    /// or the fee payer and nonce accounts of a failed one.
+    pub resulting_accounts: Vec<(Pubkey, Account)>,
+    pub rent_state_transitions: Vec<RentStateTransition>,
+    pub return_data: Vec<u8>,
+    pub logs: Vec<String>,
+}
+
+impl TxnEffects {
+    /// Returns the effects of a transaction that was rejected before
+    /// processing, leaving every account untouched.
+    pub fn not_processed(err: TransactionError) -> Self {
+        Self {
+            result: Some(err),
+            is_processed: false,
+            is_executed: false,
+            fee_details: FeeDetails::default(),
+            executed_units: 0,
+            loaded_accounts_data_size: 0,
+            resulting_accounts: vec![],
+            rent_state_transitions: vec![],
+            return_data: vec![],
+            logs: vec![],
+        }
+    }
+
+    /// Returns the resulting account for the given pubkey, if it exists.
+    pub fn get_account(&self, pubkey: &Pubkey) -> Option<&Account> {
+        self.resulting_accounts
+            .iter()
+            .find(|(pk, _)| pk == pubkey)
+            .map(|(_, acc)| acc)
+    }
+}
@@ -0,0 +1,16 @@
+//! Transaction fixture, pairing a transaction context with its expected
+//! effects.
+
+#![cfg(feature = "serde")]
+
+use {
+    crate::{txn_context::TxnContext, txn_effects::TxnEffects},
+    serde::{Deserialize, Serialize},
+};
+
+/// Transaction fixture, stored on disk as bincode.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TxnFixture {
+    pub input: TxnContext,
+    pub output: TxnEffects,
+}
@@ -0,0 +1,96 @@
+//! Module for reading and writing transaction fixtures on the local
+//! filesystem.
+//!
+//! A transaction fixture is stored as a bincode-serialized `TxnFixture`,
+//! pairing a transaction context with the effects it is expected to produce.
+//!
+//! Since these functions are intended for the local filesystem and for testing
+//! purposes, they will panic if the file cannot be read, written or decoded.
+
+#![cfg(feature = "serde")]
+
+use {
+    crate::{
+        execute_txn,
+        fixture::{txn_context::TxnContext, txn_fixture::TxnFixture},
+    },
+    std::{fs, path::Path},
+};
+
+/// Read a transaction fixture from a file.
+pub fn read_fixture<P: AsRef<Path>>(path: P) -> TxnFixture {
+    let path = path.as_ref();
+    let blob =
+        fs::read(path).unwrap_or_else(|e| panic!("Failed to read file {}: {}", path.display(), e));
+    bincode::deserialize(&blob)
+        .unwrap_or_else(|e| panic!("Failed to decode fixture {}: {}", path.display(), e))
+}
+
+/// Write a transaction fixture to a file.
+pub fn write_fixture<P: AsRef<Path>>(path: P, fixture: &TxnFixture) {
+    let path = path.as_ref();
+    let blob = bincode::serialize(fixture).unwrap();
+    fs::write(path, blob)
+        .unwrap_or_else(|e| panic!("Failed to write file {}: {}", path.display(), e));
+}
+
+/// Execute the given transaction context and pair it with its effects,
+/// producing a fixture that can be written with `write_fixture`. Returns
+/// `None` if the context cannot be executed.
+pub fn create_fixture(input: TxnContext) -> Option<TxnFixture> {
+    let output = execute_txn(&input)?;
+    Some(TxnFixture { input, output })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*, agave_feature_set::FeatureSet, solana_account::Account, solana_hash::Hash,
+        solana_keypair::Keypair, solana_pubkey::Pubkey, solana_signer::Signer,
+        solana_system_interface::program as system_program,
+    };
+
+    #[test]
+    fn test_fixture_round_trip() {
+        let from_keypair = Keypair::new();
+        let blockhash = Hash::new_unique();
+        let context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![
+                (
+                    system_program::id(),
+                    Account {
+                        lamports: 1,
+                        data: b"solana_system_program".to_vec(),
+                        owner: solana_sdk_ids::native_loader::id(),
+                        executable: true,
+                        rent_epoch: u64::MAX,
+                    },
+                ),
+                (
+                    from_keypair.pubkey(),
+                    Account::new(1_000_000_000, 0, &system_program::id()),
+                ),
+            ],
+            blockhash_queue: vec![(blockhash, 5000)],
+            slot: 10,
+            transaction: solana_system_transaction::transfer(
+                &from_keypair,
+                &Pubkey::new_unique(),
+                1_000_000,
+                blockhash,
+            ),
+        };
+        let fixture = create_fixture(context).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("txn.fix");
+        write_fixture(&path, &fixture);
+        let read = read_fixture(&path);
+
+        assert_eq!(read.input.feature_set, fixture.input.feature_set);
+        assert_eq!(read.input.transaction, fixture.input.transaction);
+        assert_eq!(read.output, fixture.output);
+        assert_eq!(execute_txn(&read.input).unwrap(), fixture.output);
+    }
+}
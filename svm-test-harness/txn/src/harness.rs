@@ -0,0 +1,547 @@
+//! Transaction harness.
+
+use {
+    crate::fixture::{
+        txn_context::TxnContext,
+        txn_effects::{RentState, RentStateTransition, TxnEffects},
+    },
+    agave_feature_set::{raise_cpi_nesting_limit_to_8, require_static_nonce_account},
+    agave_reserved_account_keys::ReservedAccountKeys,
+    agave_syscalls::create_program_runtime_environment_v1,
+    solana_account::{Account, AccountSharedData, ReadableAccount},
+    solana_builtins::BUILTINS,
+    solana_clock::{Slot, MAX_PROCESSING_AGE},
+    solana_compute_budget_instruction::instructions_processor::process_compute_budget_instructions,
+    solana_epoch_schedule::EpochSchedule,
+    solana_fee::{calculate_fee_details, FeeFeatures},
+    solana_fee_structure::{FeeBudgetLimits, FeeDetails, FeeStructure},
+    solana_nonce::state::DurableNonce,
+    solana_nonce_account::verify_nonce_account,
+    solana_program_runtime::{
+        execution_budget::SVMTransactionExecutionBudget,
+        loaded_programs::{BlockRelation, ForkGraph, ProgramCacheEntry},
+    },
+    solana_pubkey::Pubkey,
+    solana_rent::Rent,
+    solana_svm::{
+        account_loader::{CheckedTransactionDetails, TransactionCheckResult},
+        rent_calculator::{self, get_account_rent_state},
+        transaction_processing_result::ProcessedTransaction,
+        transaction_processor::{
+            ExecutionRecordingConfig, TransactionBatchProcessor, TransactionProcessingConfig,
+            TransactionProcessingEnvironment,
+        },
+    },
+    solana_svm_callback::{InvokeContextCallback, TransactionProcessingCallback},
+    solana_svm_transaction::svm_message::{SVMMessage, SVMStaticMessage},
+    solana_transaction::sanitized::SanitizedTransaction,
+    solana_transaction_error::TransactionError,
+    std::{
+        cmp::Ordering,
+        sync::{Arc, RwLock},
+    },
+};
+
+/// Fork graph of a single fork, where every older slot is an ancestor.
+struct TxnForkGraph;
+
+impl ForkGraph for TxnForkGraph {
+    fn relationship(&self, a: Slot, b: Slot) -> BlockRelation {
+        match a.cmp(&b) {
+            Ordering::Less => BlockRelation::Ancestor,
+            Ordering::Equal => BlockRelation::Equal,
+            Ordering::Greater => BlockRelation::Descendant,
+        }
+    }
+}
+
+/// Callback serving the fixture accounts, with no precompile support.
+struct TxnCallback<'a>(&'a [(Pubkey, Account)]);
+
+impl InvokeContextCallback for TxnCallback<'_> {}
+
+impl TransactionProcessingCallback for TxnCallback<'_> {
+    fn get_account_shared_data(&self, pubkey: &Pubkey) -> Option<(AccountSharedData, Slot)> {
+        // Accounts without lamports do not exist, as in accounts-db.
+        self.0
+            .iter()
+            .find(|(found_pubkey, account)| found_pubkey == pubkey && account.lamports > 0)
+            .map(|(_, account)| (AccountSharedData::from(account.clone()), 0))
+    }
+}
+
+/// Returns the nonce address, if any, and the lamports per signature of the
+/// transaction's recent blockhash, as the bank's age check does.
+fn check_transaction_age(
+    input: &TxnContext,
+    transaction: &SanitizedTransaction,
+    callback: &TxnCallback,
+) -> Option<(Option<Pubkey>, u64)> {
+    let recent_blockhash = transaction.recent_blockhash();
+    let last_index = input.blockhash_queue.len().saturating_sub(1);
+    let hash_info = input
+        .blockhash_queue
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, (hash, _))| hash == recent_blockhash);
+    if let Some((index, (_, lamports_per_signature))) = hash_info {
+        if last_index.saturating_sub(index) <= MAX_PROCESSING_AGE {
+            return Some((None, *lamports_per_signature));
+        }
+    }
+
+    let last_blockhash = input
+        .blockhash_queue
+        .last()
+        .map(|(hash, _)| *hash)
+        .unwrap_or_default();
+    let next_durable_nonce = DurableNonce::from_blockhash(&last_blockhash);
+    if recent_blockhash == next_durable_nonce.as_hash() {
+        return None;
+    }
+
+    let require_static_nonce_account = input
+        .feature_set
+        .is_active(&require_static_nonce_account::id());
+    let nonce_address = transaction.get_durable_nonce(require_static_nonce_account)?;
+    let (nonce_account, _slot) = callback.get_account_shared_data(nonce_address)?;
+    let nonce_data = verify_nonce_account(&nonce_account, recent_blockhash)?;
+    Some((
+        Some(*nonce_address),
+        nonce_data.get_lamports_per_signature(),
+    ))
+}
+
+/// Checks the compute budget limits and the age of the transaction, producing
+/// the check result the bank would pass to the SVM.
+fn check_transaction(
+    input: &TxnContext,
+    transaction: &SanitizedTransaction,
+    callback: &TxnCallback,
+) -> TransactionCheckResult {
+    let feature_set = &input.feature_set;
+    let compute_budget_limits = process_compute_budget_instructions(
+        SVMStaticMessage::program_instructions_iter(transaction),
+        feature_set,
+    )?;
+    let fee_budget = FeeBudgetLimits::from(compute_budget_limits);
+    let (nonce_address, lamports_per_signature) =
+        check_transaction_age(input, transaction, callback)
+            .ok_or(TransactionError::BlockhashNotFound)?;
+
+    // A blockhash without lamports per signature disables fees.
+    let fee_details = if lamports_per_signature == 0 {
+        FeeDetails::default()
+    } else {
+        calculate_fee_details(
+            transaction,
+            false,
+            FeeStructure::default().lamports_per_signature,
+            fee_budget.prioritization_fee,
+            FeeFeatures::from(feature_set),
+        )
+    };
+
+    Ok(CheckedTransactionDetails::new(
+        nonce_address,
+        compute_budget_limits.get_compute_budget_and_limits(
+            fee_budget.loaded_accounts_data_size_limit,
+            fee_details,
+            feature_set.is_active(&raise_cpi_nesting_limit_to_8::id()),
+        ),
+    ))
+}
+
+fn rent_state(rent: &Rent, account: &Account) -> RentState {
+    match get_account_rent_state(rent, account.lamports(), account.data().len()) {
+        rent_calculator::RentState::Uninitialized => RentState::Uninitialized,
+        rent_calculator::RentState::RentPaying {
+            lamports,
+            data_size,
+        } => RentState::RentPaying {
+            lamports,
+            data_size,
+        },
+        rent_calculator::RentState::RentExempt => RentState::RentExempt,
+    }
+}
+
+/// Execute a single transaction against the SVM.
+///
+/// The transaction is checked for its compute budget limits, blockhash age
+/// and durable nonce the same way the bank does, then loaded and executed by
+/// the transaction batch processor. Returns `None` if the fixture cannot be
+/// set up. This version does not support precompiles.
+pub fn execute_txn(input: &TxnContext) -> Option<TxnEffects> {
+    let feature_set = input.feature_set.runtime_features();
+    let callback = TxnCallback(&input.accounts);
+
+    let mut reserved_account_keys = ReservedAccountKeys::default();
+    reserved_account_keys.update_active_set(&input.feature_set);
+    let transaction = match SanitizedTransaction::try_from_legacy_transaction(
+        input.transaction.clone(),
+        &reserved_account_keys.active,
+    ) {
+        Ok(transaction) => transaction,
+        Err(err) => return Some(TxnEffects::not_processed(err)),
+    };
+
+    let epoch_schedule = callback
+        .get_account_shared_data(&solana_sdk_ids::sysvar::epoch_schedule::id())
+        .and_then(|(account, _slot)| bincode::deserialize::<EpochSchedule>(account.data()).ok())
+        .unwrap_or_default();
+    let epoch = epoch_schedule.get_epoch(input.slot);
+
+    let mut processor =
+        TransactionBatchProcessor::<TxnForkGraph>::new_uninitialized(input.slot, epoch);
+    let fork_graph = Arc::new(RwLock::new(TxnForkGraph));
+    processor
+        .global_program_cache
+        .write()
+        .unwrap()
+        .set_fork_graph(Arc::downgrade(&fork_graph));
+    processor.environments.program_runtime_v1 = Arc::new(
+        create_program_runtime_environment_v1(
+            &feature_set,
+            &SVMTransactionExecutionBudget::new_with_defaults(
+                feature_set.raise_cpi_nesting_limit_to_8,
+            ),
+            false, /* deployment */
+            false, /* debugging_features */
+        )
+        .ok()?,
+    );
+    for builtin in BUILTINS {
+        processor.add_builtin(
+            builtin.program_id,
+            ProgramCacheEntry::new_builtin(0, builtin.name.len(), builtin.entrypoint),
+        );
+    }
+    processor.fill_missing_sysvar_cache_entries(&callback);
+
+    let rent = processor
+        .sysvar_cache()
+        .get_rent()
+        .map(|rent| (*rent).clone())
+        .unwrap_or_default();
+    let (blockhash, blockhash_lamports_per_signature) =
+        input.blockhash_queue.last().copied().unwrap_or_default();
+    let environment = TransactionProcessingEnvironment {
+        blockhash,
+        blockhash_lamports_per_signature,
+        feature_set,
+        program_runtime_environments_for_execution: processor.environments.clone(),
+        program_runtime_environments_for_deployment: processor.environments.clone(),
+        rent: rent.clone(),
+        ..TransactionProcessingEnvironment::default()
+    };
+    let config = TransactionProcessingConfig {
+        recording_config: ExecutionRecordingConfig {
+            enable_log_recording: true,
+            enable_return_data_recording: true,
+            ..ExecutionRecordingConfig::default()
+        },
+        ..TransactionProcessingConfig::default()
+    };
+
+    let check_result = check_transaction(input, &transaction, &callback);
+    let output = processor.load_and_execute_sanitized_transactions(
+        &callback,
+        std::slice::from_ref(&transaction),
+        vec![check_result],
+        &environment,
+        &config,
+    );
+
+    let processed_transaction = match output.processing_results.into_iter().next()? {
+        Ok(processed_transaction) => processed_transaction,
+        Err(err) => return Some(TxnEffects::not_processed(err)),
+    };
+
+    let (resulting_accounts, return_data, logs) = match &processed_transaction {
+        ProcessedTransaction::Executed(executed_transaction) => {
+            let details = &executed_transaction.execution_details;
+            let resulting_accounts: Vec<(Pubkey, Account)> = if details.was_successful() {
+                executed_transaction
+                    .loaded_transaction
+                    .accounts
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| transaction.is_writable(*index))
+                    .map(|(_, (pubkey, account))| (*pubkey, account.clone().into()))
+                    .collect()
+            } else {
+                executed_transaction
+                    .loaded_transaction
+                    .rollback_accounts
+                    .iter()
+                    .map(|(pubkey, account)| (*pubkey, account.clone().into()))
+                    .collect()
+            };
+            let return_data = details
+                .return_data
+                .as_ref()
+                .map(|return_data| return_data.data.clone())
+                .unwrap_or_default();
+            let logs = details.log_messages.clone().unwrap_or_default();
+            (resulting_accounts, return_data, logs)
+        }
+        ProcessedTransaction::FeesOnly(fees_only_transaction) => {
+            let resulting_accounts: Vec<(Pubkey, Account)> = fees_only_transaction
+                .rollback_accounts
+                .iter()
+                .map(|(pubkey, account)| (*pubkey, account.clone().into()))
+                .collect();
+            (resulting_accounts, vec![], vec![])
+        }
+    };
+
+    let rent_state_transitions = resulting_accounts
+        .iter()
+        .map(|(pubkey, post_account)| {
+            let pre_account = input
+                .accounts
+                .iter()
+                .find(|(found_pubkey, _)| found_pubkey == pubkey)
+                .map(|(_, account)| account.clone())
+                .unwrap_or_default();
+            RentStateTransition {
+                pubkey: *pubkey,
+                pre: rent_state(&rent, &pre_account),
+                post: rent_state(&rent, post_account),
+            }
+        })
+        .collect();
+
+    Some(TxnEffects {
+        result: processed_transaction.status().err(),
+        is_processed: true,
+        is_executed: processed_transaction.executed_transaction().is_some(),
+        fee_details: processed_transaction.fee_details(),
+        executed_units: processed_transaction.executed_units(),
+        loaded_accounts_data_size: processed_transaction.loaded_accounts_data_size(),
+        resulting_accounts,
+        rent_state_transitions,
+        return_data,
+        logs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    #[allow(deprecated)]
+    use solana_sysvar::recent_blockhashes::{Entry as BlockhashesEntry, RecentBlockhashes};
+    use {
+        super::*,
+        agave_feature_set::FeatureSet,
+        solana_hash::Hash,
+        solana_instruction_error::InstructionError,
+        solana_keypair::Keypair,
+        solana_nonce::{
+            state::{Data as NonceData, State as NonceState},
+            versions::Versions as NonceVersions,
+        },
+        solana_signer::Signer,
+        solana_system_interface::{error::SystemError, program as system_program},
+        solana_sysvar_id::SysvarId,
+        solana_transaction::Transaction,
+    };
+
+    const LAMPORTS_PER_SIGNATURE: u64 = 5000;
+
+    fn system_program_account() -> (Pubkey, Account) {
+        (
+            system_program::id(),
+            Account {
+                lamports: 1,
+                data: b"solana_system_program".to_vec(),
+                owner: solana_sdk_ids::native_loader::id(),
+                executable: true,
+                rent_epoch: u64::MAX,
+            },
+        )
+    }
+
+    fn system_account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            owner: system_program::id(),
+            rent_epoch: u64::MAX,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_execute_txn_transfer() {
+        let from_keypair = Keypair::new();
+        let from_pubkey = from_keypair.pubkey();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+        let rent_exempt_minimum = Rent::default().minimum_balance(0);
+
+        let mut context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![
+                system_program_account(),
+                (from_pubkey, system_account(1_000_000_000)),
+            ],
+            blockhash_queue: vec![(blockhash, LAMPORTS_PER_SIGNATURE)],
+            slot: 10,
+            transaction: solana_system_transaction::transfer(
+                &from_keypair,
+                &to_pubkey,
+                rent_exempt_minimum,
+                blockhash,
+            ),
+        };
+
+        let effects = execute_txn(&context).unwrap();
+        assert_eq!(effects.result, None);
+        assert!(effects.is_processed);
+        assert!(effects.is_executed);
+        assert_eq!(effects.fee_details.total_fee(), LAMPORTS_PER_SIGNATURE);
+        assert_eq!(
+            effects.get_account(&from_pubkey).unwrap().lamports,
+            1_000_000_000 - rent_exempt_minimum - LAMPORTS_PER_SIGNATURE,
+        );
+        assert_eq!(
+            effects.get_account(&to_pubkey).unwrap().lamports,
+            rent_exempt_minimum,
+        );
+        assert!(effects
+            .rent_state_transitions
+            .contains(&RentStateTransition {
+                pubkey: to_pubkey,
+                pre: RentState::Uninitialized,
+                post: RentState::RentExempt,
+            }));
+
+        // A transfer leaving the recipient rent-paying fails, but the fee is
+        // still charged and the recipient is rolled back.
+        context.transaction =
+            solana_system_transaction::transfer(&from_keypair, &to_pubkey, 1, blockhash);
+        let effects = execute_txn(&context).unwrap();
+        assert_eq!(
+            effects.result,
+            Some(TransactionError::InsufficientFundsForRent { account_index: 1 }),
+        );
+        assert!(effects.is_processed);
+        assert_eq!(
+            effects.get_account(&from_pubkey).unwrap().lamports,
+            1_000_000_000 - LAMPORTS_PER_SIGNATURE,
+        );
+        assert!(effects.get_account(&to_pubkey).is_none());
+
+        // An unknown blockhash is not processed at all.
+        context.transaction = solana_system_transaction::transfer(
+            &from_keypair,
+            &to_pubkey,
+            rent_exempt_minimum,
+            Hash::new_unique(),
+        );
+        let effects = execute_txn(&context).unwrap();
+        assert_eq!(effects.result, Some(TransactionError::BlockhashNotFound));
+        assert!(!effects.is_processed);
+        assert!(effects.resulting_accounts.is_empty());
+    }
+
+    #[test]
+    fn test_execute_txn_nonce_rollback() {
+        let payer_keypair = Keypair::new();
+        let payer_pubkey = payer_keypair.pubkey();
+        let nonce_pubkey = Pubkey::new_unique();
+        let to_pubkey = Pubkey::new_unique();
+        let blockhash = Hash::new_unique();
+
+        let durable_nonce = DurableNonce::from_blockhash(&Hash::new_unique());
+        let nonce_versions = NonceVersions::new(NonceState::Initialized(NonceData::new(
+            payer_pubkey,
+            durable_nonce,
+            LAMPORTS_PER_SIGNATURE,
+        )));
+        let nonce_account = Account {
+            data: bincode::serialize(&nonce_versions).unwrap(),
+            ..system_account(1_000_000_000)
+        };
+
+        // SystemInstruction::AdvanceNonceAccount asserts RecentBlockhashes is
+        // non-empty but then just gets the blockhash from InvokeContext. So,
+        // the sysvar doesn't need real entries
+        #[allow(deprecated)]
+        let recent_blockhashes = (
+            RecentBlockhashes::id(),
+            Account {
+                lamports: 1,
+                data: bincode::serialize(&vec![BlockhashesEntry::default()]).unwrap(),
+                owner: solana_sdk_ids::sysvar::id(),
+                executable: false,
+                rent_epoch: u64::MAX,
+            },
+        );
+
+        // The transfer overdraws the payer, so the transaction fails after
+        // advancing the nonce.
+        let transaction = Transaction::new_signed_with_payer(
+            &[
+                solana_system_interface::instruction::advance_nonce_account(
+                    &nonce_pubkey,
+                    &payer_pubkey,
+                ),
+                solana_system_interface::instruction::transfer(
+                    &payer_pubkey,
+                    &to_pubkey,
+                    2_000_000_000,
+                ),
+            ],
+            Some(&payer_pubkey),
+            &[&payer_keypair],
+            *durable_nonce.as_hash(),
+        );
+
+        let context = TxnContext {
+            feature_set: FeatureSet::all_enabled(),
+            accounts: vec![
+                system_program_account(),
+                (payer_pubkey, system_account(1_000_000_000)),
+                (nonce_pubkey, nonce_account),
+                recent_blockhashes,
+            ],
+            blockhash_queue: vec![(blockhash, LAMPORTS_PER_SIGNATURE)],
+            slot: 10,
+            transaction,
+        };
+
+        let effects = execute_txn(&context).unwrap();
+        assert_eq!(
+            effects.result,
+            Some(TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(SystemError::ResultWithNegativeLamports as u32),
+            )),
+        );
+        assert!(effects.is_processed);
+        assert!(effects.is_executed);
+        assert_eq!(effects.resulting_accounts.len(), 2);
+        assert_eq!(
+            effects.get_account(&payer_pubkey).unwrap().lamports,
+            1_000_000_000 - LAMPORTS_PER_SIGNATURE,
+        );
+        assert!(effects.get_account(&to_pubkey).is_none());
+
+        // The nonce is advanced to the durable nonce of the last blockhash.
+        let nonce_account = effects.get_account(&nonce_pubkey).unwrap();
+        let NonceState::Initialized(nonce_data) =
+            bincode::deserialize::<NonceVersions>(&nonce_account.data)
+                .unwrap()
+                .state()
+                .clone()
+        else {
+            panic!("nonce account must stay initialized");
+        };
+        assert_eq!(
+            nonce_data.durable_nonce,
+            DurableNonce::from_blockhash(&blockhash),
+        );
+    }
+}
@@ -0,0 +1,10 @@
+//! Solana SVM test harness for transaction execution.
+//!
+//! This crate drives Agave's transaction batch processor in order to
+//! execute a single transaction against a given account set, including fee
+//! deduction, blockhash and nonce checks, and rollback on failure.
+
+pub mod file;
+mod harness;
+
+pub use {harness::execute_txn, solana_svm_test_harness_fixture as fixture};
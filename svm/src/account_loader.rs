@@ -26,10 +26,7 @@ use {
     },
     solana_pubkey::Pubkey,
     solana_rent::Rent,
-    solana_sdk_ids::{
-        bpf_loader_upgradeable, native_loader,
-        sysvar::{self, slot_history},
-    },
+    solana_sdk_ids::{bpf_loader_upgradeable, native_loader, sysvar},
     solana_svm_callback::{AccountState, TransactionProcessingCallback},
     solana_svm_feature_set::SVMFeatureSet,
     solana_svm_transaction::svm_message::SVMMessage,
@@ -178,12 +175,11 @@ impl<'a, CB: TransactionProcessingCallback> AccountLoader<'a, CB> {
     ) -> AccountLoader<'a, CB> {
         let mut loaded_accounts = AHashMap::with_capacity(capacity);
 
-        // SlotHistory may be overridden for simulation.
-        // No other uses of AccountOverrides are expected.
-        if let Some(slot_history) =
-            account_overrides.and_then(|overrides| overrides.get(&slot_history::id()))
-        {
-            loaded_accounts.insert(slot_history::id(), (slot_history.clone(), 0));
+        // Accounts may be overridden for simulation, e.g. SlotHistory.
+        if let Some(account_overrides) = account_overrides {
+            for (pubkey, account) in account_overrides.iter() {
+                loaded_accounts.insert(*pubkey, (account.clone(), 0));
+            }
         }
 
         Self {
@@ -1098,6 +1094,9 @@ mod tests {
         let slot_history_id = sysvar::slot_history::id();
         let account = AccountSharedData::new(42, 0, &Pubkey::default());
         account_overrides.set_slot_history(Some(account));
+        let overridden_pubkey = Pubkey::new_unique();
+        let account = AccountSharedData::new(43, 0, &Pubkey::default());
+        account_overrides.set_account(&overridden_pubkey, Some(account));
 
         let keypair = Keypair::new();
         let account = AccountSharedData::new(1_000_000, 0, &Pubkey::default());
@@ -1107,10 +1106,10 @@ mod tests {
         program_account.set_executable(true);
         program_account.set_owner(native_loader::id());
 
-        let instructions = vec![CompiledInstruction::new(2, &(), vec![0])];
+        let instructions = vec![CompiledInstruction::new(3, &(), vec![0])];
         let tx = Transaction::new_with_compiled_instructions(
             &[&keypair],
-            &[slot_history_id],
+            &[slot_history_id, overridden_pubkey],
             Hash::default(),
             vec![bpf_loader::id()],
             instructions,
@@ -1129,6 +1128,8 @@ mod tests {
                 assert_eq!(loaded_transaction.accounts[0].0, keypair.pubkey());
                 assert_eq!(loaded_transaction.accounts[1].0, slot_history_id);
                 assert_eq!(loaded_transaction.accounts[1].1.lamports(), 42);
+                assert_eq!(loaded_transaction.accounts[2].0, overridden_pubkey);
+                assert_eq!(loaded_transaction.accounts[2].1.lamports(), 43);
             }
             TransactionLoadResult::FeesOnly(fees_only_tx) => panic!("{}", fees_only_tx.load_error),
             TransactionLoadResult::NotLoaded(e) => panic!("{e}"),
@@ -4,8 +4,12 @@ use {
 };
 
 /// Encapsulates overridden accounts, typically used for transaction
-/// simulations. Account overrides are currently not used when loading the
-/// durable nonce account or when constructing the instructions sysvar account.
+/// simulations. Overridden accounts take the place of the accounts provided by
+/// the callback for the whole transaction batch, and programs whose accounts
+/// are overridden are loaded from them instead of the global program cache.
+/// Account overrides are currently not used when loading the durable nonce
+/// account for the age check, or when constructing the instructions sysvar
+/// account.
 #[derive(Default)]
 pub struct AccountOverrides {
     accounts: HashMap<Pubkey, AccountSharedData>,
@@ -13,7 +17,7 @@ pub struct AccountOverrides {
 
 impl AccountOverrides {
     /// Insert or remove an account with a given pubkey to/from the list of overrides.
-    fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
+    pub fn set_account(&mut self, pubkey: &Pubkey, account: Option<AccountSharedData>) {
         match account {
             Some(account) => self.accounts.insert(*pubkey, account),
             None => self.accounts.remove(pubkey),
@@ -28,9 +32,14 @@ impl AccountOverrides {
     }
 
     /// Gets the account if it's found in the list of overrides
-    pub(crate) fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
+    pub fn get(&self, pubkey: &Pubkey) -> Option<&AccountSharedData> {
         self.accounts.get(pubkey)
     }
+
+    /// Iterates over the overridden accounts
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, &AccountSharedData)> {
+        self.accounts.iter()
+    }
 }
 
 #[cfg(test)]
@@ -115,6 +115,18 @@ pub(crate) fn load_program_accounts<CB: TransactionProcessingCallback>(
     Some((load_result, last_modification_slot))
 }
 
+/// Returns whether the account is a program account of one of the program loaders. Program data
+/// and buffer accounts of the upgradeable loader are not program accounts.
+pub(crate) fn is_program_account(account: &AccountSharedData) -> bool {
+    if bpf_loader_upgradeable::check_id(account.owner()) {
+        matches!(account.state(), Ok(UpgradeableLoaderState::Program { .. }))
+    } else {
+        loader_v4::check_id(account.owner())
+            || bpf_loader::check_id(account.owner())
+            || bpf_loader_deprecated::check_id(account.owner())
+    }
+}
+
 /// Loads the program with the given pubkey.
 ///
 /// If the account doesn't exist it returns `None`. If the account does exist, it must be a program
@@ -418,6 +430,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_is_program_account() {
+        let mut account = AccountSharedData::default();
+        assert!(!is_program_account(&account));
+
+        account.set_owner(bpf_loader::id());
+        assert!(is_program_account(&account));
+
+        let mut account = AccountSharedData::new_data(
+            1,
+            &UpgradeableLoaderState::Program {
+                programdata_address: Pubkey::new_unique(),
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        assert!(is_program_account(&account));
+
+        account
+            .set_state(&UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: None,
+            })
+            .unwrap();
+        assert!(!is_program_account(&account));
+    }
+
     #[test]
     fn test_load_program_accounts_success() {
         let key1 = Pubkey::new_unique();
@@ -1,7 +1,7 @@
 use {
     crate::account_loader::LoadedTransaction,
     solana_message::inner_instruction::InnerInstructionsList,
-    solana_program_runtime::loaded_programs::ProgramCacheEntry,
+    solana_program_runtime::{loaded_programs::ProgramCacheEntry, profiler::ComputeUnitProfile},
     solana_pubkey::Pubkey,
     solana_transaction_context::TransactionReturnData,
     solana_transaction_error::TransactionResult,
@@ -37,6 +37,9 @@ pub struct TransactionExecutionDetails {
     /// The change in accounts data len for this transaction.
     /// NOTE: This value is valid IFF `status` is `Ok`.
     pub accounts_data_len_delta: i64,
+    /// Compute units sampled per call stack of the SBF programs executed,
+    /// when compute unit profiling is enabled.
+    pub compute_unit_profile: Option<ComputeUnitProfile>,
 }
 
 impl TransactionExecutionDetails {
@@ -8,7 +8,9 @@ use {
         account_overrides::AccountOverrides,
         message_processor::process_message,
         nonce_info::NonceInfo,
-        program_loader::{get_program_deployment_slot, load_program_with_pubkey},
+        program_loader::{
+            get_program_deployment_slot, is_program_account, load_program_with_pubkey,
+        },
         rollback_accounts::RollbackAccounts,
         transaction_account_state_info::TransactionAccountStateInfo,
         transaction_balances::{BalanceCollectionRoutines, BalanceCollector},
@@ -39,13 +41,14 @@ use {
         invoke_context::{EnvironmentConfig, InvokeContext},
         loaded_programs::{
             EpochBoundaryPreparation, ForkGraph, ProgramCache, ProgramCacheEntry,
-            ProgramCacheForTxBatch, ProgramCacheMatchCriteria, ProgramRuntimeEnvironments,
+            ProgramCacheEntryType, ProgramCacheForTxBatch, ProgramCacheMatchCriteria,
+            ProgramRuntimeEnvironments,
         },
         sysvar_cache::SysvarCache,
     },
     solana_pubkey::Pubkey,
     solana_rent::Rent,
-    solana_svm_callback::TransactionProcessingCallback,
+    solana_svm_callback::{InstructionProcessedCallback, TransactionProcessingCallback},
     solana_svm_feature_set::SVMFeatureSet,
     solana_svm_log_collector::LogCollector,
     solana_svm_measure::{measure::Measure, measure_us},
@@ -137,6 +140,14 @@ pub struct TransactionProcessingConfig<'a> {
     /// failing transactions to be committed. If both flags are set then any
     /// failing transaction will cause all transactions to be aborted.
     pub all_or_nothing: bool,
+    /// Program runtime environments created with debugging features. If set,
+    /// the SBF programs of the batch are loaded into the batch-local program
+    /// cache with these environments, which record VM register traces, and the
+    /// traces of executed transactions are folded into compute unit profiles.
+    pub compute_unit_profiling_environments: Option<&'a ProgramRuntimeEnvironments>,
+    /// Notified of the result of every instruction executed, including
+    /// instructions invoked through CPI.
+    pub instruction_processed_callback: Option<&'a dyn InstructionProcessedCallback>,
 }
 
 /// Runtime environment for transaction batch processing.
@@ -440,6 +451,29 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
             };
         }
 
+        // Programs whose accounts are overridden must not be served from the
+        // global program cache, so they are loaded into the batch-local cache
+        // from the overridden accounts. Overridden program data and buffer
+        // accounts are skipped, as they are not programs themselves.
+        if let Some(account_overrides) = config.account_overrides {
+            for (pubkey, account) in account_overrides.iter() {
+                if is_program_account(account)
+                    && let Some((program, _last_modification_slot)) = load_program_with_pubkey(
+                        &account_loader,
+                        config
+                            .compute_unit_profiling_environments
+                            .unwrap_or(&environment.program_runtime_environments_for_execution),
+                        pubkey,
+                        self.slot,
+                        &mut execute_timings,
+                        false,
+                    )
+                {
+                    program_cache_for_tx_batch.replenish(*pubkey, program);
+                }
+            }
+        }
+
         let (mut load_us, mut execution_us): (u64, u64) = (0, 0);
 
         // Validate, execute, and collect results from each transaction in order.
@@ -513,6 +547,19 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                             config.limit_to_load_programs,
                             true, // increment_usage_counter
                         );
+                        // `program_accounts_set` only holds the programs missing from the
+                        // batch-local cache, so every program is reloaded once per batch
+                        if let Some(environments) = config.compute_unit_profiling_environments
+                            && !program_cache_for_tx_batch.hit_max_limit
+                        {
+                            self.reload_programs_for_profiling(
+                                &account_loader,
+                                environments,
+                                &program_accounts_set,
+                                &mut program_cache_for_tx_batch,
+                                &mut execute_timings,
+                            );
+                        }
                     });
                     execute_timings.saturating_add_in_place(
                         ExecuteTimingType::ProgramCacheUs,
@@ -823,6 +870,42 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         program_accounts_set
     }
 
+    /// Replaces the SBF programs of `program_accounts_set` in the batch-local
+    /// cache with executables loaded with the given environments, so that
+    /// the global program cache never holds executables that record register
+    /// traces.
+    fn reload_programs_for_profiling<CB: TransactionProcessingCallback>(
+        &self,
+        account_loader: &AccountLoader<CB>,
+        environments: &ProgramRuntimeEnvironments,
+        program_accounts_set: &HashMap<Pubkey, Slot>,
+        program_cache_for_tx_batch: &mut ProgramCacheForTxBatch,
+        execute_timings: &mut ExecuteTimings,
+    ) {
+        let is_loaded =
+            |entry: &ProgramCacheEntry| matches!(entry.program, ProgramCacheEntryType::Loaded(_));
+        for pubkey in program_accounts_set.keys() {
+            // Tombstones and builtins are kept as they are
+            if !program_cache_for_tx_batch
+                .find(pubkey)
+                .is_some_and(|entry| is_loaded(&entry))
+            {
+                continue;
+            }
+            if let Some((program, _last_modification_slot)) = load_program_with_pubkey(
+                account_loader,
+                environments,
+                pubkey,
+                self.slot,
+                execute_timings,
+                false,
+            ) && is_loaded(&program)
+            {
+                program_cache_for_tx_batch.replenish(*pubkey, program);
+            }
+        }
+    }
+
     #[cfg_attr(feature = "dev-context-only-utils", qualifiers(pub))]
     fn replenish_program_cache<CB: TransactionProcessingCallback>(
         &self,
@@ -972,18 +1055,20 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         let mut executed_units = 0u64;
         let sysvar_cache = &self.sysvar_cache.read().unwrap();
 
+        let mut environment_config = EnvironmentConfig::new(
+            environment.blockhash,
+            environment.blockhash_lamports_per_signature,
+            callback,
+            &environment.feature_set,
+            &environment.program_runtime_environments_for_execution,
+            &environment.program_runtime_environments_for_deployment,
+            sysvar_cache,
+        );
+        environment_config.instruction_processed_callback = config.instruction_processed_callback;
         let mut invoke_context = InvokeContext::new(
             &mut transaction_context,
             program_cache_for_tx_batch,
-            EnvironmentConfig::new(
-                environment.blockhash,
-                environment.blockhash_lamports_per_signature,
-                callback,
-                &environment.feature_set,
-                &environment.program_runtime_environments_for_execution,
-                &environment.program_runtime_environments_for_deployment,
-                sysvar_cache,
-            ),
+            environment_config,
             log_collector.clone(),
             compute_budget,
             self.execution_cost,
@@ -999,6 +1084,11 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
         );
         process_message_time.stop();
 
+        let compute_unit_profile = config
+            .compute_unit_profiling_environments
+            .is_some()
+            .then(|| invoke_context.compute_unit_profile())
+            .filter(|profile| !profile.is_empty());
         drop(invoke_context);
 
         execute_timings.execute_accessories.process_message_us += process_message_time.as_us();
@@ -1078,6 +1168,7 @@ impl<FG: ForkGraph> TransactionBatchProcessor<FG> {
                 return_data,
                 executed_units,
                 accounts_data_len_delta,
+                compute_unit_profile,
             },
             loaded_transaction,
             programs_modified_by_tx: program_cache_for_tx_batch.drain_modified_entries(),
@@ -1204,7 +1295,7 @@ mod tests {
             execution_budget::{
                 SVMTransactionExecutionAndFeeBudgetLimits, SVMTransactionExecutionBudget,
             },
-            loaded_programs::{BlockRelation, ProgramCacheEntryType},
+            loaded_programs::BlockRelation,
         },
         solana_rent::Rent,
         solana_sdk_ids::{bpf_loader, loader_v4, system_program, sysvar},
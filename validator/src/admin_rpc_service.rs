@@ -942,6 +942,8 @@ fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAcco
         AccountIndex::ProgramId => RpcAccountIndex::ProgramId,
         AccountIndex::SplTokenOwner => RpcAccountIndex::SplTokenOwner,
         AccountIndex::SplTokenMint => RpcAccountIndex::SplTokenMint,
+        AccountIndex::SplTokenDelegate => RpcAccountIndex::SplTokenDelegate,
+        AccountIndex::StakeAuthority => RpcAccountIndex::StakeAuthority,
     }
 }
 
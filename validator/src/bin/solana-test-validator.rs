@@ -83,6 +83,8 @@ fn main() {
             "program-id" => AccountIndex::ProgramId,
             "spl-token-mint" => AccountIndex::SplTokenMint,
             "spl-token-owner" => AccountIndex::SplTokenOwner,
+            "spl-token-delegate" => AccountIndex::SplTokenDelegate,
+            "stake-authority" => AccountIndex::StakeAuthority,
             _ => unreachable!(),
         })
         .collect();
@@ -407,6 +409,7 @@ fn main() {
     genesis.transaction_account_lock_limit =
         value_t!(matches, "transaction_account_lock_limit", usize).ok();
     genesis.enable_scheduler_bindings = matches.is_present("enable_scheduler_bindings");
+    genesis.enable_compute_unit_profiling = matches.is_present("enable_compute_unit_profiling");
 
     let tower_storage = Arc::new(FileTowerStorage::new(ledger_path.clone()));
 
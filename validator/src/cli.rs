@@ -430,7 +430,13 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .long("account-index")
                 .takes_value(true)
                 .multiple(true)
-                .possible_values(&["program-id", "spl-token-owner", "spl-token-mint"])
+                .possible_values(&[
+                    "program-id",
+                    "spl-token-owner",
+                    "spl-token-mint",
+                    "spl-token-delegate",
+                    "stake-authority",
+                ])
                 .value_name("INDEX")
                 .help("Enable an accounts index, indexed by the selected account field"),
         )
@@ -835,6 +841,16 @@ pub fn test_app<'a>(version: &'a str, default_args: &'a DefaultTestArgs) -> App<
                 .takes_value(true)
                 .help("Override the runtime's account lock limit per transaction"),
         )
+        .arg(
+            Arg::with_name("enable_compute_unit_profiling")
+                .long("enable-compute-unit-profiling")
+                .takes_value(false)
+                .help(
+                    "Load the SBF programs of simulated transactions with register tracing, so \
+                     that simulateTransaction can return compute unit profiles. Slows down \
+                     simulation",
+                ),
+        )
         .arg(
             Arg::with_name("clone_feature_set")
                 .long("clone-feature-set")
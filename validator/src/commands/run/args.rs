@@ -857,7 +857,11 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .default_value(&default_args.snapshot_archive_format)
             .value_name("ARCHIVE_TYPE")
             .takes_value(true)
-            .help("Snapshot archive format to use."),
+            .help("Snapshot archive format to use.")
+            .long_help(
+                "Snapshot archive format to use. `none` creates uncompressed tar archives, which \
+                 are faster to create and unpack at the expense of disk space.",
+            ),
     )
     .arg(
         Arg::with_name("snapshot_zstd_compression_level")
@@ -872,6 +876,32 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
                  See the zstd manpage for more information.",
             ),
     )
+    .arg(
+        Arg::with_name("snapshot_archive_manifest")
+            .long("snapshot-archive-manifest")
+            .takes_value(false)
+            .help("Append an integrity manifest to generated snapshot archives")
+            .long_help(
+                "Append an integrity manifest, listing the size and blake3 checksum of every \
+                 entry, to generated snapshot archives. Validators prior to v4.0 reject archives \
+                 containing a manifest, so only enable this if the archives are not served to \
+                 such validators.",
+            ),
+    )
+    .arg(
+        Arg::with_name("snapshot_manifest_verification")
+            .long("snapshot-manifest-verification")
+            .possible_values(&["skip", "if-present", "required"])
+            .default_value("skip")
+            .value_name("MODE")
+            .takes_value(true)
+            .help("How to check snapshot archives against their manifest when loading them")
+            .long_help(
+                "How to check snapshot archives against their integrity manifest when loading \
+                 them at startup. `if-present` checks archives which contain a manifest, and \
+                 `required` also rejects archives without one.",
+            ),
+    )
     .arg(
         Arg::with_name("poh_pinned_cpu_core")
             .hidden(hidden_unless_forced())
@@ -909,7 +939,13 @@ pub fn add_args<'a>(app: App<'a, 'a>, default_args: &'a DefaultArgs) -> App<'a,
             .long("account-index")
             .takes_value(true)
             .multiple(true)
-            .possible_values(&["program-id", "spl-token-owner", "spl-token-mint"])
+            .possible_values(&[
+                "program-id",
+                "spl-token-owner",
+                "spl-token-mint",
+                "spl-token-delegate",
+                "stake-authority",
+            ])
             .value_name("INDEX")
             .help("Enable an accounts index, indexed by the selected account field"),
     )
@@ -17,6 +17,8 @@ impl FromClapArgMatches for AccountSecondaryIndexes {
                 "program-id" => AccountIndex::ProgramId,
                 "spl-token-mint" => AccountIndex::SplTokenMint,
                 "spl-token-owner" => AccountIndex::SplTokenOwner,
+                "spl-token-delegate" => AccountIndex::SplTokenDelegate,
+                "stake-authority" => AccountIndex::StakeAuthority,
                 _ => unreachable!(),
             })
             .collect();
@@ -73,6 +75,8 @@ mod tests {
     #[test_case("program-id", AccountIndex::ProgramId)]
     #[test_case("spl-token-mint", AccountIndex::SplTokenMint)]
     #[test_case("spl-token-owner", AccountIndex::SplTokenOwner)]
+    #[test_case("spl-token-delegate", AccountIndex::SplTokenDelegate)]
+    #[test_case("stake-authority", AccountIndex::StakeAuthority)]
     fn verify_args_struct_by_command_run_with_account_indexes(
         arg_value: &str,
         expected_index: AccountIndex,
@@ -5,11 +5,14 @@ use {
     },
     clap::{value_t, Arg, ArgMatches},
     solana_clap_utils::{hidden_unless_forced, input_validators::is_parsable},
-    solana_ledger::blockstore_options::{
-        AccessType, BlockstoreCompressionType, BlockstoreOptions, BlockstoreRecoveryMode,
-        LedgerColumnOptions,
+    solana_ledger::{
+        blockstore::Blockstore,
+        blockstore_options::{
+            AccessType, BlockstoreCompressionType, BlockstoreOptions, BlockstoreRecoveryMode,
+            LedgerColumnOptions, ZstdCompressionOptions, DEFAULT_ZSTD_COMPRESSION_LEVEL,
+        },
     },
-    std::{num::NonZeroUsize, sync::LazyLock},
+    std::{collections::HashMap, num::NonZeroUsize, sync::LazyLock},
 };
 
 struct RocksdbCompactionThreadsArg;
@@ -35,6 +38,9 @@ impl ThreadArg for RocksdbFlushThreadsArg {
 }
 
 const DEFAULT_ROCKSDB_LEDGER_COMPRESSION: &str = "none";
+static DEFAULT_ROCKSDB_LEDGER_ZSTD_LEVEL: LazyLock<String> =
+    LazyLock::new(|| DEFAULT_ZSTD_COMPRESSION_LEVEL.to_string());
+const DEFAULT_ROCKSDB_LEDGER_ZSTD_MAX_DICT_BYTES: &str = "0";
 const DEFAULT_ROCKSDB_PERF_SAMPLE_INTERVAL: &str = "0";
 static DEFAULT_ROCKSDB_COMPACTION_THREADS: LazyLock<String> =
     LazyLock::new(|| RocksdbCompactionThreadsArg::default().to_string());
@@ -48,23 +54,37 @@ impl FromClapArgMatches for BlockstoreOptions {
             .value_of("wal_recovery_mode")
             .map(BlockstoreRecoveryMode::from);
 
+        let zstd_options = ZstdCompressionOptions {
+            level: value_t!(matches, "rocksdb_ledger_zstd_level", i32)?,
+            max_dict_bytes: value_t!(matches, "rocksdb_ledger_zstd_max_dict_bytes", u32)?,
+        };
+
+        let compression_type = match matches.value_of("rocksdb_ledger_compression") {
+            None => BlockstoreCompressionType::default(),
+            Some(ledger_compression_string) => {
+                parse_compression_type(ledger_compression_string, zstd_options)?
+            }
+        };
+
+        let mut column_compression_types = HashMap::new();
+        for column_compression in matches
+            .values_of("rocksdb_ledger_column_compression")
+            .into_iter()
+            .flatten()
+        {
+            let (column_name, compression_string) = split_column_compression(column_compression)
+                .map_err(|err| {
+                    crate::commands::Error::Dynamic(Box::<dyn std::error::Error>::from(err))
+                })?;
+            column_compression_types.insert(
+                column_name.to_string(),
+                parse_compression_type(compression_string, zstd_options)?,
+            );
+        }
+
         let column_options = LedgerColumnOptions {
-            compression_type: match matches.value_of("rocksdb_ledger_compression") {
-                None => BlockstoreCompressionType::default(),
-                Some(ledger_compression_string) => match ledger_compression_string {
-                    "none" => BlockstoreCompressionType::None,
-                    "snappy" => BlockstoreCompressionType::Snappy,
-                    "lz4" => BlockstoreCompressionType::Lz4,
-                    "zlib" => BlockstoreCompressionType::Zlib,
-                    _ => {
-                        return Err(crate::commands::Error::Dynamic(
-                            Box::<dyn std::error::Error>::from(format!(
-                                "Unsupported ledger_compression: {ledger_compression_string}"
-                            )),
-                        ));
-                    }
-                },
-            },
+            compression_type,
+            column_compression_types,
             rocks_perf_sample_interval: value_t!(matches, "rocksdb_perf_sample_interval", usize)?,
         };
 
@@ -84,6 +104,48 @@ impl FromClapArgMatches for BlockstoreOptions {
     }
 }
 
+const COMPRESSION_TYPES: &[&str] = &["none", "lz4", "snappy", "zlib", "zstd"];
+
+fn split_column_compression(column_compression: &str) -> std::result::Result<(&str, &str), String> {
+    let Some((column_name, compression_string)) = column_compression.split_once('=') else {
+        return Err(format!(
+            "Invalid ledger column compression: {column_compression}, expected \
+             COLUMN=COMPRESSION_TYPE"
+        ));
+    };
+    if !Blockstore::column_names().contains(&column_name) {
+        return Err(format!("Unknown ledger column: {column_name}"));
+    }
+    if !COMPRESSION_TYPES.contains(&compression_string) {
+        return Err(format!(
+            "Unsupported ledger_compression: {compression_string}"
+        ));
+    }
+    Ok((column_name, compression_string))
+}
+
+fn is_column_compression(column_compression: String) -> std::result::Result<(), String> {
+    split_column_compression(&column_compression).map(|_| ())
+}
+
+fn parse_compression_type(
+    ledger_compression_string: &str,
+    zstd_options: ZstdCompressionOptions,
+) -> Result<BlockstoreCompressionType> {
+    match ledger_compression_string {
+        "none" => Ok(BlockstoreCompressionType::None),
+        "snappy" => Ok(BlockstoreCompressionType::Snappy),
+        "lz4" => Ok(BlockstoreCompressionType::Lz4),
+        "zlib" => Ok(BlockstoreCompressionType::Zlib),
+        "zstd" => Ok(BlockstoreCompressionType::Zstd(zstd_options)),
+        _ => Err(crate::commands::Error::Dynamic(
+            Box::<dyn std::error::Error>::from(format!(
+                "Unsupported ledger_compression: {ledger_compression_string}"
+            )),
+        )),
+    }
+}
+
 pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
     vec![
         Arg::with_name("wal_recovery_mode")
@@ -102,12 +164,46 @@ pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
             .long("rocksdb-ledger-compression")
             .value_name("COMPRESSION_TYPE")
             .takes_value(true)
-            .possible_values(&["none", "lz4", "snappy", "zlib"])
+            .possible_values(COMPRESSION_TYPES)
             .default_value(DEFAULT_ROCKSDB_LEDGER_COMPRESSION)
             .help(
                 "The compression algorithm that is used to compress transaction status data. \
                  Turning on compression can save ~10% of the ledger size.",
             ),
+        Arg::with_name("rocksdb_ledger_column_compression")
+            .hidden(hidden_unless_forced())
+            .long("rocksdb-ledger-column-compression")
+            .value_name("COLUMN=COMPRESSION_TYPE")
+            .takes_value(true)
+            .multiple(true)
+            .number_of_values(1)
+            .validator(is_column_compression)
+            .help(
+                "The compression algorithm that is used to compress the specified column, \
+                 overriding --rocksdb-ledger-compression. May be specified multiple times. \
+                 COMPRESSION_TYPE is one of none, lz4, snappy, zlib or zstd.",
+            ),
+        Arg::with_name("rocksdb_ledger_zstd_level")
+            .hidden(hidden_unless_forced())
+            .long("rocksdb-ledger-zstd-level")
+            .value_name("LEVEL")
+            .takes_value(true)
+            .validator(is_parsable::<i32>)
+            .default_value(&DEFAULT_ROCKSDB_LEDGER_ZSTD_LEVEL)
+            .help("The compression level of the ledger columns that are compressed with zstd."),
+        Arg::with_name("rocksdb_ledger_zstd_max_dict_bytes")
+            .hidden(hidden_unless_forced())
+            .long("rocksdb-ledger-zstd-max-dict-bytes")
+            .value_name("BYTES")
+            .takes_value(true)
+            .validator(is_parsable::<u32>)
+            .default_value(DEFAULT_ROCKSDB_LEDGER_ZSTD_MAX_DICT_BYTES)
+            .help(
+                "The maximum size of the dictionary that is trained for the values of each SST \
+                 file of the ledger columns that are compressed with zstd. A dictionary can \
+                 considerably improve the compression of columns with small values, such as \
+                 transaction statuses and rewards. 0 disables the dictionary.",
+            ),
         Arg::with_name("rocksdb_perf_sample_interval")
             .hidden(hidden_unless_forced())
             .long("rocksdb-perf-sample-interval")
@@ -139,6 +235,27 @@ pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
             .max_values(1)
             /* .default_value() intentionally not used here! */
             .help("Keep this amount of shreds in root slots."),
+        Arg::with_name("ledger_archive_path")
+            .long("ledger-archive-path")
+            .value_name("DIR")
+            .takes_value(true)
+            .help(
+                "Move old rooted slots to a blockstore in this directory, typically on a cheaper \
+                 disk, instead of dropping them. Slots are moved once the ledger holds more \
+                 shreds than --limit-ledger-size, or once they are older than \
+                 --ledger-archive-max-slot-age. Archived blocks and transactions can still be \
+                 queried over RPC.",
+            ),
+        Arg::with_name("ledger_archive_max_slot_age")
+            .long("ledger-archive-max-slot-age")
+            .value_name("SLOTS")
+            .takes_value(true)
+            .validator(is_parsable::<u64>)
+            .requires("ledger_archive_path")
+            .help(
+                "Move rooted slots that are more than this many slots older than the latest root \
+                 to the ledger archive",
+            ),
     ]
 }
 
@@ -221,6 +338,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_args_struct_by_command_run_with_rocksdb_ledger_compression_zstd() {
+        let default_run_args = crate::commands::run::args::RunArgs::default();
+        let expected_args = RunArgs {
+            blockstore_options: BlockstoreOptions {
+                column_options: LedgerColumnOptions {
+                    compression_type: BlockstoreCompressionType::Zstd(ZstdCompressionOptions {
+                        level: 9,
+                        max_dict_bytes: 16384,
+                    }),
+                    ..default_run_args.blockstore_options.column_options.clone()
+                },
+                ..default_run_args.blockstore_options.clone()
+            },
+            ..default_run_args.clone()
+        };
+        verify_args_struct_by_command_run_with_identity_setup(
+            default_run_args,
+            vec![
+                "--rocksdb-ledger-compression",
+                "zstd",
+                "--rocksdb-ledger-zstd-level",
+                "9",
+                "--rocksdb-ledger-zstd-max-dict-bytes",
+                "16384",
+            ],
+            expected_args,
+        );
+    }
+
+    #[test]
+    fn verify_args_struct_by_command_run_with_rocksdb_ledger_column_compression() {
+        let default_run_args = crate::commands::run::args::RunArgs::default();
+        let expected_args = RunArgs {
+            blockstore_options: BlockstoreOptions {
+                column_options: LedgerColumnOptions {
+                    column_compression_types: HashMap::from([
+                        (
+                            "transaction_status".to_string(),
+                            BlockstoreCompressionType::Zstd(ZstdCompressionOptions::default()),
+                        ),
+                        ("rewards".to_string(), BlockstoreCompressionType::Lz4),
+                    ]),
+                    ..default_run_args.blockstore_options.column_options.clone()
+                },
+                ..default_run_args.blockstore_options.clone()
+            },
+            ..default_run_args.clone()
+        };
+        verify_args_struct_by_command_run_with_identity_setup(
+            default_run_args,
+            vec![
+                "--rocksdb-ledger-column-compression",
+                "transaction_status=zstd",
+                "--rocksdb-ledger-column-compression",
+                "rewards=lz4",
+            ],
+            expected_args,
+        );
+    }
+
+    #[test_case("transaction_status")]
+    #[test_case("transaction_status=invalid")]
+    #[test_case("invalid=zstd")]
+    fn verify_args_struct_by_command_run_with_rocksdb_ledger_column_compression_invalid(
+        arg_value: &str,
+    ) {
+        let default_run_args = crate::commands::run::args::RunArgs::default();
+        verify_args_struct_by_command_run_is_error_with_identity_setup(
+            default_run_args,
+            vec!["--rocksdb-ledger-column-compression", arg_value],
+        );
+    }
+
     #[test]
     fn verify_args_struct_by_command_run_with_rocksdb_ledger_compression_invalid() {
         let default_run_args = crate::commands::run::args::RunArgs::default();
@@ -295,6 +486,16 @@ mod tests {
         assert_eq!(DEFAULT_ROCKSDB_LEDGER_COMPRESSION, "none");
     }
 
+    #[test]
+    fn test_default_rocksdb_ledger_zstd_level_unchanged() {
+        assert_eq!(*DEFAULT_ROCKSDB_LEDGER_ZSTD_LEVEL, "3");
+    }
+
+    #[test]
+    fn test_default_rocksdb_ledger_zstd_max_dict_bytes_unchanged() {
+        assert_eq!(DEFAULT_ROCKSDB_LEDGER_ZSTD_MAX_DICT_BYTES, "0");
+    }
+
     #[test]
     fn test_default_rocksdb_perf_sample_interval_unchanged() {
         assert_eq!(DEFAULT_ROCKSDB_PERF_SAMPLE_INTERVAL, "0");
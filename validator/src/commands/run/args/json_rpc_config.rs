@@ -1,10 +1,14 @@
 use {
     crate::commands::{FromClapArgMatches, Result},
-    clap::{value_t, Arg, ArgMatches},
+    clap::{value_t, values_t, Arg, ArgMatches},
     solana_accounts_db::accounts_index::AccountSecondaryIndexes,
-    solana_clap_utils::input_validators::is_parsable,
-    solana_rpc::rpc::{JsonRpcConfig, RpcBigtableConfig},
-    std::sync::LazyLock,
+    solana_clap_utils::input_validators::{is_parsable, is_pubkey},
+    solana_pubkey::Pubkey,
+    solana_rpc::{
+        account_history::AccountHistoryConfig,
+        rpc::{JsonRpcConfig, RpcBigtableConfig},
+    },
+    std::{collections::HashSet, sync::LazyLock},
 };
 
 static DEFAULT_HEALTH_CHECK_SLOT_DISTANCE: LazyLock<String> = LazyLock::new(|| {
@@ -29,6 +33,24 @@ impl FromClapArgMatches for JsonRpcConfig {
             None
         };
 
+        let account_history_owners: HashSet<Pubkey> =
+            values_t!(matches, "account_history_owner", Pubkey)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+        let account_history_pubkeys: HashSet<Pubkey> =
+            values_t!(matches, "account_history_pubkey", Pubkey)
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+        let account_history = (!account_history_owners.is_empty()
+            || !account_history_pubkeys.is_empty())
+        .then_some(AccountHistoryConfig {
+            owners: account_history_owners,
+            pubkeys: account_history_pubkeys,
+            backfill: matches.is_present("account_history_backfill"),
+        });
+
         Ok(JsonRpcConfig {
             enable_rpc_transaction_history: matches.is_present("enable_rpc_transaction_history"),
             enable_extended_tx_metadata_storage: matches
@@ -55,6 +77,7 @@ impl FromClapArgMatches for JsonRpcConfig {
             rpc_scan_and_fix_roots: matches.is_present("rpc_scan_and_fix_roots"),
             max_request_body_size: Some(value_t!(matches, "rpc_max_request_body_size", usize)?),
             disable_health_check: false,
+            account_history,
         })
     }
 }
@@ -168,6 +191,35 @@ pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
             .validator(is_parsable::<usize>)
             .default_value(&DEFAULT_RPC_MAX_REQUEST_BODY_SIZE)
             .help("The maximum request body size accepted by rpc service"),
+        Arg::with_name("account_history_owner")
+            .long("account-history-owner")
+            .value_name("PROGRAM_ID")
+            .takes_value(true)
+            .multiple(true)
+            .validator(is_pubkey)
+            .help(
+                "Record the state of the accounts owned by this program at every rooted slot, for \
+                 the 'getAccountInfoAtSlot' API. The history is stored in the ledger, is cleaned \
+                 up with it, and continues across restarts from a snapshot it covers",
+            ),
+        Arg::with_name("account_history_pubkey")
+            .long("account-history-pubkey")
+            .value_name("PUBKEY")
+            .takes_value(true)
+            .multiple(true)
+            .validator(is_pubkey)
+            .help(
+                "Record the state of this account at every rooted slot, for the \
+                 'getAccountInfoAtSlot' API",
+            ),
+        Arg::with_name("account_history_backfill")
+            .long("account-history-backfill")
+            .takes_value(false)
+            .help(
+                "Record the state of the selected accounts restored from the snapshot the \
+                 validator starts from, so that the accounts that are not written afterwards are \
+                 in the account history. This notifies every account of the snapshot at startup",
+            ),
     ]
 }
 
@@ -438,6 +490,40 @@ pub(super) mod tests {
         }
     }
 
+    #[test]
+    fn verify_args_struct_by_command_run_with_account_history() {
+        let owner = Pubkey::new_unique();
+        let pubkey1 = Pubkey::new_unique();
+        let pubkey2 = Pubkey::new_unique();
+        {
+            let default_run_args = RunArgs::default();
+            let expected_args = RunArgs {
+                json_rpc_config: JsonRpcConfig {
+                    account_history: Some(AccountHistoryConfig {
+                        owners: HashSet::from([owner]),
+                        pubkeys: HashSet::from([pubkey1, pubkey2]),
+                        backfill: true,
+                    }),
+                    ..default_run_args.json_rpc_config.clone()
+                },
+                ..default_run_args.clone()
+            };
+            verify_args_struct_by_command_run_with_identity_setup(
+                default_run_args,
+                vec![
+                    "--account-history-owner",
+                    &owner.to_string(),
+                    "--account-history-pubkey",
+                    &pubkey1.to_string(),
+                    "--account-history-pubkey",
+                    &pubkey2.to_string(),
+                    "--account-history-backfill",
+                ],
+                expected_args,
+            );
+        }
+    }
+
     #[test]
     fn verify_args_struct_by_command_run_with_rpc_max_request_body_size() {
         // long arg
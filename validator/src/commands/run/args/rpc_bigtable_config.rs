@@ -22,6 +22,7 @@ impl FromClapArgMatches for RpcBigtableConfig {
                 .ok()
                 .map(Duration::from_secs),
             max_message_size: value_t!(matches, "rpc_bigtable_max_message_size", usize)?,
+            storage_url: value_t!(matches, "rpc_bigtable_storage_url", String).ok(),
         })
     }
 }
@@ -59,6 +60,16 @@ pub(crate) fn args<'a, 'b>() -> Vec<Arg<'a, 'b>> {
             .takes_value(true)
             .default_value(&DEFAULT_BIGTABLE_MAX_MESSAGE_SIZE)
             .help("Max encoding and decoding message size used in Bigtable Grpc client"),
+        Arg::with_name("rpc_bigtable_storage_url")
+            .long("rpc-bigtable-storage-url")
+            .value_name("URL")
+            .takes_value(true)
+            .help(
+                "Store the ledger in an object store instead of BigTable: file://<PATH> for a \
+                 local directory, or s3://<BUCKET>[/<PREFIX>] for an S3-compatible bucket \
+                 configured by the AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, AWS_REGION and \
+                 AWS_ENDPOINT_URL environment variables",
+            ),
     ]
 }
 
@@ -234,6 +245,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn verify_args_struct_by_command_run_with_rpc_bigtable_storage_url() {
+        let default_run_args = crate::commands::run::args::RunArgs::default();
+        let expected_args = RunArgs {
+            json_rpc_config: JsonRpcConfig {
+                enable_rpc_transaction_history: true,
+                rpc_bigtable_config: Some(RpcBigtableConfig {
+                    storage_url: Some("s3://ledger-bucket/mainnet".to_string()),
+                    ..default_rpc_bigtable_config()
+                }),
+                ..default_run_args.json_rpc_config.clone()
+            },
+            ..default_run_args.clone()
+        };
+        verify_args_struct_by_command_run_with_identity_setup(
+            default_run_args,
+            vec![
+                "--enable-rpc-transaction-history", // required by enable-rpc-bigtable-ledger-storage
+                "--enable-rpc-bigtable-ledger-storage",
+                "--rpc-bigtable-storage-url",
+                "s3://ledger-bucket/mainnet",
+            ],
+            expected_args,
+        );
+    }
+
     #[test]
     fn test_default_bigtable_instance_name_unchanged() {
         assert_eq!(DEFAULT_BIGTABLE_INSTANCE_NAME, "solana-ledger");
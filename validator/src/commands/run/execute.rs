@@ -7,6 +7,7 @@ use {
         ledger_lockfile, lock_ledger,
     },
     agave_snapshots::{
+        manifest::ManifestVerification,
         paths::BANK_SNAPSHOTS_DIR,
         snapshot_config::{SnapshotConfig, SnapshotUsage},
         ArchiveFormat, SnapshotInterval, SnapshotVersion,
@@ -420,6 +421,17 @@ pub fn execute(
     } else {
         None
     };
+    let ledger_archive_path = value_t!(matches, "ledger_archive_path", PathBuf).ok();
+    let ledger_archive_max_slot_age = value_t!(matches, "ledger_archive_max_slot_age", u64).ok();
+    if ledger_archive_path.is_some()
+        && max_ledger_shreds.is_none()
+        && ledger_archive_max_slot_age.is_none()
+    {
+        Err(
+            "--ledger-archive-path requires --limit-ledger-size or --ledger-archive-max-slot-age"
+                .to_string(),
+        )?;
+    }
 
     let debug_keys: Option<Arc<HashSet<_>>> = if matches.is_present("debug_key") {
         Some(Arc::new(
@@ -773,6 +785,8 @@ pub fn execute(
         repair_handler_type: RepairHandlerType::default(),
         gossip_validators,
         max_ledger_shreds,
+        ledger_archive_path,
+        ledger_archive_max_slot_age,
         blockstore_options: run_args.blockstore_options,
         run_verification: !matches.is_present("skip_startup_ledger_verification"),
         debug_keys,
@@ -1362,6 +1376,12 @@ fn new_snapshot_config(
     let snapshot_packager_niceness_adj =
         value_t_or_exit!(matches, "snapshot_packager_niceness_adj", i8);
 
+    let manifest_verification = match matches.value_of("snapshot_manifest_verification") {
+        Some("if-present") => ManifestVerification::IfPresent,
+        Some("required") => ManifestVerification::Required,
+        _ => ManifestVerification::Skip,
+    };
+
     let snapshot_config = SnapshotConfig {
         usage: if full_snapshot_archive_interval == SnapshotInterval::Disabled {
             SnapshotUsage::LoadOnly
@@ -1375,6 +1395,8 @@ fn new_snapshot_config(
         incremental_snapshot_archives_dir,
         archive_format,
         snapshot_version,
+        archive_manifest: matches.is_present("snapshot_archive_manifest"),
+        manifest_verification,
         maximum_full_snapshot_archives_to_retain,
         maximum_incremental_snapshot_archives_to_retain,
         packager_thread_niceness_adj: snapshot_packager_niceness_adj,